@@ -0,0 +1,398 @@
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use cdrs::consistency::Consistency;
+use cdrs::query::QueryValues;
+use cdrs::types::value::{Value, ValueType};
+use cdrs_tokio::frame::{Frame, TryFromRow};
+use cdrs_tokio::query::{QueryExecutor, QueryParamsBuilder};
+use cdrs_tokio::transport::CDRSTransport;
+use cdrs_tokio::types::CBytes;
+use cdrs_tokio::types::rows::Row;
+use futures::stream::{self, Stream, StreamExt};
+use tokio::sync::Mutex;
+
+use crate::{check_query_hook, CassandraTable, CqlQuery, Error, KeyspaceResolver, Projection, SelectQuery};
+use crate::raw_rows::async_rows_with_raw_columns;
+
+/// Same as `CassandraRepository`, over a `cdrs-tokio` session
+///
+/// Only available with the `cdrs-tokio` feature. The query structs
+/// are not changed, their values are converted to the `cdrs-tokio`
+/// values when executed.
+pub struct AsyncCassandraRepository<S, T, M> {
+    session: S,
+    executor: PhantomData<fn() -> (T, M)>,
+}
+
+impl<S, T, M> AsyncCassandraRepository<S, T, M>
+    where S: QueryExecutor<T, M>,
+          T: CDRSTransport + Unpin + 'static,
+          M: bb8::ManageConnection<Connection=Mutex<T>, Error=cdrs_tokio::Error> + Sized {
+    pub fn new(session: S) -> Self {
+        AsyncCassandraRepository { session, executor: PhantomData }
+    }
+
+    /// The wrapped session
+    pub fn session(&self) -> &S {
+        &self.session
+    }
+
+    /// Execute any query with its values and consistency level
+    ///
+    /// The query hook checks the queries when they are built, the
+    /// operations of the repository build theirs with the fallible
+    /// constructors, and check the selects before executing them.
+    pub async fn execute<Q: CqlQuery>(&self, query: &Q) -> Result<Frame, Error> {
+        let mut params = QueryParamsBuilder::new().values(driver_values(query.values()));
+
+        if let Some(consistency) = query.consistency() {
+            params = params.consistency(driver_consistency(consistency));
+        }
+
+        Ok(self.session.query_with_params(query.query(), params.finalize()).await?)
+    }
+
+    /// Create the table of the entity, if it does not exist
+    pub async fn create_table<E: CassandraTable>(&self) -> Result<(), Error> {
+        self.session.query(E::create_table_cql()).await?;
+
+        Ok(())
+    }
+
+    /// Insert the entity
+    pub async fn save<E: CassandraTable>(&self, entity: &E) -> Result<(), Error> {
+        self.execute(&entity.try_store_query()?).await.map(|_| ())
+    }
+
+    /// Update all non key columns of the entity
+    pub async fn update<E: CassandraTable>(&self, entity: &E) -> Result<(), Error> {
+        self.execute(&entity.try_update_query()?).await.map(|_| ())
+    }
+
+    /// Delete the row of the entity
+    pub async fn delete<E: CassandraTable>(&self, entity: &E) -> Result<(), Error> {
+        self.execute(&entity.try_delete_query()?).await.map(|_| ())
+    }
+
+    /// Find the row by the primary and cluster keys,
+    /// given in the order of `primary_key_columns` then `cluster_key_columns`
+    pub async fn find_by_key<E: CassandraTable + TryFromRow>(&self, keys: QueryValues) -> Result<Option<E>, Error> {
+        let query = SelectQuery::for_table::<E>(E::select_by_primary_and_cluster_keys(Projection::All), keys);
+
+        Ok(self.rows(&query).await?.into_iter().next())
+    }
+
+    /// Find all rows of the partition, the primary keys are
+    /// given in the order of `primary_key_columns`
+    pub async fn find_all_in_partition<E: CassandraTable + TryFromRow>(&self, primary_keys: QueryValues) -> Result<Vec<E>, Error> {
+        let query = SelectQuery::for_table::<E>(E::select_by_primary_keys(Projection::All), primary_keys);
+
+        self.rows(&query).await
+    }
+
+    /// Same as `create_table`, in the keyspace of the resolver
+    pub async fn create_table_in<E: CassandraTable, R: KeyspaceResolver>(&self, resolver: &R) -> Result<(), Error> {
+        self.session.query(E::create_table_cql_in(&resolver.keyspace_for(E::table_name()))?).await?;
+
+        Ok(())
+    }
+
+    /// Same as `save`, in the keyspace of the resolver
+    pub async fn save_in<E: CassandraTable, R: KeyspaceResolver>(&self, resolver: &R, entity: &E) -> Result<(), Error> {
+        self.execute(&entity.store_query_in(&resolver.keyspace_for(E::table_name()))?).await.map(|_| ())
+    }
+
+    /// Same as `update`, in the keyspace of the resolver
+    pub async fn update_in<E: CassandraTable, R: KeyspaceResolver>(&self, resolver: &R, entity: &E) -> Result<(), Error> {
+        self.execute(&entity.update_query_in(&resolver.keyspace_for(E::table_name()))?).await.map(|_| ())
+    }
+
+    /// Same as `delete`, in the keyspace of the resolver
+    pub async fn delete_in<E: CassandraTable, R: KeyspaceResolver>(&self, resolver: &R, entity: &E) -> Result<(), Error> {
+        self.execute(&entity.delete_query_in(&resolver.keyspace_for(E::table_name()))?).await.map(|_| ())
+    }
+
+    /// Same as `find_by_key`, in the keyspace of the resolver
+    pub async fn find_by_key_in<E, R>(&self, resolver: &R, keys: QueryValues) -> Result<Option<E>, Error>
+        where E: CassandraTable + TryFromRow,
+              R: KeyspaceResolver {
+        let ks = resolver.keyspace_for(E::table_name());
+        let query = SelectQuery::new(E::select_by_primary_and_cluster_keys_in(&ks, Projection::All)?, keys)
+            .with_table(ks, E::table_name());
+
+        Ok(self.rows(&query).await?.into_iter().next())
+    }
+
+    /// Same as `find_all_in_partition`, in the keyspace of the resolver
+    pub async fn find_all_in_partition_in<E, R>(&self, resolver: &R, primary_keys: QueryValues) -> Result<Vec<E>, Error>
+        where E: CassandraTable + TryFromRow,
+              R: KeyspaceResolver {
+        let ks = resolver.keyspace_for(E::table_name());
+        let query = SelectQuery::new(E::select_by_primary_keys_in(&ks, Projection::All)?, primary_keys)
+            .with_table(ks, E::table_name());
+
+        self.rows(&query).await
+    }
+
+    /// Stream of all rows of the table, fetching the next
+    /// page of the given size when the previous one is consumed
+    pub fn find_all_paged<'a, E: CassandraTable + TryFromRow + Send + 'a>(&'a self, page_size: i32) -> RowStream<'a, E> {
+        let query = SelectQuery::for_table::<E>(E::select_all(Projection::All), QueryValues::SimpleValues(vec![]))
+            .with_page_size(page_size);
+
+        row_stream(query, move |query| async move { self.fetch_page_with_raw_columns(&query, E::raw_columns()).await })
+    }
+
+    /// Rows of the page of the query, and the paging state of the next page, if any
+    pub async fn fetch_page(&self, query: &SelectQuery) -> Result<(Vec<Row>, Option<Vec<u8>>), Error> {
+        self.fetch_page_with_raw_columns(query, &[]).await
+    }
+
+    /// Same as `fetch_page`, reading the `raw_columns` as a `BLOB`
+    async fn fetch_page_with_raw_columns(&self, query: &SelectQuery, raw_columns: &[&str]) -> Result<(Vec<Row>, Option<Vec<u8>>), Error> {
+        check_query_hook(query)?;
+
+        let mut params = QueryParamsBuilder::new().values(driver_values(query.values()));
+
+        if let Some(consistency) = query.consistency() {
+            params = params.consistency(driver_consistency(consistency));
+        }
+
+        if let Some(page_size) = query.page_size() {
+            params = params.page_size(page_size);
+        }
+
+        if let Some(paging_state) = query.paging_state() {
+            params = params.paging_state(CBytes::new(paging_state.to_vec()));
+        }
+
+        let body = self.session.query_with_params(query.query(), params.finalize()).await?.get_body()?;
+
+        let paging_state = body.as_rows_metadata()
+            .and_then(|metadata| metadata.paging_state)
+            .and_then(|paging_state| paging_state.into_plain());
+
+        Ok((async_rows_with_raw_columns(body, raw_columns).unwrap_or_default(), paging_state))
+    }
+
+    async fn rows<E: CassandraTable + TryFromRow>(&self, query: &SelectQuery) -> Result<Vec<E>, Error> {
+        check_query_hook(query)?;
+
+        let frame = self.session.query_with_values(query.query(), driver_values(query.values())).await?;
+
+        async_rows_with_raw_columns(frame.get_body()?, E::raw_columns())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|row| E::try_from_row(row).map_err(Error::from))
+            .collect()
+    }
+}
+
+/// Rows of a query fetched page by page, see `AsyncCassandraRepository::find_all_paged`
+///
+/// A page is only fetched when the rows of the previous one are
+/// consumed, dropping the stream does not fetch the remaining ones.
+/// After an error fetching a page, the stream ends.
+pub struct RowStream<'a, E> {
+    rows: Pin<Box<dyn Stream<Item=Result<E, Error>> + Send + 'a>>,
+}
+
+impl<E> Stream for RowStream<'_, E> {
+    type Item = Result<E, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rows.as_mut().poll_next(cx)
+    }
+}
+
+/// Rows of a page, and the paging state of the next page, if any
+type Page = (Vec<Row>, Option<Vec<u8>>);
+
+/// Rows of the pages of the query, until the page
+/// without a paging state or the first error
+fn row_stream<'a, E, F, P>(query: SelectQuery, fetch_page: F) -> RowStream<'a, E>
+    where E: TryFromRow + Send + 'a,
+          F: FnMut(SelectQuery) -> P + Send + 'a,
+          P: Future<Output=Result<Page, Error>> + Send + 'a {
+    let pages = stream::unfold((Some(query), fetch_page), |(query, mut fetch_page)| async move {
+        let query = query?;
+
+        match fetch_page(query.clone()).await {
+            Ok((rows, Some(paging_state))) => Some((Ok(rows), (Some(query.with_paging_state(paging_state)), fetch_page))),
+            Ok((rows, None)) => Some((Ok(rows), (None, fetch_page))),
+            Err(e) => Some((Err(e), (None, fetch_page))),
+        }
+    });
+
+    let rows = pages.flat_map(|page| {
+        let rows: Vec<Result<E, Error>> = match page {
+            Ok(rows) => rows.into_iter().map(|row| E::try_from_row(row).map_err(Error::from)).collect(),
+            Err(e) => vec![Err(e)],
+        };
+
+        stream::iter(rows)
+    });
+
+    RowStream { rows: Box::pin(rows.fuse()) }
+}
+
+fn driver_value(value: &Value) -> cdrs_tokio::types::value::Value {
+    let value_type = match value.value_type {
+        ValueType::Normal(len) => cdrs_tokio::types::value::ValueType::Normal(len),
+        ValueType::Null => cdrs_tokio::types::value::ValueType::Null,
+        ValueType::NotSet => cdrs_tokio::types::value::ValueType::NotSet,
+    };
+
+    cdrs_tokio::types::value::Value { body: value.body.clone(), value_type }
+}
+
+fn driver_values(values: &QueryValues) -> cdrs_tokio::query::QueryValues {
+    match values {
+        QueryValues::SimpleValues(values) => {
+            cdrs_tokio::query::QueryValues::SimpleValues(values.iter().map(driver_value).collect())
+        }
+        QueryValues::NamedValues(values) => {
+            cdrs_tokio::query::QueryValues::NamedValues(values.iter().map(|(k, v)| (k.clone(), driver_value(v))).collect())
+        }
+    }
+}
+
+fn driver_consistency(consistency: Consistency) -> cdrs_tokio::consistency::Consistency {
+    match consistency {
+        Consistency::Any => cdrs_tokio::consistency::Consistency::Any,
+        Consistency::One => cdrs_tokio::consistency::Consistency::One,
+        Consistency::Two => cdrs_tokio::consistency::Consistency::Two,
+        Consistency::Three => cdrs_tokio::consistency::Consistency::Three,
+        Consistency::Quorum => cdrs_tokio::consistency::Consistency::Quorum,
+        Consistency::All => cdrs_tokio::consistency::Consistency::All,
+        Consistency::LocalQuorum => cdrs_tokio::consistency::Consistency::LocalQuorum,
+        Consistency::EachQuorum => cdrs_tokio::consistency::Consistency::EachQuorum,
+        Consistency::Serial => cdrs_tokio::consistency::Consistency::Serial,
+        Consistency::LocalSerial => cdrs_tokio::consistency::Consistency::LocalSerial,
+        Consistency::LocalOne => cdrs_tokio::consistency::Consistency::LocalOne,
+        Consistency::Unknown => cdrs_tokio::consistency::Consistency::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use cdrs::query::QueryValues;
+    use cdrs::types::value::Value;
+    use cdrs_tokio::frame::TryFromRow;
+    use cdrs_tokio::frame::frame_result::{BodyResResultRows, ColSpec, ColType, ColTypeOption, RowsMetadata};
+    use cdrs_tokio::types::{ByName, CBytes, CString};
+    use cdrs_tokio::types::rows::Row;
+    use futures::executor::block_on;
+    use futures::StreamExt;
+
+    use crate::async_repository::{driver_values, row_stream, Page, RowStream};
+    use crate::{Error, SelectQuery};
+
+    #[derive(Debug, PartialEq)]
+    struct Name(String);
+
+    impl TryFromRow for Name {
+        fn try_from_row(row: Row) -> Result<Self, cdrs_tokio::Error> {
+            Ok(Name(row.r_by_name::<String>("name")?))
+        }
+    }
+
+    fn page(names: &[&str]) -> Vec<Row> {
+        let col_specs = vec![ColSpec {
+            ksname: None,
+            tablename: None,
+            name: CString::new(String::from("name")),
+            col_type: ColTypeOption { id: ColType::Varchar, value: None },
+        }];
+
+        Row::from_frame_body(BodyResResultRows {
+            metadata: RowsMetadata {
+                flags: 0,
+                columns_count: 1,
+                paging_state: None,
+                global_table_space: None,
+                col_specs,
+            },
+            rows_count: names.len() as i32,
+            rows_content: names.iter().map(|n| vec![CBytes::new(n.as_bytes().to_vec())]).collect(),
+        })
+    }
+
+    /// Page size and paging state of each fetched page
+    type Fetches = Arc<Mutex<Vec<(Option<i32>, Option<Vec<u8>>)>>>;
+
+    /// Stream of the pages, recording each fetch
+    fn paged(pages: Vec<Result<Page, Error>>, fetches: Fetches) -> RowStream<'static, Name> {
+        let pages = Mutex::new(pages.into_iter());
+        let query = SelectQuery::new(String::from("SELECT name FROM test.user"), QueryValues::SimpleValues(vec![])).with_page_size(2);
+
+        row_stream(query, move |query: SelectQuery| {
+            fetches.lock().unwrap().push((query.page_size(), query.paging_state().map(<[u8]>::to_vec)));
+
+            let page = pages.lock().unwrap().next().expect("No page after the last one");
+
+            async move { page }
+        })
+    }
+
+    #[test]
+    fn test_stream_of_pages() {
+        let fetches = Arc::new(Mutex::new(Vec::new()));
+        let stream = paged(vec![Ok((page(&["a", "b"]), Some(vec![7]))), Ok((page(&["c"]), None))], fetches.clone());
+
+        let names: Vec<Name> = block_on(stream.map(Result::unwrap).collect());
+
+        assert_eq!(names, vec![Name(String::from("a")), Name(String::from("b")), Name(String::from("c"))]);
+        assert_eq!(*fetches.lock().unwrap(), vec![(Some(2), None), (Some(2), Some(vec![7]))]);
+    }
+
+    #[test]
+    fn test_stream_ends_after_error() {
+        let fetches = Arc::new(Mutex::new(Vec::new()));
+        let mut stream = paged(vec![
+            Ok((page(&["a"]), Some(vec![7]))),
+            Err(Error::from(cdrs_tokio::Error::General(String::from("timeout")))),
+        ], fetches.clone());
+
+        block_on(async {
+            assert_eq!(stream.next().await.unwrap().unwrap(), Name(String::from("a")));
+            assert!(stream.next().await.unwrap().is_err());
+            assert!(stream.next().await.is_none());
+            assert!(stream.next().await.is_none());
+        });
+
+        assert_eq!(fetches.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_stream_dropped_early() {
+        let fetches = Arc::new(Mutex::new(Vec::new()));
+        let mut stream = paged(vec![Ok((page(&["a", "b"]), Some(vec![7]))), Ok((page(&["c"]), None))], fetches.clone());
+
+        assert_eq!(block_on(stream.next()).unwrap().unwrap(), Name(String::from("a")));
+
+        drop(stream);
+
+        assert_eq!(*fetches.lock().unwrap(), vec![(Some(2), None)]);
+    }
+
+    #[test]
+    fn test_driver_values() {
+        let values = QueryValues::SimpleValues(vec![Value::from("rust"), Value::from(7i32), Value::new_null(), Value::new_not_set()]);
+
+        match driver_values(&values) {
+            cdrs_tokio::query::QueryValues::SimpleValues(values) => {
+                assert_eq!(values[0].body, b"rust".to_vec());
+                assert_eq!(values[1].body, 7i32.to_be_bytes().to_vec());
+                assert_eq!(values[2].value_type, cdrs_tokio::types::value::ValueType::Null);
+                assert_eq!(values[3].value_type, cdrs_tokio::types::value::ValueType::NotSet);
+            }
+            _ => panic!("Values without names must stay without names"),
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+
+use cdrs::query::QueryValues;
+use cdrs::types::value::Value;
+
+use crate::{check_query_hook, CqlQuery, DeleteQuery, Error, QueryKind, StoreQuery, UpdateQuery};
+
+/// Kind of batch, which is selected when the builder is created
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BatchKind {
+    /// Atomic batch, `BEGIN BATCH`
+    #[default]
+    Logged,
+    /// Batch without the batch log, `BEGIN UNLOGGED BATCH`
+    Unlogged,
+    /// Batch of counter mutations only, `BEGIN COUNTER BATCH`
+    Counter,
+}
+
+impl Display for BatchKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchKind::Logged => write!(f, "BEGIN BATCH"),
+            BatchKind::Unlogged => write!(f, "BEGIN UNLOGGED BATCH"),
+            BatchKind::Counter => write!(f, "BEGIN COUNTER BATCH"),
+        }
+    }
+}
+
+/// Builder of a `BatchQuery`, the statements are
+/// executed in the order they were added
+#[derive(Debug, Default)]
+pub struct BatchBuilder {
+    kind: BatchKind,
+    statements: Vec<BatchStatement>,
+    values: Vec<Value>,
+    timestamp: Option<i64>,
+    error: Option<String>,
+}
+
+impl BatchBuilder {
+    /// New logged batch without statements
+    pub fn new() -> Self {
+        BatchBuilder::default()
+    }
+
+    /// New batch of the given kind without statements
+    pub fn with_kind(kind: BatchKind) -> Self {
+        BatchBuilder { kind, ..BatchBuilder::default() }
+    }
+
+    /// Write all statements with the same client timestamp,
+    /// `BEGIN BATCH USING TIMESTAMP ? ...`
+    ///
+    /// The timestamp is bound first, before the values of the statements,
+    /// which then can not have their own `USING TIMESTAMP`.
+    pub fn with_timestamp(mut self, timestamp_micros: i64) -> Self {
+        self.timestamp = Some(timestamp_micros);
+
+        if self.error.is_none() {
+            self.error = self.statements.iter()
+                .find(|statement| uses_timestamp(&statement.query))
+                .map(|statement| timestamp_error(&statement.query));
+        }
+
+        self
+    }
+
+    /// Add an insert to the batch
+    pub fn add_store(self, query: StoreQuery) -> Self {
+        self.add(query, false, StoreQuery::into_parts)
+    }
+
+    /// Add an update to the batch
+    pub fn add_update(self, query: UpdateQuery) -> Self {
+        let counter = query.is_counter();
+
+        self.add(query, counter, UpdateQuery::into_parts)
+    }
+
+    /// Add a delete to the batch
+    pub fn add_delete(self, query: DeleteQuery) -> Self {
+        self.add(query, false, DeleteQuery::into_parts)
+    }
+
+    /// Create the `BatchQuery`, which must have at least one statement,
+    /// only counter mutations if it is a counter batch, and no statement
+    /// with its own timestamp if the batch has one, failing if the query
+    /// hook rejects it
+    pub fn build(self) -> Result<BatchQuery, Error> {
+        if let Some(error) = self.error {
+            return Err(Error::InvalidBatch(error));
+        }
+
+        if self.statements.is_empty() {
+            return Err(Error::InvalidBatch(String::from("Batch must have at least one statement")));
+        }
+
+        let batch = BatchQuery::new(self.kind, self.timestamp, self.statements, self.values);
+
+        check_query_hook(&batch)?;
+
+        Ok(batch)
+    }
+
+    /// Add the statement, moving its values into the batch
+    fn add<Q: CqlQuery>(mut self, query: Q, counter: bool, into_parts: fn(Q) -> (Cow<'static, str>, QueryValues)) -> Self {
+        let keyspace = query.keyspace().to_string();
+        let table = query.table().to_string();
+        let (query, values) = into_parts(query);
+
+        if self.error.is_none() && counter != (self.kind == BatchKind::Counter) {
+            self.error = Some(if counter {
+                format!("Counter mutation `{}` only can be added to a counter batch", query)
+            } else {
+                format!("Counter batch only can have counter mutations, found `{}`", query)
+            });
+        }
+
+        if self.error.is_none() && self.timestamp.is_some() && uses_timestamp(&query) {
+            self.error = Some(timestamp_error(&query));
+        }
+
+        let values = match values {
+            QueryValues::SimpleValues(values) => values,
+            QueryValues::NamedValues(_) => {
+                if self.error.is_none() {
+                    self.error = Some(format!("Batch statements only can bind values without names, found `{}`", query));
+                }
+
+                return self;
+            }
+        };
+
+        self.statements.push(BatchStatement { query: query.into_owned(), values: values.len(), keyspace, table });
+        self.values.extend(values);
+        self
+    }
+}
+
+/// Whether the statement has its own timestamp, `USING TIMESTAMP ?`
+/// or `USING TTL ? AND TIMESTAMP ?`
+fn uses_timestamp(query: &str) -> bool {
+    let words: Vec<String> = query.split_whitespace().map(str::to_uppercase).collect();
+
+    words.iter().enumerate().filter(|(_, word)| *word == "USING").any(|(using, _)| {
+        let mut clauses = words[using + 1..].chunks(3);
+
+        loop {
+            match clauses.next() {
+                Some([clause, ..]) if clause == "TIMESTAMP" => return true,
+                Some([clause, _, and]) if clause == "TTL" && and == "AND" => continue,
+                _ => return false,
+            }
+        }
+    })
+}
+
+fn timestamp_error(query: &str) -> String {
+    format!("Batch with a timestamp can not have statements with their own timestamp, found `{}`", query)
+}
+
+/// Statement added to a batch
+#[derive(Debug, Clone, PartialEq)]
+struct BatchStatement {
+    query: String,
+    /// Number of its values
+    values: usize,
+    keyspace: String,
+    table: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchQuery {
+    query: String,
+    values: QueryValues,
+    keyspace: String,
+    table: String,
+    kind: BatchKind,
+    timestamp: Option<i64>,
+    statements: Vec<BatchStatement>,
+}
+
+impl BatchQuery {
+    /// Batch of the statements and their values, without the timestamp
+    fn new(kind: BatchKind, timestamp: Option<i64>, statements: Vec<BatchStatement>, values: Vec<Value>) -> Self {
+        let (using, values) = match timestamp {
+            Some(timestamp) => (" USING TIMESTAMP ?", [vec![Value::from(timestamp)], values].concat()),
+            None => ("", values),
+        };
+
+        let queries: Vec<&str> = statements.iter().map(|s| s.query.as_str()).collect();
+
+        let query = format!("{}{} {}; APPLY BATCH", kind, using, queries.join("; "));
+
+        let (keyspace, table) = match statements.split_first() {
+            Some((first, others)) if others.iter().all(|s| s.keyspace == first.keyspace && s.table == first.table) => {
+                (first.keyspace.clone(), first.table.clone())
+            }
+            _ => (String::new(), String::new()),
+        };
+
+        BatchQuery { query, values: QueryValues::SimpleValues(values), keyspace, table, kind, timestamp, statements }
+    }
+
+    /// Split the batch into batches of at most `max_statements`
+    /// statements and about `max_bytes` of values, to stay below the
+    /// `batch_size_fail_threshold` of Cassandra
+    ///
+    /// The size of a statement is the serialized length of its values,
+    /// a statement larger than `max_bytes` is a batch of its own. The
+    /// batches have the kind and the timestamp of this one, and their
+    /// statements are in the order of this batch.
+    pub fn chunked(&self, max_statements: usize, max_bytes: usize) -> Vec<BatchQuery> {
+        let values = match self.values {
+            QueryValues::SimpleValues(ref values) => &values[self.timestamp.map_or(0, |_| 1)..],
+            QueryValues::NamedValues(_) => return vec![self.clone()],
+        };
+
+        let mut chunks = Vec::new();
+        let mut statements = Vec::new();
+        let mut chunk_values: Vec<Value> = Vec::new();
+        let mut size = 0;
+        let mut offset = 0;
+
+        for statement in &self.statements {
+            let statement_values = &values[offset..offset + statement.values];
+            let statement_size: usize = statement_values.iter().map(|v| v.body.len()).sum();
+
+            offset += statement.values;
+
+            if !statements.is_empty() && (statements.len() >= max_statements || size + statement_size > max_bytes) {
+                chunks.push(BatchQuery::new(self.kind, self.timestamp, std::mem::take(&mut statements), std::mem::take(&mut chunk_values)));
+                size = 0;
+            }
+
+            statements.push(statement.clone());
+            chunk_values.extend(statement_values.iter().cloned());
+            size += statement_size;
+        }
+
+        if !statements.is_empty() {
+            chunks.push(BatchQuery::new(self.kind, self.timestamp, statements, chunk_values));
+        }
+
+        chunks
+    }
+
+    /// Statements of the batch, in the order they were added
+    pub fn statements(&self) -> Vec<&str> {
+        self.statements.iter().map(|s| s.query.as_str()).collect()
+    }
+
+    /// Prepared statement of the batch, `BEGIN BATCH ... APPLY BATCH`
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+    /// Values of all statements, in the order they were added
+    pub fn values(&self) -> &QueryValues {
+        &self.values
+    }
+    /// Keyspace of all statements, empty if they are of more than one table
+    pub fn keyspace(&self) -> &str {
+        &self.keyspace
+    }
+    /// Table of all statements, empty if they are of more than one table
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+    /// Operation of the statement, always `QueryKind::Batch`
+    pub fn kind(&self) -> QueryKind {
+        QueryKind::Batch
+    }
+}
+
+impl CqlQuery for BatchQuery {
+    fn query(&self) -> &str {
+        &self.query
+    }
+
+    fn values(&self) -> &QueryValues {
+        &self.values
+    }
+
+    fn keyspace(&self) -> &str {
+        &self.keyspace
+    }
+
+    fn table(&self) -> &str {
+        &self.table
+    }
+
+    fn kind(&self) -> QueryKind {
+        QueryKind::Batch
+    }
+}
+
+impl Display for BatchQuery {
+    /// Only display the prepared statement
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "query:{}", self.query)
+    }
+}
@@ -0,0 +1,56 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{validate_identifier, Error};
+
+/// Row of a count, e.g. `exists_query` or `Projection::Count`,
+/// read from its `count` column
+///
+/// A count with another alias, `Projection::CountAs`, needs its
+/// own mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CountRow {
+    pub count: i64,
+}
+
+/// Alias of the count column read by `CountRow`
+pub const COUNT_ALIAS: &str = "count";
+
+/// Alias of `Projection::CountAs`, a valid identifier, so that it
+/// is written as is in the selection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountAlias(String);
+
+impl CountAlias {
+    pub fn new(alias: &str) -> Result<Self, Error> {
+        validate_identifier(alias)?;
+
+        Ok(CountAlias(alias.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for CountAlias {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl cdrs::frame::TryFromRow for CountRow {
+    fn try_from_row(row: cdrs::types::rows::Row) -> Result<Self, cdrs::Error> {
+        use cdrs::types::ByName;
+
+        Ok(CountRow { count: row.r_by_name::<i64>(COUNT_ALIAS)? })
+    }
+}
+
+#[cfg(feature = "cdrs-tokio")]
+impl cdrs_tokio::frame::TryFromRow for CountRow {
+    fn try_from_row(row: cdrs_tokio::types::rows::Row) -> Result<Self, cdrs_tokio::Error> {
+        use cdrs_tokio::types::ByName;
+
+        Ok(CountRow { count: row.r_by_name::<i64>(COUNT_ALIAS)? })
+    }
+}
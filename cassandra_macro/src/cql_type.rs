@@ -0,0 +1,135 @@
+use std::fmt::{Display, Formatter};
+
+use crate::Error;
+
+const NATIVE_TYPES: [&str; 22] = [
+    "ASCII", "BIGINT", "BLOB", "BOOLEAN", "COUNTER", "DATE", "DECIMAL", "DOUBLE", "DURATION",
+    "FLOAT", "INET", "INT", "SMALLINT", "TEXT", "TIME", "TIMESTAMP", "TIMEUUID", "TINYINT",
+    "UUID", "VARCHAR", "VARINT", "EMPTY",
+];
+
+/// CQL type of a column, parsed from the declared `type`
+/// of the `column` attribute
+#[derive(Debug, Clone, PartialEq)]
+pub enum CqlType {
+    /// Native type, upper case, e.g. `TEXT`
+    Native(String),
+    /// `list<T>`
+    List(Box<CqlType>),
+    /// `set<T>`
+    Set(Box<CqlType>),
+    /// `map<K, V>`
+    Map(Box<CqlType>, Box<CqlType>),
+    /// `tuple<A, B, ...>`
+    Tuple(Vec<CqlType>),
+    /// `frozen<T>`
+    Frozen(Box<CqlType>),
+    /// Any other type, e.g. a user defined type
+    Custom(String),
+}
+
+impl CqlType {
+    /// Parse a CQL type, e.g. `map<text, frozen<list<int>>>`
+    pub fn parse(cql: &str) -> Result<CqlType, Error> {
+        let cql = cql.trim();
+
+        let (name, arguments) = match cql.find('<') {
+            Some(start) if cql.ends_with('>') => {
+                let arguments = split_arguments(&cql[start + 1..cql.len() - 1])?
+                    .into_iter()
+                    .map(CqlType::parse)
+                    .collect::<Result<Vec<CqlType>, Error>>()?;
+
+                (cql[..start].trim().to_uppercase(), arguments)
+            }
+            None if !cql.is_empty() && !cql.contains('>') => (cql.to_uppercase(), Vec::new()),
+            _ => return Err(Error::InvalidType(cql.to_string())),
+        };
+
+        let mut arguments = arguments.into_iter();
+
+        let cql_type = match (name.as_str(), arguments.len()) {
+            ("LIST", 1) => CqlType::List(Box::new(arguments.next().unwrap())),
+            ("SET", 1) => CqlType::Set(Box::new(arguments.next().unwrap())),
+            ("FROZEN", 1) => CqlType::Frozen(Box::new(arguments.next().unwrap())),
+            ("MAP", 2) => CqlType::Map(Box::new(arguments.next().unwrap()), Box::new(arguments.next().unwrap())),
+            ("TUPLE", n) if n > 0 => CqlType::Tuple(arguments.collect()),
+            (name, 0) if NATIVE_TYPES.contains(&name) => CqlType::Native(name.to_string()),
+            (_, 0) => CqlType::Custom(cql.to_string()),
+            _ => return Err(Error::InvalidType(cql.to_string())),
+        };
+
+        Ok(cql_type)
+    }
+
+    /// If the type is a non frozen `list`, `set` or `map`,
+    /// which can be partially updated
+    pub fn is_collection(&self) -> bool {
+        matches!(self, CqlType::List(_) | CqlType::Set(_) | CqlType::Map(_, _))
+    }
+}
+
+impl Display for CqlType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CqlType::Native(name) | CqlType::Custom(name) => write!(f, "{}", name),
+            CqlType::List(t) => write!(f, "list<{}>", t),
+            CqlType::Set(t) => write!(f, "set<{}>", t),
+            CqlType::Map(k, v) => write!(f, "map<{}, {}>", k, v),
+            CqlType::Tuple(types) => {
+                write!(f, "tuple<{}>", types.iter().map(|t| t.to_string()).collect::<Vec<String>>().join(", "))
+            }
+            CqlType::Frozen(t) => write!(f, "frozen<{}>", t),
+        }
+    }
+}
+
+/// Split the arguments of a parameterized type
+/// on the commas that are not nested
+fn split_arguments(arguments: &str) -> Result<Vec<&str>, Error> {
+    let mut depth = 0;
+    let mut start = 0;
+    let mut parts = Vec::new();
+
+    for (i, c) in arguments.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' if depth == 0 => return Err(Error::InvalidType(arguments.to_string())),
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&arguments[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    if depth != 0 {
+        return Err(Error::InvalidType(arguments.to_string()));
+    }
+
+    parts.push(&arguments[start..]);
+
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::CqlType;
+
+    #[test]
+    fn test_parse_cql_types() {
+        assert_eq!(CqlType::parse("text").unwrap(), CqlType::Native(String::from("TEXT")));
+
+        let frozen_list = CqlType::Frozen(Box::new(CqlType::List(Box::new(CqlType::Native(String::from("INT"))))));
+
+        assert_eq!(CqlType::parse("map<text, frozen<list<int>>>").unwrap(),
+                   CqlType::Map(Box::new(CqlType::Native(String::from("TEXT"))), Box::new(frozen_list)));
+
+        assert_eq!(CqlType::parse("SET<address>").unwrap(), CqlType::Set(Box::new(CqlType::Custom(String::from("address")))));
+
+        assert!(CqlType::parse("set<text").is_err());
+        assert!(CqlType::parse("map<text>").is_err());
+        assert!(!CqlType::parse("frozen<set<text>>").unwrap().is_collection());
+    }
+}
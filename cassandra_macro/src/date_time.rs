@@ -0,0 +1,105 @@
+use std::convert::TryFrom;
+
+use cdrs::types::value::Bytes;
+use chrono::{Duration, NaiveDate, NaiveTime, Timelike};
+
+const EPOCH_DAY: i64 = 1 << 31;
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+
+fn epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()
+}
+
+/// Value of a `DATE` column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CqlDate(pub NaiveDate);
+
+impl CqlDate {
+    /// Serialization in the CQL binary protocol, the days since the
+    /// epoch as an unsigned integer, with the epoch at `2^31`
+    pub fn to_cql_bytes(&self) -> Vec<u8> {
+        self.to_cql_value().to_be_bytes().to_vec()
+    }
+
+    /// Unsigned days of the date, with the epoch at `2^31`
+    pub fn to_cql_value(&self) -> u32 {
+        (self.0.signed_duration_since(epoch()).num_days() + EPOCH_DAY) as u32
+    }
+
+    /// Date of its unsigned days, `None` if chrono cannot represent it
+    ///
+    /// The rows of the driver read a `DATE` as an `i32` of these same
+    /// bits, which is converted with `as u32`.
+    pub fn from_cql_value(days: u32) -> Option<Self> {
+        epoch().checked_add_signed(Duration::days(days as i64 - EPOCH_DAY)).map(CqlDate)
+    }
+
+    /// Date of its serialization in the CQL binary protocol,
+    /// `None` if the bytes are not a date
+    pub fn from_cql_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::from_cql_value(u32::from_be_bytes(<[u8; 4]>::try_from(bytes).ok()?))
+    }
+
+    pub fn into_inner(self) -> NaiveDate {
+        self.0
+    }
+}
+
+impl From<NaiveDate> for CqlDate {
+    fn from(date: NaiveDate) -> Self {
+        CqlDate(date)
+    }
+}
+
+impl From<CqlDate> for Bytes {
+    fn from(date: CqlDate) -> Self {
+        Bytes::new(date.to_cql_bytes())
+    }
+}
+
+/// Value of a `TIME` column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CqlTime(pub NaiveTime);
+
+impl CqlTime {
+    /// Serialization in the CQL binary protocol, the nanoseconds
+    /// since midnight as a big integer
+    pub fn to_cql_bytes(&self) -> Vec<u8> {
+        self.to_cql_value().to_be_bytes().to_vec()
+    }
+
+    /// Nanoseconds since midnight of the time
+    pub fn to_cql_value(&self) -> i64 {
+        self.0.num_seconds_from_midnight() as i64 * NANOS_PER_SECOND + self.0.nanosecond() as i64
+    }
+
+    /// Time of its nanoseconds since midnight, `None` if
+    /// they are not between `0` and `86399999999999`
+    pub fn from_cql_value(nanos: i64) -> Option<Self> {
+        let seconds = u32::try_from(nanos.div_euclid(NANOS_PER_SECOND)).ok()?;
+
+        NaiveTime::from_num_seconds_from_midnight_opt(seconds, nanos.rem_euclid(NANOS_PER_SECOND) as u32).map(CqlTime)
+    }
+
+    /// Time of its serialization in the CQL binary protocol,
+    /// `None` if the bytes are not a time
+    pub fn from_cql_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::from_cql_value(i64::from_be_bytes(<[u8; 8]>::try_from(bytes).ok()?))
+    }
+
+    pub fn into_inner(self) -> NaiveTime {
+        self.0
+    }
+}
+
+impl From<NaiveTime> for CqlTime {
+    fn from(time: NaiveTime) -> Self {
+        CqlTime(time)
+    }
+}
+
+impl From<CqlTime> for Bytes {
+    fn from(time: CqlTime) -> Self {
+        Bytes::new(time.to_cql_bytes())
+    }
+}
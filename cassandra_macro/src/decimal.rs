@@ -0,0 +1,87 @@
+use std::convert::TryFrom;
+
+use bigdecimal::BigDecimal;
+use bigdecimal::num_bigint::BigInt;
+use cdrs::types::decimal::Decimal;
+use cdrs::types::value::Bytes;
+
+use crate::Error;
+
+/// Value of a `DECIMAL` column, of any precision and scale
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct CqlDecimal(pub BigDecimal);
+
+impl CqlDecimal {
+    /// Serialization in the CQL binary protocol, the scale followed
+    /// by the unscaled value as a varint
+    ///
+    /// A scale that does not fit in an `i32`, which Cassandra would
+    /// not accept, is an error.
+    pub fn to_cql_bytes(&self) -> Result<Vec<u8>, Error> {
+        let (unscaled, scale) = self.0.as_bigint_and_exponent();
+
+        let scale = i32::try_from(scale)
+            .map_err(|_| Error::InvalidValue(format!("Scale {} of the decimal does not fit in a `DECIMAL`", scale)))?;
+
+        let mut bytes = scale.to_be_bytes().to_vec();
+        bytes.extend(unscaled.to_signed_bytes_be());
+
+        Ok(bytes)
+    }
+
+    /// Decimal of its serialization in the CQL binary protocol,
+    /// `None` if the bytes are not a decimal
+    pub fn from_cql_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 5 {
+            return None;
+        }
+
+        let (scale, unscaled) = bytes.split_at(4);
+        let scale = i32::from_be_bytes([scale[0], scale[1], scale[2], scale[3]]);
+
+        Some(CqlDecimal(BigDecimal::new(BigInt::from_signed_bytes_be(unscaled), scale as i64)))
+    }
+
+    pub fn into_inner(self) -> BigDecimal {
+        self.0
+    }
+}
+
+impl From<BigDecimal> for CqlDecimal {
+    fn from(decimal: BigDecimal) -> Self {
+        CqlDecimal(decimal)
+    }
+}
+
+impl From<CqlDecimal> for BigDecimal {
+    fn from(decimal: CqlDecimal) -> Self {
+        decimal.0
+    }
+}
+
+/// Decimal read by the driver, whose unscaled value is an `i64`
+///
+/// The driver reads the `i32` scale as an `u32`, a negative
+/// scale is cast back.
+impl From<Decimal> for CqlDecimal {
+    fn from(decimal: Decimal) -> Self {
+        CqlDecimal(BigDecimal::new(BigInt::from(decimal.unscaled), decimal.scale as i32 as i64))
+    }
+}
+
+/// Same as the decimal of cdrs, read by the `cdrs-tokio` driver
+#[cfg(feature = "cdrs-tokio")]
+impl From<cdrs_tokio::types::decimal::Decimal> for CqlDecimal {
+    fn from(decimal: cdrs_tokio::types::decimal::Decimal) -> Self {
+        CqlDecimal(BigDecimal::new(BigInt::from(decimal.unscaled), decimal.scale as i32 as i64))
+    }
+}
+
+/// Bytes of `to_cql_bytes`
+impl TryFrom<CqlDecimal> for Bytes {
+    type Error = Error;
+
+    fn try_from(decimal: CqlDecimal) -> Result<Self, Self::Error> {
+        decimal.to_cql_bytes().map(Bytes::new)
+    }
+}
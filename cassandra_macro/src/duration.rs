@@ -0,0 +1,133 @@
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use cdrs::types::value::Bytes;
+
+use crate::Error;
+
+const NANOS_PER_DAY: u64 = 86_400_000_000_000;
+
+/// Value of a `DURATION` column
+///
+/// Months and days are separate from the nanoseconds, because
+/// they do not have a fixed length. The three must have the
+/// same sign.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CqlDuration {
+    pub months: i32,
+    pub days: i32,
+    pub nanos: i64,
+}
+
+impl CqlDuration {
+    pub fn new(months: i32, days: i32, nanos: i64) -> Self {
+        CqlDuration { months, days, nanos }
+    }
+
+    /// Serialization in the CQL binary protocol, the months, days
+    /// and nanoseconds as zig-zag encoded variable length integers
+    pub fn to_cql_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(3);
+
+        for n in [self.months as i64, self.days as i64, self.nanos].iter() {
+            write_vint(&mut bytes, *n);
+        }
+
+        bytes
+    }
+
+    /// Duration of its serialization in the CQL binary protocol,
+    /// `None` if the bytes are not a duration
+    pub fn from_cql_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut bytes = bytes;
+
+        let months = i32::try_from(read_vint(&mut bytes)?).ok()?;
+        let days = i32::try_from(read_vint(&mut bytes)?).ok()?;
+        let nanos = read_vint(&mut bytes)?;
+
+        if bytes.is_empty() {
+            Some(CqlDuration { months, days, nanos })
+        } else {
+            None
+        }
+    }
+}
+
+/// Whole days and the remaining nanoseconds, an error
+/// when the days do not fit in an `i32`
+impl TryFrom<Duration> for CqlDuration {
+    type Error = Error;
+
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        let nanos = duration.as_nanos();
+        let days = nanos / NANOS_PER_DAY as u128;
+
+        let days = i32::try_from(days)
+            .map_err(|_| Error::InvalidValue(format!("Duration of {} days does not fit in a `DURATION`", days)))?;
+
+        Ok(CqlDuration { months: 0, days, nanos: (nanos % NANOS_PER_DAY as u128) as i64 })
+    }
+}
+
+/// Days and nanoseconds, an error when there are months,
+/// which do not have a fixed length, or when it is negative
+impl TryFrom<CqlDuration> for Duration {
+    type Error = Error;
+
+    fn try_from(duration: CqlDuration) -> Result<Self, Self::Error> {
+        if duration.months != 0 || duration.days < 0 || duration.nanos < 0 {
+            return Err(Error::InvalidValue(
+                format!("Duration of {} months, {} days and {} nanoseconds is not a `std::time::Duration`",
+                        duration.months, duration.days, duration.nanos)));
+        }
+
+        Ok(Duration::from_secs(duration.days as u64 * 86_400) + Duration::from_nanos(duration.nanos as u64))
+    }
+}
+
+impl From<CqlDuration> for Bytes {
+    fn from(duration: CqlDuration) -> Self {
+        Bytes::new(duration.to_cql_bytes())
+    }
+}
+
+fn write_vint(bytes: &mut Vec<u8>, n: i64) {
+    let zig_zag = ((n << 1) ^ (n >> 63)) as u64;
+
+    let bits = 64 - (zig_zag | 1).leading_zeros() as usize;
+    let size = bits.div_ceil(7).min(9);
+
+    if size == 1 {
+        bytes.push(zig_zag as u8);
+    } else if size == 9 {
+        bytes.push(0xFF);
+        bytes.extend_from_slice(&zig_zag.to_be_bytes());
+    } else {
+        let value = zig_zag.to_be_bytes();
+        let mut value = value[8 - size..].to_vec();
+
+        value[0] |= !(0xFFu8 >> (size - 1));
+
+        bytes.extend_from_slice(&value);
+    }
+}
+
+fn read_vint(bytes: &mut &[u8]) -> Option<i64> {
+    let (first, rest) = bytes.split_first()?;
+
+    let extra = first.leading_ones() as usize;
+
+    if rest.len() < extra {
+        return None;
+    }
+
+    let mut zig_zag = if extra == 8 { 0 } else { (*first & (0xFF >> extra)) as u64 };
+
+    for byte in &rest[..extra] {
+        zig_zag = (zig_zag << 8) | *byte as u64;
+    }
+
+    *bytes = &rest[extra..];
+
+    Some((zig_zag >> 1) as i64 ^ -((zig_zag & 1) as i64))
+}
@@ -0,0 +1,139 @@
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+
+use crate::{CassandraTable, Error};
+
+/// Object safe metadata of a table, for handling different
+/// tables together, e.g. `Vec<Box<dyn DynCassandraTable>>`
+/// of all tables to create them
+///
+/// Implemented for the `TableHandle` of any `CassandraTable`.
+pub trait DynCassandraTable {
+    /// key space
+    fn key_space(&self) -> &'static str;
+
+    /// Table name
+    fn table_name(&self) -> &'static str;
+
+    /// CQL for table creation
+    fn create_table_cql(&self) -> &'static str;
+
+    /// CQL for drop table
+    fn drop_table_cql(&self) -> &'static str;
+
+    /// CQL for creation of the secondary indexes
+    fn create_indexes_cql(&self) -> Vec<&'static str>;
+
+    /// CQL for drop of the secondary indexes
+    fn drop_indexes_cql(&self) -> Vec<&'static str>;
+
+    /// Columns of the table
+    fn columns(&self) -> &'static [&'static str];
+
+    /// Primary key columns, sorted by their position
+    fn primary_key_columns(&self) -> &'static [&'static str];
+
+    /// Cluster key columns, sorted by their position
+    fn cluster_key_columns(&self) -> &'static [&'static str];
+
+    /// Columns marked `static`
+    fn static_columns(&self) -> &'static [&'static str];
+
+    /// Declared CQL type of the column
+    fn column_type(&self, column: &str) -> Option<&'static str>;
+
+    /// Same as `create_table_cql`, in the keyspace `ks`
+    fn create_table_cql_in(&self, ks: &str) -> Result<String, Error>;
+
+    /// Same as `drop_table_cql`, in the keyspace `ks`
+    fn drop_table_cql_in(&self, ks: &str) -> Result<String, Error>;
+}
+
+/// Zero sized value standing for the table of `T`
+pub struct TableHandle<T>(PhantomData<fn() -> T>);
+
+impl<T: CassandraTable> TableHandle<T> {
+    pub fn new() -> Self {
+        TableHandle(PhantomData)
+    }
+
+    /// The handle as a trait object
+    pub fn boxed() -> Box<dyn DynCassandraTable>
+        where T: 'static {
+        Box::new(Self::new())
+    }
+}
+
+impl<T: CassandraTable> Default for TableHandle<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: CassandraTable> Clone for TableHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: CassandraTable> Copy for TableHandle<T> {}
+
+impl<T: CassandraTable> Debug for TableHandle<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TableHandle({}.{})", T::key_space(), T::table_name())
+    }
+}
+
+impl<T: CassandraTable> DynCassandraTable for TableHandle<T> {
+    fn key_space(&self) -> &'static str {
+        T::key_space()
+    }
+
+    fn table_name(&self) -> &'static str {
+        T::table_name()
+    }
+
+    fn create_table_cql(&self) -> &'static str {
+        T::create_table_cql()
+    }
+
+    fn drop_table_cql(&self) -> &'static str {
+        T::drop_table_cql()
+    }
+
+    fn create_indexes_cql(&self) -> Vec<&'static str> {
+        T::create_indexes_cql()
+    }
+
+    fn drop_indexes_cql(&self) -> Vec<&'static str> {
+        T::drop_indexes_cql()
+    }
+
+    fn columns(&self) -> &'static [&'static str] {
+        T::columns()
+    }
+
+    fn primary_key_columns(&self) -> &'static [&'static str] {
+        T::primary_key_columns()
+    }
+
+    fn cluster_key_columns(&self) -> &'static [&'static str] {
+        T::cluster_key_columns()
+    }
+
+    fn static_columns(&self) -> &'static [&'static str] {
+        T::static_columns()
+    }
+
+    fn column_type(&self, column: &str) -> Option<&'static str> {
+        T::column_type(column)
+    }
+
+    fn create_table_cql_in(&self, ks: &str) -> Result<String, Error> {
+        T::create_table_cql_in(ks)
+    }
+
+    fn drop_table_cql_in(&self, ks: &str) -> Result<String, Error> {
+        T::drop_table_cql_in(ks)
+    }
+}
@@ -0,0 +1,297 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{PolicyError, TableDefinitionError, TableWithNoUpdatableColumnsError};
+
+/// Error of the statements that are validated
+/// against the metadata of the table
+///
+/// More variants may be added, matches must have a wildcard arm.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Table only has key columns
+    NoUpdatableColumns(TableWithNoUpdatableColumnsError),
+    /// Columns that do not exist in the table, with the closest known columns
+    UnknownColumn(UnknownColumnError),
+    /// Key column given where only non key columns are allowed
+    KeyColumn(String),
+    /// Value of a key column is missing
+    MissingKeyValue(String),
+    /// Name that is not a valid CQL identifier
+    InvalidIdentifier(String),
+    /// Column that cannot be used in the statement, with the reason
+    InvalidColumn(String),
+    /// CQL type that cannot be parsed
+    InvalidType(String),
+    /// Value that does not fit in its column type, with the reason
+    InvalidValue(String),
+    /// Values with names, where values are bound by position
+    NamedValues,
+    /// Time to live above `MAX_TTL`
+    InvalidTtl(u32),
+    /// Batch that Cassandra would not accept, with the reason
+    InvalidBatch(String),
+    /// Tracked entity of the table does not have changes
+    NoChanges(String),
+    /// Table of a `CqlTableBuilder` that Cassandra would not accept
+    InvalidTable(TableDefinitionError),
+    /// Query rejected by the query hook
+    Policy(PolicyError),
+    /// Error of the driver while executing a statement or reading its rows
+    Driver(cdrs::Error),
+    /// Error of the `cdrs-tokio` driver while executing a statement or reading its rows
+    #[cfg(feature = "cdrs-tokio")]
+    AsyncDriver(cdrs_tokio::Error),
+    /// Values that the `scylla` driver cannot send
+    #[cfg(feature = "scylla")]
+    ScyllaValues(scylla::frame::value::SerializeValuesError),
+    /// Field of a `json` column that cannot be serialized
+    #[cfg(feature = "json")]
+    Json(serde_json::Error),
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::NoUpdatableColumns(e) => Some(e),
+            Error::UnknownColumn(e) => Some(e),
+            Error::InvalidTable(e) => Some(e),
+            Error::Policy(e) => Some(e),
+            Error::Driver(e) => Some(e),
+            #[cfg(feature = "cdrs-tokio")]
+            Error::AsyncDriver(e) => Some(e),
+            #[cfg(feature = "scylla")]
+            Error::ScyllaValues(e) => Some(e),
+            #[cfg(feature = "json")]
+            Error::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::NoUpdatableColumns(e) => write!(f, "{}", e),
+            Error::UnknownColumn(e) => write!(f, "{}", e),
+            Error::KeyColumn(column) => write!(f, "Column `{}` is part of the key", column),
+            Error::MissingKeyValue(column) => write!(f, "Missing value for key column `{}`", column),
+            Error::InvalidIdentifier(identifier) => write!(f, "Invalid identifier `{}`", identifier),
+            Error::InvalidColumn(message) | Error::InvalidValue(message) | Error::InvalidBatch(message) => write!(f, "{}", message),
+            Error::InvalidType(cql) => write!(f, "Invalid CQL type `{}`", cql),
+            Error::NamedValues => write!(f, "Values with names cannot be combined with values bound by position"),
+            Error::InvalidTtl(ttl) => write!(f, "TTL of {} seconds is above the maximum of {} seconds", ttl, crate::MAX_TTL),
+            Error::NoChanges(table) => write!(f, "Entity of table {} does not have changes", table),
+            Error::InvalidTable(e) => write!(f, "{}", e),
+            Error::Policy(e) => write!(f, "Query rejected by the query hook: {}", e),
+            Error::Driver(e) => write!(f, "{}", e),
+            #[cfg(feature = "cdrs-tokio")]
+            Error::AsyncDriver(e) => write!(f, "{}", e),
+            #[cfg(feature = "scylla")]
+            Error::ScyllaValues(e) => write!(f, "{}", e),
+            #[cfg(feature = "json")]
+            Error::Json(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<TableWithNoUpdatableColumnsError> for Error {
+    fn from(e: TableWithNoUpdatableColumnsError) -> Self {
+        Error::NoUpdatableColumns(e)
+    }
+}
+
+impl From<UnknownColumnError> for Error {
+    fn from(e: UnknownColumnError) -> Self {
+        Error::UnknownColumn(e)
+    }
+}
+
+impl From<TableDefinitionError> for Error {
+    fn from(e: TableDefinitionError) -> Self {
+        Error::InvalidTable(e)
+    }
+}
+
+impl From<PolicyError> for Error {
+    fn from(e: PolicyError) -> Self {
+        Error::Policy(e)
+    }
+}
+
+impl From<cdrs::Error> for Error {
+    fn from(e: cdrs::Error) -> Self {
+        Error::Driver(e)
+    }
+}
+
+#[cfg(feature = "cdrs-tokio")]
+impl From<cdrs_tokio::Error> for Error {
+    fn from(e: cdrs_tokio::Error) -> Self {
+        Error::AsyncDriver(e)
+    }
+}
+
+#[cfg(feature = "scylla")]
+impl From<scylla::frame::value::SerializeValuesError> for Error {
+    fn from(e: scylla::frame::value::SerializeValuesError) -> Self {
+        Error::ScyllaValues(e)
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+/// Columns referenced by a statement that are not columns
+/// of the table, with the closest column of the table
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownColumnError {
+    table: Option<String>,
+    columns: Vec<(String, Option<String>)>,
+    non_key: bool,
+}
+
+impl UnknownColumnError {
+    /// Error of the unknown columns, suggesting the known column
+    /// that is at most a few edits away from each of them
+    pub fn new(unknown: &[&str], known: &[&str]) -> Self {
+        let columns = unknown.iter()
+            .map(|c| (c.to_string(), closest_column(c, known).map(String::from)))
+            .collect();
+
+        UnknownColumnError { table: None, columns, non_key: false }
+    }
+
+    /// Same as `new`, where only the non key columns are `known`
+    ///
+    /// Without unknown columns, the error is that no column was given.
+    pub fn non_key(unknown: &[&str], known: &[&str]) -> Self {
+        UnknownColumnError { non_key: true, ..UnknownColumnError::new(unknown, known) }
+    }
+
+    /// Same error, naming the table of the columns
+    pub fn with_table(mut self, table: &str) -> Self {
+        self.table = Some(table.to_string());
+        self
+    }
+
+    /// Table of the columns, when it is known
+    pub fn table(&self) -> Option<&str> {
+        self.table.as_deref()
+    }
+
+    /// The unknown columns, in the order they were referenced
+    pub fn columns(&self) -> Vec<&str> {
+        self.columns.iter().map(|(c, _)| c.as_str()).collect()
+    }
+
+    /// Known column that is close to the unknown column, if any
+    pub fn suggestion(&self, column: &str) -> Option<&str> {
+        self.columns.iter()
+            .find(|(c, _)| c == column)
+            .and_then(|(_, suggestion)| suggestion.as_deref())
+    }
+}
+
+impl std::error::Error for UnknownColumnError {}
+
+impl Display for UnknownColumnError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let columns = self.columns.iter()
+            .map(|(c, suggestion)| match suggestion {
+                Some(s) => format!("`{}` (did you mean `{}`?)", c, s),
+                None => format!("`{}`", c),
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        let kind = match (self.non_key, self.columns.len() == 1) {
+            (true, true) => "non key column",
+            (true, false) => "non key columns",
+            (false, true) => "column",
+            (false, false) => "columns",
+        };
+
+        let table = self.table.as_ref().map(|t| format!(" of table {}", t)).unwrap_or_default();
+
+        if self.columns.is_empty() {
+            write!(f, "At least one of the {}{} is required", kind, table)
+        } else {
+            write!(f, "Unknown {} {}{}", kind, columns, table)
+        }
+    }
+}
+
+/// Known column with the fewest edits from `column`,
+/// when there are at most a third of its length
+fn closest_column<'a>(column: &str, known: &[&'a str]) -> Option<&'a str> {
+    let max_edits = std::cmp::max(1, column.chars().count() / 3);
+
+    known.iter()
+        .map(|k| (edit_distance(column, k), *k))
+        .filter(|(edits, _)| *edits <= max_edits)
+        .min_by_key(|(edits, _)| *edits)
+        .map(|(_, k)| k)
+}
+
+/// Levenshtein distance between `a` and `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{edit_distance, UnknownColumnError};
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("created", "created"), 0);
+        assert_eq!(edit_distance("craeted", "created"), 2);
+        assert_eq!(edit_distance("", "tags"), 4);
+        assert_eq!(edit_distance("usernam", "username"), 1);
+    }
+
+    #[test]
+    fn test_unknown_column_suggestions() {
+        let error = UnknownColumnError::new(&["craeted", "colour"], &["username", "created", "payload"]);
+
+        assert_eq!(error.columns(), vec!["craeted", "colour"]);
+        assert_eq!(error.suggestion("craeted"), Some("created"));
+        assert_eq!(error.suggestion("colour"), None);
+        assert_eq!(error.to_string(), "Unknown columns `craeted` (did you mean `created`?), `colour`");
+        assert_eq!(error.table(), None);
+    }
+
+    #[test]
+    fn test_unknown_non_key_columns() {
+        let error = UnknownColumnError::non_key(&["username", "paylod"], &["payload"]).with_table("test.user_event");
+
+        assert_eq!(error.table(), Some("test.user_event"));
+        assert_eq!(error.to_string(),
+                   "Unknown non key columns `username`, `paylod` (did you mean `payload`?) of table test.user_event");
+
+        let error = UnknownColumnError::non_key(&[], &["payload"]).with_table("test.user_event");
+
+        assert_eq!(error.to_string(), "At least one of the non key columns of table test.user_event is required");
+    }
+}
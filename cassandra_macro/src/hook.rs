@@ -0,0 +1,64 @@
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{CqlQuery, Error};
+
+/// Hook checking each query when it is constructed, see `set_query_hook`
+pub type QueryHook = fn(&dyn CqlQuery) -> Result<(), PolicyError>;
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+static HOOK: RwLock<Option<QueryHook>> = RwLock::new(None);
+
+/// Install the hook of the whole process, replacing the previous one
+///
+/// The hook is called by the fallible constructors `StoreQuery::try_new`,
+/// `UpdateQuery::try_new`, `DeleteQuery::try_new` and `BatchBuilder::build`,
+/// and by the methods of the tables returning a `Result`, e.g. `try_store_query`
+/// or `delete_query_if_exists`, which fail with `Error::Policy` when it rejects
+/// the query. A query built from another one is only checked once complete.
+/// The repositories also check the selects before executing them.
+pub fn set_query_hook(hook: QueryHook) {
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(hook);
+    INSTALLED.store(true, Ordering::Release);
+}
+
+/// Remove the hook, queries are no longer checked
+pub fn clear_query_hook() {
+    INSTALLED.store(false, Ordering::Release);
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Check the query with the hook, if any
+pub fn check_query_hook(query: &dyn CqlQuery) -> Result<(), Error> {
+    if !INSTALLED.load(Ordering::Acquire) {
+        return Ok(());
+    }
+
+    let hook = *HOOK.read().unwrap_or_else(|e| e.into_inner());
+
+    match hook {
+        Some(hook) => hook(query).map_err(Error::Policy),
+        None => Ok(()),
+    }
+}
+
+/// Error of a query hook rejecting a query
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyError {
+    message: String,
+}
+
+impl PolicyError {
+    pub fn new<M: Into<String>>(message: M) -> Self {
+        PolicyError { message: message.into() }
+    }
+}
+
+impl std::error::Error for PolicyError {}
+
+impl std::fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
@@ -0,0 +1,19 @@
+/// Keyspace of each table for one logical operation,
+/// e.g. `tenant_<id>` when tenants are sharded by keyspace
+///
+/// The repositories rewrite all statements of the operation with
+/// the `_in` methods of `CassandraTable`. The rewrite is a single
+/// substitution of the keyspace placeholder of a statement template
+/// generated by the derive, which is as cheap as looking
+/// the statement up in a cache keyed by keyspace and statement,
+/// so the statements are not cached.
+pub trait KeyspaceResolver {
+    /// Keyspace of the table, which must be a valid identifier
+    fn keyspace_for(&self, table: &str) -> String;
+}
+
+impl<F: Fn(&str) -> String> KeyspaceResolver for F {
+    fn keyspace_for(&self, table: &str) -> String {
+        self(table)
+    }
+}
@@ -28,18 +28,12 @@
 //! cassandra_macro_derive = "0.1.3"
 //! ```
 //!
-//! In your `main.rs`
-//!
-//! ```
-//! #[macro_use]
-//! extern crate cdrs;
-//! ```
+//! The generated code only refers to `cassandra_macro`, which
+//! re-exports the `cdrs` types it needs, so `#[macro_use] extern
+//! crate cdrs` is not required.
 //!
 //! # Example
-//! ```
-//! #[macro_use]
-//!extern crate cdrs;
-//!
+//! ```no_run
 //!use std::sync::Arc;
 //!
 //!use cassandra_macro::{CassandraTable, DeleteQuery, Projection, UpdateQuery};
@@ -58,8 +52,8 @@
 //!use chrono::Utc;
 //!use uuid::Uuid;
 //!
-//!#[table(keyspace = "test", options = "comment='Only for RUST users' | COMPACTION = {'class':'SizeTieredCompactionStrategy'}")]
 //!#[derive(Debug, CassandraTable)]
+//!#[table(keyspace = "test", options = "comment='Only for RUST users' | COMPACTION = {'class':'SizeTieredCompactionStrategy'}")]
 //!pub struct User {
 //!    #[column(type = "TEXT", primary_key)]
 //!    username: String,
@@ -134,21 +128,27 @@
 //!        }
 //!    }
 //!
-//!    pub fn execute_store_query(&self, query: &StoreQuery) -> Result<bool, CassandraDriverError> {
-//!        self.execute_query(query.query(), query.values())
+//!    pub fn execute_store_query(&self, query: StoreQuery) -> Result<bool, CassandraDriverError> {
+//!        let (query, values) = query.into_parts();
+//!
+//!        self.execute_query(&query, values)
 //!    }
 //!
-//!    pub fn execute_update_query(&self, query: &UpdateQuery) -> Result<bool, CassandraDriverError> {
-//!        self.execute_query(query.query(), query.values())
+//!    pub fn execute_update_query(&self, query: UpdateQuery) -> Result<bool, CassandraDriverError> {
+//!        let (query, values) = query.into_parts();
+//!
+//!        self.execute_query(&query, values)
 //!    }
 //!
-//!    pub fn execute_delete_query(&self, query: &DeleteQuery) -> Result<bool, CassandraDriverError> {
-//!        self.execute_query(query.query(), query.values())
+//!    pub fn execute_delete_query(&self, query: DeleteQuery) -> Result<bool, CassandraDriverError> {
+//!        let (query, values) = query.into_parts();
+//!
+//!        self.execute_query(&query, values)
 //!    }
 //!
-//!    pub fn execute_query(&self, query: &String, values: &QueryValues) -> Result<bool, CassandraDriverError> {
+//!    pub fn execute_query(&self, query: &str, values: QueryValues) -> Result<bool, CassandraDriverError> {
 //!        let result = self.connection
-//!            .query_with_values(query, values.to_owned());
+//!            .query_with_values(query, values);
 //!
 //!        result.map(|_| true)
 //!    }
@@ -161,7 +161,7 @@
 //!        let result_frame = self.connection.query_with_values(stmt, QueryValues::SimpleValues(values))?;
 //!
 //!        Ok(result_frame.get_body()?.into_rows()
-//!            .map(|r| { r.first().map(|r| T::try_from_row(r.to_owned()).unwrap()) }).flatten())
+//!            .and_then(|r| { r.first().map(|r| T::try_from_row(r.to_owned()).unwrap()) }))
 //!    }
 //!
 //!    pub fn new_from_config(cassandra_configs: &CassandraConfig) -> Self {
@@ -204,15 +204,15 @@
 //!    println!("You can test those by yourself");
 //!    println!("{}", User::select_by_primary_keys(Projection::Columns(vec!["created".to_string()])));
 //!    println!("{}", User::select_by_primary_and_cluster_keys(Projection::All));
-//!    println!("{}", User::update_by_primary_keys(vec!["updated".to_string()]));
-//!    println!("{}", User::update_by_primary_and_cluster_keys(vec!["updated".to_string()]));
+//!    println!("{}", User::update_by_primary_keys(vec!["updated".to_string()]).unwrap());
+//!    println!("{}", User::update_by_primary_and_cluster_keys(vec!["updated".to_string()]).unwrap());
 //!    println!("{}", User::delete_by_primary_keys());
 //!    println!("{}", User::delete_by_primary_and_cluster_keys());
 //!
 //!    let mut rust_user = User::default();
 //!
 //!    println!("Storing rust: {}", rust_user.store_query().query());
-//!    connection.execute_store_query(&rust_user.store_query()).expect("User must be stored");
+//!    connection.execute_store_query(rust_user.store_query()).expect("User must be stored");
 //!
 //!    let rust_user_from_db: Option<User> = connection.find::<User>(vec!["Rust".to_string()]).unwrap();
 //!    assert!(rust_user_from_db.unwrap().username.eq(&rust_user.username), "Must be the same");
@@ -220,184 +220,2383 @@
 //!    println!("Update rust:{}", rust_user.update_query().unwrap().query());
 //!    rust_user.set_first_name(String::from("IamRoot"));
 //!
-//!    connection.execute_update_query(&rust_user.update_query().unwrap()).unwrap();
+//!    connection.execute_update_query(rust_user.update_query().unwrap()).unwrap();
 //!
 //!    let rust_user_from_db_1 = connection.find::<User>(vec!["Rust".to_string()]).unwrap();
 //!
 //!    assert!(rust_user_from_db_1.unwrap().username.eq(&rust_user.username), "Must be the same");
 //!
 //!    println!("Delete:{}", rust_user.delete_query().query());
-//!    connection.execute_delete_query(&rust_user.delete_query()).expect("Must be deleted");
+//!    connection.execute_delete_query(rust_user.delete_query()).expect("Must be deleted");
 //!
 //!    println!("Dropping table: {}", User::drop_table_cql());
 //!    connection.execute_simple_statement(User::drop_table_cql()).expect("Table must be removed");
 //!}
 //! ```
-use cdrs::query::QueryValues;
+use cdrs::consistency::Consistency;
+use cdrs::frame::IntoBytes;
+use cdrs::types::value::ValueType;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::fmt::{Display, Formatter};
 
+use cassandra_macro_table::is_valid_name;
+
+/// Driver used by the generated code, so that it is not required in scope
+pub use cdrs;
+/// Values of the statements, the CQL binary protocol serialization of the fields
+pub use cdrs::query::QueryValues;
+pub use cdrs::types::value::Value;
+#[cfg(feature = "cdrs-tokio")]
+pub use cdrs_tokio;
+#[cfg(feature = "json")]
+pub use serde_json;
+#[cfg(feature = "bigdecimal")]
+pub use bigdecimal;
+#[cfg(feature = "chrono")]
+pub use chrono;
+#[cfg(feature = "bigint")]
+pub use num_bigint;
+
+#[cfg(feature = "cdrs-tokio")]
+pub use async_repository::{AsyncCassandraRepository, RowStream};
+pub use batch::{BatchBuilder, BatchKind, BatchQuery};
+/// Tables defined at runtime, the derive generates its statements with them
+pub use cassandra_macro_table::{CqlTable, CqlTableBuilder, KEY_SPACE_PLACEHOLDER, Order, TableDefinitionError};
+pub use count_row::{COUNT_ALIAS, CountAlias, CountRow};
+pub use cql_type::CqlType;
+#[cfg(feature = "chrono")]
+pub use date_time::{CqlDate, CqlTime};
+#[cfg(feature = "bigdecimal")]
+pub use decimal::CqlDecimal;
+pub use duration::CqlDuration;
+pub use select_builder::SelectBuilder;
+pub use table_projection::CassandraProjection;
+pub use dyn_table::{DynCassandraTable, TableHandle};
+pub use error::{Error, UnknownColumnError};
+pub use hook::{check_query_hook, clear_query_hook, set_query_hook, PolicyError, QueryHook};
+pub use keyspace::KeyspaceResolver;
+pub use lwt::LwtResult;
+#[cfg(feature = "cdrs-tokio")]
+pub use raw_rows::async_rows_with_raw_columns;
+pub use raw_rows::rows_with_raw_columns;
+#[cfg(feature = "repository")]
+pub use prepared::{PreparedCache, UnboundedPreparedCache};
+#[cfg(feature = "repository")]
+pub use repository::{CassandraRepository, RowIterator};
+#[cfg(feature = "repository")]
+pub use session::CqlSession;
+pub use tracked::Tracked;
+pub use udt::CassandraUdt;
+#[cfg(feature = "bigint")]
+pub use varint::Varint;
+
+#[cfg(feature = "cdrs-tokio")]
+mod async_repository;
+mod batch;
+mod count_row;
+mod cql_type;
+#[cfg(feature = "chrono")]
+mod date_time;
+#[cfg(feature = "bigdecimal")]
+mod decimal;
+mod duration;
+mod dyn_table;
+mod error;
+mod hook;
+mod keyspace;
+mod lwt;
+#[cfg(feature = "repository")]
+mod prepared;
+mod raw_rows;
+#[cfg(feature = "repository")]
+mod repository;
+#[cfg(feature = "scylla")]
+mod scylla_values;
+mod select_builder;
+#[cfg(feature = "repository")]
+mod session;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod table_projection;
+mod tracked;
+mod udt;
+#[cfg(feature = "bigint")]
+mod varint;
+
 pub enum Projection {
+    /// `count(*) AS count`, read by a `CountRow`
     Count,
+    /// `count(*) AS alias`
+    CountAs(CountAlias),
     All,
     Columns(Vec<String>),
+    /// Aggregate function over a column, with an optional alias
+    ///
+    /// When no alias is given, the alias defaults to `function_column`.
+    Aggregate(AggregateFn, String, Option<String>),
+    /// Mix of plain columns and aggregates
+    Selectors(Vec<Selector>),
+    /// All columns as a single JSON value, `SELECT JSON *`
+    Json,
+    /// Given columns as a single JSON value, `SELECT JSON a,b`
+    JsonColumns(Vec<String>),
+    /// Write time of each column, `writetime(a) AS writetime_a`
+    ///
+    /// Not defined for primary and cluster keys.
+    WriteTime(Vec<String>),
+    /// Remaining time to live of each column, `ttl(a) AS ttl_a`
+    ///
+    /// Not defined for primary and cluster keys.
+    Ttl(Vec<String>),
 }
 
-pub trait CassandraTable {
-    /// key space
-    fn key_space() -> &'static str;
+impl Projection {
+    /// Same as `Columns`, with the column enum generated for the table
+    pub fn typed<C: TableColumn>(columns: &[C]) -> Projection {
+        Projection::Columns(columns.iter().map(|c| c.as_str().to_string()).collect())
+    }
 
-    /// Table name
-    fn table_name() -> &'static str;
+    /// The selection that goes between `SELECT` and `FROM`
+    pub fn selection(&self) -> String {
+        match self {
+            Projection::Count => format!("count(*) AS {}", COUNT_ALIAS),
+            Projection::CountAs(alias) => format!("count(*) AS {}", alias),
+            Projection::All => String::from("*"),
+            Projection::Columns(c) => distinct(c).join(","),
+            Projection::Json => String::from("JSON *"),
+            Projection::JsonColumns(c) => format!("JSON {}", distinct(c).join(",")),
+            _ => self.selectors().iter().map(|s| s.to_cql()).collect::<Vec<String>>().join(","),
+        }
+    }
 
-    /// CQL for table creation
-    fn create_table_cql() -> &'static str;
+    /// Same projection, after ensuring that its columns exist in the
+    /// table `T`, see `validate`
+    pub fn validated<T: CassandraTable + ?Sized>(self) -> Result<Projection, Error> {
+        validate_projection::<T>(&self)?;
 
-    /// CQL for drop table
-    fn drop_table_cql() -> &'static str;
+        Ok(self)
+    }
 
-    /// Prepared statement for selection by primary keys
-    fn select_by_primary_keys(projection: Projection) -> String;
+    /// Ensure that columns referenced by the projection exist in the table,
+    /// all unknown columns are reported with the closest known column
+    ///
+    /// Write time and ttl are also rejected for key columns.
+    pub fn validate(&self, columns: &[&str], key_columns: &[&str]) -> Result<(), Error> {
+        let selectors = self.selectors();
 
-    /// Prepared statement for selection by primary keys and cluster keys
-    fn select_by_primary_and_cluster_keys(projection: Projection) -> String;
+        let selector_columns: Vec<String> = selectors.iter().map(|s| s.column().to_owned()).collect();
 
-    /// Prepared statement for update by primary keys
-    fn update_by_primary_keys(columns: Vec<String>) -> String;
+        let referenced = match self {
+            Projection::Columns(c) | Projection::JsonColumns(c) => distinct(c),
+            _ => distinct(&selector_columns),
+        };
 
-    /// Prepared statement for update by primary keys and cluster keys
-    fn update_by_primary_and_cluster_keys(columns: Vec<String>) -> String;
+        let unknown: Vec<&str> = referenced.into_iter().filter(|c| !columns.contains(c)).collect();
 
-    /// Prepared statement for delete by primary keys
-    fn delete_by_primary_keys() -> String;
+        if !unknown.is_empty() {
+            return Err(UnknownColumnError::new(&unknown, columns).into());
+        }
 
-    /// Prepared statement for delete by primary keys and cluster key
-    fn delete_by_primary_and_cluster_keys() -> String;
+        for selector in selectors {
+            let column = selector.column().as_str();
 
-    /// Create `StoreQuery` containing the prepared statement
-    /// to store this entity
-    fn store_query(&self) -> StoreQuery;
+            match selector {
+                Selector::WriteTime(_) | Selector::Ttl(_) if key_columns.contains(&column) => {
+                    return Err(Error::InvalidColumn(
+                        format!("Cannot select writetime or ttl of key column `{}`", column)));
+                }
+                _ => {}
+            }
+        }
 
-    /// Create `UpdateQuery` containing the prepared statement
-    /// to update this entity
-    ///
-    /// The statement only can update columns that are not
-    /// part of the primary keys.
-    fn update_query(&self) -> Result<UpdateQuery, TableWithNoUpdatableColumnsError>;
+        Ok(())
+    }
 
-    /// Create `DeleteQuery` containing the prepared statement
-    /// to delete this entity
-    fn delete_query(&self) -> DeleteQuery;
+    fn selectors(&self) -> Vec<Selector> {
+        match self {
+            Projection::Aggregate(function, column, alias) => {
+                vec![Selector::Aggregate(*function, column.to_owned(), alias.to_owned())]
+            }
+            Projection::Selectors(selectors) => selectors.to_owned(),
+            Projection::WriteTime(c) => c.iter().map(|c| Selector::WriteTime(c.to_owned())).collect(),
+            Projection::Ttl(c) => c.iter().map(|c| Selector::Ttl(c.to_owned())).collect(),
+            _ => Vec::new(),
+        }
+    }
 }
 
-#[derive(Debug)]
-pub struct StoreQuery {
-    query: String,
-    values: QueryValues,
+/// Columns without the repeated ones, in the order they were given
+fn distinct(columns: &[String]) -> Vec<&str> {
+    let mut distinct: Vec<&str> = Vec::with_capacity(columns.len());
+
+    for column in columns {
+        if !distinct.contains(&column.as_str()) {
+            distinct.push(column);
+        }
+    }
+
+    distinct
 }
 
-impl StoreQuery {
-    /// New instance
-    pub fn new(query: String, values: QueryValues) -> Self {
-        StoreQuery { query, values }
+/// Column of a table, implemented by the `<Struct>Column`
+/// enum that `#[derive(Cassandra)]` generates next to the struct
+pub trait TableColumn: Copy {
+    /// Name of the column in the statements
+    fn as_str(&self) -> &'static str;
+}
+
+/// Keys of a row of a table, implemented by the `<Struct>Key`
+/// struct that `#[derive(Cassandra)]` generates next to the struct
+pub trait TableKey {
+    /// Values of the partition keys, in the order of the where clauses
+    fn partition_values(&self) -> Vec<Value>;
+}
+
+/// Aggregate functions supported by Cassandra
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AggregateFn {
+    Min,
+    Max,
+    Sum,
+    Avg,
+    Count,
+}
+
+impl Display for AggregateFn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AggregateFn::Min => write!(f, "min"),
+            AggregateFn::Max => write!(f, "max"),
+            AggregateFn::Sum => write!(f, "sum"),
+            AggregateFn::Avg => write!(f, "avg"),
+            AggregateFn::Count => write!(f, "count"),
+        }
     }
+}
 
-    /// Prepared statement for insertion
-    pub fn query(&self) -> &String {
-        &self.query
+/// Single item of `Projection::Selectors`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Selector {
+    Column(String),
+    Aggregate(AggregateFn, String, Option<String>),
+    WriteTime(String),
+    Ttl(String),
+}
+
+impl Selector {
+    fn column(&self) -> &String {
+        match self {
+            Selector::Column(column) => column,
+            Selector::Aggregate(_, column, _) => column,
+            Selector::WriteTime(column) => column,
+            Selector::Ttl(column) => column,
+        }
     }
 
-    /// Values for executing prepared statement
-    pub fn values(&self) -> &QueryValues {
-        &self.values
+    fn to_cql(&self) -> String {
+        match self {
+            Selector::Column(column) => column.to_owned(),
+            Selector::Aggregate(function, column, alias) => {
+                let alias = alias.to_owned().unwrap_or_else(|| format!("{}_{}", function, column));
+
+                format!("{}({}) AS {}", function, column, alias)
+            }
+            Selector::WriteTime(column) => format!("writetime({}) AS writetime_{}", column, column),
+            Selector::Ttl(column) => format!("ttl({}) AS ttl_{}", column, column),
+        }
     }
 }
 
-impl Display for StoreQuery {
-    /// Only display the prepared statement
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "query:{}", self.query)
+/// Restriction of a cluster key column
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClusterKeyFilter {
+    /// `column=?`
+    Eq,
+    /// `column<?`
+    Lt,
+    /// `column<=?`
+    Lte,
+    /// `column>?`
+    Gt,
+    /// `column>=?`
+    Gte,
+}
+
+impl ClusterKeyFilter {
+    fn operator(&self) -> &'static str {
+        match self {
+            ClusterKeyFilter::Eq => "=",
+            ClusterKeyFilter::Lt => "<",
+            ClusterKeyFilter::Lte => "<=",
+            ClusterKeyFilter::Gt => ">",
+            ClusterKeyFilter::Gte => ">=",
+        }
+    }
+
+    fn is_lower_bound(&self) -> bool {
+        matches!(self, ClusterKeyFilter::Gt | ClusterKeyFilter::Gte)
+    }
+
+    fn is_upper_bound(&self) -> bool {
+        matches!(self, ClusterKeyFilter::Lt | ClusterKeyFilter::Lte)
     }
 }
 
-#[derive(Debug)]
-pub struct UpdateQuery {
-    query: String,
-    values: QueryValues,
+/// Where clause `a=? AND b>=? AND b<?` restricting cluster keys
+///
+/// Restricted columns must be a prefix of the cluster keys, in
+/// clustering order. All columns must be restricted by `Eq`
+/// except the last one, which can also be restricted by a lower
+/// bound, an upper bound or both.
+pub fn cluster_range_where(cluster_keys: &[&str], filters: &[(&str, ClusterKeyFilter)]) -> Result<String, Error> {
+    if filters.is_empty() {
+        return Err(Error::InvalidColumn(String::from("At least one cluster key filter is required")));
+    }
+
+    let mut restricted: Vec<(&str, Vec<ClusterKeyFilter>)> = Vec::new();
+
+    for (column, filter) in filters {
+        match restricted.last_mut() {
+            Some((last, last_filters)) if last == column => last_filters.push(*filter),
+            _ => restricted.push((column, vec![*filter])),
+        }
+    }
+
+    for (position, (column, column_filters)) in restricted.iter().enumerate() {
+        if cluster_keys.get(position) != Some(column) {
+            return Err(Error::InvalidColumn(
+                format!("Cannot restrict column `{}`, restricted columns must be a prefix of the cluster keys [{}]",
+                        column, cluster_keys.join(","))));
+        }
+
+        let is_last = position == restricted.len() - 1;
+
+        let is_valid = match column_filters.as_slice() {
+            [ClusterKeyFilter::Eq] => true,
+            [filter] => is_last && filter != &ClusterKeyFilter::Eq,
+            [first, second] => {
+                is_last && ((first.is_lower_bound() && second.is_upper_bound())
+                    || (first.is_upper_bound() && second.is_lower_bound()))
+            }
+            _ => false,
+        };
+
+        if !is_valid {
+            return Err(Error::InvalidColumn(
+                format!("Invalid restriction of column `{}`, only the last restricted cluster key can be a range", column)));
+        }
+    }
+
+    Ok(filters.iter()
+        .map(|(column, filter)| format!("{}{}?", column, filter.operator()))
+        .collect::<Vec<String>>()
+        .join(" AND "))
 }
 
-impl UpdateQuery {
-    /// New instance
-    pub fn new(query: String, values: QueryValues) -> Self {
-        UpdateQuery { query, values }
+/// Extra clauses appended to the generated selects
+///
+/// Clauses are always rendered in the order that Cassandra
+/// expects: `GROUP BY ... ORDER BY ... PER PARTITION LIMIT ? LIMIT ?`.
+#[derive(Debug, Clone, Default)]
+pub struct SelectOptions {
+    group_by: Vec<String>,
+    order_by: Vec<(String, Order)>,
+    per_partition_limit: Option<Limit>,
+    limit: Option<Limit>,
+}
+
+/// Limit of the rows, bound or inlined in the statement
+#[derive(Debug, Clone, Copy)]
+enum Limit {
+    Bound,
+    Literal(u32),
+}
+
+impl SelectOptions {
+    /// New instance without any clause
+    pub fn new() -> Self {
+        SelectOptions::default()
     }
-    /// Prepared statement for update
-    pub fn query(&self) -> &String {
-        &self.query
+
+    /// Group rows by the given primary key columns
+    ///
+    /// Columns must be all the primary keys, optionally
+    /// followed by a prefix of the cluster keys.
+    pub fn group_by(mut self, columns: &[&str]) -> Self {
+        self.group_by = columns.iter().map(|c| c.to_string()).collect();
+        self
     }
-    /// Values for executing prepared statement
-    pub fn values(&self) -> &QueryValues {
-        &self.values
+
+    /// Order rows by the given cluster key column
+    ///
+    /// Can be called multiple times, columns are
+    /// rendered in the same order as they were given.
+    pub fn order(mut self, column: &str, order: Order) -> Self {
+        self.order_by.push((column.to_string(), order));
+        self
+    }
+
+    /// Append `LIMIT ?`, the limit must be bound after
+    /// the key values
+    pub fn limit(mut self) -> Self {
+        self.limit = Some(Limit::Bound);
+        self
+    }
+
+    /// Append `LIMIT <limit>`, the limit is part of
+    /// the statement so no value is bound
+    pub fn limit_to(mut self, limit: u32) -> Self {
+        self.limit = Some(Limit::Literal(limit));
+        self
+    }
+
+    /// Append `PER PARTITION LIMIT ?`, the limit must be bound
+    /// after the key values and before the value of `limit`
+    pub fn per_partition_limit(mut self) -> Self {
+        self.per_partition_limit = Some(Limit::Bound);
+        self
+    }
+
+    /// Append `PER PARTITION LIMIT <limit>`
+    pub fn per_partition_limit_to(mut self, limit: u32) -> Self {
+        self.per_partition_limit = Some(Limit::Literal(limit));
+        self
+    }
+
+    /// Render the clauses for a table with the given primary and cluster keys
+    ///
+    /// Only cluster key columns can be used in the `ORDER BY` clause.
+    pub fn to_cql(&self, primary_keys: &[&str], cluster_keys: &[&str]) -> Result<String, Error> {
+        let mut clauses = Vec::new();
+
+        if !self.group_by.is_empty() {
+            let key_prefix = [primary_keys, cluster_keys].concat();
+
+            let is_prefix = self.group_by.len() >= primary_keys.len()
+                && self.group_by.len() <= key_prefix.len()
+                && self.group_by.iter().zip(key_prefix.iter()).all(|(g, k)| g == k);
+
+            if !is_prefix {
+                return Err(Error::InvalidColumn(
+                    format!("Cannot group by [{}], columns must be a prefix of the primary key ({})",
+                            self.group_by.join(","), key_prefix.join(","))));
+            }
+
+            clauses.push(format!("GROUP BY {}", self.group_by.join(",")));
+        }
+
+        if !self.order_by.is_empty() {
+            for (column, _) in self.order_by.iter() {
+                if !cluster_keys.contains(&column.as_str()) {
+                    return Err(Error::InvalidColumn(
+                        format!("Cannot order by column `{}`, only cluster keys [{}] are allowed",
+                                column, cluster_keys.join(","))));
+                }
+            }
+
+            let order_by = self.order_by
+                .iter()
+                .map(|(column, order)| format!("{} {}", column, order))
+                .collect::<Vec<String>>()
+                .join(",");
+
+            clauses.push(format!("ORDER BY {}", order_by));
+        }
+
+        match self.per_partition_limit {
+            Some(Limit::Bound) => clauses.push(String::from("PER PARTITION LIMIT ?")),
+            Some(Limit::Literal(limit)) => clauses.push(format!("PER PARTITION LIMIT {}", limit)),
+            None => {}
+        }
+
+        match self.limit {
+            Some(Limit::Bound) => clauses.push(String::from("LIMIT ?")),
+            Some(Limit::Literal(limit)) => clauses.push(format!("LIMIT {}", limit)),
+            None => {}
+        }
+
+        Ok(clauses.join(" "))
     }
 }
 
-impl Display for UpdateQuery {
-    /// Only display the prepared statement
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "query:{}", self.query)
+/// Append values to the values of a query, which must not have names
+fn with_values(values: QueryValues, mut extra: Vec<Value>) -> Result<QueryValues, Error> {
+    match values {
+        QueryValues::SimpleValues(mut values) => {
+            values.append(&mut extra);
+            Ok(QueryValues::SimpleValues(values))
+        }
+        QueryValues::NamedValues(_) => Err(Error::NamedValues),
     }
 }
 
-#[derive(Debug)]
-pub struct DeleteQuery {
-    query: String,
-    values: QueryValues,
+/// Prepend values to the values of a query, which must not have names
+fn prepend_values(mut values: Vec<Value>, query_values: QueryValues) -> Result<QueryValues, Error> {
+    match query_values {
+        QueryValues::SimpleValues(current) => {
+            values.extend(current);
+            Ok(QueryValues::SimpleValues(values))
+        }
+        QueryValues::NamedValues(_) => Err(Error::NamedValues),
+    }
 }
 
-impl DeleteQuery {
-    /// New instance
-    pub fn new(query: String, values: QueryValues) -> Self {
-        DeleteQuery { query, values }
+/// Values of the given columns of the entity
+fn column_values<T: CassandraTable + ?Sized>(entity: &T, columns: &[&str]) -> Result<Vec<Value>, Error> {
+    columns.iter()
+        .map(|c| entity.try_column_value(c)?.ok_or_else(|| unknown_column::<T>(c)))
+        .collect()
+}
+
+/// Error of a column that is not a column of the table of `T`
+pub(crate) fn unknown_column<T: CassandraTable + ?Sized>(column: &str) -> Error {
+    Error::UnknownColumn(UnknownColumnError::new(&[column], T::columns()).with_table(&format!("{}.{}", T::key_space(), T::table_name())))
+}
+
+/// Where clause `a=? AND b=?` of the given key columns
+fn key_where(keys: &[&str]) -> String {
+    keys.iter()
+        .map(|k| format!("{}=?", k))
+        .collect::<Vec<String>>()
+        .join(" AND ")
+}
+
+/// Where clause `a=? AND b=?` of primary and cluster keys,
+/// in the same order as the generated instance queries
+fn full_key_where<T: CassandraTable + ?Sized>() -> String {
+    key_where(&[T::primary_key_columns(), T::cluster_key_columns()].concat())
+}
+
+/// Ensure that there is at least one column and
+/// that all columns are non key columns of the table
+///
+/// The error names the table, the unknown and key columns,
+/// and suggests the closest non key column of each of them.
+pub fn validate_non_key_columns<T: CassandraTable + ?Sized>(columns: &[&str]) -> Result<(), UnknownColumnError> {
+    let known: Vec<&str> = T::columns().iter()
+        .filter(|c| !T::primary_key_columns().contains(c) && !T::cluster_key_columns().contains(c))
+        .copied()
+        .collect();
+
+    let mut unknown: Vec<&str> = vec![];
+
+    for column in columns {
+        if !known.contains(column) && !unknown.contains(column) {
+            unknown.push(column);
+        }
     }
 
-    /// Prepared statement for deletion
-    pub fn query(&self) -> &String {
-        &self.query
+    if columns.is_empty() || !unknown.is_empty() {
+        return Err(UnknownColumnError::non_key(&unknown, &known).with_table(&format!("{}.{}", T::key_space(), T::table_name())));
     }
 
-    /// Values for executing prepared statement
-    pub fn values(&self) -> &QueryValues {
-        &self.values
+    Ok(())
+}
+
+/// Parsed type of a non key column, which must satisfy `accepts`
+fn typed_column<T: CassandraTable + ?Sized>(column: &str, accepts: fn(&CqlType) -> bool) -> Result<CqlType, Error> {
+    validate_non_key_columns::<T>(&[column])?;
+
+    let declared = T::column_type(column).unwrap_or_default();
+
+    let cql_type = CqlType::parse(declared)?;
+
+    if !accepts(&cql_type) {
+        return Err(Error::InvalidColumn(format!("Column `{}` of type `{}` does not support this operation", column, declared)));
     }
+
+    Ok(cql_type)
 }
 
-impl Display for DeleteQuery {
-    /// Only display the prepared statement
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "query:{}", self.query)
+/// Update by primary and cluster keys, `UPDATE ... SET <set_part> WHERE ...`
+fn key_update_cql<T: CassandraTable + ?Sized>(set_part: String) -> String {
+    format!("UPDATE {}.{} SET {} WHERE {}", T::key_space(), T::table_name(), set_part, full_key_where::<T>())
+}
+
+/// Update by primary and cluster keys binding only the key values of the entity
+fn key_update_query<T: CassandraTable>(entity: &T, set_part: String) -> Result<UpdateQuery, Error> {
+    let values = column_values(entity, &[T::primary_key_columns(), T::cluster_key_columns()].concat())?;
+
+    checked(UpdateQuery::for_table::<T>(key_update_cql::<T>(set_part), QueryValues::SimpleValues(values)))
+}
+
+/// Update of the given non key columns of the entity, see `CassandraTable::update_columns_query`
+fn columns_update_query<T: CassandraTable>(entity: &T, columns: &[&str]) -> Result<UpdateQuery, Error> {
+    validate_non_key_columns::<T>(columns)?;
+
+    let keys = [T::primary_key_columns(), T::cluster_key_columns()].concat();
+
+    let set_part = columns.iter().map(|c| format!("{}=?", c)).collect::<Vec<String>>().join(",");
+
+    let values = column_values(entity, &[columns, &keys[..]].concat())?;
+
+    Ok(UpdateQuery::for_table::<T>(format!("UPDATE {}.{} SET {} WHERE {}",
+                                           T::key_space(),
+                                           T::table_name(),
+                                           set_part,
+                                           key_where(&keys)), QueryValues::SimpleValues(values))
+        .with_idempotent(T::idempotence().update))
+}
+
+/// The query, unless the query hook rejects it
+///
+/// The queries built from other queries are only checked once
+/// they are complete, so that the hook sees the executed statement.
+fn checked<Q: CqlQuery>(query: Q) -> Result<Q, Error> {
+    check_query_hook(&query)?;
+
+    Ok(query)
+}
+
+/// Maximum time to live accepted by Cassandra, 20 years in seconds
+pub const MAX_TTL: u32 = 630_720_000;
+
+/// Bound value of the TTL, which must be at most `MAX_TTL`
+fn ttl_value(ttl: u32) -> Result<Value, Error> {
+    if ttl > MAX_TTL {
+        return Err(Error::InvalidTtl(ttl));
     }
+
+    Ok(Value::from(ttl as i32))
 }
 
-/// Error if user tries to create
-/// invalid update statement
-#[derive(Debug)]
-pub struct TableWithNoUpdatableColumnsError {
-    message: String
+/// Insert with `USING TTL ? AND TIMESTAMP ?` after the values,
+/// the TTL and the timestamp are bound after the column values
+fn with_store_using<T: CassandraTable + ?Sized>(query: StoreQuery, ttl: Option<Value>, timestamp_micros: Option<i64>) -> Result<StoreQuery, Error> {
+    let mut clauses = Vec::new();
+    let mut values = Vec::new();
+
+    if let Some(ttl) = ttl {
+        clauses.push("TTL ?");
+        values.push(ttl);
+    }
+
+    if let Some(timestamp) = timestamp_micros {
+        clauses.push("TIMESTAMP ?");
+        values.push(Value::from(timestamp));
+    }
+
+    if clauses.is_empty() {
+        return Ok(query);
+    }
+
+    let idempotent = query.is_idempotent();
+
+    Ok(StoreQuery::for_table::<T>(format!("{} USING {}", query.query, clauses.join(" AND ")), with_values(query.values, values)?)
+        .with_idempotent(idempotent))
 }
 
-impl TableWithNoUpdatableColumnsError {
-    pub fn new(message: String) -> Self {
-        TableWithNoUpdatableColumnsError { message }
+/// Update with `USING TTL ?` before the set clause, the TTL is bound first
+fn with_update_ttl(query: UpdateQuery, ttl: u32) -> Result<UpdateQuery, Error> {
+    let ttl = ttl_value(ttl)?;
+    let statement = query.query.replacen(" SET ", " USING TTL ? SET ", 1);
+
+    UpdateQuery { query: Cow::Owned(statement), ..query }.prepend_values(vec![ttl])
+}
+
+/// Ensure that the name is a valid unquoted CQL keyspace name,
+/// alphanumeric characters and underscores, at most 48 of them
+pub fn validate_identifier(identifier: &str) -> Result<(), Error> {
+    if is_valid_name(identifier) {
+        Ok(())
+    } else {
+        Err(Error::InvalidIdentifier(identifier.to_string()))
     }
 }
 
-impl std::error::Error for TableWithNoUpdatableColumnsError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        Some(self)
+/// Operations of a table whose generated queries are safe to retry,
+/// the `idempotent` of the `table` attribute, see `CassandraTable::idempotence`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Idempotence {
+    /// The inserts, unless the table has `generated` columns
+    pub store: bool,
+    pub update: bool,
+    pub delete: bool,
+}
+
+/// Statements of `CassandraTable::statement_template`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableStatement {
+    CreateTable,
+    DropTable,
+    SelectAll,
+    SelectByPrimaryKeys,
+    SelectByPrimaryAndClusterKeys,
+    Store,
+    Update,
+    Delete,
+}
+
+/// Template of the statement of the table with the keyspace `ks`
+/// instead of `KEY_SPACE_PLACEHOLDER`
+///
+/// Only the first placeholder is replaced, which is always the
+/// keyspace of the table of the statement, table options come after it.
+fn in_key_space(template: &str, ks: &str) -> Result<String, Error> {
+    validate_identifier(ks)?;
+
+    Ok(template.replacen(KEY_SPACE_PLACEHOLDER, ks, 1))
+}
+
+/// Select template of the table in the keyspace `ks`, with the
+/// selection of `projection` in place of its `{}`
+fn select_in_key_space(template: &str, ks: &str, projection: Projection) -> Result<String, Error> {
+    Ok(in_key_space(template, ks)?.replacen("{}", &projection.selection(), 1))
+}
+
+fn validate_projection<T: CassandraTable + ?Sized>(projection: &Projection) -> Result<(), Error> {
+    let key_columns = [T::primary_key_columns(), T::cluster_key_columns()].concat();
+
+    projection.validate(T::columns(), &key_columns).map_err(|e| match e {
+        Error::UnknownColumn(e) => Error::UnknownColumn(e.with_table(&format!("{}.{}", T::key_space(), T::table_name()))),
+        e => e,
+    })
+}
+
+fn with_select_options<T: CassandraTable + ?Sized>(statement: String, options: &SelectOptions) -> Result<String, Error> {
+    let clauses = options.to_cql(T::primary_key_columns(), T::cluster_key_columns())?;
+
+    if clauses.is_empty() {
+        Ok(statement)
+    } else {
+        Ok(format!("{} {}", statement.trim_end(), clauses))
     }
 }
 
-impl std::fmt::Display for TableWithNoUpdatableColumnsError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.message.as_str())
+pub trait CassandraTable {
+    /// Primary and cluster key fields of a row, the `<Struct>Key`
+    /// struct that `#[derive(Cassandra)]` generates next to the struct
+    type Key: TableKey;
+
+    /// key space
+    fn key_space() -> &'static str;
+
+    /// Table name
+    fn table_name() -> &'static str;
+
+    /// CQL for table creation
+    fn create_table_cql() -> &'static str;
+
+    /// CQL for drop table
+    fn drop_table_cql() -> &'static str;
+
+    /// CQL for creation of the secondary indexes of the `index` columns,
+    /// `CREATE INDEX IF NOT EXISTS <table>_<column>_idx ON ks.table (column)`
+    /// unless the index is named, e.g. `index = "by_email"`
+    fn create_indexes_cql() -> Vec<&'static str>;
+
+    /// CQL for drop of the secondary indexes, `DROP INDEX IF EXISTS ks.index`
+    fn drop_indexes_cql() -> Vec<&'static str>;
+
+    /// Columns of the table
+    fn columns() -> &'static [&'static str];
+
+    /// Primary key columns, sorted by their position
+    fn primary_key_columns() -> &'static [&'static str];
+
+    /// Cluster key columns, sorted by their position
+    fn cluster_key_columns() -> &'static [&'static str];
+
+    /// Columns marked `static`, in the order they were declared
+    fn static_columns() -> &'static [&'static str];
+
+    /// Columns bound by `store_query`, in the order of its values
+    ///
+    /// The `generated` columns are inserted with their
+    /// expression instead of a bound value. Empty for
+    /// counter tables, which can not be inserted.
+    fn insert_columns() -> &'static [&'static str];
+
+    /// Columns set by `update_query`, in the order of its values,
+    /// all but the keys, the counters and the `generated` columns
+    fn updatable_columns() -> &'static [&'static str];
+
+    /// Declared CQL type of the column, as written
+    /// in the `type` of the `column` attribute
+    fn column_type(column: &str) -> Option<&'static str>;
+
+    /// Columns that `TryFromRow` reads from their bytes, which the
+    /// repositories read with `rows_with_raw_columns`
+    ///
+    /// With `derive_try_from_row`, the `DURATION` columns, the `VARINT`
+    /// columns of a `BigInt` or a `Varint` and the `DECIMAL` columns
+    /// of a `BigDecimal` or a `CqlDecimal`.
+    fn raw_columns() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Operations whose queries are safe to retry, the generated ones
+    /// and the ones built by the default methods of the trait
+    fn idempotence() -> Idempotence {
+        Idempotence::default()
+    }
+
+    /// Prepared statement for selection of the whole table
+    fn select_all(projection: Projection) -> String;
+
+    /// Prepared statement for selection of the primary keys and static
+    /// columns of each partition, `SELECT DISTINCT pk,static FROM ...`
+    fn select_static_columns() -> Result<String, Error> {
+        if Self::static_columns().is_empty() {
+            return Err(Error::InvalidColumn(format!("Table {} does not have static columns", Self::table_name())));
+        }
+
+        Ok(format!("SELECT DISTINCT {} FROM {}.{}",
+                   [Self::primary_key_columns(), Self::static_columns()].concat().join(","),
+                   Self::key_space(),
+                   Self::table_name()))
+    }
+
+    /// Builder of a select statement of the table, for combining
+    /// restrictions and clauses, see `SelectBuilder`
+    fn select() -> SelectBuilder<Self> {
+        SelectBuilder::new()
     }
-}
\ No newline at end of file
+
+    /// Prepared statement for selection of the whole table
+    /// with the clauses of `SelectOptions`
+    ///
+    /// `ORDER BY` is rejected since Cassandra only supports
+    /// it when the primary keys are restricted.
+    fn select_all_with(projection: Projection, options: &SelectOptions) -> Result<String, Error> {
+        validate_projection::<Self>(&projection)?;
+
+        if !options.order_by.is_empty() {
+            return Err(Error::InvalidColumn(String::from("Cannot order by columns without restricting the primary keys")));
+        }
+
+        with_select_options::<Self>(Self::select_all(projection), options)
+    }
+
+    /// Prepared statement for selection of a token range,
+    /// `token(primary keys) > ? AND token(primary keys) <= ?`
+    ///
+    /// Used to split full table scans in token ranges.
+    fn select_token_range(projection: Projection) -> String {
+        let token = format!("token({})", Self::primary_key_columns().join(", "));
+
+        format!("{} WHERE {} > ? AND {} <= ?", Self::select_all(projection), token, token)
+    }
+
+    /// Same as `select_token_range` followed by `LIMIT ?`
+    fn select_token_range_with_limit(projection: Projection) -> String {
+        format!("{} LIMIT ?", Self::select_token_range(projection))
+    }
+
+    /// Prepared statement for selection by primary keys
+    fn select_by_primary_keys(projection: Projection) -> String;
+
+    /// Prepared statement for selection by primary keys and cluster keys
+    fn select_by_primary_and_cluster_keys(projection: Projection) -> String;
+
+    /// Same as `select_by_primary_keys` followed by `LIMIT 1`,
+    /// for reading at most one row of the partition
+    fn select_one_by_primary_keys(projection: Projection) -> String {
+        with_select_options::<Self>(Self::select_by_primary_keys(projection), &SelectOptions::new().limit_to(1))
+            .expect("A limit alone is always valid")
+    }
+
+    /// Same as `select_by_primary_and_cluster_keys` followed by `LIMIT 1`
+    fn select_one_by_primary_and_cluster_keys(projection: Projection) -> String {
+        with_select_options::<Self>(Self::select_by_primary_and_cluster_keys(projection), &SelectOptions::new().limit_to(1))
+            .expect("A limit alone is always valid")
+    }
+
+    /// Prepared statement for selection by primary keys
+    /// with the clauses of `SelectOptions`
+    fn select_by_primary_keys_with(projection: Projection, options: &SelectOptions) -> Result<String, Error> {
+        validate_projection::<Self>(&projection)?;
+
+        with_select_options::<Self>(Self::select_by_primary_keys(projection), options)
+    }
+
+    /// Prepared statement for selection by primary keys and cluster keys
+    /// with the clauses of `SelectOptions`
+    fn select_by_primary_and_cluster_keys_with(projection: Projection, options: &SelectOptions) -> Result<String, Error> {
+        validate_projection::<Self>(&projection)?;
+
+        with_select_options::<Self>(Self::select_by_primary_and_cluster_keys(projection), options)
+    }
+
+    /// Prepared statement for update of the given columns by primary keys
+    ///
+    /// Columns must be non key columns.
+    fn update_by_primary_keys(columns: Vec<String>) -> Result<String, UnknownColumnError>;
+
+    /// Prepared statement for update of the given columns
+    /// by primary keys and cluster keys
+    ///
+    /// Columns must be non key columns.
+    fn update_by_primary_and_cluster_keys(columns: Vec<String>) -> Result<String, UnknownColumnError>;
+
+    /// Prepared statement for delete by primary keys
+    fn delete_by_primary_keys() -> String;
+
+    /// Prepared statement for delete by primary keys and cluster key
+    fn delete_by_primary_and_cluster_keys() -> String;
+
+    /// Prepared statement for delete by primary and cluster keys with
+    /// the write timestamp, `DELETE FROM ... USING TIMESTAMP ? WHERE ...`
+    ///
+    /// The timestamp is bound first, before the key values.
+    fn delete_by_primary_and_cluster_keys_with_timestamp() -> String {
+        format!("DELETE FROM {}.{} USING TIMESTAMP ? WHERE {}", Self::key_space(), Self::table_name(), full_key_where::<Self>())
+    }
+
+    /// Prepared statement for delete of a cluster key range
+    /// within a partition, `DELETE ... WHERE pk=? AND ck>=? AND ck<?`
+    ///
+    /// Values are bound in the order of the where clause, first the
+    /// primary keys, then the filters in the order they were given.
+    fn delete_by_cluster_range(filters: &[(&str, ClusterKeyFilter)]) -> Result<String, Error> {
+        let range = cluster_range_where(Self::cluster_key_columns(), filters)?;
+
+        Ok(format!("DELETE FROM {}.{} WHERE {} AND {}",
+                   Self::key_space(),
+                   Self::table_name(),
+                   key_where(Self::primary_key_columns()),
+                   range))
+    }
+
+    /// Prepared statement to increment or decrement a `COUNTER`
+    /// column, `UPDATE ... SET c = c + ? WHERE pk=? AND ck=?`
+    ///
+    /// The delta is the first bind marker, a negative delta decrements,
+    /// followed by the primary and cluster keys.
+    fn counter_delta_cql(column: &str) -> Result<String, Error> {
+        typed_column::<Self>(column, |t| *t == CqlType::Native(String::from("COUNTER")))?;
+
+        Ok(key_update_cql::<Self>(format!("{}={}+?", column, column)))
+    }
+
+    /// Prepared statement for delete by primary keys and cluster key
+    /// only if the row exists, `DELETE ... IF EXISTS`
+    fn delete_by_primary_and_cluster_keys_if_exists() -> String {
+        format!("{} IF EXISTS", Self::delete_by_primary_and_cluster_keys().trim_end())
+    }
+
+    /// CQL for insertion of a JSON document, `INSERT INTO ... JSON ?`
+    fn store_json_cql() -> &'static str;
+
+    /// CQL for insertion of a JSON document, leaving the
+    /// missing fields unset instead of writing nulls
+    fn store_json_default_unset_cql() -> &'static str;
+
+    /// Values of the primary and cluster keys given by name, in the
+    /// same order as the where clause of `select_by_primary_and_cluster_keys`
+    ///
+    /// All key columns must be given, and only key columns.
+    fn primary_key_values_named(keys: HashMap<&str, Value>) -> Result<QueryValues, Error> {
+        let key_columns = [Self::primary_key_columns(), Self::cluster_key_columns()].concat();
+
+        if let Some(extra) = keys.keys().find(|k| !key_columns.contains(k)) {
+            return Err(Error::InvalidColumn(format!("Column `{}` is not a key column of table {}", extra, Self::table_name())));
+        }
+
+        let mut keys = keys;
+
+        let values = key_columns.iter()
+            .map(|k| keys.remove(k).ok_or_else(|| Error::MissingKeyValue(k.to_string())))
+            .collect::<Result<Vec<Value>, Error>>()?;
+
+        Ok(QueryValues::SimpleValues(values))
+    }
+
+    /// Value of the given column of this entity
+    ///
+    /// `None` for a `#[column(json)]` field that cannot be serialized,
+    /// see `try_column_value`.
+    fn column_value(&self, column: &str) -> Option<Value>;
+
+    /// Same as `column_value`, returning the serialization error
+    /// of a `#[column(json)]` field
+    fn try_column_value(&self, column: &str) -> Result<Option<Value>, Error> {
+        Ok(self.column_value(column))
+    }
+
+    /// Create `StoreQuery` containing the prepared statement
+    /// to store this entity
+    ///
+    /// Only tables whose inserts cannot fail have it, see `InfallibleQueries`.
+    fn store_query(&self) -> StoreQuery where Self: InfallibleQueries {
+        self.infallible_store_query()
+    }
+
+    /// Same as `store_query`, for all tables, returning the serialization
+    /// error of a `#[column(json)]` field, an error for the counter
+    /// tables, which cannot be inserted, and the rejection of the query hook
+    fn try_store_query(&self) -> Result<StoreQuery, Error> {
+        checked(self.store_query_without_hook()?)
+    }
+
+    /// Same as `try_store_query`, without checking the query with the
+    /// query hook, for building the queries that are checked once complete
+    fn store_query_without_hook(&self) -> Result<StoreQuery, Error>;
+
+    /// Same as `store_query`, moving the `moved` columns into the values
+    ///
+    /// The values of the driver own their serialized bytes, so
+    /// `store_query` clones each field, e.g. a 10 MB `BLOB` is held
+    /// twice until the query is dropped. `String` and `Blob` fields
+    /// marked with `#[column(moved)]` become the value without a copy.
+    fn into_store_query(self) -> StoreQuery where Self: Sized + InfallibleQueries {
+        self.infallible_into_store_query()
+    }
+
+    /// Create `StoreQuery` to store this entity with a time to live,
+    /// `INSERT ... USING TTL ?`
+    ///
+    /// The TTL in seconds is bound after the column values,
+    /// a TTL of 0 means that the row never expires. A TTL above
+    /// `MAX_TTL` is an `Error::InvalidTtl`.
+    fn store_query_with_ttl(&self, ttl: u32) -> Result<StoreQuery, Error> {
+        self.store_query_using(Some(ttl), None)
+    }
+
+    /// Create `StoreQuery` to store this entity with a client
+    /// supplied write timestamp, `INSERT ... USING TIMESTAMP ?`
+    ///
+    /// The timestamp in microseconds is bound after the column values.
+    fn store_query_with_timestamp(&self, timestamp_micros: i64) -> Result<StoreQuery, Error> {
+        checked(with_store_using::<Self>(self.store_query_without_hook()?, None, Some(timestamp_micros))?)
+    }
+
+    /// Create `StoreQuery` to store this entity with an optional
+    /// time to live and write timestamp, `INSERT ... USING TTL ? AND TIMESTAMP ?`
+    ///
+    /// Values are bound after the column values, first the TTL
+    /// and then the timestamp. Without both, it is the same as `store_query`.
+    /// A TTL above `MAX_TTL` is an `Error::InvalidTtl`.
+    fn store_query_using(&self, ttl: Option<u32>, timestamp_micros: Option<i64>) -> Result<StoreQuery, Error> {
+        let ttl = ttl.map(ttl_value).transpose()?;
+
+        checked(with_store_using::<Self>(self.store_query_without_hook()?, ttl, timestamp_micros)?)
+    }
+
+    /// Create `StoreQuery` to store this entity only if
+    /// it does not exist, `INSERT ... IF NOT EXISTS`
+    ///
+    /// The query is marked as lightweight transaction.
+    fn store_query_if_not_exists(&self) -> Result<StoreQuery, Error> {
+        let query = self.store_query_without_hook()?;
+
+        checked(StoreQuery::for_table::<Self>(format!("{} IF NOT EXISTS", query.query), query.values).lwt())
+    }
+
+    /// Create an unlogged `BatchQuery` with the insert of each
+    /// entity, in the order of the slice
+    ///
+    /// The values of the inserts are bound one after the other,
+    /// an empty slice is an `Error::InvalidBatch`.
+    fn store_many_query(entities: &[Self]) -> Result<BatchQuery, Error> where Self: Sized {
+        entities.iter()
+            .try_fold(BatchBuilder::with_kind(BatchKind::Unlogged), |batch, entity| Ok::<_, Error>(batch.add_store(entity.try_store_query()?)))?
+            .build()
+    }
+
+    /// Same as `store_many_query`, with one batch per partition
+    ///
+    /// Each batch only writes one partition, which Cassandra applies
+    /// as a single mutation. The batches are in the order of the first
+    /// entity of their partition, and their inserts in the order of the slice.
+    fn store_many_by_partition_query(entities: &[Self]) -> Result<Vec<BatchQuery>, Error> where Self: Sized {
+        if entities.is_empty() {
+            return Err(Error::InvalidBatch(String::from("Batch must have at least one statement")));
+        }
+
+        // values are not hashable, their serialized bytes are
+        let mut positions: HashMap<Vec<Vec<u8>>, usize> = HashMap::new();
+        let mut partitions: Vec<Vec<StoreQuery>> = Vec::new();
+
+        for entity in entities {
+            let partition = entity.key().partition_values().into_iter().map(|v| v.into_cbytes()).collect();
+            let position = *positions.entry(partition).or_insert_with(|| {
+                partitions.push(vec![]);
+                partitions.len() - 1
+            });
+
+            partitions[position].push(entity.try_store_query()?);
+        }
+
+        partitions.into_iter()
+            .map(|inserts| inserts.into_iter().fold(BatchBuilder::with_kind(BatchKind::Unlogged), BatchBuilder::add_store).build())
+            .collect()
+    }
+
+    /// Create `StoreQuery` to store the given JSON document
+    fn store_json_query(json: String) -> StoreQuery where Self: Sized {
+        StoreQuery::from_json::<Self>(json)
+    }
+
+    /// Create `UpdateQuery` containing the prepared statement
+    /// to update this entity
+    ///
+    /// The statement only can update columns that are not
+    /// part of the primary keys. Only tables whose updates cannot
+    /// fail have it, see `InfallibleQueries`.
+    fn update_query(&self) -> Result<UpdateQuery, TableWithNoUpdatableColumnsError> where Self: InfallibleQueries {
+        self.infallible_update_query()
+    }
+
+    /// Same as `update_query`, for all tables, also returning the
+    /// serialization error of a `#[column(json)]` field and the
+    /// rejection of the query hook
+    fn try_update_query(&self) -> Result<UpdateQuery, Error> {
+        checked(self.update_query_without_hook()?)
+    }
+
+    /// Same as `try_update_query`, without checking the query with the query hook
+    fn update_query_without_hook(&self) -> Result<UpdateQuery, Error>;
+
+    /// Same as `update_query`, moving the fields into the values,
+    /// see `into_store_query`
+    fn into_update_query(self) -> Result<UpdateQuery, TableWithNoUpdatableColumnsError> where Self: Sized + InfallibleQueries {
+        self.infallible_into_update_query()
+    }
+
+    /// Create `UpdateQuery` to update only the given columns of this entity
+    ///
+    /// Columns must be non key columns. The values of the columns are
+    /// bound in the order they were given, followed by the key values.
+    fn update_columns_query(&self, columns: &[&str]) -> Result<UpdateQuery, Error> where Self: Sized {
+        checked(columns_update_query(self, columns)?)
+    }
+
+    /// Create `UpdateQuery` to update only the static columns of
+    /// the partition of this entity, cluster keys are not bound
+    fn update_static_query(&self) -> Result<UpdateQuery, Error> where Self: Sized {
+        if Self::static_columns().is_empty() {
+            return Err(Error::InvalidColumn(format!("Table {} does not have static columns", Self::table_name())));
+        }
+
+        let set_part = Self::static_columns().iter().map(|c| format!("{}=?", c)).collect::<Vec<String>>().join(",");
+
+        let values = column_values(self, &[Self::static_columns(), Self::primary_key_columns()].concat())?;
+
+        checked(UpdateQuery::for_table::<Self>(format!("UPDATE {}.{} SET {} WHERE {}",
+                                                       Self::key_space(),
+                                                       Self::table_name(),
+                                                       set_part,
+                                                       key_where(Self::primary_key_columns())), QueryValues::SimpleValues(values))
+            .with_idempotent(Self::idempotence().update))
+    }
+
+    /// Create `UpdateQuery` to update this entity and refresh the time
+    /// to live of the updated columns, `UPDATE ... USING TTL ? SET ...`
+    ///
+    /// The TTL is bound first, before the set and where values.
+    fn update_query_with_ttl(&self, ttl: u32) -> Result<UpdateQuery, Error> {
+        checked(with_update_ttl(self.update_query_without_hook()?, ttl)?)
+    }
+
+    /// Same as `update_columns_query` with `USING TTL ?`,
+    /// the TTL is bound first, before the values of the columns
+    fn update_columns_query_with_ttl(&self, columns: &[&str], ttl: u32) -> Result<UpdateQuery, Error> where Self: Sized {
+        checked(with_update_ttl(columns_update_query(self, columns)?, ttl)?)
+    }
+
+    /// Create `UpdateQuery` to update this entity only
+    /// if it exists, `UPDATE ... IF EXISTS`
+    ///
+    /// The query is marked as lightweight transaction.
+    fn update_query_if_exists(&self) -> Result<UpdateQuery, Error> {
+        let query = self.update_query_without_hook()?;
+
+        checked(UpdateQuery::for_table::<Self>(format!("{} IF EXISTS", query.query), query.values).lwt())
+    }
+
+    /// Create `UpdateQuery` to update this entity only if the given
+    /// conditions hold, `UPDATE ... IF a=? AND b=?`
+    ///
+    /// Conditions must reference non key columns. Condition values are
+    /// bound after the set and where values, in the order they were given.
+    /// The query is marked as lightweight transaction.
+    fn update_query_if(&self, conditions: &[(&str, Value)]) -> Result<UpdateQuery, Error> {
+        let columns = conditions.iter().map(|(c, _)| *c).collect::<Vec<&str>>();
+
+        validate_non_key_columns::<Self>(&columns)?;
+
+        let query = self.update_query_without_hook()?;
+
+        let if_part = columns.iter().map(|c| format!("{}=?", c)).collect::<Vec<String>>().join(" AND ");
+
+        let values = conditions.iter().map(|(_, v)| v.to_owned()).collect();
+
+        checked(UpdateQuery::for_table::<Self>(format!("{} IF {}", query.query, if_part), with_values(query.values, values)?).lwt())
+    }
+
+    /// Create `UpdateQuery` to increment or decrement a `COUNTER` column
+    ///
+    /// Only the key values are bound, the `i64` delta must be
+    /// bound first, see `UpdateQuery::prepend_values`.
+    /// The query is marked as counter mutation.
+    fn counter_delta_query(&self, column: &str) -> Result<UpdateQuery, Error> where Self: Sized {
+        let statement = Self::counter_delta_cql(column)?;
+
+        let values = column_values(self, &[Self::primary_key_columns(), Self::cluster_key_columns()].concat())?;
+
+        checked(UpdateQuery::for_table::<Self>(statement, QueryValues::SimpleValues(values)).counter())
+    }
+
+    /// Create `UpdateQuery` to append to a `list` or
+    /// add to a `set` column, `SET c = c + ?`
+    ///
+    /// Only the key values are bound, the collection with the
+    /// elements to add must be bound first, see `UpdateQuery::prepend_values`.
+    fn collection_append_query(&self, column: &str) -> Result<UpdateQuery, Error> where Self: Sized {
+        typed_column::<Self>(column, |t| matches!(t, CqlType::List(_) | CqlType::Set(_)))?;
+
+        key_update_query(self, format!("{}={}+?", column, column))
+    }
+
+    /// Create `UpdateQuery` to prepend to a `list` column, `SET c = ? + c`
+    ///
+    /// Only the key values are bound, the list with the
+    /// elements to prepend must be bound first.
+    fn collection_prepend_query(&self, column: &str) -> Result<UpdateQuery, Error> where Self: Sized {
+        typed_column::<Self>(column, |t| matches!(t, CqlType::List(_)))?;
+
+        key_update_query(self, format!("{}=?+{}", column, column))
+    }
+
+    /// Create `UpdateQuery` to remove elements of a `list` or `set`,
+    /// or keys of a `map` column, `SET c = c - ?`
+    ///
+    /// Only the key values are bound, the elements, or the set
+    /// of map keys, to remove must be bound first.
+    fn collection_remove_query(&self, column: &str) -> Result<UpdateQuery, Error> where Self: Sized {
+        typed_column::<Self>(column, CqlType::is_collection)?;
+
+        key_update_query(self, format!("{}={}-?", column, column))
+    }
+
+    /// Create `UpdateQuery` to put an entry in a `map` column, `SET c[?] = ?`
+    ///
+    /// Only the key values are bound, the map key
+    /// and the value must be bound first.
+    fn map_put_query(&self, column: &str) -> Result<UpdateQuery, Error> where Self: Sized {
+        typed_column::<Self>(column, |t| matches!(t, CqlType::Map(_, _)))?;
+
+        key_update_query(self, format!("{}[?]=?", column))
+    }
+
+    /// Create `DeleteQuery` containing the prepared statement
+    /// to delete this entity
+    fn delete_query(&self) -> DeleteQuery;
+
+    /// Same as `delete_query`, returning the rejection of the query hook
+    fn try_delete_query(&self) -> Result<DeleteQuery, Error> {
+        checked(self.delete_query())
+    }
+
+    /// Create `DeleteQuery` containing the prepared statement
+    /// to delete the whole partition of this entity
+    ///
+    /// Only the primary keys are bound, for tables without cluster
+    /// keys it is the same as `delete_query`.
+    fn delete_partition_query(&self) -> DeleteQuery;
+
+    /// Create `DeleteQuery` to delete a cluster key range
+    /// within the partition of this entity
+    ///
+    /// The primary keys of this entity are bound first, then
+    /// the value of each filter, in the order they were given.
+    fn delete_cluster_range_query(&self, filters: &[(&str, ClusterKeyFilter, Value)]) -> Result<DeleteQuery, Error> {
+        let range = filters.iter().map(|(c, f, _)| (*c, *f)).collect::<Vec<(&str, ClusterKeyFilter)>>();
+
+        let statement = Self::delete_by_cluster_range(&range)?;
+
+        let mut values = column_values(self, Self::primary_key_columns())?;
+        values.extend(filters.iter().map(|(_, _, v)| v.to_owned()));
+
+        checked(DeleteQuery::for_table::<Self>(statement, QueryValues::SimpleValues(values)).with_idempotent(Self::idempotence().delete))
+    }
+
+    /// Create `DeleteQuery` to delete this entity only
+    /// if it exists, `DELETE ... IF EXISTS`
+    ///
+    /// The query is marked as lightweight transaction.
+    fn delete_query_if_exists(&self) -> Result<DeleteQuery, Error> {
+        let query = self.delete_query();
+
+        checked(DeleteQuery::for_table::<Self>(format!("{} IF EXISTS", query.query), query.values).lwt())
+    }
+
+    /// Create `DeleteQuery` to delete this entity only if the given
+    /// conditions hold, `DELETE ... IF a=? AND b=?`
+    ///
+    /// Conditions must reference non key columns. Condition values are
+    /// bound after the key values, in the order they were given.
+    /// The query is marked as lightweight transaction.
+    fn delete_query_if(&self, conditions: &[(&str, Value)]) -> Result<DeleteQuery, Error> {
+        let columns = conditions.iter().map(|(c, _)| *c).collect::<Vec<&str>>();
+
+        validate_non_key_columns::<Self>(&columns)?;
+
+        let query = self.delete_query();
+
+        let if_part = columns.iter().map(|c| format!("{}=?", c)).collect::<Vec<String>>().join(" AND ");
+
+        let values = conditions.iter().map(|(_, v)| v.to_owned()).collect();
+
+        checked(DeleteQuery::for_table::<Self>(format!("{} IF {}", query.query, if_part), with_values(query.values, values)?).lwt())
+    }
+
+    /// Create `DeleteQuery` to delete this entity with the given write
+    /// timestamp, so that writes with a newer timestamp are kept
+    ///
+    /// The timestamp is bound first, before the key values.
+    fn delete_query_with_timestamp(&self, timestamp_micros: i64) -> Result<DeleteQuery, Error> {
+        let query = self.delete_query();
+        let idempotent = query.is_idempotent();
+
+        let values = prepend_values(vec![Value::from(timestamp_micros)], query.values)?;
+
+        checked(DeleteQuery::for_table::<Self>(Self::delete_by_primary_and_cluster_keys_with_timestamp(), values)
+            .with_idempotent(idempotent))
+    }
+
+    /// Create `DeleteQuery` to delete only the given columns
+    /// of this entity, `DELETE a,b FROM ...`
+    ///
+    /// Columns must be non key columns.
+    fn delete_columns_query(&self, columns: &[&str]) -> Result<DeleteQuery, Error> {
+        validate_non_key_columns::<Self>(columns)?;
+
+        let query = self.delete_query();
+        let idempotent = query.is_idempotent();
+
+        checked(DeleteQuery::for_table::<Self>(format!("DELETE {} FROM {}.{} WHERE {}",
+                                                       columns.join(","),
+                                                       Self::key_space(),
+                                                       Self::table_name(),
+                                                       full_key_where::<Self>()), query.values)
+            .with_idempotent(idempotent))
+    }
+
+    /// Create `SelectQuery` containing the prepared statement
+    /// to select this entity by primary and cluster keys
+    ///
+    /// Key values are bound in the same order as the where clause.
+    fn select_query(&self, projection: Projection) -> SelectQuery;
+
+    /// Primary and cluster keys of this entity
+    fn key(&self) -> Self::Key;
+
+    /// Create `SelectQuery` to select the row of the key, the same
+    /// statement and values as `select_query` of an entity with the key
+    fn select_by_key(key: &Self::Key, projection: Projection) -> SelectQuery;
+
+    /// Create `DeleteQuery` to delete the row of the key, the same
+    /// statement and values as `delete_query` of an entity with the key
+    fn delete_by_key(key: &Self::Key) -> DeleteQuery;
+
+    /// Create `SelectQuery` counting the rows with the
+    /// primary and cluster keys of this entity
+    ///
+    /// The count is either 0 or 1.
+    fn exists_query(&self) -> SelectQuery;
+
+    /// Create `SelectQuery` counting the rows in the
+    /// partition of this entity
+    ///
+    /// Only the primary keys are bound, cluster keys are ignored.
+    fn count_partition_query(&self) -> SelectQuery;
+
+    /// Statement with `KEY_SPACE_PLACEHOLDER` instead of the keyspace of
+    /// the `table` attribute, which the `_in` methods replace by theirs
+    ///
+    /// The select statements have `{}` in place of the selection, the
+    /// update of a table with no updatable columns is empty.
+    fn statement_template(statement: TableStatement) -> &'static str;
+
+    /// Same as `create_table_cql`, in the keyspace `ks`
+    /// instead of the keyspace of the `table` attribute
+    fn create_table_cql_in(ks: &str) -> Result<String, Error> {
+        in_key_space(Self::statement_template(TableStatement::CreateTable), ks)
+    }
+
+    /// Same as `drop_table_cql`, in the keyspace `ks`
+    fn drop_table_cql_in(ks: &str) -> Result<String, Error> {
+        in_key_space(Self::statement_template(TableStatement::DropTable), ks)
+    }
+
+    /// Same as `select_all`, in the keyspace `ks`
+    fn select_all_in(ks: &str, projection: Projection) -> Result<String, Error> {
+        select_in_key_space(Self::statement_template(TableStatement::SelectAll), ks, projection)
+    }
+
+    /// Same as `select_by_primary_keys`, in the keyspace `ks`
+    fn select_by_primary_keys_in(ks: &str, projection: Projection) -> Result<String, Error> {
+        select_in_key_space(Self::statement_template(TableStatement::SelectByPrimaryKeys), ks, projection)
+    }
+
+    /// Same as `select_by_primary_and_cluster_keys`, in the keyspace `ks`
+    fn select_by_primary_and_cluster_keys_in(ks: &str, projection: Projection) -> Result<String, Error> {
+        select_in_key_space(Self::statement_template(TableStatement::SelectByPrimaryAndClusterKeys), ks, projection)
+    }
+
+    /// Same as `store_query`, in the keyspace `ks`
+    fn store_query_in(&self, ks: &str) -> Result<StoreQuery, Error> {
+        let query = self.store_query_without_hook()?;
+        let idempotent = query.is_idempotent();
+        let (_, values) = query.into_parts();
+
+        checked(StoreQuery::new(in_key_space(Self::statement_template(TableStatement::Store), ks)?, values).with_table(ks.to_string(), Self::table_name()).with_idempotent(idempotent))
+    }
+
+    /// Same as `update_query`, in the keyspace `ks`
+    fn update_query_in(&self, ks: &str) -> Result<UpdateQuery, Error> {
+        let query = self.update_query_without_hook()?;
+        let idempotent = query.is_idempotent();
+        let (_, values) = query.into_parts();
+
+        checked(UpdateQuery::new(in_key_space(Self::statement_template(TableStatement::Update), ks)?, values).with_table(ks.to_string(), Self::table_name()).with_idempotent(idempotent))
+    }
+
+    /// Same as `delete_query`, in the keyspace `ks`
+    fn delete_query_in(&self, ks: &str) -> Result<DeleteQuery, Error> {
+        let query = self.delete_query();
+        let idempotent = query.is_idempotent();
+        let (_, values) = query.into_parts();
+
+        checked(DeleteQuery::new(in_key_space(Self::statement_template(TableStatement::Delete), ks)?, values).with_table(ks.to_string(), Self::table_name()).with_idempotent(idempotent))
+    }
+
+    /// Same as `select_query`, in the keyspace `ks`
+    fn select_query_in(&self, ks: &str, projection: Projection) -> Result<SelectQuery, Error> {
+        let values = column_values(self, &[Self::primary_key_columns(), Self::cluster_key_columns()].concat())?;
+
+        Ok(SelectQuery::new(Self::select_by_primary_and_cluster_keys_in(ks, projection)?, QueryValues::SimpleValues(values)).with_table(ks.to_string(), Self::table_name()))
+    }
+}
+
+/// Table whose inserts and updates always have their values, which
+/// `#[derive(Cassandra)]` implements unless the table has fields that
+/// can fail to convert, `#[column(json)]` fields serialized by serde,
+/// `std::time::Duration` fields and decimal fields, nor counter tables,
+/// whose inserts Cassandra rejects
+///
+/// `store_query`, `update_query` and their `into_` versions are only
+/// available for these tables, calling them on any other table does not
+/// compile. All tables have `try_store_query` and `try_update_query`.
+pub trait InfallibleQueries: CassandraTable {
+    /// Insert of `CassandraTable::store_query`
+    fn infallible_store_query(&self) -> StoreQuery;
+
+    /// Insert of `CassandraTable::into_store_query`
+    fn infallible_into_store_query(self) -> StoreQuery where Self: Sized {
+        self.infallible_store_query()
+    }
+
+    /// Update of `CassandraTable::update_query`
+    fn infallible_update_query(&self) -> Result<UpdateQuery, TableWithNoUpdatableColumnsError>;
+
+    /// Update of `CassandraTable::into_update_query`
+    fn infallible_into_update_query(self) -> Result<UpdateQuery, TableWithNoUpdatableColumnsError> where Self: Sized {
+        self.infallible_update_query()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoreQuery {
+    query: Cow<'static, str>,
+    values: QueryValues,
+    keyspace: Cow<'static, str>,
+    table: &'static str,
+    lwt: bool,
+    idempotent: bool,
+    consistency: Option<Consistency>,
+}
+
+impl StoreQuery {
+    /// New instance
+    pub fn new<Q: Into<Cow<'static, str>>>(query: Q, values: QueryValues) -> Self {
+        StoreQuery { query: query.into(), values, keyspace: Cow::Borrowed(""), table: "", lwt: false, idempotent: false, consistency: None }
+    }
+
+    /// Same as `new` followed by `with_table` of the table of `T`
+    pub fn for_table<T: CassandraTable + ?Sized>(query: impl Into<Cow<'static, str>>, values: QueryValues) -> Self {
+        StoreQuery::new(query, values).with_table(T::key_space(), T::table_name())
+    }
+
+    /// Same as `new` followed by `with_table`, failing if the query hook rejects it
+    pub fn try_new<Q, K>(query: Q, values: QueryValues, keyspace: K, table: &'static str) -> Result<Self, Error>
+        where Q: Into<Cow<'static, str>>,
+              K: Into<Cow<'static, str>> {
+        let query = StoreQuery::new(query, values).with_table(keyspace, table);
+
+        check_query_hook(&query)?;
+
+        Ok(query)
+    }
+
+    /// Keyspace and table of the statement, for metrics and routing
+    pub fn with_table<K: Into<Cow<'static, str>>>(mut self, keyspace: K, table: &'static str) -> Self {
+        self.keyspace = keyspace.into();
+        self.table = table;
+        self
+    }
+
+    /// Keyspace of the table of the statement, empty if it is not known
+    pub fn keyspace(&self) -> &str {
+        &self.keyspace
+    }
+
+    /// Table of the statement, empty if it is not known
+    pub fn table(&self) -> &str {
+        self.table
+    }
+
+    /// Operation of the statement, always `QueryKind::Insert`
+    pub fn kind(&self) -> QueryKind {
+        QueryKind::Insert
+    }
+
+    /// Mark the query as a lightweight transaction
+    pub fn lwt(mut self) -> Self {
+        self.lwt = true;
+        self
+    }
+
+    /// If the query is a lightweight transaction, which
+    /// must be executed with a serial consistency level
+    pub fn is_lwt(&self) -> bool {
+        self.lwt
+    }
+
+    /// Mark the query as safe to retry, or not
+    pub fn with_idempotent(mut self, idempotent: bool) -> Self {
+        self.idempotent = idempotent;
+        self
+    }
+
+    /// If the query is safe to retry, as marked with `with_idempotent`,
+    /// a lightweight transaction never is
+    pub fn is_idempotent(&self) -> bool {
+        self.idempotent && !self.lwt
+    }
+
+    /// Execute the query with the given consistency level
+    ///
+    /// The level is advisory, driver wrappers read it
+    /// when building the query parameters.
+    pub fn with_consistency(mut self, consistency: Consistency) -> Self {
+        self.consistency = Some(consistency);
+        self
+    }
+
+    /// Consistency level to execute the query with, if any
+    pub fn consistency(&self) -> Option<Consistency> {
+        self.consistency
+    }
+
+    /// New instance storing a JSON document in the table `T`
+    pub fn from_json<T: CassandraTable>(json: String) -> Self {
+        StoreQuery::for_table::<T>(T::store_json_cql(), QueryValues::SimpleValues(vec![Value::from(json)]))
+            .with_idempotent(T::idempotence().store)
+    }
+
+    /// Prepared statement for insertion
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Values for executing prepared statement
+    pub fn values(&self) -> &QueryValues {
+        &self.values
+    }
+    /// Prepared statement and values, moving the
+    /// values out of the query without cloning them
+    pub fn into_parts(self) -> (Cow<'static, str>, QueryValues) {
+        (self.query, self.values)
+    }
+    /// Values for executing prepared statement, without cloning them
+    pub fn into_values(self) -> QueryValues {
+        self.values
+    }
+}
+
+impl Display for StoreQuery {
+    /// Only display the prepared statement
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "query:{}", self.query)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateQuery {
+    query: Cow<'static, str>,
+    values: QueryValues,
+    keyspace: Cow<'static, str>,
+    table: &'static str,
+    lwt: bool,
+    counter: bool,
+    idempotent: bool,
+    consistency: Option<Consistency>,
+}
+
+impl UpdateQuery {
+    /// New instance
+    pub fn new<Q: Into<Cow<'static, str>>>(query: Q, values: QueryValues) -> Self {
+        UpdateQuery { query: query.into(), values, keyspace: Cow::Borrowed(""), table: "", lwt: false, counter: false, idempotent: false, consistency: None }
+    }
+
+    /// Same as `new` followed by `with_table` of the table of `T`
+    pub fn for_table<T: CassandraTable + ?Sized>(query: impl Into<Cow<'static, str>>, values: QueryValues) -> Self {
+        UpdateQuery::new(query, values).with_table(T::key_space(), T::table_name())
+    }
+
+    /// Same as `new` followed by `with_table`, failing if the query hook rejects it
+    pub fn try_new<Q, K>(query: Q, values: QueryValues, keyspace: K, table: &'static str) -> Result<Self, Error>
+        where Q: Into<Cow<'static, str>>,
+              K: Into<Cow<'static, str>> {
+        let query = UpdateQuery::new(query, values).with_table(keyspace, table);
+
+        check_query_hook(&query)?;
+
+        Ok(query)
+    }
+
+    /// Keyspace and table of the statement, for metrics and routing
+    pub fn with_table<K: Into<Cow<'static, str>>>(mut self, keyspace: K, table: &'static str) -> Self {
+        self.keyspace = keyspace.into();
+        self.table = table;
+        self
+    }
+
+    /// Keyspace of the table of the statement, empty if it is not known
+    pub fn keyspace(&self) -> &str {
+        &self.keyspace
+    }
+
+    /// Table of the statement, empty if it is not known
+    pub fn table(&self) -> &str {
+        self.table
+    }
+
+    /// Operation of the statement, always `QueryKind::Update`
+    pub fn kind(&self) -> QueryKind {
+        QueryKind::Update
+    }
+
+    /// Mark the query as a counter mutation
+    pub fn counter(mut self) -> Self {
+        self.counter = true;
+        self
+    }
+
+    /// If the query is a counter mutation, which only
+    /// can be batched in a counter batch
+    pub fn is_counter(&self) -> bool {
+        self.counter
+    }
+
+    /// Mark the query as a lightweight transaction
+    pub fn lwt(mut self) -> Self {
+        self.lwt = true;
+        self
+    }
+
+    /// If the query is a lightweight transaction, which
+    /// must be executed with a serial consistency level
+    pub fn is_lwt(&self) -> bool {
+        self.lwt
+    }
+
+    /// Mark the query as safe to retry, or not
+    pub fn with_idempotent(mut self, idempotent: bool) -> Self {
+        self.idempotent = idempotent;
+        self
+    }
+
+    /// If the query is safe to retry, as marked with `with_idempotent`,
+    /// a lightweight transaction or a counter mutation never is
+    pub fn is_idempotent(&self) -> bool {
+        self.idempotent && !self.lwt && !self.counter
+    }
+
+    /// Execute the query with the given consistency level
+    ///
+    /// The level is advisory, driver wrappers read it
+    /// when building the query parameters.
+    pub fn with_consistency(mut self, consistency: Consistency) -> Self {
+        self.consistency = Some(consistency);
+        self
+    }
+
+    /// Consistency level to execute the query with, if any
+    pub fn consistency(&self) -> Option<Consistency> {
+        self.consistency
+    }
+    /// Prepared statement for update
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+    /// Values for executing prepared statement
+    pub fn values(&self) -> &QueryValues {
+        &self.values
+    }
+    /// Prepared statement and values, moving the
+    /// values out of the query without cloning them
+    pub fn into_parts(self) -> (Cow<'static, str>, QueryValues) {
+        (self.query, self.values)
+    }
+    /// Values for executing prepared statement, without cloning them
+    pub fn into_values(self) -> QueryValues {
+        self.values
+    }
+    /// Bind the given values before the values of the query,
+    /// e.g. the delta of a collection update
+    ///
+    /// The values of the query must not have names.
+    pub fn prepend_values(mut self, values: Vec<Value>) -> Result<Self, Error> {
+        self.values = prepend_values(values, self.values)?;
+        Ok(self)
+    }
+}
+
+impl Display for UpdateQuery {
+    /// Only display the prepared statement
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "query:{}", self.query)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeleteQuery {
+    query: Cow<'static, str>,
+    values: QueryValues,
+    keyspace: Cow<'static, str>,
+    table: &'static str,
+    lwt: bool,
+    idempotent: bool,
+    consistency: Option<Consistency>,
+}
+
+impl DeleteQuery {
+    /// New instance
+    pub fn new<Q: Into<Cow<'static, str>>>(query: Q, values: QueryValues) -> Self {
+        DeleteQuery { query: query.into(), values, keyspace: Cow::Borrowed(""), table: "", lwt: false, idempotent: false, consistency: None }
+    }
+
+    /// Same as `new` followed by `with_table` of the table of `T`
+    pub fn for_table<T: CassandraTable + ?Sized>(query: impl Into<Cow<'static, str>>, values: QueryValues) -> Self {
+        DeleteQuery::new(query, values).with_table(T::key_space(), T::table_name())
+    }
+
+    /// Same as `new` followed by `with_table`, failing if the query hook rejects it
+    pub fn try_new<Q, K>(query: Q, values: QueryValues, keyspace: K, table: &'static str) -> Result<Self, Error>
+        where Q: Into<Cow<'static, str>>,
+              K: Into<Cow<'static, str>> {
+        let query = DeleteQuery::new(query, values).with_table(keyspace, table);
+
+        check_query_hook(&query)?;
+
+        Ok(query)
+    }
+
+    /// Keyspace and table of the statement, for metrics and routing
+    pub fn with_table<K: Into<Cow<'static, str>>>(mut self, keyspace: K, table: &'static str) -> Self {
+        self.keyspace = keyspace.into();
+        self.table = table;
+        self
+    }
+
+    /// Keyspace of the table of the statement, empty if it is not known
+    pub fn keyspace(&self) -> &str {
+        &self.keyspace
+    }
+
+    /// Table of the statement, empty if it is not known
+    pub fn table(&self) -> &str {
+        self.table
+    }
+
+    /// Operation of the statement, always `QueryKind::Delete`
+    pub fn kind(&self) -> QueryKind {
+        QueryKind::Delete
+    }
+
+    /// Mark the query as a lightweight transaction
+    pub fn lwt(mut self) -> Self {
+        self.lwt = true;
+        self
+    }
+
+    /// If the query is a lightweight transaction, which
+    /// must be executed with a serial consistency level
+    pub fn is_lwt(&self) -> bool {
+        self.lwt
+    }
+
+    /// Mark the query as safe to retry, or not
+    pub fn with_idempotent(mut self, idempotent: bool) -> Self {
+        self.idempotent = idempotent;
+        self
+    }
+
+    /// If the query is safe to retry, as marked with `with_idempotent`,
+    /// a lightweight transaction never is
+    pub fn is_idempotent(&self) -> bool {
+        self.idempotent && !self.lwt
+    }
+
+    /// Execute the query with the given consistency level
+    ///
+    /// The level is advisory, driver wrappers read it
+    /// when building the query parameters.
+    pub fn with_consistency(mut self, consistency: Consistency) -> Self {
+        self.consistency = Some(consistency);
+        self
+    }
+
+    /// Consistency level to execute the query with, if any
+    pub fn consistency(&self) -> Option<Consistency> {
+        self.consistency
+    }
+
+    /// Prepared statement for deletion
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Values for executing prepared statement
+    pub fn values(&self) -> &QueryValues {
+        &self.values
+    }
+    /// Prepared statement and values, moving the
+    /// values out of the query without cloning them
+    pub fn into_parts(self) -> (Cow<'static, str>, QueryValues) {
+        (self.query, self.values)
+    }
+    /// Values for executing prepared statement, without cloning them
+    pub fn into_values(self) -> QueryValues {
+        self.values
+    }
+}
+
+impl Display for DeleteQuery {
+    /// Only display the prepared statement
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "query:{}", self.query)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectQuery {
+    query: Cow<'static, str>,
+    values: QueryValues,
+    keyspace: Cow<'static, str>,
+    table: &'static str,
+    page_size: Option<i32>,
+    paging_state: Option<Vec<u8>>,
+}
+
+impl SelectQuery {
+    /// New instance
+    pub fn new<Q: Into<Cow<'static, str>>>(query: Q, values: QueryValues) -> Self {
+        SelectQuery { query: query.into(), values, keyspace: Cow::Borrowed(""), table: "", page_size: None, paging_state: None }
+    }
+
+    /// Same as `new` followed by `with_table` of the table of `T`
+    pub fn for_table<T: CassandraTable + ?Sized>(query: impl Into<Cow<'static, str>>, values: QueryValues) -> Self {
+        SelectQuery::new(query, values).with_table(T::key_space(), T::table_name())
+    }
+
+    /// Keyspace and table of the statement, for metrics and routing
+    pub fn with_table<K: Into<Cow<'static, str>>>(mut self, keyspace: K, table: &'static str) -> Self {
+        self.keyspace = keyspace.into();
+        self.table = table;
+        self
+    }
+
+    /// Keyspace of the table of the statement, empty if it is not known
+    pub fn keyspace(&self) -> &str {
+        &self.keyspace
+    }
+
+    /// Table of the statement, empty if it is not known
+    pub fn table(&self) -> &str {
+        self.table
+    }
+
+    /// Operation of the statement, always `QueryKind::Select`
+    pub fn kind(&self) -> QueryKind {
+        QueryKind::Select
+    }
+
+    /// Fetch the rows in pages of the given size
+    pub fn with_page_size(mut self, page_size: i32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Continue from the paging state returned with the previous page
+    pub fn with_paging_state(mut self, paging_state: Vec<u8>) -> Self {
+        self.paging_state = Some(paging_state);
+        self
+    }
+
+    /// Size of the pages, if any
+    pub fn page_size(&self) -> Option<i32> {
+        self.page_size
+    }
+
+    /// Paging state of the page to fetch, if any
+    pub fn paging_state(&self) -> Option<&[u8]> {
+        self.paging_state.as_deref()
+    }
+
+    /// Prepared statement for selection
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Values for executing prepared statement
+    pub fn values(&self) -> &QueryValues {
+        &self.values
+    }
+}
+
+impl Display for SelectQuery {
+    /// Only display the prepared statement
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "query:{}", self.query)
+    }
+}
+
+/// Operation of a query, e.g. for tagging metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QueryKind {
+    Insert,
+    Update,
+    Delete,
+    Select,
+    Batch,
+}
+
+impl Display for QueryKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryKind::Insert => write!(f, "insert"),
+            QueryKind::Update => write!(f, "update"),
+            QueryKind::Delete => write!(f, "delete"),
+            QueryKind::Select => write!(f, "select"),
+            QueryKind::Batch => write!(f, "batch"),
+        }
+    }
+}
+
+/// Prepared statement and its values, implemented by all
+/// query structs for executing them in a generic way
+pub trait CqlQuery {
+    /// Prepared statement
+    fn query(&self) -> &str;
+
+    /// Values for executing prepared statement
+    fn values(&self) -> &QueryValues;
+
+    /// Keyspace of the table of the statement,
+    /// empty if it is not known
+    fn keyspace(&self) -> &str;
+
+    /// Table of the statement, empty if it is not known
+    fn table(&self) -> &str;
+
+    /// Operation of the statement
+    fn kind(&self) -> QueryKind;
+
+    /// Consistency level to execute the query with, if any
+    fn consistency(&self) -> Option<Consistency> {
+        None
+    }
+
+    /// If the query is safe to retry, which is not known by default
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+
+    /// Display the prepared statement followed by the bound values,
+    /// which should only be used in logs that may hold the data
+    fn display_with_values(&self) -> QueryWithValues<'_> {
+        QueryWithValues::new(self.query(), self.values())
+    }
+
+    /// Values for executing the prepared statement with the `scylla` driver,
+    /// copied from the `QueryValues` of cdrs
+    #[cfg(feature = "scylla")]
+    fn serialized_values(&self) -> Result<scylla::frame::value::SerializedValues, Error> {
+        scylla_values::serialized_values(self.values())
+    }
+}
+
+/// Prepared statement and its bound values, see `CqlQuery::display_with_values`
+///
+/// Values are rendered by the CQL type of their column when it is
+/// known, see `with_columns`, and as hexadecimal otherwise.
+pub struct QueryWithValues<'a> {
+    query: &'a str,
+    values: &'a QueryValues,
+    columns: Vec<&'a str>,
+    column_type: fn(&str) -> Option<&'static str>,
+    redacted: Vec<&'a str>,
+}
+
+impl<'a> QueryWithValues<'a> {
+    fn new(query: &'a str, values: &'a QueryValues) -> Self {
+        QueryWithValues { query, values, columns: vec![], column_type: |_| None, redacted: vec![] }
+    }
+
+    /// Render the values by the types of the columns of the table `T`,
+    /// the values bound by position being the values of `columns`, in order
+    ///
+    /// Values with names are of the column with the same name.
+    pub fn with_columns<T: CassandraTable + ?Sized>(mut self, columns: &[&'a str]) -> Self {
+        self.columns = columns.to_vec();
+        self.column_type = T::column_type;
+        self
+    }
+
+    /// Render the values of `columns` as `<redacted>`, the values bound
+    /// by position are redacted when `with_columns` names their column
+    pub fn redact(mut self, columns: &[&'a str]) -> Self {
+        self.redacted.extend(columns);
+        self
+    }
+
+    fn render(&self, column: Option<&str>, value: &Value) -> String {
+        match column {
+            Some(column) if self.redacted.contains(&column) => String::from("<redacted>"),
+            _ => render_value(column.and_then(self.column_type), value),
+        }
+    }
+}
+
+impl Display for QueryWithValues<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let values = match self.values {
+            QueryValues::SimpleValues(values) => values.iter()
+                .enumerate()
+                .map(|(i, v)| self.render(self.columns.get(i).copied(), v))
+                .collect::<Vec<String>>(),
+            QueryValues::NamedValues(values) => {
+                let mut names = values.keys().collect::<Vec<&String>>();
+                names.sort();
+
+                names.iter().map(|n| format!("{}={}", n, self.render(Some(n), &values[*n]))).collect()
+            }
+        };
+
+        write!(f, "query:{} values:[{}]", self.query, values.join(", "))
+    }
+}
+
+/// Value rendered by its declared CQL type, as hexadecimal when the
+/// type is not known or the bytes do not have the size of the type
+fn render_value(declared: Option<&str>, value: &Value) -> String {
+    let bytes = value.body.as_slice();
+
+    let native = declared.and_then(|d| CqlType::parse(d).ok()).and_then(|t| match t {
+        CqlType::Native(name) => Some(name),
+        _ => None,
+    });
+
+    let rendered = match value.value_type {
+        ValueType::Null => Some(String::from("null")),
+        ValueType::NotSet => Some(String::from("unset")),
+        ValueType::Normal(_) => match native.as_deref() {
+            Some("TEXT") | Some("VARCHAR") | Some("ASCII") => std::str::from_utf8(bytes).ok().map(|t| format!("'{}'", t)),
+            Some("BOOLEAN") => match bytes {
+                [b] => Some((*b != 0).to_string()),
+                _ => None,
+            },
+            Some("TINYINT") => <[u8; 1]>::try_from(bytes).ok().map(|b| i8::from_be_bytes(b).to_string()),
+            Some("SMALLINT") => <[u8; 2]>::try_from(bytes).ok().map(|b| i16::from_be_bytes(b).to_string()),
+            Some("INT") => <[u8; 4]>::try_from(bytes).ok().map(|b| i32::from_be_bytes(b).to_string()),
+            Some("BIGINT") | Some("COUNTER") | Some("TIMESTAMP") | Some("TIME") => {
+                <[u8; 8]>::try_from(bytes).ok().map(|b| i64::from_be_bytes(b).to_string())
+            }
+            Some("FLOAT") => <[u8; 4]>::try_from(bytes).ok().map(|b| f32::from_be_bytes(b).to_string()),
+            Some("DOUBLE") => <[u8; 8]>::try_from(bytes).ok().map(|b| f64::from_be_bytes(b).to_string()),
+            Some("UUID") | Some("TIMEUUID") if bytes.len() == 16 => {
+                let hex = hex(bytes);
+                Some(format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..]))
+            }
+            _ => None,
+        },
+    };
+
+    rendered.unwrap_or_else(|| format!("0x{}", hex(bytes)))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl CqlQuery for StoreQuery {
+    fn query(&self) -> &str {
+        &self.query
+    }
+
+    fn values(&self) -> &QueryValues {
+        &self.values
+    }
+
+    fn keyspace(&self) -> &str {
+        &self.keyspace
+    }
+
+    fn table(&self) -> &str {
+        self.table
+    }
+
+    fn kind(&self) -> QueryKind {
+        QueryKind::Insert
+    }
+
+    fn consistency(&self) -> Option<Consistency> {
+        self.consistency
+    }
+
+    fn is_idempotent(&self) -> bool {
+        self.is_idempotent()
+    }
+}
+
+impl CqlQuery for UpdateQuery {
+    fn query(&self) -> &str {
+        &self.query
+    }
+
+    fn values(&self) -> &QueryValues {
+        &self.values
+    }
+
+    fn keyspace(&self) -> &str {
+        &self.keyspace
+    }
+
+    fn table(&self) -> &str {
+        self.table
+    }
+
+    fn kind(&self) -> QueryKind {
+        QueryKind::Update
+    }
+
+    fn consistency(&self) -> Option<Consistency> {
+        self.consistency
+    }
+
+    fn is_idempotent(&self) -> bool {
+        self.is_idempotent()
+    }
+}
+
+impl CqlQuery for DeleteQuery {
+    fn query(&self) -> &str {
+        &self.query
+    }
+
+    fn values(&self) -> &QueryValues {
+        &self.values
+    }
+
+    fn keyspace(&self) -> &str {
+        &self.keyspace
+    }
+
+    fn table(&self) -> &str {
+        self.table
+    }
+
+    fn kind(&self) -> QueryKind {
+        QueryKind::Delete
+    }
+
+    fn consistency(&self) -> Option<Consistency> {
+        self.consistency
+    }
+
+    fn is_idempotent(&self) -> bool {
+        self.is_idempotent()
+    }
+}
+
+impl CqlQuery for SelectQuery {
+    fn query(&self) -> &str {
+        &self.query
+    }
+
+    fn values(&self) -> &QueryValues {
+        &self.values
+    }
+
+    fn keyspace(&self) -> &str {
+        &self.keyspace
+    }
+
+    fn table(&self) -> &str {
+        self.table
+    }
+
+    fn kind(&self) -> QueryKind {
+        QueryKind::Select
+    }
+}
+
+/// Error if user tries to create
+/// invalid update statement
+#[derive(Debug)]
+pub struct TableWithNoUpdatableColumnsError {
+    message: String
+}
+
+impl TableWithNoUpdatableColumnsError {
+    pub fn new(message: String) -> Self {
+        TableWithNoUpdatableColumnsError { message }
+    }
+}
+
+impl std::error::Error for TableWithNoUpdatableColumnsError {}
+
+impl std::fmt::Display for TableWithNoUpdatableColumnsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AggregateFn, cluster_range_where, ClusterKeyFilter, Order, Projection, SelectOptions, Selector};
+
+    #[test]
+    fn test_select_options_order() {
+        let cluster_keys = ["created", "updated"];
+
+        let asc = SelectOptions::new().order("created", Order::Asc).to_cql(&["username"], &cluster_keys).unwrap();
+
+        assert_eq!(asc, "ORDER BY created ASC");
+
+        let desc = SelectOptions::new()
+            .order("created", Order::Desc)
+            .order("updated", Order::Desc)
+            .limit()
+            .to_cql(&["username"], &cluster_keys)
+            .unwrap();
+
+        assert_eq!(desc, "ORDER BY created DESC,updated DESC LIMIT ?");
+    }
+
+    #[test]
+    fn test_select_options_order_by_non_cluster_key() {
+        let result = SelectOptions::new().order("first_name", Order::Asc).to_cql(&["username"], &["created"]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_aggregate_projection() {
+        let functions = [
+            (AggregateFn::Min, "min(created) AS min_created"),
+            (AggregateFn::Max, "max(created) AS max_created"),
+            (AggregateFn::Sum, "sum(created) AS sum_created"),
+            (AggregateFn::Avg, "avg(created) AS avg_created"),
+            (AggregateFn::Count, "count(created) AS count_created"),
+        ];
+
+        for (function, expected) in functions.iter() {
+            let projection = Projection::Aggregate(*function, String::from("created"), None);
+
+            assert_eq!(projection.selection(), *expected);
+        }
+
+        let aliased = Projection::Aggregate(AggregateFn::Max, String::from("created"), Some(String::from("last")));
+
+        assert_eq!(aliased.selection(), "max(created) AS last");
+    }
+
+    #[test]
+    fn test_aggregate_projection_validation() {
+        let projection = Projection::Selectors(vec![
+            Selector::Column(String::from("username")),
+            Selector::Aggregate(AggregateFn::Max, String::from("craeted"), None),
+        ]);
+
+        assert!(projection.validate(&["username", "created"], &["username"]).is_err());
+    }
+
+    #[test]
+    fn test_select_options_group_by() {
+        let primary_keys = ["tenant", "username"];
+        let cluster_keys = ["created", "updated"];
+
+        let by_partition = SelectOptions::new()
+            .group_by(&["tenant", "username"])
+            .to_cql(&primary_keys, &cluster_keys)
+            .unwrap();
+
+        assert_eq!(by_partition, "GROUP BY tenant,username");
+
+        let by_partition_and_cluster = SelectOptions::new()
+            .limit()
+            .order("created", Order::Desc)
+            .group_by(&["tenant", "username", "created"])
+            .to_cql(&primary_keys, &cluster_keys)
+            .unwrap();
+
+        assert_eq!(by_partition_and_cluster, "GROUP BY tenant,username,created ORDER BY created DESC LIMIT ?");
+
+        assert!(SelectOptions::new().group_by(&["tenant"]).to_cql(&primary_keys, &cluster_keys).is_err());
+        assert!(SelectOptions::new().group_by(&["tenant", "username", "updated"]).to_cql(&primary_keys, &cluster_keys).is_err());
+    }
+
+    #[test]
+    fn test_write_time_and_ttl_projection() {
+        let write_time = Projection::WriteTime(vec![String::from("first_name")]);
+
+        assert_eq!(write_time.selection(), "writetime(first_name) AS writetime_first_name");
+
+        let ttl = Projection::Ttl(vec![String::from("first_name"), String::from("updated")]);
+
+        assert_eq!(ttl.selection(), "ttl(first_name) AS ttl_first_name,ttl(updated) AS ttl_updated");
+
+        let mixed = Projection::Selectors(vec![
+            Selector::Column(String::from("first_name")),
+            Selector::WriteTime(String::from("first_name")),
+            Selector::Ttl(String::from("first_name")),
+        ]);
+
+        assert_eq!(mixed.selection(), "first_name,writetime(first_name) AS writetime_first_name,ttl(first_name) AS ttl_first_name");
+
+        let columns = ["username", "first_name", "updated"];
+
+        assert!(ttl.validate(&columns, &["username"]).is_ok());
+        assert!(Projection::WriteTime(vec![String::from("username")]).validate(&columns, &["username"]).is_err());
+    }
+
+    #[test]
+    fn test_cluster_range_where() {
+        let cluster_keys = ["created", "sequence"];
+
+        let lower = cluster_range_where(&cluster_keys, &[("created", ClusterKeyFilter::Gte)]).unwrap();
+
+        assert_eq!(lower, "created>=?");
+
+        let two_sided = cluster_range_where(&cluster_keys, &[
+            ("created", ClusterKeyFilter::Eq),
+            ("sequence", ClusterKeyFilter::Gt),
+            ("sequence", ClusterKeyFilter::Lte),
+        ]).unwrap();
+
+        assert_eq!(two_sided, "created=? AND sequence>? AND sequence<=?");
+
+        assert!(cluster_range_where(&cluster_keys, &[]).is_err());
+        assert!(cluster_range_where(&cluster_keys, &[("sequence", ClusterKeyFilter::Lt)]).is_err());
+        assert!(cluster_range_where(&cluster_keys, &[("created", ClusterKeyFilter::Lt), ("sequence", ClusterKeyFilter::Eq)]).is_err());
+        assert!(cluster_range_where(&cluster_keys, &[("created", ClusterKeyFilter::Lt), ("created", ClusterKeyFilter::Lte)]).is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use cdrs::frame::{Frame, TryFromRow};
+use cdrs::types::ByName;
+use cdrs::types::rows::Row;
+
+use crate::Error;
+
+/// Row returned by a conditional query, e.g. `store_query_if_not_exists`
+///
+/// When the query is not applied, the row also has the current
+/// values of the columns of the condition, or of the whole row
+/// for `IF NOT EXISTS`.
+#[derive(Debug, Clone)]
+pub struct LwtResult {
+    applied: bool,
+    row: Row,
+}
+
+impl LwtResult {
+    /// Read the `[applied]` column of the row
+    pub fn from_row(row: Row) -> Result<Self, Error> {
+        let applied = row.r_by_name::<bool>("[applied]")?;
+
+        Ok(LwtResult { applied, row })
+    }
+
+    /// Read the only row of the response
+    pub fn from_frame(frame: Frame) -> Result<Self, Error> {
+        let row = frame.get_body()?
+            .into_rows()
+            .and_then(|rows| rows.into_iter().next())
+            .ok_or_else(|| cdrs::Error::from("Response of a conditional query must have one row"))?;
+
+        LwtResult::from_row(row)
+    }
+
+    /// If the query was applied
+    pub fn applied(&self) -> bool {
+        self.applied
+    }
+
+    /// Row of the response, with the `[applied]` column
+    pub fn row(&self) -> &Row {
+        &self.row
+    }
+
+    pub fn into_row(self) -> Row {
+        self.row
+    }
+
+    /// Current entity when the query was not applied, which
+    /// requires that the response has all columns of the entity
+    pub fn current<E: TryFromRow>(self) -> Result<Option<E>, Error> {
+        if self.applied {
+            return Ok(None);
+        }
+
+        Ok(Some(E::try_from_row(self.row)?))
+    }
+}
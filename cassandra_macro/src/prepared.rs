@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use cdrs::query::PreparedQuery;
+
+/// Ids of the statements prepared by the server, keyed by statement
+///
+/// The repository prepares each statement once and executes it by
+/// id thereafter. When the server no longer knows the id, e.g. after
+/// a restart, the statement is prepared again and the id replaced.
+pub trait PreparedCache {
+    /// Id of the prepared statement, if any
+    fn get(&self, statement: &str) -> Option<PreparedQuery>;
+
+    /// Id of the statement, replacing the previous one
+    fn insert(&self, statement: &str, prepared: PreparedQuery);
+}
+
+/// Cache keeping every statement it is given, never evicting any
+///
+/// The statements built at runtime are as many entries as there are
+/// distinct statements, e.g. one per keyspace of the `_in` queries,
+/// per projection, per set of updated columns or per select of a
+/// `SelectBuilder`. When those are not bounded, implement
+/// `PreparedCache` with an evicting cache instead.
+#[derive(Debug, Default)]
+pub struct UnboundedPreparedCache {
+    statements: RwLock<HashMap<String, PreparedQuery>>,
+}
+
+impl UnboundedPreparedCache {
+    pub fn new() -> Self {
+        UnboundedPreparedCache::default()
+    }
+}
+
+impl PreparedCache for UnboundedPreparedCache {
+    fn get(&self, statement: &str) -> Option<PreparedQuery> {
+        self.statements.read().ok()?.get(statement).cloned()
+    }
+
+    fn insert(&self, statement: &str, prepared: PreparedQuery) {
+        if let Ok(mut statements) = self.statements.write() {
+            statements.insert(statement.to_string(), prepared);
+        }
+    }
+}
@@ -0,0 +1,44 @@
+/// Rows of the body of a RESULT frame, where the `raw_columns`
+/// are read as a `BLOB`, see `CassandraTable::raw_columns`
+///
+/// The rows of the driver only read the bytes of a `BLOB` column,
+/// e.g. a `DURATION` can not be read with its own type.
+pub fn rows_with_raw_columns(body: cdrs::frame::frame_response::ResponseBody,
+                             raw_columns: &[&str]) -> Option<Vec<cdrs::types::rows::Row>> {
+    use cdrs::frame::frame_response::ResponseBody;
+    use cdrs::frame::frame_result::{ColType, ColTypeOption, ResResultBody};
+
+    match body {
+        ResponseBody::Result(ResResultBody::Rows(mut rows)) => {
+            for col_spec in rows.metadata.col_specs.iter_mut() {
+                if raw_columns.contains(&col_spec.name.as_str()) {
+                    col_spec.col_type = ColTypeOption { id: ColType::Blob, value: None };
+                }
+            }
+
+            Some(cdrs::types::rows::Row::from_frame_body(rows))
+        }
+        body => body.into_rows(),
+    }
+}
+
+/// Same as `rows_with_raw_columns`, of the `cdrs-tokio` driver
+#[cfg(feature = "cdrs-tokio")]
+pub fn async_rows_with_raw_columns(body: cdrs_tokio::frame::frame_response::ResponseBody,
+                                   raw_columns: &[&str]) -> Option<Vec<cdrs_tokio::types::rows::Row>> {
+    use cdrs_tokio::frame::frame_response::ResponseBody;
+    use cdrs_tokio::frame::frame_result::{ColType, ColTypeOption, ResResultBody};
+
+    match body {
+        ResponseBody::Result(ResResultBody::Rows(mut rows)) => {
+            for col_spec in rows.metadata.col_specs.iter_mut() {
+                if raw_columns.contains(&col_spec.name.as_str()) {
+                    col_spec.col_type = ColTypeOption { id: ColType::Blob, value: None };
+                }
+            }
+
+            Some(cdrs_tokio::types::rows::Row::from_frame_body(rows))
+        }
+        body => body.into_rows(),
+    }
+}
@@ -0,0 +1,285 @@
+use std::marker::PhantomData;
+
+use cdrs::frame::{Frame, TryFromRow};
+use cdrs::frame::frame_error::AdditionalErrorInfo;
+use cdrs::query::{QueryParams, QueryParamsBuilder, QueryValues};
+use cdrs::types::CBytes;
+use cdrs::types::rows::Row;
+
+use crate::{check_query_hook, CassandraTable, CqlQuery, CqlSession, Error, KeyspaceResolver, LwtResult, PreparedCache, Projection, SelectQuery};
+use crate::raw_rows::rows_with_raw_columns;
+
+/// CRUD operations of the tables over a cdrs session,
+/// or anything else implementing `CqlSession`
+///
+/// Only available with the `repository` feature.
+pub struct CassandraRepository<S, T = (), M = ()> {
+    session: S,
+    prepared: Option<Box<dyn PreparedCache + Send + Sync>>,
+    executor: PhantomData<fn() -> (T, M)>,
+}
+
+impl<S: CqlSession<T, M>, T, M> CassandraRepository<S, T, M> {
+    pub fn new(session: S) -> Self {
+        CassandraRepository { session, prepared: None, executor: PhantomData }
+    }
+
+    /// Prepare the statements once, and execute them by id thereafter
+    pub fn with_prepared_cache<C: PreparedCache + Send + Sync + 'static>(mut self, cache: C) -> Self {
+        self.prepared = Some(Box::new(cache));
+        self
+    }
+
+    /// The wrapped session
+    pub fn session(&self) -> &S {
+        &self.session
+    }
+
+    /// Execute any query with its values and consistency level
+    ///
+    /// The query hook checks the queries when they are built, the
+    /// operations of the repository build theirs with the fallible
+    /// constructors, and check the selects before executing them.
+    pub fn execute<Q: CqlQuery>(&self, query: &Q) -> Result<Frame, Error> {
+        self.send(query.query(), || {
+            let mut params = QueryParamsBuilder::new().values(query.values().clone());
+
+            if let Some(consistency) = query.consistency() {
+                params = params.consistency(consistency);
+            }
+
+            params.finalize()
+        })
+    }
+
+    /// Create the table of the entity, if it does not exist
+    pub fn create_table<E: CassandraTable>(&self) -> Result<(), Error> {
+        self.session.execute_simple(E::create_table_cql())?;
+
+        Ok(())
+    }
+
+    /// Insert the entity
+    pub fn save<E: CassandraTable>(&self, entity: &E) -> Result<(), Error> {
+        self.execute(&entity.try_store_query()?).map(|_| ())
+    }
+
+    /// Update all non key columns of the entity
+    pub fn update<E: CassandraTable>(&self, entity: &E) -> Result<(), Error> {
+        self.execute(&entity.try_update_query()?).map(|_| ())
+    }
+
+    /// Delete the row of the entity
+    pub fn delete<E: CassandraTable>(&self, entity: &E) -> Result<(), Error> {
+        self.execute(&entity.try_delete_query()?).map(|_| ())
+    }
+
+    /// Insert the entity only if it does not exist, when it is not
+    /// applied the result has the current row
+    pub fn save_if_not_exists<E: CassandraTable>(&self, entity: &E) -> Result<LwtResult, Error> {
+        LwtResult::from_frame(self.execute(&entity.store_query_if_not_exists()?)?)
+    }
+
+    /// Update all non key columns of the entity, only if it exists
+    pub fn update_if_exists<E: CassandraTable>(&self, entity: &E) -> Result<LwtResult, Error> {
+        LwtResult::from_frame(self.execute(&entity.update_query_if_exists()?)?)
+    }
+
+    /// Delete the row of the entity, only if it exists
+    pub fn delete_if_exists<E: CassandraTable>(&self, entity: &E) -> Result<LwtResult, Error> {
+        LwtResult::from_frame(self.execute(&entity.delete_query_if_exists()?)?)
+    }
+
+    /// Find the row by the primary and cluster keys,
+    /// given in the order of `primary_key_columns` then `cluster_key_columns`
+    pub fn find_by_key<E: CassandraTable + TryFromRow>(&self, keys: QueryValues) -> Result<Option<E>, Error> {
+        let query = SelectQuery::for_table::<E>(E::select_by_primary_and_cluster_keys(Projection::All), keys);
+
+        Ok(self.rows(&query)?.into_iter().next())
+    }
+
+    /// Find all rows of the partition, the primary keys are
+    /// given in the order of `primary_key_columns`
+    pub fn find_all_in_partition<E: CassandraTable + TryFromRow>(&self, primary_keys: QueryValues) -> Result<Vec<E>, Error> {
+        let query = SelectQuery::for_table::<E>(E::select_by_primary_keys(Projection::All), primary_keys);
+
+        self.rows(&query)
+    }
+
+    /// Same as `create_table`, in the keyspace of the resolver
+    pub fn create_table_in<E: CassandraTable, R: KeyspaceResolver>(&self, resolver: &R) -> Result<(), Error> {
+        self.session.execute_simple(&E::create_table_cql_in(&resolver.keyspace_for(E::table_name()))?)?;
+
+        Ok(())
+    }
+
+    /// Same as `save`, in the keyspace of the resolver
+    pub fn save_in<E: CassandraTable, R: KeyspaceResolver>(&self, resolver: &R, entity: &E) -> Result<(), Error> {
+        self.execute(&entity.store_query_in(&resolver.keyspace_for(E::table_name()))?).map(|_| ())
+    }
+
+    /// Same as `update`, in the keyspace of the resolver
+    pub fn update_in<E: CassandraTable, R: KeyspaceResolver>(&self, resolver: &R, entity: &E) -> Result<(), Error> {
+        self.execute(&entity.update_query_in(&resolver.keyspace_for(E::table_name()))?).map(|_| ())
+    }
+
+    /// Same as `delete`, in the keyspace of the resolver
+    pub fn delete_in<E: CassandraTable, R: KeyspaceResolver>(&self, resolver: &R, entity: &E) -> Result<(), Error> {
+        self.execute(&entity.delete_query_in(&resolver.keyspace_for(E::table_name()))?).map(|_| ())
+    }
+
+    /// Same as `find_by_key`, in the keyspace of the resolver
+    pub fn find_by_key_in<E, R>(&self, resolver: &R, keys: QueryValues) -> Result<Option<E>, Error>
+        where E: CassandraTable + TryFromRow,
+              R: KeyspaceResolver {
+        let ks = resolver.keyspace_for(E::table_name());
+        let query = SelectQuery::new(E::select_by_primary_and_cluster_keys_in(&ks, Projection::All)?, keys)
+            .with_table(ks, E::table_name());
+
+        Ok(self.rows(&query)?.into_iter().next())
+    }
+
+    /// Same as `find_all_in_partition`, in the keyspace of the resolver
+    pub fn find_all_in_partition_in<E, R>(&self, resolver: &R, primary_keys: QueryValues) -> Result<Vec<E>, Error>
+        where E: CassandraTable + TryFromRow,
+              R: KeyspaceResolver {
+        let ks = resolver.keyspace_for(E::table_name());
+        let query = SelectQuery::new(E::select_by_primary_keys_in(&ks, Projection::All)?, primary_keys)
+            .with_table(ks, E::table_name());
+
+        self.rows(&query)
+    }
+
+    /// Iterate over all rows of the table, fetching the next
+    /// page of the given size when the previous one is consumed
+    pub fn find_all_paged<E: CassandraTable + TryFromRow>(&self, page_size: i32) -> RowIterator<'_, E, S, T, M> {
+        RowIterator {
+            repository: self,
+            query: SelectQuery::for_table::<E>(E::select_all(Projection::All), QueryValues::SimpleValues(vec![]))
+                .with_page_size(page_size),
+            rows: Vec::new().into_iter(),
+            last_page: false,
+            row: PhantomData,
+        }
+    }
+
+    /// Rows of the page of the query, and the paging state of the next page, if any
+    pub fn fetch_page(&self, query: &SelectQuery) -> Result<(Vec<Row>, Option<Vec<u8>>), Error> {
+        self.fetch_page_with_raw_columns(query, &[])
+    }
+
+    /// Same as `fetch_page`, reading the `raw_columns` as a `BLOB`
+    fn fetch_page_with_raw_columns(&self, query: &SelectQuery, raw_columns: &[&str]) -> Result<(Vec<Row>, Option<Vec<u8>>), Error> {
+        check_query_hook(query)?;
+
+        let frame = self.send(query.query(), || {
+            let mut params = QueryParamsBuilder::new().values(query.values().clone());
+
+            if let Some(page_size) = query.page_size() {
+                params = params.page_size(page_size);
+            }
+
+            if let Some(paging_state) = query.paging_state() {
+                params = params.paging_state(CBytes::new(paging_state.to_vec()));
+            }
+
+            params.finalize()
+        })?;
+
+        let body = frame.get_body()?;
+
+        let paging_state = body.as_rows_metadata()
+            .and_then(|metadata| metadata.paging_state)
+            .and_then(|paging_state| paging_state.into_plain());
+
+        Ok((rows_with_raw_columns(body, raw_columns).unwrap_or_default(), paging_state))
+    }
+
+    /// Execute the statement, by id if there is a prepared cache
+    fn send<P: Fn() -> QueryParams>(&self, stmt: &str, params: P) -> Result<Frame, Error> {
+        let cache = match self.prepared {
+            Some(ref cache) => cache,
+            None => return self.session.execute_with_params(stmt, params()),
+        };
+
+        let prepared = match cache.get(stmt) {
+            Some(prepared) => prepared,
+            None => match self.session.prepare(stmt)? {
+                Some(prepared) => {
+                    cache.insert(stmt, prepared.clone());
+                    prepared
+                }
+                None => return self.session.execute_with_params(stmt, params()),
+            },
+        };
+
+        match self.session.execute_prepared(stmt, &prepared, params()) {
+            Err(Error::Driver(cdrs::Error::Server(ref e))) if matches!(e.additional_info, AdditionalErrorInfo::Unprepared(_)) => {
+                match self.session.prepare(stmt)? {
+                    Some(prepared) => {
+                        cache.insert(stmt, prepared.clone());
+
+                        self.session.execute_prepared(stmt, &prepared, params())
+                    }
+                    None => self.session.execute_with_params(stmt, params()),
+                }
+            }
+            result => result,
+        }
+    }
+
+    fn rows<E: CassandraTable + TryFromRow>(&self, query: &SelectQuery) -> Result<Vec<E>, Error> {
+        check_query_hook(query)?;
+
+        let frame = self.send(query.query(), || QueryParamsBuilder::new().values(query.values().clone()).finalize())?;
+
+        rows_with_raw_columns(frame.get_body()?, E::raw_columns())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|row| E::try_from_row(row).map_err(Error::from))
+            .collect()
+    }
+}
+
+/// Rows of a query fetched page by page, see `CassandraRepository::find_all_paged`
+///
+/// After an error fetching a page, the iteration ends.
+pub struct RowIterator<'a, E, S, T = (), M = ()> {
+    repository: &'a CassandraRepository<S, T, M>,
+    query: SelectQuery,
+    rows: std::vec::IntoIter<Row>,
+    last_page: bool,
+    row: PhantomData<fn() -> E>,
+}
+
+impl<E: CassandraTable + TryFromRow, S: CqlSession<T, M>, T, M> Iterator for RowIterator<'_, E, S, T, M> {
+    type Item = Result<E, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(row) = self.rows.next() {
+                return Some(E::try_from_row(row).map_err(Error::from));
+            }
+
+            if self.last_page {
+                return None;
+            }
+
+            match self.repository.fetch_page_with_raw_columns(&self.query, E::raw_columns()) {
+                Ok((rows, Some(paging_state))) => {
+                    self.rows = rows.into_iter();
+                    self.query = self.query.clone().with_paging_state(paging_state);
+                }
+                Ok((rows, None)) => {
+                    self.rows = rows.into_iter();
+                    self.last_page = true;
+                }
+                Err(e) => {
+                    self.last_page = true;
+
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use std::convert::TryFrom;
+
+use cdrs::query::QueryValues;
+use cdrs::types::value::{Value, ValueType};
+use scylla::frame::value::{SerializedValues, ValueTooBig};
+
+use crate::Error;
+
+/// Value that is already serialized in the CQL binary
+/// protocol, which is the same for all drivers
+struct SerializedValue<'a>(&'a Value);
+
+impl scylla::frame::value::Value for SerializedValue<'_> {
+    fn serialize(&self, buf: &mut Vec<u8>) -> Result<(), ValueTooBig> {
+        match self.0.value_type {
+            ValueType::Normal(_) => {
+                let len = i32::try_from(self.0.body.len()).map_err(|_| ValueTooBig)?;
+
+                buf.extend_from_slice(&len.to_be_bytes());
+                buf.extend_from_slice(&self.0.body);
+            }
+            ValueType::Null => buf.extend_from_slice(&(-1i32).to_be_bytes()),
+            ValueType::NotSet => buf.extend_from_slice(&(-2i32).to_be_bytes()),
+        }
+
+        Ok(())
+    }
+}
+
+/// Values of the query for executing it with the `scylla` driver
+pub fn serialized_values(values: &QueryValues) -> Result<SerializedValues, Error> {
+    let mut serialized = SerializedValues::new();
+
+    match values {
+        QueryValues::SimpleValues(values) => {
+            for value in values {
+                serialized.add_value(&SerializedValue(value))?;
+            }
+        }
+        QueryValues::NamedValues(values) => {
+            for (name, value) in values {
+                serialized.add_named_value(name, &SerializedValue(value))?;
+            }
+        }
+    }
+
+    Ok(serialized)
+}
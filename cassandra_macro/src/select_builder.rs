@@ -0,0 +1,151 @@
+use std::marker::PhantomData;
+
+use crate::{cluster_range_where, key_where, validate_projection, CassandraTable, ClusterKeyFilter, Error, Order, Projection, SelectOptions};
+
+/// Select statement of the table `T` built clause by clause,
+/// see `CassandraTable::select`
+///
+/// `build` checks the statement against the keys of the table, with
+/// the same rules as Cassandra. The values are bound in the order of
+/// the clauses: primary keys, cluster key restrictions, per partition
+/// limit and limit.
+pub struct SelectBuilder<T: ?Sized> {
+    projection: Projection,
+    primary_keys: bool,
+    cluster_filters: Vec<(String, ClusterKeyFilter)>,
+    options: SelectOptions,
+    allow_filtering: bool,
+    table: PhantomData<fn() -> T>,
+}
+
+impl<T: CassandraTable + ?Sized> SelectBuilder<T> {
+    /// `SELECT * FROM ks.table`
+    pub fn new() -> Self {
+        SelectBuilder {
+            projection: Projection::All,
+            primary_keys: false,
+            cluster_filters: Vec::new(),
+            options: SelectOptions::new(),
+            allow_filtering: false,
+            table: PhantomData,
+        }
+    }
+
+    /// Select the projection instead of all columns
+    pub fn projection(mut self, projection: Projection) -> Self {
+        self.projection = projection;
+        self
+    }
+
+    /// Select the given columns instead of all columns
+    pub fn columns(self, columns: &[&str]) -> Self {
+        self.projection(Projection::Columns(columns.iter().map(|c| c.to_string()).collect()))
+    }
+
+    /// Restrict all primary keys, `a=? AND b=?`
+    pub fn where_key_eq(mut self) -> Self {
+        self.primary_keys = true;
+        self
+    }
+
+    /// Restrict the cluster key column, see `cluster_range_where`
+    ///
+    /// Called once per bound, e.g. `Gte` then `Lt` for a range.
+    pub fn cluster_range(mut self, column: &str, filter: ClusterKeyFilter) -> Self {
+        self.cluster_filters.push((column.to_string(), filter));
+        self
+    }
+
+    /// Same as `SelectOptions::group_by`
+    pub fn group_by(mut self, columns: &[&str]) -> Self {
+        self.options = self.options.group_by(columns);
+        self
+    }
+
+    /// Same as `SelectOptions::order`, the primary keys must be restricted
+    pub fn order_by(mut self, column: &str, order: Order) -> Self {
+        self.options = self.options.order(column, order);
+        self
+    }
+
+    /// Same as `SelectOptions::per_partition_limit`, `PER PARTITION LIMIT ?`
+    pub fn per_partition_limit(mut self) -> Self {
+        self.options = self.options.per_partition_limit();
+        self
+    }
+
+    /// Same as `SelectOptions::per_partition_limit_to`, `PER PARTITION LIMIT <limit>`
+    pub fn per_partition_limit_to(mut self, limit: u32) -> Self {
+        self.options = self.options.per_partition_limit_to(limit);
+        self
+    }
+
+    /// Same as `SelectOptions::limit`, `LIMIT ?`
+    pub fn limit(mut self) -> Self {
+        self.options = self.options.limit();
+        self
+    }
+
+    /// Same as `SelectOptions::limit_to`, `LIMIT <limit>`
+    pub fn limit_to(mut self, limit: u32) -> Self {
+        self.options = self.options.limit_to(limit);
+        self
+    }
+
+    /// Append `ALLOW FILTERING`, which also allows restricting
+    /// cluster keys without restricting the primary keys
+    pub fn allow_filtering(mut self) -> Self {
+        self.allow_filtering = true;
+        self
+    }
+
+    /// The statement, or the first clause that Cassandra would reject
+    pub fn build(&self) -> Result<String, Error> {
+        validate_projection::<T>(&self.projection)?;
+
+        let mut where_parts = Vec::new();
+
+        if self.primary_keys {
+            where_parts.push(key_where(T::primary_key_columns()));
+        }
+
+        if !self.cluster_filters.is_empty() {
+            if !self.primary_keys && !self.allow_filtering {
+                return Err(Error::InvalidColumn(
+                    String::from("Cannot restrict cluster keys without restricting the primary keys, unless filtering is allowed")));
+            }
+
+            let filters = self.cluster_filters.iter().map(|(c, f)| (c.as_str(), *f)).collect::<Vec<(&str, ClusterKeyFilter)>>();
+
+            where_parts.push(cluster_range_where(T::cluster_key_columns(), &filters)?);
+        }
+
+        if !self.options.order_by.is_empty() && !self.primary_keys {
+            return Err(Error::InvalidColumn(String::from("Cannot order by columns without restricting the primary keys")));
+        }
+
+        let mut statement = format!("SELECT {} FROM {}.{}", self.projection.selection(), T::key_space(), T::table_name());
+
+        if !where_parts.is_empty() {
+            statement = format!("{} WHERE {}", statement, where_parts.join(" AND "));
+        }
+
+        let clauses = self.options.to_cql(T::primary_key_columns(), T::cluster_key_columns())?;
+
+        if !clauses.is_empty() {
+            statement = format!("{} {}", statement, clauses);
+        }
+
+        if self.allow_filtering {
+            statement.push_str(" ALLOW FILTERING");
+        }
+
+        Ok(statement)
+    }
+}
+
+impl<T: CassandraTable + ?Sized> Default for SelectBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
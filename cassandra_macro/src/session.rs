@@ -0,0 +1,67 @@
+use std::cell::RefCell;
+
+use cdrs::frame::Frame;
+use cdrs::query::{ExecExecutor, PrepareExecutor, PreparedQuery, QueryExecutor, QueryParams, QueryValues};
+use cdrs::transport::CDRSTransport;
+
+use crate::Error;
+
+/// Session executing the statements of `CassandraRepository`
+///
+/// Implemented by the cdrs sessions, and by any fake session
+/// returning its own frames, so that the repository can be tested
+/// without a cluster. `T` and `M` are the transport and connection
+/// manager of a cdrs session, other sessions keep the defaults.
+pub trait CqlSession<T = (), M = ()> {
+    /// Execute the statement with its values
+    fn execute(&self, cql: &str, values: &QueryValues) -> Result<Frame, Error>;
+
+    /// Execute the statement without values
+    fn execute_simple(&self, cql: &str) -> Result<Frame, Error>;
+
+    /// Execute the statement with its parameters, e.g. the
+    /// consistency level, the page size and the paging state
+    ///
+    /// By default only the values of the parameters are bound.
+    fn execute_with_params(&self, cql: &str, params: QueryParams) -> Result<Frame, Error> {
+        self.execute(cql, &params.values.unwrap_or(QueryValues::SimpleValues(vec![])))
+    }
+
+    /// Prepare the statement, `None` if the session does
+    /// not prepare statements, which is the default
+    fn prepare(&self, _cql: &str) -> Result<Option<PreparedQuery>, Error> {
+        Ok(None)
+    }
+
+    /// Execute the statement `cql` by the id it was prepared with
+    ///
+    /// By default the statement is executed as it is.
+    fn execute_prepared(&self, cql: &str, _prepared: &PreparedQuery, params: QueryParams) -> Result<Frame, Error> {
+        self.execute_with_params(cql, params)
+    }
+}
+
+impl<S, T, M> CqlSession<T, M> for S
+    where S: QueryExecutor<T, M> + PrepareExecutor<T, M> + ExecExecutor<T, M>,
+          T: CDRSTransport + 'static,
+          M: r2d2::ManageConnection<Connection=RefCell<T>, Error=cdrs::Error> + Sized {
+    fn execute(&self, cql: &str, values: &QueryValues) -> Result<Frame, Error> {
+        Ok(self.query_with_values(cql, values.clone())?)
+    }
+
+    fn execute_simple(&self, cql: &str) -> Result<Frame, Error> {
+        Ok(self.query(cql)?)
+    }
+
+    fn execute_with_params(&self, cql: &str, params: QueryParams) -> Result<Frame, Error> {
+        Ok(self.query_with_params(cql, params)?)
+    }
+
+    fn prepare(&self, cql: &str) -> Result<Option<PreparedQuery>, Error> {
+        Ok(Some(PrepareExecutor::prepare(self, cql)?))
+    }
+
+    fn execute_prepared(&self, _cql: &str, prepared: &PreparedQuery, params: QueryParams) -> Result<Frame, Error> {
+        Ok(self.exec_with_params(prepared, params)?)
+    }
+}
@@ -0,0 +1,32 @@
+use crate::{CassandraTable, Projection};
+
+/// Some columns of a table read into a smaller struct,
+/// implemented by `#[derive(CassandraProjection)]`
+///
+/// The columns are checked against the `<Table>Column` enum of
+/// the table, a field that is not a column does not compile.
+/// With `#[projection(of = "User", derive_try_from_row)]` the
+/// derive also implements `TryFromRow` of the struct.
+pub trait CassandraProjection {
+    /// Table with the columns
+    type Table: CassandraTable;
+
+    /// Columns of the projection, in the order they were declared
+    fn columns() -> &'static [&'static str];
+
+    /// `Projection::Columns` of the columns
+    fn projection() -> Projection {
+        Projection::Columns(Self::columns().iter().map(|c| c.to_string()).collect())
+    }
+
+    /// Prepared statement selecting the columns by the primary keys of the table
+    fn select_by_primary_keys() -> String {
+        Self::Table::select_by_primary_keys(Self::projection())
+    }
+
+    /// Prepared statement selecting the columns by the primary
+    /// keys and cluster keys of the table
+    fn select_by_primary_and_cluster_keys() -> String {
+        Self::Table::select_by_primary_and_cluster_keys(Self::projection())
+    }
+}
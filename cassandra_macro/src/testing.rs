@@ -0,0 +1,264 @@
+//! Session recording the statements, for unit tests of the code using the repository
+
+use std::sync::Mutex;
+
+use cdrs::frame::{Flag, Frame, IntoBytes, Opcode, Version};
+use cdrs::frame::frame_result::ColType;
+use cdrs::query::QueryValues;
+use cdrs::types::value::Value;
+
+use crate::{CqlSession, Error};
+
+/// Canned response of the statements containing a pattern
+#[derive(Debug, Clone)]
+enum Response {
+    Body(Vec<u8>),
+    Error(String),
+}
+
+/// Session recording every statement executed with its values,
+/// answering with the canned responses or with a void result
+///
+/// ```rust
+/// use cassandra_macro::{CassandraRepository, CassandraTable};
+/// use cassandra_macro::testing::MockSession;
+/// use cassandra_macro_derive::CassandraTable;
+/// use cdrs::frame::frame_result::ColType;
+/// use cdrs::query::QueryValues;
+/// use cdrs::types::value::Value;
+///
+/// #[derive(Debug, CassandraTable)]
+/// #[table(keyspace = "test", derive_try_from_row)]
+/// pub struct User {
+///     #[column(type = "TEXT", primary_key)]
+///     username: String,
+/// }
+///
+/// let session = MockSession::new()
+///     .with_rows("SELECT", &[("username", ColType::Varchar)], vec![vec![Value::from("rust")]])
+///     .with_error("DELETE", "unconfigured table user");
+///
+/// let repository = CassandraRepository::new(session);
+/// let user: Option<User> = repository.find_by_key(QueryValues::SimpleValues(vec![Value::from("rust")])).unwrap();
+///
+/// assert_eq!(user.unwrap().username, "rust");
+/// assert!(repository.delete(&User { username: String::from("rust") }).is_err());
+/// assert_eq!(repository.session().executed_statements()[1], "DELETE FROM test.user WHERE username=?");
+/// ```
+#[derive(Debug, Default)]
+pub struct MockSession {
+    executed: Mutex<Vec<(String, QueryValues)>>,
+    responses: Vec<(String, Response)>,
+}
+
+impl MockSession {
+    pub fn new() -> Self {
+        MockSession::default()
+    }
+
+    /// Body of the RESULT frame answering the statements containing `pattern`
+    ///
+    /// Patterns are matched in the order they were added.
+    pub fn with_response(mut self, pattern: &str, body: Vec<u8>) -> Self {
+        self.responses.push((pattern.to_string(), Response::Body(body)));
+        self
+    }
+
+    /// Rows answering the statements containing `pattern`, each row
+    /// has one value per column, in the order of the columns
+    pub fn with_rows(self, pattern: &str, columns: &[(&str, ColType)], rows: Vec<Vec<Value>>) -> Self {
+        self.with_response(pattern, rows_body(columns, rows))
+    }
+
+    /// Row of the `[applied]` column answering the conditional
+    /// statements containing `pattern`
+    pub fn with_applied(self, pattern: &str, applied: bool) -> Self {
+        self.with_rows(pattern, &[("[applied]", ColType::Boolean)], vec![vec![Value::from(applied)]])
+    }
+
+    /// Driver error answering the statements containing `pattern`
+    pub fn with_error(mut self, pattern: &str, message: &str) -> Self {
+        self.responses.push((pattern.to_string(), Response::Error(message.to_string())));
+        self
+    }
+
+    /// Statements executed so far with their values, statements
+    /// executed without values have no values
+    pub fn executed(&self) -> Vec<(String, QueryValues)> {
+        self.executed.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Statements executed so far
+    pub fn executed_statements(&self) -> Vec<String> {
+        self.executed().into_iter().map(|(cql, _)| cql).collect()
+    }
+
+    fn respond(&self, cql: &str, values: QueryValues) -> Result<Frame, Error> {
+        self.executed.lock().unwrap_or_else(|e| e.into_inner()).push((cql.to_string(), values));
+
+        let body = match self.responses.iter().find(|(pattern, _)| cql.contains(pattern.as_str())) {
+            Some((_, Response::Body(body))) => body.clone(),
+            Some((_, Response::Error(message))) => return Err(Error::Driver(cdrs::Error::General(message.clone()))),
+            None => void_body(),
+        };
+
+        Ok(Frame {
+            version: Version::Response,
+            flags: vec![Flag::Ignore],
+            opcode: Opcode::Result,
+            stream: 0,
+            body,
+            tracing_id: None,
+            warnings: vec![],
+        })
+    }
+}
+
+impl CqlSession for MockSession {
+    fn execute(&self, cql: &str, values: &QueryValues) -> Result<Frame, Error> {
+        self.respond(cql, values.clone())
+    }
+
+    fn execute_simple(&self, cql: &str) -> Result<Frame, Error> {
+        self.respond(cql, QueryValues::SimpleValues(vec![]))
+    }
+}
+
+fn void_body() -> Vec<u8> {
+    1i32.to_be_bytes().to_vec()
+}
+
+fn string(s: &str) -> Vec<u8> {
+    [&(s.len() as u16).to_be_bytes()[..], s.as_bytes()].concat()
+}
+
+/// Id of the column type in the rows metadata
+fn col_type_id(col_type: &ColType) -> u16 {
+    match col_type {
+        ColType::Ascii => 0x0001,
+        ColType::Bigint => 0x0002,
+        ColType::Blob => 0x0003,
+        ColType::Boolean => 0x0004,
+        ColType::Counter => 0x0005,
+        ColType::Decimal => 0x0006,
+        ColType::Double => 0x0007,
+        ColType::Float => 0x0008,
+        ColType::Int => 0x0009,
+        ColType::Timestamp => 0x000B,
+        ColType::Uuid => 0x000C,
+        ColType::Varchar => 0x000D,
+        ColType::Varint => 0x000E,
+        ColType::Timeuuid => 0x000F,
+        ColType::Inet => 0x0010,
+        ColType::Date => 0x0011,
+        ColType::Time => 0x0012,
+        ColType::Smallint => 0x0013,
+        ColType::Tinyint => 0x0014,
+        other => panic!("Only native column types can be mocked, use `with_response` for {:?} columns", other),
+    }
+}
+
+/// Body of a RESULT frame of kind rows
+fn rows_body(columns: &[(&str, ColType)], rows: Vec<Vec<Value>>) -> Vec<u8> {
+    let mut body = [2i32.to_be_bytes(), 1i32.to_be_bytes(), (columns.len() as i32).to_be_bytes()].concat();
+
+    body.extend([string("mock"), string("mock")].concat());
+
+    for (name, col_type) in columns {
+        body.extend(string(name));
+        body.extend(&col_type_id(col_type).to_be_bytes());
+    }
+
+    body.extend(&(rows.len() as i32).to_be_bytes());
+
+    for row in rows {
+        assert_eq!(row.len(), columns.len(), "Each mocked row must have one value per column");
+
+        for value in row {
+            body.extend(value.into_cbytes());
+        }
+    }
+
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use cdrs::frame::frame_result::ColType;
+    use cdrs::query::QueryValues;
+    use cdrs::types::{ByName, IntoRustByName};
+    use cdrs::types::rows::Row;
+    use cdrs::types::value::Value;
+
+    use crate::testing::MockSession;
+    use crate::{CqlSession, Error};
+
+    fn rows(session: &MockSession, cql: &str) -> Vec<Row> {
+        let body = session.execute_simple(cql).unwrap().get_body().unwrap();
+
+        body.into_rows().unwrap()
+    }
+
+    #[test]
+    fn test_records_statements() {
+        let session = MockSession::new();
+        let values = QueryValues::SimpleValues(vec![Value::from("rust")]);
+
+        session.execute("INSERT INTO test.user (username) VALUES (?)", &values).unwrap();
+        session.execute_simple("TRUNCATE test.user").unwrap();
+
+        assert_eq!(session.executed(), vec![
+            (String::from("INSERT INTO test.user (username) VALUES (?)"), values),
+            (String::from("TRUNCATE test.user"), QueryValues::SimpleValues(vec![])),
+        ]);
+        assert_eq!(session.executed_statements(), vec!["INSERT INTO test.user (username) VALUES (?)", "TRUNCATE test.user"]);
+    }
+
+    #[test]
+    fn test_canned_rows() {
+        let session = MockSession::new()
+            .with_rows("FROM test.user", &[("username", ColType::Varchar), ("age", ColType::Int)],
+                       vec![vec![Value::from("rust"), Value::from(7i32)], vec![Value::from("go"), Value::from(None::<i32>)]]);
+
+        let rows = rows(&session, "SELECT * FROM test.user");
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].r_by_name::<String>("username").unwrap(), "rust");
+        assert_eq!(rows[0].r_by_name::<i32>("age").unwrap(), 7);
+        let age: Option<i32> = rows[1].get_by_name("age").unwrap();
+        assert_eq!(age, None);
+
+        assert!(session.execute_simple("SELECT * FROM test.other").unwrap().get_body().unwrap().into_rows().is_none());
+    }
+
+    #[test]
+    fn test_first_matching_pattern() {
+        let session = MockSession::new()
+            .with_applied("IF NOT EXISTS", false)
+            .with_applied("INSERT", true);
+
+        let applied = rows(&session, "INSERT INTO test.user (username) VALUES (?) IF NOT EXISTS");
+        assert!(!applied[0].r_by_name::<bool>("[applied]").unwrap());
+
+        let applied = rows(&session, "INSERT INTO test.user (username) VALUES (?) IF EXISTS");
+        assert!(applied[0].r_by_name::<bool>("[applied]").unwrap());
+    }
+
+    #[test]
+    fn test_canned_error() {
+        let session = MockSession::new().with_error("DELETE", "unconfigured table user");
+
+        match session.execute_simple("DELETE FROM test.user") {
+            Err(Error::Driver(e)) => assert!(e.to_string().contains("unconfigured table user")),
+            other => panic!("Expected driver error, got {:?}", other),
+        }
+
+        assert_eq!(session.executed_statements(), vec!["DELETE FROM test.user"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "one value per column")]
+    fn test_row_arity() {
+        MockSession::new().with_rows("SELECT", &[("username", ColType::Varchar)], vec![vec![]]);
+    }
+}
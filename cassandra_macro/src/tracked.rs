@@ -0,0 +1,96 @@
+use std::ops::Deref;
+
+use crate::{unknown_column, CassandraTable, Error, UpdateQuery};
+
+/// Entity wrapper that records which columns were changed
+///
+/// Changes are made with `update`, which marks the column as
+/// dirty, so that `dirty_update_query` only sets those columns.
+#[derive(Debug)]
+pub struct Tracked<T: CassandraTable> {
+    entity: T,
+    dirty: Vec<&'static str>,
+}
+
+impl<T: CassandraTable> Tracked<T> {
+    /// New instance without changes
+    pub fn new(entity: T) -> Self {
+        Tracked { entity, dirty: Vec::new() }
+    }
+
+    /// Change the entity and mark the column as dirty
+    ///
+    /// Only non key columns can be changed.
+    pub fn update<F: FnOnce(&mut T)>(&mut self, column: &str, change: F) -> Result<(), Error> {
+        self.mark_dirty(column)?;
+
+        change(&mut self.entity);
+
+        Ok(())
+    }
+
+    /// Mark the column as dirty without changing the entity
+    pub fn mark_dirty(&mut self, column: &str) -> Result<(), Error> {
+        if T::primary_key_columns().contains(&column) || T::cluster_key_columns().contains(&column) {
+            return Err(Error::KeyColumn(column.to_string()));
+        }
+
+        let column = T::columns()
+            .iter()
+            .find(|c| **c == column)
+            .ok_or_else(|| unknown_column::<T>(column))?;
+
+        if !self.dirty.contains(column) {
+            self.dirty.push(column);
+        }
+
+        Ok(())
+    }
+
+    /// Columns changed since creation or the last reset,
+    /// in the order they were changed
+    pub fn dirty_columns(&self) -> &[&'static str] {
+        &self.dirty
+    }
+
+    /// If any column was changed
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    /// Forget the changes, usually after executing the update
+    pub fn reset(&mut self) {
+        self.dirty.clear();
+    }
+
+    /// Create `UpdateQuery` setting only the dirty columns
+    pub fn dirty_update_query(&self) -> Result<UpdateQuery, Error> {
+        if self.dirty.is_empty() {
+            return Err(Error::NoChanges(T::table_name().to_string()));
+        }
+
+        self.entity.update_columns_query(&self.dirty)
+    }
+
+    /// Same as `dirty_update_query`, then resets the changes
+    pub fn take_dirty_update_query(&mut self) -> Result<UpdateQuery, Error> {
+        let query = self.dirty_update_query()?;
+
+        self.reset();
+
+        Ok(query)
+    }
+
+    /// The wrapped entity
+    pub fn into_inner(self) -> T {
+        self.entity
+    }
+}
+
+impl<T: CassandraTable> Deref for Tracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.entity
+    }
+}
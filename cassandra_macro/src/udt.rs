@@ -0,0 +1,71 @@
+use crate::{validate_identifier, CqlType, Error, UnknownColumnError};
+
+/// Metadata of a user defined type, implemented by `#[derive(CassandraUdt)]`
+///
+/// With `#[udt(derive_try_from_udt)]` the derive also implements
+/// `cdrs::frame::TryFromUDT`, reading each field by the name used
+/// in `create_type_cql`.
+pub trait CassandraUdt {
+    /// Create type statement, `CREATE TYPE IF NOT EXISTS ks.name (...)`
+    fn create_type_cql() -> &'static str;
+
+    /// Drop type statement, `DROP TYPE IF EXISTS ks.name`
+    fn drop_type_cql() -> &'static str;
+
+    /// Keyspace of the type
+    fn key_space() -> &'static str;
+
+    /// Name of the type, the struct name in snake case
+    fn type_name() -> &'static str;
+
+    /// Fields of the type, in the order they were declared
+    fn fields() -> &'static [&'static str];
+
+    /// Statement adding a field to the type, `ALTER TYPE ks.name ADD field type`
+    ///
+    /// The field must be a new valid identifier, and its type
+    /// a valid CQL type other than `COUNTER`.
+    fn alter_add_field_cql(field: &str, cql_type: &str) -> Result<String, Error> {
+        validate_new_field::<Self>(field)?;
+
+        validate_field_type(&CqlType::parse(cql_type)?, cql_type)?;
+
+        Ok(format!("ALTER TYPE {}.{} ADD {} {}", Self::key_space(), Self::type_name(), field, cql_type.trim()))
+    }
+
+    /// Statement renaming a field of the type, `ALTER TYPE ks.name RENAME old TO new`
+    fn alter_rename_field_cql(old: &str, new: &str) -> Result<String, Error> {
+        if !Self::fields().contains(&old) {
+            return Err(Error::UnknownColumn(UnknownColumnError::new(&[old], Self::fields())));
+        }
+
+        validate_new_field::<Self>(new)?;
+
+        Ok(format!("ALTER TYPE {}.{} RENAME {} TO {}", Self::key_space(), Self::type_name(), old, new))
+    }
+}
+
+fn validate_new_field<T: CassandraUdt + ?Sized>(field: &str) -> Result<(), Error> {
+    validate_identifier(field)?;
+
+    // unquoted identifiers are case insensitive, as the columns of a table
+    match T::fields().iter().find(|f| f.eq_ignore_ascii_case(field)) {
+        Some(existing) => Err(Error::InvalidColumn(format!("type {} already has the field `{}`", T::type_name(), existing))),
+        None => Ok(()),
+    }
+}
+
+/// Cassandra does not accept counters in a user defined type,
+/// and the other user defined types are referenced by their name
+fn validate_field_type(cql_type: &CqlType, declared: &str) -> Result<(), Error> {
+    match cql_type {
+        CqlType::Native(name) if name == "COUNTER" => {
+            Err(Error::InvalidColumn(format!("a user defined type can not have a `COUNTER` field, declared as `{}`", declared.trim())))
+        }
+        CqlType::Native(_) => Ok(()),
+        CqlType::Custom(name) => name.split('.').try_for_each(validate_identifier).map_err(|_| Error::InvalidType(declared.trim().to_string())),
+        CqlType::List(t) | CqlType::Set(t) | CqlType::Frozen(t) => validate_field_type(t, declared),
+        CqlType::Map(k, v) => validate_field_type(k, declared).and(validate_field_type(v, declared)),
+        CqlType::Tuple(types) => types.iter().try_for_each(|t| validate_field_type(t, declared)),
+    }
+}
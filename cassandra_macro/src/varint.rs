@@ -0,0 +1,52 @@
+use cdrs::types::value::Bytes;
+use num_bigint::BigInt;
+
+/// Value of a `VARINT` column, of any precision
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Varint(pub BigInt);
+
+impl Varint {
+    /// Serialization in the CQL binary protocol, the big endian
+    /// two's complement in the fewest bytes
+    pub fn to_cql_bytes(&self) -> Vec<u8> {
+        self.0.to_signed_bytes_be()
+    }
+
+    /// Integer of its serialization in the CQL binary protocol,
+    /// `None` if there are no bytes
+    pub fn from_cql_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.is_empty() {
+            None
+        } else {
+            Some(Varint(BigInt::from_signed_bytes_be(bytes)))
+        }
+    }
+
+    pub fn into_inner(self) -> BigInt {
+        self.0
+    }
+}
+
+impl From<BigInt> for Varint {
+    fn from(n: BigInt) -> Self {
+        Varint(n)
+    }
+}
+
+impl From<i64> for Varint {
+    fn from(n: i64) -> Self {
+        Varint(BigInt::from(n))
+    }
+}
+
+impl From<Varint> for BigInt {
+    fn from(n: Varint) -> Self {
+        n.0
+    }
+}
+
+impl From<Varint> for Bytes {
+    fn from(n: Varint) -> Self {
+        Bytes::new(n.to_cql_bytes())
+    }
+}
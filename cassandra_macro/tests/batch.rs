@@ -0,0 +1,298 @@
+use cassandra_macro::{BatchBuilder, BatchKind, CassandraTable, StoreQuery};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::query::QueryValues;
+use cdrs::types::value::Value;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT")]
+    first_name: String,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct UserByName {
+    #[column(type = "TEXT", primary_key)]
+    first_name: String,
+
+    #[column(type = "TEXT", cluster_key(order = "ASC", position = 1))]
+    username: String,
+}
+
+fn user() -> User {
+    User { username: String::from("rust"), first_name: String::from("ferris") }
+}
+
+fn user_by_name() -> UserByName {
+    UserByName { first_name: String::from("ferris"), username: String::from("rust") }
+}
+
+#[test]
+fn test_mixed_batch() {
+    let batch = BatchBuilder::new()
+        .add_update(user().update_query().unwrap())
+        .add_store(user_by_name().store_query())
+        .add_delete(user_by_name().delete_query())
+        .build()
+        .unwrap();
+
+    assert_eq!(batch.query(), &format!("BEGIN BATCH {}; {}; {}; APPLY BATCH",
+                                       user().update_query().unwrap().query(),
+                                       user_by_name().store_query().query(),
+                                       user_by_name().delete_query().query()));
+
+    assert_eq!(batch.values().len(), 6);
+
+    let update = BatchBuilder::new().add_update(user().update_query().unwrap()).build().unwrap();
+
+    assert_eq!(update.query(), "BEGIN BATCH UPDATE test.user SET first_name=? WHERE username=?; APPLY BATCH");
+    assert_eq!(update.values(), &QueryValues::SimpleValues(vec![Value::from("ferris"), Value::from("rust")]));
+}
+
+#[test]
+fn test_empty_batch() {
+    assert!(BatchBuilder::new().build().is_err());
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct UserStats {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "COUNTER")]
+    logins: i64,
+}
+
+fn user_stats() -> UserStats {
+    UserStats { username: String::from("rust"), logins: 0 }
+}
+
+#[test]
+fn test_batch_kinds() {
+    let unlogged = BatchBuilder::with_kind(BatchKind::Unlogged)
+        .add_store(user().store_query())
+        .build()
+        .unwrap();
+
+    assert!(unlogged.query().starts_with("BEGIN UNLOGGED BATCH INSERT INTO test.user "));
+
+    let counter = BatchBuilder::with_kind(BatchKind::Counter)
+        .add_update(user_stats().counter_delta_query("logins").unwrap().prepend_values(vec![Value::from(1i64)]).unwrap())
+        .build()
+        .unwrap();
+
+    assert_eq!(counter.query(), "BEGIN COUNTER BATCH UPDATE test.user_stats SET logins=logins+? WHERE username=?; APPLY BATCH");
+    assert_eq!(counter.values(), &QueryValues::SimpleValues(vec![Value::from(1i64), Value::from("rust")]));
+
+    let logged = BatchBuilder::with_kind(BatchKind::Logged).add_delete(user().delete_query()).build().unwrap();
+
+    assert_eq!(logged.query(), "BEGIN BATCH DELETE FROM test.user WHERE username=?; APPLY BATCH");
+}
+
+#[test]
+fn test_batch_mixing_counter_mutations() {
+    let counter_in_logged = BatchBuilder::new()
+        .add_store(user().store_query())
+        .add_update(user_stats().counter_delta_query("logins").unwrap());
+
+    assert!(counter_in_logged.build().is_err());
+
+    let store_in_counter = BatchBuilder::with_kind(BatchKind::Counter)
+        .add_update(user_stats().counter_delta_query("logins").unwrap())
+        .add_store(user().store_query());
+
+    assert!(store_in_counter.build().is_err());
+}
+
+#[test]
+fn test_batch_with_named_values() {
+    let values = vec![(String::from("username"), Value::from("rust"))].into_iter().collect();
+    let named = StoreQuery::new(String::from("INSERT INTO test.user (username) VALUES (:username)"), QueryValues::NamedValues(values));
+
+    let batch = BatchBuilder::new()
+        .add_store(user().store_query())
+        .add_store(named);
+
+    match batch.build() {
+        Err(e) => assert!(e.to_string().contains("VALUES (:username)")),
+        other => panic!("Expected invalid batch, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_batch_with_timestamp() {
+    let batch = BatchBuilder::with_kind(BatchKind::Unlogged)
+        .with_timestamp(1_000_000)
+        .add_update(user().update_query().unwrap())
+        .build()
+        .unwrap();
+
+    assert_eq!(batch.query(),
+               "BEGIN UNLOGGED BATCH USING TIMESTAMP ? UPDATE test.user SET first_name=? WHERE username=?; APPLY BATCH");
+    assert_eq!(batch.values(), &QueryValues::SimpleValues(vec![
+        Value::from(1_000_000i64), Value::from("ferris"), Value::from("rust")
+    ]));
+
+    let logged = BatchBuilder::new().add_delete(user().delete_query()).with_timestamp(7).build().unwrap();
+
+    assert!(logged.query().starts_with("BEGIN BATCH USING TIMESTAMP ? DELETE"));
+}
+
+#[test]
+fn test_batch_with_timestamp_rejects_statements_with_their_own() {
+    let batch = BatchBuilder::new()
+        .with_timestamp(7)
+        .add_delete(user().delete_query_with_timestamp(1).unwrap())
+        .build();
+
+    match batch {
+        Err(e) => assert_eq!(e.to_string(),
+            "Batch with a timestamp can not have statements with their own timestamp, \
+             found `DELETE FROM test.user USING TIMESTAMP ? WHERE username=?`"),
+        other => panic!("Expected invalid batch, got {:?}", other),
+    }
+
+    let batch = BatchBuilder::new()
+        .add_store(user().store_query_using(Some(60), Some(1)).unwrap())
+        .with_timestamp(7)
+        .build();
+
+    assert!(batch.is_err());
+
+    let with_ttl = BatchBuilder::new()
+        .with_timestamp(7)
+        .add_store(user().store_query_with_ttl(60).unwrap())
+        .build()
+        .unwrap();
+
+    assert!(with_ttl.query().contains("USING TTL ?"));
+    assert!(BatchBuilder::new().add_delete(user().delete_query_with_timestamp(1).unwrap()).build().is_ok());
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Reading {
+    #[column(type = "TEXT", primary_key)]
+    sensor: String,
+
+    #[column(type = "INT", cluster_key(order = "ASC", position = 1))]
+    sequence: i32,
+
+    #[column(type = "DOUBLE")]
+    value: f64,
+}
+
+fn readings() -> Vec<Reading> {
+    vec![
+        Reading { sensor: String::from("a"), sequence: 1, value: 0.5 },
+        Reading { sensor: String::from("b"), sequence: 1, value: 1.5 },
+        Reading { sensor: String::from("a"), sequence: 2, value: 2.5 },
+    ]
+}
+
+#[test]
+fn test_store_many_query() {
+    let readings = readings();
+    let batch = Reading::store_many_query(&readings).unwrap();
+
+    let insert = readings[0].store_query().query().to_string();
+
+    assert_eq!(batch.query(), format!("BEGIN UNLOGGED BATCH {}; {}; {}; APPLY BATCH", insert, insert, insert));
+    assert_eq!(batch.values().len(), readings.len() * Reading::insert_columns().len());
+    assert_eq!(batch.values(), &QueryValues::SimpleValues(vec![
+        Value::from("a"), Value::from(1), Value::from(0.5),
+        Value::from("b"), Value::from(1), Value::from(1.5),
+        Value::from("a"), Value::from(2), Value::from(2.5),
+    ]));
+    assert_eq!(batch.table(), "reading");
+
+    assert!(Reading::store_many_query(&[]).is_err());
+}
+
+#[test]
+fn test_store_many_by_partition_query() {
+    let readings = readings();
+    let batches = Reading::store_many_by_partition_query(&readings).unwrap();
+
+    let insert = readings[0].store_query().query().to_string();
+
+    assert_eq!(batches.len(), 2);
+    assert_eq!(batches[0].query(), format!("BEGIN UNLOGGED BATCH {}; {}; APPLY BATCH", insert, insert));
+    assert_eq!(batches[0].values(), &QueryValues::SimpleValues(vec![
+        Value::from("a"), Value::from(1), Value::from(0.5),
+        Value::from("a"), Value::from(2), Value::from(2.5),
+    ]));
+    assert_eq!(batches[1].query(), format!("BEGIN UNLOGGED BATCH {}; APPLY BATCH", insert));
+    assert_eq!(batches[1].values().len(), Reading::insert_columns().len());
+
+    assert_eq!(Reading::store_many_by_partition_query(&[]).unwrap_err().to_string(), "Batch must have at least one statement");
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Attachment {
+    #[column(type = "INT", primary_key)]
+    id: i32,
+
+    #[column(type = "BLOB")]
+    content: Vec<u8>,
+}
+
+fn attachments() -> Vec<Attachment> {
+    (0..10).map(|id| Attachment { id, content: vec![0; 1000] }).collect()
+}
+
+#[test]
+fn test_chunked_by_statements() {
+    let batch = BatchBuilder::with_kind(BatchKind::Unlogged)
+        .with_timestamp(7)
+        .add_store(user().store_query())
+        .add_update(user().update_query().unwrap())
+        .add_delete(user_by_name().delete_query())
+        .build()
+        .unwrap();
+
+    let chunks = batch.chunked(2, usize::MAX);
+
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].query(), format!("BEGIN UNLOGGED BATCH USING TIMESTAMP ? {}; {}; APPLY BATCH",
+                                          user().store_query().query(), user().update_query().unwrap().query()));
+    assert_eq!(chunks[0].values(), &QueryValues::SimpleValues(vec![
+        Value::from(7i64), Value::from("rust"), Value::from("ferris"), Value::from("ferris"), Value::from("rust")
+    ]));
+    assert_eq!(chunks[0].table(), "user");
+    assert_eq!(chunks[1].values(), &QueryValues::SimpleValues(vec![Value::from(7i64), Value::from("ferris"), Value::from("rust")]));
+    assert_eq!(chunks[1].table(), "user_by_name");
+
+    assert_eq!(batch.chunked(3, usize::MAX), vec![batch.clone()]);
+    assert_eq!(batch.chunked(0, 0).len(), 3);
+}
+
+#[test]
+fn test_chunked_by_size() {
+    let batch = Attachment::store_many_query(&attachments()).unwrap();
+
+    // Each insert binds 4 bytes of id and 1000 bytes of content
+    assert_eq!(batch.chunked(100, 2008).len(), 5);
+    assert_eq!(batch.chunked(100, 2007).len(), 10);
+    assert_eq!(batch.chunked(4, 2008).len(), 5);
+    assert_eq!(batch.chunked(3, usize::MAX).len(), 4);
+    assert_eq!(batch.chunked(3, 10).len(), 10);
+
+    for chunks in [batch.chunked(3, usize::MAX), batch.chunked(100, 3100)].iter() {
+        let statements: Vec<&str> = chunks.iter().flat_map(|c| c.statements()).collect();
+        let values: Vec<Value> = chunks.iter().flat_map(|c| match c.values() {
+            QueryValues::SimpleValues(values) => values.clone(),
+            QueryValues::NamedValues(_) => unreachable!(),
+        }).collect();
+
+        assert_eq!(statements, batch.statements());
+        assert_eq!(&QueryValues::SimpleValues(values), batch.values());
+        assert!(chunks.iter().all(|c| c.query().starts_with("BEGIN UNLOGGED BATCH INSERT")));
+    }
+}
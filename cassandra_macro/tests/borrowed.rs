@@ -0,0 +1,110 @@
+use cassandra_macro::{CassandraTable, Projection};
+use cassandra_macro_derive::CassandraTable;
+use uuid::Uuid;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", infer_types)]
+pub struct Event<'a> {
+    #[column(compound_key(position = 1))]
+    source: &'a str,
+
+    #[column(type = "UUID", cluster_key(order = "DESC", position = 1))]
+    id: Uuid,
+
+    #[column(type = "ASCII", moved)]
+    kind: &'a str,
+
+    #[column()]
+    payload: &'a [u8],
+
+    #[column()]
+    note: Option<&'a str>,
+
+    #[column()]
+    checksum: Option<&'a [u8]>,
+}
+
+/// The same table with owned fields
+mod owned {
+    use cassandra_macro_derive::CassandraTable;
+    use uuid::Uuid;
+
+    #[derive(Debug, CassandraTable)]
+    #[table(keyspace = "test", infer_types)]
+    pub struct Event {
+        #[column(compound_key(position = 1))]
+        source: String,
+
+        #[column(type = "UUID", cluster_key(order = "DESC", position = 1))]
+        id: Uuid,
+
+        #[column(type = "ASCII", moved)]
+        kind: String,
+
+        #[column()]
+        payload: Vec<u8>,
+
+        #[column()]
+        note: Option<String>,
+
+        #[column()]
+        checksum: Option<Vec<u8>>,
+    }
+
+    pub fn event(checksum: Option<Vec<u8>>) -> Event {
+        Event {
+            source: String::from("sensor-1"),
+            id: Uuid::nil(),
+            kind: String::from("boot"),
+            payload: vec![1, 2, 3],
+            note: Some(String::from("first")),
+            checksum,
+        }
+    }
+}
+
+fn event(payload: &[u8]) -> Event<'_> {
+    Event {
+        source: "sensor-1",
+        id: Uuid::nil(),
+        kind: "boot",
+        payload,
+        note: Some("first"),
+        checksum: None,
+    }
+}
+
+#[test]
+fn test_borrowed_ddl_as_owned() {
+    assert_eq!(Event::create_table_cql(), owned::Event::create_table_cql());
+    assert_eq!(Event::create_table_cql(),
+               "CREATE TABLE IF NOT EXISTS test.event  (source TEXT,id UUID,kind ASCII,payload BLOB,note TEXT,checksum BLOB, \
+                PRIMARY KEY ((source), id) ) WITH CLUSTERING ORDER BY (id DESC)");
+    assert_eq!(Event::columns(), owned::Event::columns());
+}
+
+#[test]
+fn test_borrowed_statements_as_owned() {
+    let payload = vec![1, 2, 3];
+    let owned = owned::event(None);
+
+    assert_eq!(event(&payload).store_query().query(), owned.store_query().query());
+    assert_eq!(event(&payload).store_query().values(), owned.store_query().values());
+    assert_eq!(event(&payload).update_query().unwrap().values(), owned.update_query().unwrap().values());
+    assert_eq!(event(&payload).delete_query().values(), owned.delete_query().values());
+    assert_eq!(event(&payload).column_value("payload"), owned.column_value("payload"));
+
+    let event = Event { checksum: Some(&[9]), ..event(&payload) };
+
+    assert_eq!(event.column_value("checksum"), owned::event(Some(vec![9])).column_value("checksum"));
+}
+
+#[test]
+fn test_borrowed_key() {
+    let payload = vec![1, 2, 3];
+    let key = event(&payload).key();
+
+    assert_eq!(key, EventKey { source: String::from("sensor-1"), id: Uuid::nil() });
+    assert_eq!(Event::select_by_key(&key, Projection::All).values(),
+               owned::Event::select_by_key(&owned::event(None).key(), Projection::All).values());
+}
@@ -0,0 +1,319 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use cassandra_macro::CassandraTable;
+use cassandra_macro_derive::CassandraTable;
+use cdrs::frame::frame_result::{BodyResResultRows, ColSpec, ColType, ColTypeOption, RowsMetadata};
+use cdrs::frame::TryFromRow;
+use cdrs::query::QueryValues;
+use cdrs::types::{CBytes, CString};
+use cdrs::types::blob::Blob;
+use cdrs::types::rows::Row;
+use cdrs::types::value::Value;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Article {
+    #[column(type = "TEXT", primary_key)]
+    slug: String,
+
+    #[column(type = "list<text>")]
+    paragraphs: Vec<String>,
+
+    #[column(type = "set<text>")]
+    tags: Vec<String>,
+
+    #[column(type = "map<text, text>")]
+    metadata: HashMap<String, String>,
+
+    #[column(type = "frozen<list<text>>")]
+    authors: Vec<String>,
+
+    #[column(type = "TEXT")]
+    title: String,
+}
+
+fn article() -> Article {
+    Article {
+        slug: String::from("rust"),
+        paragraphs: vec![],
+        tags: vec![],
+        metadata: HashMap::new(),
+        authors: vec![],
+        title: String::from("Rust"),
+    }
+}
+
+#[test]
+fn test_list_append_and_prepend() {
+    let query = article().collection_append_query("paragraphs").unwrap()
+        .prepend_values(vec![Value::from(vec![String::from("last")])]).unwrap();
+
+    assert_eq!(query.query(), "UPDATE test.article SET paragraphs=paragraphs+? WHERE slug=?");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![
+        Value::from(vec![String::from("last")]), Value::from("rust")
+    ]));
+
+    let query = article().collection_prepend_query("paragraphs").unwrap();
+
+    assert_eq!(query.query(), "UPDATE test.article SET paragraphs=?+paragraphs WHERE slug=?");
+
+    assert!(article().collection_prepend_query("tags").is_err());
+}
+
+#[test]
+fn test_set_remove() {
+    let query = article().collection_remove_query("tags").unwrap();
+
+    assert_eq!(query.query(), "UPDATE test.article SET tags=tags-? WHERE slug=?");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![Value::from("rust")]));
+
+    assert!(article().collection_remove_query("authors").is_err());
+    assert!(article().collection_remove_query("title").is_err());
+}
+
+#[test]
+fn test_map_put() {
+    let query = article().map_put_query("metadata").unwrap()
+        .prepend_values(vec![Value::from("lang"), Value::from("en")]).unwrap();
+
+    assert_eq!(query.query(), "UPDATE test.article SET metadata[?]=? WHERE slug=?");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![
+        Value::from("lang"), Value::from("en"), Value::from("rust")
+    ]));
+
+    assert!(article().map_put_query("tags").is_err());
+    assert!(article().map_put_query("slug").is_err());
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", infer_types)]
+pub struct Leaderboard {
+    #[column(primary_key)]
+    game: String,
+
+    #[column()]
+    scores: HashMap<String, i64>,
+
+    #[column()]
+    rounds: Option<Vec<i32>>,
+}
+
+#[test]
+fn test_inferred_map_column() {
+    assert_eq!(Leaderboard::column_type("game"), Some("TEXT"));
+    assert_eq!(Leaderboard::column_type("scores"), Some("MAP<TEXT, BIGINT>"));
+    assert_eq!(Leaderboard::column_type("rounds"), Some("LIST<INT>"));
+    assert_eq!(Leaderboard::create_table_cql(),
+               "CREATE TABLE IF NOT EXISTS test.leaderboard  (game TEXT,scores MAP<TEXT, BIGINT>,rounds LIST<INT>, PRIMARY KEY (game) ) ");
+
+    let mut scores = HashMap::new();
+    scores.insert(String::from("ferris"), 7i64);
+
+    let leaderboard = Leaderboard { game: String::from("rust"), scores: scores.clone(), rounds: None };
+
+    assert_eq!(leaderboard.store_query().query(), "INSERT INTO test.leaderboard (game,scores,rounds) VALUES (?,?,?)");
+    assert_eq!(leaderboard.store_query().values(), &QueryValues::SimpleValues(vec![
+        Value::from("rust"), Value::from(scores.clone()), Value::from(None::<Vec<i32>>)
+    ]));
+    assert_eq!(leaderboard.update_query().unwrap().values(), &QueryValues::SimpleValues(vec![
+        Value::from(scores), Value::from(None::<Vec<i32>>), Value::from("rust")
+    ]));
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", infer_types)]
+pub struct Tournament {
+    #[column(primary_key)]
+    game: String,
+
+    #[column()]
+    scores_by_round: HashMap<String, HashMap<String, i64>>,
+}
+
+#[test]
+fn test_inferred_nested_map_column_is_frozen() {
+    assert_eq!(Tournament::column_type("scores_by_round"), Some("MAP<TEXT, FROZEN<MAP<TEXT, BIGINT>>>"));
+    assert_eq!(Tournament::create_table_cql(),
+               "CREATE TABLE IF NOT EXISTS test.tournament  (game TEXT,scores_by_round MAP<TEXT, FROZEN<MAP<TEXT, BIGINT>>>, PRIMARY KEY (game) ) ");
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", infer_types)]
+pub struct Roster {
+    #[column(primary_key)]
+    team: String,
+
+    #[column()]
+    players_by_role: HashMap<String, Vec<String>>,
+
+    #[column()]
+    lineups: Vec<Vec<String>>,
+}
+
+#[test]
+fn test_inferred_nested_list_columns_are_frozen() {
+    assert_eq!(Roster::create_table_cql(),
+               "CREATE TABLE IF NOT EXISTS test.roster  (team TEXT,players_by_role MAP<TEXT, FROZEN<LIST<TEXT>>>,lineups LIST<FROZEN<LIST<TEXT>>>, PRIMARY KEY (team) ) ");
+
+    let mut players_by_role = HashMap::new();
+    players_by_role.insert(String::from("captain"), vec![String::from("ferris")]);
+
+    let roster = Roster { team: String::from("rust"), players_by_role: players_by_role.clone(), lineups: vec![vec![String::from("ferris")]] };
+
+    assert_eq!(roster.store_query().values(), &QueryValues::SimpleValues(vec![
+        Value::from("rust"), Value::from(players_by_role), Value::from(vec![vec![String::from("ferris")]])
+    ]));
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", infer_types)]
+pub struct Upload {
+    #[column(primary_key)]
+    name: String,
+
+    #[column()]
+    labels: Vec<String>,
+
+    #[column(type = "list<bigint>")]
+    sizes: Vec<i64>,
+
+    #[column()]
+    content: Vec<u8>,
+
+    #[column(type = "BLOB")]
+    thumbnail: Option<Vec<u8>>,
+}
+
+#[test]
+fn test_inferred_list_and_blob_columns() {
+    assert_eq!(Upload::create_table_cql(),
+               "CREATE TABLE IF NOT EXISTS test.upload  (name TEXT,labels LIST<TEXT>,sizes LIST<BIGINT>,content BLOB,thumbnail BLOB, PRIMARY KEY (name) ) ");
+
+    let upload = Upload {
+        name: String::from("rust.png"),
+        labels: vec![String::from("logo")],
+        sizes: vec![7],
+        content: vec![1, 2, 3],
+        thumbnail: None,
+    };
+
+    assert_eq!(upload.store_query().values(), &QueryValues::SimpleValues(vec![
+        Value::from("rust.png"),
+        Value::from(vec![String::from("logo")]),
+        Value::from(vec![7i64]),
+        Value::from(Blob::new(vec![1, 2, 3])),
+        Value::from(None::<Blob>),
+    ]));
+
+    assert_eq!(upload.column_value("content").unwrap().body, vec![1, 2, 3]);
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", derive_try_from_row, infer_types)]
+pub struct Image {
+    #[column(primary_key)]
+    name: String,
+
+    #[column()]
+    content: Vec<u8>,
+
+    #[column()]
+    thumbnail: Option<Vec<u8>>,
+}
+
+fn col_spec(name: &str, id: ColType) -> ColSpec {
+    ColSpec {
+        ksname: None,
+        tablename: None,
+        name: CString::new(name.to_string()),
+        col_type: ColTypeOption { id, value: None },
+    }
+}
+
+#[test]
+fn test_blob_column_from_row() {
+    let col_specs = vec![
+        col_spec("name", ColType::Varchar),
+        col_spec("content", ColType::Blob),
+        col_spec("thumbnail", ColType::Blob),
+    ];
+
+    let body = BodyResResultRows {
+        metadata: RowsMetadata { flags: 0, columns_count: 3, paging_state: None, global_table_space: None, col_specs },
+        rows_count: 1,
+        rows_content: vec![vec![CBytes::new(b"rust.png".to_vec()), CBytes::new(vec![1, 2, 3]), CBytes::new_empty()]],
+    };
+
+    let image = Image::try_from_row(Row::from_frame_body(body).remove(0)).unwrap();
+
+    assert_eq!(image.name, "rust.png");
+    assert_eq!(image.content, vec![1, 2, 3]);
+    assert_eq!(image.thumbnail, None);
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", infer_types)]
+pub struct Team {
+    #[column(primary_key)]
+    name: String,
+
+    #[column()]
+    members: HashSet<String>,
+
+    #[column(type = "set<int>")]
+    lucky_numbers: BTreeSet<i32>,
+
+    #[column()]
+    retired_numbers: Option<BTreeSet<i32>>,
+
+    #[column(type = "set<text>")]
+    sponsors: Vec<String>,
+}
+
+#[test]
+fn test_set_columns() {
+    assert_eq!(Team::create_table_cql(),
+               "CREATE TABLE IF NOT EXISTS test.team  (name TEXT,members SET<TEXT>,lucky_numbers SET<INT>,retired_numbers SET<INT>,\
+                sponsors SET<TEXT>, PRIMARY KEY (name) ) ");
+
+    let team = Team {
+        name: String::from("rust"),
+        members: vec![String::from("ferris")].into_iter().collect(),
+        lucky_numbers: vec![7, 3].into_iter().collect(),
+        retired_numbers: None,
+        sponsors: vec![String::from("b"), String::from("a"), String::from("b")],
+    };
+
+    assert_eq!(team.store_query().values(), &QueryValues::SimpleValues(vec![
+        Value::from("rust"),
+        Value::from(vec![String::from("ferris")]),
+        Value::from(vec![3i32, 7]),
+        Value::from(None::<Vec<i32>>),
+        Value::from(vec![String::from("b"), String::from("a"), String::from("b")]),
+    ]));
+
+    assert_eq!(team.update_query().unwrap().values(), &QueryValues::SimpleValues(vec![
+        Value::from(vec![String::from("ferris")]),
+        Value::from(vec![3i32, 7]),
+        Value::from(None::<Vec<i32>>),
+        Value::from(vec![String::from("b"), String::from("a"), String::from("b")]),
+        Value::from("rust"),
+    ]));
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Route {
+    #[column(type = "TEXT", primary_key)]
+    city: String,
+
+    #[column(type = "frozen<map<text, int>>", cluster_key(order = "ASC", position = 1))]
+    stops: HashMap<String, i32>,
+}
+
+#[test]
+fn test_frozen_collection_key() {
+    assert_eq!(Route::create_table_cql(),
+               "CREATE TABLE IF NOT EXISTS test.route  (city TEXT,stops FROZEN<MAP<TEXT, INT>>, \
+                PRIMARY KEY ((city), stops) ) WITH CLUSTERING ORDER BY (stops ASC)");
+}
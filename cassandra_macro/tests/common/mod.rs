@@ -0,0 +1,41 @@
+//! Result bodies of the CQL binary protocol shared by the session tests
+
+pub fn string(s: &str) -> Vec<u8> {
+    [&(s.len() as u16).to_be_bytes()[..], s.as_bytes()].concat()
+}
+
+pub fn bytes(b: &[u8]) -> Vec<u8> {
+    [&(b.len() as i32).to_be_bytes()[..], b].concat()
+}
+
+/// Body of a void result
+pub fn void_body() -> Vec<u8> {
+    1i32.to_be_bytes().to_vec()
+}
+
+/// Body of the rows of the users of `test.user`,
+/// followed by more pages if there is a paging state
+pub fn user_rows_body(users: &[(&str, i64, &str)], paging_state: Option<&[u8]>) -> Vec<u8> {
+    let flags = if paging_state.is_some() { 0x0003i32 } else { 0x0001 };
+
+    let mut body = [2i32.to_be_bytes(), flags.to_be_bytes(), 3i32.to_be_bytes()].concat();
+
+    if let Some(paging_state) = paging_state {
+        body.extend(bytes(paging_state));
+    }
+
+    body.extend([string("test"), string("user")].concat());
+
+    for (column, id) in [("username", 0x000Du16), ("created", 0x0002), ("nickname", 0x000D)].iter() {
+        body.extend(string(column));
+        body.extend(&id.to_be_bytes());
+    }
+
+    body.extend(&(users.len() as i32).to_be_bytes());
+
+    for (username, created, nickname) in users {
+        body.extend([bytes(username.as_bytes()), bytes(&created.to_be_bytes()), bytes(nickname.as_bytes())].concat());
+    }
+
+    body
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "chrono")]
+
+use cassandra_macro::chrono::{NaiveDate, NaiveTime};
+use cassandra_macro::{CassandraTable, CqlDate, CqlTime};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::frame::frame_result::{BodyResResultRows, ColSpec, ColType, ColTypeOption, RowsMetadata};
+use cdrs::frame::TryFromRow;
+use cdrs::query::QueryValues;
+use cdrs::types::{CBytes, CString};
+use cdrs::types::rows::Row;
+use cdrs::types::value::Value;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", derive_try_from_row, infer_types)]
+pub struct Shift {
+    #[column(type = "TEXT", primary_key)]
+    worker: String,
+
+    #[column(type = "DATE", cluster_key(order = "DESC", position = 1))]
+    day: NaiveDate,
+
+    #[column(type = "TIME")]
+    starts: NaiveTime,
+
+    #[column()]
+    ends: Option<NaiveTime>,
+}
+
+fn col_spec(name: &str, id: ColType) -> ColSpec {
+    ColSpec {
+        ksname: None,
+        tablename: None,
+        name: CString::new(name.to_string()),
+        col_type: ColTypeOption { id, value: None },
+    }
+}
+
+fn last_nanosecond() -> NaiveTime {
+    NaiveTime::from_hms_nano_opt(23, 59, 59, 999_999_999).unwrap()
+}
+
+fn epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()
+}
+
+#[test]
+fn test_date_time_ddl() {
+    assert_eq!(Shift::create_table_cql(),
+               "CREATE TABLE IF NOT EXISTS test.shift  (worker TEXT,day DATE,starts TIME,ends TIME, \
+                PRIMARY KEY ((worker), day) ) WITH CLUSTERING ORDER BY (day DESC)");
+}
+
+#[test]
+fn test_date_time_values() {
+    let shift = Shift { worker: String::from("ferris"), day: epoch(), starts: NaiveTime::MIN, ends: Some(last_nanosecond()) };
+
+    assert_eq!(shift.store_query().values(), &QueryValues::SimpleValues(vec![
+        Value::from("ferris"),
+        Value::from(CqlDate(epoch())),
+        Value::from(CqlTime(NaiveTime::MIN)),
+        Value::from(Some(CqlTime(last_nanosecond()))),
+    ]));
+
+    assert_eq!(shift.column_value("day").unwrap().body, vec![0x80, 0, 0, 0]);
+    assert_eq!(shift.column_value("ends").unwrap().body, 86_399_999_999_999i64.to_be_bytes().to_vec());
+}
+
+#[test]
+fn test_date_time_round_trip() {
+    for date in [epoch(), NaiveDate::from_ymd_opt(1969, 12, 31).unwrap(), NaiveDate::from_ymd_opt(2038, 1, 19).unwrap()].iter() {
+        assert_eq!(CqlDate::from_cql_bytes(&CqlDate(*date).to_cql_bytes()), Some(CqlDate(*date)));
+    }
+
+    for time in [NaiveTime::MIN, last_nanosecond()].iter() {
+        assert_eq!(CqlTime::from_cql_bytes(&CqlTime(*time).to_cql_bytes()), Some(CqlTime(*time)));
+    }
+
+    assert_eq!(CqlDate(NaiveDate::from_ymd_opt(1969, 12, 31).unwrap()).to_cql_value(), (1 << 31) - 1);
+    assert_eq!(CqlTime::from_cql_value(86_400_000_000_000), None);
+    assert_eq!(CqlTime::from_cql_value(-1), None);
+}
+
+#[test]
+fn test_date_time_from_row() {
+    let col_specs = vec![
+        col_spec("worker", ColType::Varchar),
+        col_spec("day", ColType::Date),
+        col_spec("starts", ColType::Time),
+        col_spec("ends", ColType::Time),
+    ];
+
+    let body = BodyResResultRows {
+        metadata: RowsMetadata { flags: 0, columns_count: 4, paging_state: None, global_table_space: None, col_specs },
+        rows_count: 1,
+        rows_content: vec![vec![
+            CBytes::new(b"ferris".to_vec()),
+            CBytes::new(CqlDate(epoch()).to_cql_bytes()),
+            CBytes::new(CqlTime(last_nanosecond()).to_cql_bytes()),
+            CBytes::new_empty(),
+        ]],
+    };
+
+    let shift = Shift::try_from_row(Row::from_frame_body(body).remove(0)).unwrap();
+
+    assert_eq!(shift.day, epoch());
+    assert_eq!(shift.starts, last_nanosecond());
+    assert_eq!(shift.ends, None);
+}
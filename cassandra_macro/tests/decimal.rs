@@ -0,0 +1,155 @@
+#![cfg(feature = "bigdecimal")]
+
+use std::str::FromStr;
+
+use cassandra_macro::bigdecimal::BigDecimal;
+use cassandra_macro::bigdecimal::num_bigint::BigInt;
+use cassandra_macro::{CassandraTable, CqlDecimal, Error, rows_with_raw_columns};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::frame::frame_response::ResponseBody;
+use cdrs::frame::frame_result::{BodyResResultRows, ColSpec, ColType, ColTypeOption, ResResultBody, RowsMetadata};
+use cdrs::frame::TryFromRow;
+use cdrs::query::QueryValues;
+use cdrs::types::{CBytes, CString};
+use cdrs::types::rows::Row;
+use cdrs::types::value::{Bytes, Value};
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "shop", derive_try_from_row, infer_types)]
+pub struct Product {
+    #[column(type = "TEXT", primary_key)]
+    sku: String,
+
+    #[column(type = "DECIMAL")]
+    price: BigDecimal,
+
+    #[column()]
+    discount: Option<BigDecimal>,
+}
+
+fn col_spec(name: &str, id: ColType) -> ColSpec {
+    ColSpec {
+        ksname: None,
+        tablename: None,
+        name: CString::new(name.to_string()),
+        col_type: ColTypeOption { id, value: None },
+    }
+}
+
+#[test]
+fn test_decimal_ddl() {
+    assert_eq!(Product::create_table_cql(),
+               "CREATE TABLE IF NOT EXISTS shop.product  (sku TEXT,price DECIMAL,discount DECIMAL, PRIMARY KEY (sku) ) ");
+}
+
+#[test]
+fn test_decimal_values() {
+    let price = BigDecimal::from_str("19.990").unwrap();
+
+    let product = Product { sku: String::from("rust-book"), price: price.clone(), discount: None };
+
+    assert_eq!(product.try_store_query().unwrap().values(), &QueryValues::SimpleValues(vec![
+        Value::from("rust-book"),
+        Value::from(Bytes::new(CqlDecimal(price).to_cql_bytes().unwrap())),
+        Value::from(None::<Bytes>),
+    ]));
+
+    assert_eq!(product.column_value("price").unwrap().body, vec![0, 0, 0, 3, 0x4E, 0x16]);
+}
+
+#[test]
+fn test_decimal_round_trip() {
+    for decimal in ["19.990", "-0.000001", "123456789012345678901234567890.123456789", "1E+3"].iter() {
+        let decimal = CqlDecimal(BigDecimal::from_str(decimal).unwrap());
+
+        let read = CqlDecimal::from_cql_bytes(&decimal.to_cql_bytes().unwrap()).unwrap();
+
+        assert_eq!(read.0.as_bigint_and_exponent(), decimal.0.as_bigint_and_exponent());
+    }
+
+    assert_eq!(CqlDecimal::from_cql_bytes(&[0, 0, 0, 1]), None);
+}
+
+#[test]
+fn test_decimal_scale_out_of_range() {
+    let decimal = CqlDecimal(BigDecimal::new(BigInt::from(1), i64::from(i32::MAX) + 1));
+
+    assert!(matches!(decimal.to_cql_bytes(), Err(Error::InvalidValue(_))));
+
+    let product = Product { sku: String::from("rust-book"), price: decimal.into_inner(), discount: None };
+
+    assert!(matches!(product.try_store_query(), Err(Error::InvalidValue(_))));
+    assert_eq!(product.column_value("price"), None);
+}
+
+#[test]
+fn test_decimal_from_row() {
+    let price = CqlDecimal(BigDecimal::from_str("19.990").unwrap());
+
+    let body = BodyResResultRows {
+        metadata: RowsMetadata {
+            flags: 0,
+            columns_count: 3,
+            paging_state: None,
+            global_table_space: None,
+            col_specs: vec![col_spec("sku", ColType::Varchar), col_spec("price", ColType::Decimal), col_spec("discount", ColType::Decimal)],
+        },
+        rows_count: 1,
+        rows_content: vec![vec![CBytes::new(b"rust-book".to_vec()), CBytes::new(price.to_cql_bytes().unwrap()), CBytes::new_empty()]],
+    };
+
+    let product = Product::try_from_row(Row::from_frame_body(body).remove(0)).unwrap();
+
+    assert_eq!(product.price.as_bigint_and_exponent(), price.0.as_bigint_and_exponent());
+    assert_eq!(product.discount, None);
+}
+
+#[test]
+fn test_decimal_raw_row_round_trip() {
+    assert_eq!(Product::raw_columns(), &["price", "discount"]);
+
+    let price = BigDecimal::from_str("123456789012345678901234567890.12").unwrap();
+    let discount = BigDecimal::new(BigInt::from_str("-123456789012345678901234567890").unwrap(), -3);
+
+    let product = Product { sku: String::from("rust-book"), price: price.clone(), discount: Some(discount.clone()) };
+
+    let body = BodyResResultRows {
+        metadata: RowsMetadata {
+            flags: 0,
+            columns_count: 3,
+            paging_state: None,
+            global_table_space: None,
+            col_specs: vec![col_spec("sku", ColType::Varchar), col_spec("price", ColType::Decimal), col_spec("discount", ColType::Decimal)],
+        },
+        rows_count: 1,
+        rows_content: vec![Product::columns().iter().map(|c| CBytes::new(product.column_value(c).unwrap().body)).collect()],
+    };
+
+    let rows = rows_with_raw_columns(ResponseBody::Result(ResResultBody::Rows(body)), Product::raw_columns()).unwrap();
+
+    let from_db = Product::try_from_row(rows.into_iter().next().unwrap()).unwrap();
+
+    assert_eq!(from_db.price.as_bigint_and_exponent(), price.as_bigint_and_exponent());
+    assert_eq!(from_db.discount.unwrap().as_bigint_and_exponent(), discount.as_bigint_and_exponent());
+}
+
+#[test]
+fn test_negative_scale_from_driver_row() {
+    let price = CqlDecimal(BigDecimal::new(BigInt::from(42), -2));
+
+    let body = BodyResResultRows {
+        metadata: RowsMetadata {
+            flags: 0,
+            columns_count: 3,
+            paging_state: None,
+            global_table_space: None,
+            col_specs: vec![col_spec("sku", ColType::Varchar), col_spec("price", ColType::Decimal), col_spec("discount", ColType::Decimal)],
+        },
+        rows_count: 1,
+        rows_content: vec![vec![CBytes::new(b"rust-book".to_vec()), CBytes::new(price.to_cql_bytes().unwrap()), CBytes::new_empty()]],
+    };
+
+    let product = Product::try_from_row(Row::from_frame_body(body).remove(0)).unwrap();
+
+    assert_eq!(product.price.as_bigint_and_exponent(), (BigInt::from(42), -2));
+}
@@ -0,0 +1,149 @@
+use cassandra_macro::{CassandraTable, ClusterKeyFilter, Error};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::query::QueryValues;
+use cdrs::types::value::Value;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct UserEvent {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TIMESTAMP", cluster_key(order = "ASC", position = 1))]
+    created: i64,
+
+    #[column(type = "TEXT")]
+    payload: String,
+
+    #[column(type = "TIMESTAMP")]
+    updated: i64,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT")]
+    first_name: String,
+}
+
+fn user_event() -> UserEvent {
+    UserEvent {
+        username: String::from("rust"),
+        created: 1,
+        payload: String::from("payload"),
+        updated: 2,
+    }
+}
+
+#[test]
+fn test_delete_if_exists() {
+    let event = user_event();
+
+    let query = event.delete_query_if_exists().unwrap();
+
+    assert_eq!(query.query(), "DELETE FROM test.user_event WHERE username=? AND created=? IF EXISTS");
+    assert_eq!(query.values(), event.delete_query().values());
+    assert!(query.is_lwt());
+
+    assert_eq!(UserEvent::delete_by_primary_and_cluster_keys_if_exists(),
+               "DELETE FROM test.user_event WHERE  username=?  AND  created=? IF EXISTS");
+}
+
+#[test]
+fn test_delete_query_with_timestamp() {
+    let event = user_event();
+
+    let query = event.delete_query_with_timestamp(1_600_000_000_000_000).unwrap();
+
+    assert_eq!(query.query(), "DELETE FROM test.user_event USING TIMESTAMP ? WHERE username=? AND created=?");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![
+        Value::from(1_600_000_000_000_000i64), Value::from("rust"), Value::from(1i64)
+    ]));
+    assert_eq!(query.query(), UserEvent::delete_by_primary_and_cluster_keys_with_timestamp());
+}
+
+#[test]
+fn test_delete_query_if() {
+    let event = user_event();
+
+    let single = event.delete_query_if(&[("updated", Value::from(2i64))]).unwrap();
+
+    assert_eq!(single.query(), "DELETE FROM test.user_event WHERE username=? AND created=? IF updated=?");
+    assert_eq!(single.values(), &QueryValues::SimpleValues(vec![
+        Value::from("rust"), Value::from(1i64), Value::from(2i64)
+    ]));
+    assert!(single.is_lwt());
+
+    let two = event.delete_query_if(&[("updated", Value::from(2i64)), ("payload", Value::from("old"))]).unwrap();
+
+    assert_eq!(two.query(), "DELETE FROM test.user_event WHERE username=? AND created=? IF updated=? AND payload=?");
+    assert_eq!(two.values(), &QueryValues::SimpleValues(vec![
+        Value::from("rust"), Value::from(1i64), Value::from(2i64), Value::from("old")
+    ]));
+
+    assert!(matches!(event.delete_query_if(&[("created", Value::from(1i64))]), Err(Error::UnknownColumn(e)) if e.columns() == vec!["created"]));
+    assert!(event.delete_query_if(&[("unknown", Value::from(1i64))]).is_err());
+    assert!(event.delete_query_if(&[]).is_err());
+}
+
+#[test]
+fn test_delete_columns_query() {
+    let event = user_event();
+
+    let single = event.delete_columns_query(&["payload"]).unwrap();
+
+    assert_eq!(single.query(), "DELETE payload FROM test.user_event WHERE username=? AND created=?");
+    assert_eq!(single.values(), event.delete_query().values());
+
+    let several = event.delete_columns_query(&["payload", "updated"]).unwrap();
+
+    assert_eq!(several.query(), "DELETE payload,updated FROM test.user_event WHERE username=? AND created=?");
+
+    assert!(event.delete_columns_query(&[]).is_err());
+    assert!(event.delete_columns_query(&["username"]).is_err());
+    assert!(event.delete_columns_query(&["payload", "created"]).is_err());
+}
+
+#[test]
+fn test_delete_partition_query() {
+    let event = user_event();
+
+    let query = event.delete_partition_query();
+
+    assert_eq!(query.query(), "DELETE FROM test.user_event WHERE username=?");
+    assert_eq!(query.values().len(), 1);
+
+    let user = User {
+        username: String::from("rust"),
+        first_name: String::from("ferris"),
+    };
+
+    assert_eq!(user.delete_partition_query().query(), user.delete_query().query());
+    assert_eq!(user.delete_partition_query().values(), user.delete_query().values());
+}
+
+#[test]
+fn test_delete_by_cluster_range() {
+    assert_eq!(UserEvent::delete_by_cluster_range(&[("created", ClusterKeyFilter::Lt)]).unwrap(),
+               "DELETE FROM test.user_event WHERE username=? AND created<?");
+
+    assert_eq!(UserEvent::delete_by_cluster_range(&[("created", ClusterKeyFilter::Gte)]).unwrap(),
+               "DELETE FROM test.user_event WHERE username=? AND created>=?");
+
+    let event = user_event();
+
+    let query = event.delete_cluster_range_query(&[
+        ("created", ClusterKeyFilter::Gte, Value::from(1i64)),
+        ("created", ClusterKeyFilter::Lt, Value::from(10i64)),
+    ]).unwrap();
+
+    assert_eq!(query.query(), "DELETE FROM test.user_event WHERE username=? AND created>=? AND created<?");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![Value::from("rust"), Value::from(1i64), Value::from(10i64)]));
+
+    assert!(event.delete_cluster_range_query(&[("payload", ClusterKeyFilter::Lt, Value::from("x"))]).is_err());
+
+    assert!(UserEvent::delete_by_cluster_range(&[("payload", ClusterKeyFilter::Lt)]).is_err());
+}
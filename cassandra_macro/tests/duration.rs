@@ -0,0 +1,151 @@
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use cassandra_macro::{CassandraTable, CqlDuration, Error, rows_with_raw_columns};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::frame::frame_response::ResponseBody;
+use cdrs::frame::frame_result::{BodyResResultRows, ColSpec, ColType, ColTypeOption, ColTypeOptionValue, ResResultBody, RowsMetadata};
+use cdrs::frame::TryFromRow;
+use cdrs::query::QueryValues;
+use cdrs::types::{CBytes, CString};
+use cdrs::types::rows::Row;
+use cdrs::types::value::Value;
+
+#[derive(Debug, PartialEq, CassandraTable)]
+#[table(keyspace = "test", derive_try_from_row, infer_types)]
+pub struct Schedule {
+    #[column(type = "TEXT", primary_key)]
+    job: String,
+
+    #[column(type = "DURATION")]
+    every: CqlDuration,
+
+    #[column()]
+    timeout: Duration,
+
+    #[column(type = "duration")]
+    retry_after: Option<Duration>,
+}
+
+fn schedule() -> Schedule {
+    Schedule {
+        job: String::from("backup"),
+        every: CqlDuration::new(1, 2, 3),
+        timeout: Duration::from_secs(86_400 + 90),
+        retry_after: None,
+    }
+}
+
+#[test]
+fn test_duration_ddl() {
+    assert_eq!(Schedule::create_table_cql(),
+               "CREATE TABLE IF NOT EXISTS test.schedule  (job TEXT,every DURATION,timeout DURATION,retry_after DURATION, \
+                PRIMARY KEY (job) ) ");
+    assert_eq!(Schedule::column_type("timeout"), Some("DURATION"));
+}
+
+#[test]
+fn test_duration_values() {
+    let values = vec![
+        Value::from("backup"),
+        Value::from(CqlDuration::new(1, 2, 3)),
+        Value::from(CqlDuration::new(0, 1, 90_000_000_000)),
+        Value::from(None::<CqlDuration>),
+    ];
+
+    assert_eq!(schedule().try_store_query().unwrap().values(), &QueryValues::SimpleValues(values));
+
+    let schedule = Schedule { retry_after: Some(Duration::from_millis(1500)), ..schedule() };
+
+    assert_eq!(schedule.column_value("retry_after"), Some(Value::from(CqlDuration::new(0, 0, 1_500_000_000))));
+}
+
+#[test]
+fn test_duration_serialization() {
+    assert_eq!(CqlDuration::new(1, 2, 3).to_cql_bytes(), vec![2, 4, 6]);
+    assert_eq!(CqlDuration::new(-1, 0, 64).to_cql_bytes(), vec![1, 0, 0x80, 0x80]);
+
+    for duration in [CqlDuration::new(0, 0, 0),
+                     CqlDuration::new(12, 30, 86_399_999_999_999),
+                     CqlDuration::new(-3, -7, -1),
+                     CqlDuration::new(i32::MAX, i32::MIN, i64::MIN),
+                     CqlDuration::new(0, 0, i64::MAX)].iter() {
+        assert_eq!(CqlDuration::from_cql_bytes(&duration.to_cql_bytes()), Some(*duration));
+    }
+
+    assert_eq!(CqlDuration::from_cql_bytes(&[2, 4]), None);
+    assert_eq!(CqlDuration::from_cql_bytes(&[2, 4, 6, 0]), None);
+}
+
+#[test]
+fn test_std_duration_conversion() {
+    assert_eq!(CqlDuration::try_from(Duration::new(2 * 86_400 + 1, 5)).unwrap(), CqlDuration::new(0, 2, 1_000_000_005));
+    assert!(matches!(CqlDuration::try_from(Duration::MAX), Err(Error::InvalidValue(_))));
+
+    assert_eq!(Duration::try_from(CqlDuration::new(0, 2, 1_000_000_005)).unwrap(), Duration::new(2 * 86_400 + 1, 5));
+    assert!(matches!(Duration::try_from(CqlDuration::new(1, 0, 0)), Err(Error::InvalidValue(_))));
+    assert!(matches!(Duration::try_from(CqlDuration::new(0, 0, -1)), Err(Error::InvalidValue(_))));
+}
+
+#[test]
+fn test_std_duration_out_of_range() {
+    let schedule = Schedule { timeout: Duration::MAX, ..schedule() };
+
+    assert!(matches!(schedule.try_store_query(), Err(Error::InvalidValue(_))));
+    assert!(matches!(schedule.try_column_value("timeout"), Err(Error::InvalidValue(_))));
+    assert_eq!(schedule.column_value("timeout"), None);
+}
+
+fn col_spec(name: &str, id: ColType) -> ColSpec {
+    let value = match id {
+        ColType::Custom => Some(ColTypeOptionValue::CString(CString::new(String::from("org.apache.cassandra.db.marshal.DurationType")))),
+        _ => None,
+    };
+
+    ColSpec {
+        ksname: None,
+        tablename: None,
+        name: CString::new(name.to_string()),
+        col_type: ColTypeOption { id, value },
+    }
+}
+
+fn body(timeout: CqlDuration) -> BodyResResultRows {
+    BodyResResultRows {
+        metadata: RowsMetadata {
+            flags: 0,
+            columns_count: 4,
+            paging_state: None,
+            global_table_space: None,
+            col_specs: vec![col_spec("job", ColType::Varchar), col_spec("every", ColType::Custom),
+                            col_spec("timeout", ColType::Custom), col_spec("retry_after", ColType::Custom)],
+        },
+        rows_count: 1,
+        rows_content: vec![vec![CBytes::new(b"backup".to_vec()), CBytes::new(CqlDuration::new(1, 2, 3).to_cql_bytes()),
+                                CBytes::new(timeout.to_cql_bytes()), CBytes::new_empty()]],
+    }
+}
+
+#[test]
+fn test_duration_from_row() {
+    assert_eq!(Schedule::raw_columns(), &["every", "timeout", "retry_after"]);
+
+    let rows = rows_with_raw_columns(ResponseBody::Result(ResResultBody::Rows(body(CqlDuration::new(0, 1, 90_000_000_000)))),
+                                     Schedule::raw_columns()).unwrap();
+
+    assert_eq!(Schedule::try_from_row(rows.into_iter().next().unwrap()).unwrap(), schedule());
+
+    let rows = rows_with_raw_columns(ResponseBody::Result(ResResultBody::Rows(body(CqlDuration::new(1, 0, 0)))),
+                                     Schedule::raw_columns()).unwrap();
+
+    let error = Schedule::try_from_row(rows.into_iter().next().unwrap()).unwrap_err();
+
+    assert!(error.to_string().contains("column `timeout`"));
+}
+
+#[test]
+fn test_duration_from_driver_row() {
+    let row = Row::from_frame_body(body(CqlDuration::new(0, 1, 90_000_000_000))).remove(0);
+
+    assert!(Schedule::try_from_row(row).is_err());
+}
@@ -0,0 +1,64 @@
+use cassandra_macro::{CassandraTable, DynCassandraTable, TableHandle};
+use cassandra_macro_derive::CassandraTable;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT")]
+    nickname: String,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct UserEvent {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TIMESTAMP", cluster_key(order = "DESC", position = 1))]
+    created: i64,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "audit")]
+pub struct Login {
+    #[column(type = "UUID", primary_key)]
+    id: uuid::Uuid,
+}
+
+fn all_tables() -> Vec<Box<dyn DynCassandraTable>> {
+    vec![TableHandle::<User>::boxed(), TableHandle::<UserEvent>::boxed(), Box::new(TableHandle::<Login>::new())]
+}
+
+#[test]
+fn test_dyn_tables_ddl() {
+    let tables = all_tables();
+
+    let names: Vec<String> = tables.iter().map(|t| format!("{}.{}", t.key_space(), t.table_name())).collect();
+    assert_eq!(names, vec!["test.user", "test.user_event", "audit.login"]);
+
+    let create: Vec<&str> = tables.iter().map(|t| t.create_table_cql()).collect();
+    assert_eq!(create, vec![User::create_table_cql(), UserEvent::create_table_cql(), Login::create_table_cql()]);
+
+    let drop: Vec<&str> = tables.iter().rev().map(|t| t.drop_table_cql()).collect();
+    assert_eq!(drop, vec!["DROP TABLE IF EXISTS audit.login", "DROP TABLE IF EXISTS test.user_event", "DROP TABLE IF EXISTS test.user"]);
+
+    assert!(tables.iter().all(|t| t.create_indexes_cql().is_empty() && t.drop_indexes_cql().is_empty()));
+
+    for table in tables.iter() {
+        assert!(table.create_table_cql_in("branch_42").unwrap().contains(&format!("branch_42.{}", table.table_name())));
+    }
+}
+
+#[test]
+fn test_dyn_table_columns() {
+    let user_event = TableHandle::<UserEvent>::new();
+
+    assert_eq!(user_event.columns(), &["username", "created"]);
+    assert_eq!(user_event.primary_key_columns(), &["username"]);
+    assert_eq!(user_event.cluster_key_columns(), &["created"]);
+    assert_eq!(user_event.column_type("created"), Some("TIMESTAMP"));
+    assert_eq!(format!("{:?}", user_event), "TableHandle(test.user_event)");
+}
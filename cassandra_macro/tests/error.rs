@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::error::Error as StdError;
+
+use cassandra_macro::{CassandraTable, Error, Tracked};
+use cassandra_macro_derive::CassandraTable;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct UserFollower {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT", cluster_key(order = "ASC", position = 1))]
+    follower: String,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT")]
+    first_name: String,
+}
+
+fn source_chain_length(error: &dyn StdError) -> usize {
+    let mut length = 0;
+    let mut source = error.source();
+
+    while let Some(e) = source {
+        length += 1;
+        source = e.source();
+    }
+
+    length
+}
+
+#[test]
+fn test_source_chain_terminates() {
+    let follower = UserFollower { username: String::from("rust"), follower: String::from("ferris") };
+
+    let no_updatable = follower.update_query().unwrap_err();
+
+    assert!(no_updatable.source().is_none());
+
+    let error = Error::from(no_updatable);
+
+    assert_eq!(source_chain_length(&error), 1);
+    assert_eq!(error.to_string(), "Table user_follower does not have any updatable column");
+
+    assert!(matches!(follower.update_query_if(&[]), Err(Error::UnknownColumn(e)) if e.columns().is_empty()));
+}
+
+#[test]
+fn test_error_variants() {
+    let user = User { username: String::from("rust"), first_name: String::from("ferris") };
+
+    assert!(matches!(user.update_columns_query(&["removed"]), Err(Error::UnknownColumn(e)) if e.columns() == vec!["removed"]));
+    assert!(matches!(user.update_columns_query(&["username"]), Err(Error::UnknownColumn(e)) if e.columns() == vec!["username"]));
+    assert!(matches!(User::primary_key_values_named(HashMap::new()), Err(Error::MissingKeyValue(c)) if c == "username"));
+    assert!(matches!(Tracked::new(user).dirty_update_query(), Err(Error::NoChanges(t)) if t == "user"));
+
+    let user = User { username: String::from("rust"), first_name: String::from("ferris") };
+    let error = user.update_columns_query(&["removed"]).unwrap_err();
+
+    assert_eq!(source_chain_length(&error), 1);
+    assert_eq!(error.to_string(), "Unknown non key column `removed` of table test.user");
+}
@@ -0,0 +1,119 @@
+#![cfg(feature = "repository")]
+#[macro_use]
+extern crate cdrs;
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use cassandra_macro::{CassandraRepository, CassandraTable, CqlSession, Error};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::frame::{Flag, Frame, Opcode, Version};
+use cdrs::query::QueryValues;
+
+use common::{user_rows_body, void_body};
+
+mod common;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", derive_try_from_row)]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "BIGINT", cluster_key(order = "ASC", position = 1))]
+    created: i64,
+
+    #[column(type = "TEXT")]
+    nickname: String,
+}
+
+/// Session answering with canned result bodies, recording the statements
+#[derive(Default)]
+struct FakeSession {
+    statements: RefCell<Vec<(String, Option<QueryValues>)>>,
+    results: RefCell<VecDeque<Result<Vec<u8>, Error>>>,
+}
+
+impl FakeSession {
+    fn new(results: Vec<Result<Vec<u8>, Error>>) -> Self {
+        FakeSession { results: RefCell::new(results.into()), ..Default::default() }
+    }
+
+    fn respond(&self, cql: &str, values: Option<QueryValues>) -> Result<Frame, Error> {
+        self.statements.borrow_mut().push((cql.to_string(), values));
+
+        let body = self.results.borrow_mut().pop_front().unwrap_or_else(|| Ok(void_body()))?;
+
+        Ok(Frame {
+            version: Version::Response,
+            flags: vec![Flag::Ignore],
+            opcode: Opcode::Result,
+            stream: 0,
+            body,
+            tracing_id: None,
+            warnings: vec![],
+        })
+    }
+}
+
+impl CqlSession for FakeSession {
+    fn execute(&self, cql: &str, values: &QueryValues) -> Result<Frame, Error> {
+        self.respond(cql, Some(values.clone()))
+    }
+
+    fn execute_simple(&self, cql: &str) -> Result<Frame, Error> {
+        self.respond(cql, None)
+    }
+}
+
+fn user_rows(users: &[(&str, i64, &str)]) -> Vec<u8> {
+    user_rows_body(users, None)
+}
+
+fn user() -> User {
+    User { username: String::from("rust"), created: 7, nickname: String::from("ferris") }
+}
+
+#[test]
+fn test_fake_session_mutations() {
+    let repository = CassandraRepository::new(FakeSession::default());
+
+    repository.create_table::<User>().unwrap();
+    repository.save(&user()).unwrap();
+    repository.delete(&user()).unwrap();
+
+    let statements = repository.session().statements.borrow();
+
+    assert_eq!(statements.len(), 3);
+    assert_eq!(statements[0], (User::create_table_cql().to_string(), None));
+    assert_eq!(statements[1].0, user().store_query().query());
+    assert_eq!(statements[1].1, Some(user().store_query().values().clone()));
+    assert_eq!(statements[2].0, user().delete_query().query());
+    assert_eq!(statements[2].1, Some(user().delete_query().values().clone()));
+}
+
+#[test]
+fn test_fake_session_find_by_key() {
+    let results = vec![Ok(user_rows(&[("rust", 7, "ferris")])), Ok(user_rows(&[]))];
+    let repository = CassandraRepository::new(FakeSession::new(results));
+
+    let found: Option<User> = repository.find_by_key(query_values!("rust", 7i64)).unwrap();
+    assert_eq!(found.unwrap().nickname, "ferris");
+
+    let not_found: Option<User> = repository.find_by_key(query_values!("go", 7i64)).unwrap();
+    assert!(not_found.is_none());
+
+    let statements = repository.session().statements.borrow();
+    assert_eq!(statements[1].1, Some(query_values!("go", 7i64)));
+}
+
+#[test]
+fn test_fake_session_error() {
+    let results = vec![Err(Error::Driver(cdrs::Error::General(String::from("unconfigured table user"))))];
+    let repository = CassandraRepository::new(FakeSession::new(results));
+
+    match repository.save(&user()) {
+        Err(Error::Driver(e)) => assert!(e.to_string().contains("unconfigured table user")),
+        other => panic!("Expected driver error, got {:?}", other.map(|_| ())),
+    }
+}
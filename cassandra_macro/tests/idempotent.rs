@@ -0,0 +1,173 @@
+use cassandra_macro::{CassandraTable, ClusterKeyFilter, CqlQuery, Idempotence, Value};
+use cassandra_macro_derive::CassandraTable;
+use uuid::Uuid;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", idempotent)]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT")]
+    first_name: String,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", idempotent(store, delete))]
+pub struct Profile {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT")]
+    bio: String,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Session {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT")]
+    device: String,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", idempotent)]
+pub struct Login {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TIMEUUID", cluster_key(order = "DESC", position = 1), generated = "now()")]
+    id: Uuid,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", idempotent)]
+pub struct UserStats {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "COUNTER")]
+    logins: i64,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", idempotent)]
+pub struct Channel {
+    #[column(type = "TEXT", primary_key)]
+    name: String,
+
+    #[column(type = "BIGINT", cluster_key(order = "DESC", position = 1))]
+    created: i64,
+
+    #[column(type = "TEXT", static)]
+    topic: String,
+
+    #[column(type = "TEXT")]
+    message: String,
+}
+
+fn user() -> User {
+    User { username: String::from("rust"), first_name: String::from("ferris") }
+}
+
+fn profile() -> Profile {
+    Profile { username: String::from("rust"), bio: String::from("crab") }
+}
+
+fn session() -> Session {
+    Session { username: String::from("rust"), device: String::from("laptop") }
+}
+
+#[test]
+fn test_idempotent_table() {
+    assert!(user().store_query().is_idempotent());
+    assert!(user().try_store_query().unwrap().is_idempotent());
+    assert!(user().update_query().unwrap().is_idempotent());
+    assert!(user().into_update_query().unwrap().is_idempotent());
+    assert!(user().delete_query().is_idempotent());
+    assert!(user().delete_partition_query().is_idempotent());
+    assert!(User::delete_by_key(&user().key()).is_idempotent());
+
+    assert!(CqlQuery::is_idempotent(&user().into_store_query()));
+}
+
+#[test]
+fn test_idempotent_operations() {
+    assert!(profile().store_query().is_idempotent());
+    assert!(!profile().update_query().unwrap().is_idempotent());
+    assert!(!profile().try_update_query().unwrap().is_idempotent());
+    assert!(profile().delete_query().is_idempotent());
+
+    assert!(!session().store_query().is_idempotent());
+    assert!(!session().update_query().unwrap().is_idempotent());
+    assert!(!session().delete_query().is_idempotent());
+}
+
+#[test]
+fn test_idempotent_override() {
+    assert!(!user().store_query().with_idempotent(false).is_idempotent());
+    assert!(session().delete_query().with_idempotent(true).is_idempotent());
+}
+
+#[test]
+fn test_derived_queries_keep_idempotence() {
+    assert!(user().store_query_with_ttl(60).unwrap().is_idempotent());
+    assert!(user().store_query_with_timestamp(7).unwrap().is_idempotent());
+    assert!(user().store_query_in("tenant_1").unwrap().is_idempotent());
+    assert!(user().update_query_in("tenant_1").unwrap().is_idempotent());
+    assert!(user().delete_query_in("tenant_1").unwrap().is_idempotent());
+    assert!(user().delete_query_with_timestamp(7).unwrap().is_idempotent());
+    assert!(user().delete_columns_query(&["first_name"]).unwrap().is_idempotent());
+
+    assert!(!session().store_query_with_ttl(60).unwrap().is_idempotent());
+}
+
+#[test]
+fn test_default_queries_of_idempotent_table() {
+    let channel = Channel { name: String::from("rust"), created: 7, topic: String::from("crabs"), message: String::from("hi") };
+
+    assert!(channel.update_columns_query(&["message"]).unwrap().is_idempotent());
+    assert!(channel.update_static_query().unwrap().is_idempotent());
+    assert!(channel.update_columns_query_with_ttl(&["message"], 60).unwrap().is_idempotent());
+    assert!(channel.delete_cluster_range_query(&[("created", ClusterKeyFilter::Lt, Value::from(7i64))]).unwrap().is_idempotent());
+    assert!(Channel::store_json_query(String::from("{}")).is_idempotent());
+
+    assert!(!profile().update_columns_query(&["bio"]).unwrap().is_idempotent());
+    assert!(Profile::store_json_query(String::from("{}")).is_idempotent());
+    assert!(!session().update_columns_query(&["device"]).unwrap().is_idempotent());
+    assert!(!Session::store_json_query(String::from("{}")).is_idempotent());
+}
+
+#[test]
+fn test_table_idempotence() {
+    assert_eq!(User::idempotence(), Idempotence { store: true, update: true, delete: true });
+    assert_eq!(Profile::idempotence(), Idempotence { store: true, update: false, delete: true });
+    assert_eq!(Session::idempotence(), Idempotence::default());
+    assert_eq!(Login::idempotence(), Idempotence { store: false, update: true, delete: true });
+}
+
+#[test]
+fn test_lwt_and_counter_never_idempotent() {
+    assert!(!user().store_query_if_not_exists().unwrap().is_idempotent());
+    assert!(!user().update_query_if_exists().unwrap().is_idempotent());
+    assert!(!user().update_query_if(&[("first_name", Value::from("ferris"))]).unwrap().is_idempotent());
+    assert!(!user().delete_query_if_exists().unwrap().is_idempotent());
+    assert!(!user().store_query().lwt().is_idempotent());
+    assert!(!user().store_query_if_not_exists().unwrap().with_idempotent(true).is_idempotent());
+
+    let stats = UserStats { username: String::from("rust"), logins: 0 };
+
+    assert!(!stats.counter_delta_query("logins").unwrap().is_idempotent());
+    assert!(!stats.counter_delta_query("logins").unwrap().with_idempotent(true).is_idempotent());
+    assert!(stats.delete_query().is_idempotent());
+}
+
+#[test]
+fn test_generated_columns_not_idempotent() {
+    let login = Login { username: String::from("rust"), id: Uuid::nil() };
+
+    assert!(!login.store_query().is_idempotent());
+    assert!(login.delete_query().is_idempotent());
+}
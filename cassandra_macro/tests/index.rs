@@ -0,0 +1,49 @@
+use cassandra_macro::{CassandraTable, CqlTableBuilder};
+use cassandra_macro_derive::CassandraTable;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Account {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT", index = "by_email")]
+    email: String,
+
+    #[column(type = "TEXT", index, queryable)]
+    country: String,
+}
+
+#[test]
+fn test_create_indexes_cql() {
+    assert_eq!(Account::create_indexes_cql(), vec![
+        "CREATE INDEX IF NOT EXISTS by_email ON test.account (email)",
+        "CREATE INDEX IF NOT EXISTS account_country_idx ON test.account (country)",
+    ]);
+    assert_eq!(Account::select_by_country(cassandra_macro::Projection::All), "SELECT * FROM test.account WHERE country=?");
+}
+
+#[test]
+fn test_drop_indexes_cql() {
+    assert_eq!(Account::drop_indexes_cql(), vec![
+        "DROP INDEX IF EXISTS test.by_email",
+        "DROP INDEX IF EXISTS test.account_country_idx",
+    ]);
+}
+
+#[test]
+fn test_index_names_as_builder() {
+    let table = CqlTableBuilder::new("account")
+        .keyspace("test")
+        .column("username", "TEXT")
+        .column("email", "TEXT")
+        .column("country", "TEXT")
+        .partition_key("username")
+        .named_index("email", "by_email")
+        .index("country")
+        .build()
+        .unwrap();
+
+    assert_eq!(table.create_indexes_cql(), Account::create_indexes_cql());
+    assert_eq!(table.drop_indexes_cql(), Account::drop_indexes_cql());
+}
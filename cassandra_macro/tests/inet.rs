@@ -0,0 +1,99 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use cassandra_macro::CassandraTable;
+use cassandra_macro_derive::CassandraTable;
+use cdrs::frame::frame_result::{BodyResResultRows, ColSpec, ColType, ColTypeOption, RowsMetadata};
+use cdrs::frame::TryFromRow;
+use cdrs::query::QueryValues;
+use cdrs::types::{CBytes, CString};
+use cdrs::types::rows::Row;
+use cdrs::types::value::Value;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "audit", derive_try_from_row, infer_types)]
+pub struct Access {
+    #[column(type = "UUID", primary_key)]
+    id: uuid::Uuid,
+
+    #[column(type = "INET")]
+    client: Ipv4Addr,
+
+    #[column()]
+    proxy: Option<Ipv6Addr>,
+
+    #[column()]
+    gateway: IpAddr,
+}
+
+fn col_spec(name: &str, id: ColType) -> ColSpec {
+    ColSpec {
+        ksname: None,
+        tablename: None,
+        name: CString::new(name.to_string()),
+        col_type: ColTypeOption { id, value: None },
+    }
+}
+
+fn row(client: &[u8], proxy: &[u8]) -> Row {
+    let col_specs = vec![
+        col_spec("id", ColType::Uuid),
+        col_spec("client", ColType::Inet),
+        col_spec("proxy", ColType::Inet),
+        col_spec("gateway", ColType::Inet),
+    ];
+
+    let body = BodyResResultRows {
+        metadata: RowsMetadata { flags: 0, columns_count: 4, paging_state: None, global_table_space: None, col_specs },
+        rows_count: 1,
+        rows_content: vec![vec![
+            CBytes::new(uuid::Uuid::nil().as_bytes().to_vec()),
+            CBytes::new(client.to_vec()),
+            if proxy.is_empty() { CBytes::new_empty() } else { CBytes::new(proxy.to_vec()) },
+            CBytes::new(vec![10, 0, 0, 1]),
+        ]],
+    };
+
+    Row::from_frame_body(body).remove(0)
+}
+
+#[test]
+fn test_inet_ddl() {
+    assert_eq!(Access::create_table_cql(),
+               "CREATE TABLE IF NOT EXISTS audit.access  (id UUID,client INET,proxy INET,gateway INET, PRIMARY KEY (id) ) ");
+}
+
+#[test]
+fn test_inet_values() {
+    let access = Access {
+        id: uuid::Uuid::nil(),
+        client: Ipv4Addr::new(192, 168, 0, 7),
+        proxy: Some(Ipv6Addr::LOCALHOST),
+        gateway: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+    };
+
+    assert_eq!(access.update_query().unwrap().values(), &QueryValues::SimpleValues(vec![
+        Value::from(IpAddr::V4(Ipv4Addr::new(192, 168, 0, 7))),
+        Value::from(IpAddr::V6(Ipv6Addr::LOCALHOST)),
+        Value::from(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+        Value::from(uuid::Uuid::nil()),
+    ]));
+
+    assert_eq!(access.column_value("client").unwrap().body, vec![192, 168, 0, 7]);
+    assert_eq!(access.column_value("proxy").unwrap().body.len(), 16);
+}
+
+#[test]
+fn test_inet_from_row() {
+    let access = Access::try_from_row(row(&[192, 168, 0, 7], &Ipv6Addr::LOCALHOST.octets())).unwrap();
+
+    assert_eq!(access.client, Ipv4Addr::new(192, 168, 0, 7));
+    assert_eq!(access.proxy, Some(Ipv6Addr::LOCALHOST));
+    assert_eq!(access.gateway, IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+
+    let access = Access::try_from_row(row(&[127, 0, 0, 1], &[])).unwrap();
+
+    assert_eq!(access.proxy, None);
+
+    assert!(Access::try_from_row(row(&Ipv6Addr::LOCALHOST.octets(), &[])).is_err());
+    assert!(Access::try_from_row(row(&[127, 0, 0, 1], &[127, 0, 0, 1])).is_err());
+}
@@ -0,0 +1,171 @@
+#![cfg(feature = "json")]
+
+use std::collections::HashMap;
+
+use cassandra_macro::{CassandraTable, Error, QueryValues, Value};
+use cassandra_macro::serde_json::{self, json};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::frame::frame_result::{BodyResResultRows, ColSpec, ColType, ColTypeOption, RowsMetadata};
+use cdrs::frame::TryFromRow;
+use cdrs::types::{CBytes, CString};
+use cdrs::types::rows::Row;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", derive_try_from_row)]
+pub struct Profile {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT")]
+    settings: serde_json::Value,
+}
+
+fn profile() -> Profile {
+    Profile {
+        username: String::from("rust"),
+        settings: json!({"theme": {"name": "dark", "contrast": [1, 2.5]}, "beta": true, "motto": null}),
+    }
+}
+
+fn column(name: &str) -> ColSpec {
+    ColSpec {
+        ksname: None,
+        tablename: None,
+        name: CString::new(name.to_string()),
+        col_type: ColTypeOption { id: ColType::Varchar, value: None },
+    }
+}
+
+fn synthetic_row(username: &[u8], settings: &[u8]) -> Row {
+    let col_specs = vec![column("username"), column("settings")];
+
+    let body = BodyResResultRows {
+        metadata: RowsMetadata {
+            flags: 0,
+            columns_count: col_specs.len() as i32,
+            paging_state: None,
+            global_table_space: None,
+            col_specs,
+        },
+        rows_count: 1,
+        rows_content: vec![vec![CBytes::new(username.to_vec()), CBytes::new(settings.to_vec())]],
+    };
+
+    Row::from_frame_body(body).remove(0)
+}
+
+fn bodies(values: &QueryValues) -> Vec<Vec<u8>> {
+    match values {
+        QueryValues::SimpleValues(values) => values.iter().map(|v: &Value| v.body.clone()).collect(),
+        _ => panic!("The generated queries bind values without names"),
+    }
+}
+
+#[test]
+fn test_json_column_round_trip() {
+    let profile = profile();
+
+    let stored = bodies(profile.store_query().values());
+
+    assert_eq!(stored[1], profile.settings.to_string().into_bytes());
+    assert_eq!(bodies(profile.update_query().unwrap().values())[0], stored[1]);
+    assert_eq!(profile.column_value("settings").unwrap().body, stored[1]);
+
+    let from_db = Profile::try_from_row(synthetic_row(&stored[0], &stored[1])).unwrap();
+
+    assert_eq!(from_db.username, "rust");
+    assert_eq!(from_db.settings, profile.settings);
+    assert_eq!(from_db.settings["theme"]["contrast"][1], json!(2.5));
+}
+
+#[test]
+fn test_json_column_invalid() {
+    match Profile::try_from_row(synthetic_row(b"rust", b"{\"theme\":")) {
+        Err(cdrs::Error::General(message)) => assert!(message.contains("column `settings` is not valid JSON")),
+        other => panic!("Expected a driver error, got {:?}", other),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Theme {
+    name: String,
+    contrast: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    theme: Theme,
+    beta: bool,
+    motto: Option<String>,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", derive_try_from_row)]
+pub struct Settings {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(json)]
+    config: Config,
+
+    #[column(type = "TEXT", json)]
+    shortcuts: HashMap<(u8, u8), String>,
+}
+
+fn settings() -> Settings {
+    Settings {
+        username: String::from("rust"),
+        config: Config {
+            theme: Theme { name: String::from("dark"), contrast: vec![1.0, 2.5] },
+            beta: true,
+            motto: None,
+        },
+        shortcuts: HashMap::new(),
+    }
+}
+
+#[test]
+fn test_json_marker_round_trip() {
+    assert_eq!(Settings::column_type("config"), Some("TEXT"));
+    assert_eq!(Settings::create_table_cql(),
+               "CREATE TABLE IF NOT EXISTS test.settings  (username TEXT,config TEXT,shortcuts TEXT, PRIMARY KEY (username) ) ");
+
+    let settings = settings();
+
+    let stored = bodies(settings.try_store_query().unwrap().values());
+
+    assert_eq!(stored, bodies(&QueryValues::SimpleValues(vec![settings.try_column_value("username").unwrap().unwrap(),
+                                                              settings.try_column_value("config").unwrap().unwrap(),
+                                                              settings.try_column_value("shortcuts").unwrap().unwrap()])));
+    assert_eq!(stored[1], serde_json::to_string(&settings.config).unwrap().into_bytes());
+    assert_eq!(stored[2], b"{}".to_vec());
+
+    let row = Row::from_frame_body(BodyResResultRows {
+        metadata: RowsMetadata {
+            flags: 0,
+            columns_count: 3,
+            paging_state: None,
+            global_table_space: None,
+            col_specs: vec![column("username"), column("config"), column("shortcuts")],
+        },
+        rows_count: 1,
+        rows_content: vec![stored.into_iter().map(CBytes::new).collect()],
+    }).remove(0);
+
+    let from_db = Settings::try_from_row(row).unwrap();
+
+    assert_eq!(from_db.config, settings.config);
+    assert!(from_db.shortcuts.is_empty());
+}
+
+#[test]
+fn test_json_marker_serialization_error() {
+    let mut settings = settings();
+    settings.shortcuts.insert((1, 2), String::from("save"));
+
+    assert!(matches!(settings.try_store_query(), Err(Error::Json(_))));
+    assert!(matches!(settings.try_update_query(), Err(Error::Json(_))));
+    assert!(matches!(settings.try_column_value("shortcuts"), Err(Error::Json(_))));
+    assert!(settings.column_value("shortcuts").is_none());
+}
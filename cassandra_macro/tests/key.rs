@@ -0,0 +1,87 @@
+use cassandra_macro::{CassandraTable, Projection, TableKey};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::query::QueryValues;
+use cdrs::types::value::Value;
+use uuid::Uuid;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Login {
+    #[column(type = "TEXT")]
+    device: String,
+
+    #[column(type = "TIMESTAMP", cluster_key(order = "DESC", position = 1))]
+    created: i64,
+
+    #[column(type = "UUID", compound_key(position = 2))]
+    account: Uuid,
+
+    #[column(type = "TEXT", compound_key(position = 1))]
+    tenant: String,
+}
+
+fn login() -> Login {
+    Login {
+        device: String::from("laptop"),
+        created: 1_600_000_000_000,
+        account: Uuid::nil(),
+        tenant: String::from("acme"),
+    }
+}
+
+#[test]
+fn test_key_fields_in_where_clause_order() {
+    let key = LoginKey { tenant: String::from("acme"), account: Uuid::nil(), created: 1_600_000_000_000 };
+
+    assert_eq!(login().key(), key);
+
+    assert_eq!(format!("{:?}", key), format!("LoginKey {{ tenant: \"acme\", account: {:?}, created: 1600000000000 }}", Uuid::nil()));
+}
+
+#[test]
+fn test_partition_values_of_key() {
+    assert_eq!(login().key().partition_values(), vec![Value::from("acme"), Value::from(Uuid::nil())]);
+}
+
+#[test]
+fn test_select_by_key() {
+    let query = Login::select_by_key(&login().key(), Projection::All);
+
+    assert_eq!(query.query(), "SELECT * FROM test.login WHERE  tenant=? AND account=?  AND  created=? ");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![
+        Value::from("acme"), Value::from(Uuid::nil()), Value::from(1_600_000_000_000i64)
+    ]));
+
+    let entity_query = login().select_query(Projection::All);
+
+    assert_eq!(query.query(), entity_query.query());
+    assert_eq!(query.values(), entity_query.values());
+    assert_eq!(query.table(), "login");
+}
+
+#[test]
+fn test_delete_by_key() {
+    let query = Login::delete_by_key(&login().key());
+
+    assert_eq!(query.query(), "DELETE FROM test.login WHERE tenant=? AND account=? AND created=?");
+    assert_eq!(query.values(), login().delete_query().values());
+}
+
+/// The crate already has a `LoginKey`
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", key = "SessionId")]
+pub struct Session {
+    #[column(type = "UUID", primary_key)]
+    id: Uuid,
+
+    #[column(type = "TEXT")]
+    device: String,
+}
+
+#[test]
+fn test_renamed_key_struct() {
+    let session = Session { id: Uuid::nil(), device: String::from("laptop") };
+
+    assert_eq!(session.key(), SessionId { id: Uuid::nil() });
+    assert_eq!(Session::delete_by_key(&session.key()).query(), "DELETE FROM test.session WHERE id=?");
+}
@@ -0,0 +1,83 @@
+use cassandra_macro::{CassandraTable, Error, Projection, TableStatement};
+use cassandra_macro_derive::CassandraTable;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", options = "comment='Copy of test.user'")]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "BIGINT", cluster_key(order = "ASC", position = 1))]
+    created: i64,
+
+    #[column(type = "TEXT")]
+    nickname: String,
+}
+
+fn user() -> User {
+    User { username: String::from("rust"), created: 7, nickname: String::from("ferris") }
+}
+
+/// Default statement, with the keyspace of the attribute replaced
+fn in_branch(statement: &str) -> String {
+    statement.replacen("test.user", "branch_42.user", 1)
+}
+
+#[test]
+fn test_static_statements_in_keyspace() {
+    assert_eq!(User::create_table_cql_in("branch_42").unwrap(), in_branch(User::create_table_cql()));
+    assert!(User::create_table_cql_in("branch_42").unwrap().ends_with("comment='Copy of test.user'"));
+
+    assert_eq!(User::drop_table_cql_in("branch_42").unwrap(), "DROP TABLE IF EXISTS branch_42.user");
+    assert_eq!(User::select_all_in("branch_42", Projection::All).unwrap(), in_branch(&User::select_all(Projection::All)));
+    assert_eq!(User::select_by_primary_keys_in("branch_42", Projection::Count).unwrap(),
+               in_branch(&User::select_by_primary_keys(Projection::Count)));
+    assert_eq!(User::select_by_primary_and_cluster_keys_in("branch_42", Projection::All).unwrap(),
+               in_branch(&User::select_by_primary_and_cluster_keys(Projection::All)));
+}
+
+#[test]
+fn test_instance_queries_in_keyspace() {
+    let user = user();
+
+    let store = user.store_query_in("branch_42").unwrap();
+    assert_eq!(store.query(), in_branch(user.store_query().query()));
+    assert_eq!(store.values(), user.store_query().values());
+
+    let update = user.update_query_in("branch_42").unwrap();
+    assert_eq!(update.query(), in_branch(user.update_query().unwrap().query()));
+    assert_eq!(update.values(), user.update_query().unwrap().values());
+
+    let delete = user.delete_query_in("branch_42").unwrap();
+    assert_eq!(delete.query(), in_branch(user.delete_query().query()));
+    assert_eq!(delete.values(), user.delete_query().values());
+
+    let select = user.select_query_in("branch_42", Projection::All).unwrap();
+    assert_eq!(select.query(), in_branch(user.select_query(Projection::All).query()));
+    assert_eq!(select.values(), user.select_query(Projection::All).values());
+
+    assert_eq!(User::key_space(), "test");
+    assert_eq!(user.store_query().query(), "INSERT INTO test.user (username,created,nickname) VALUES (?,?,?)");
+}
+
+#[test]
+fn test_statement_templates() {
+    assert_eq!(User::statement_template(TableStatement::Store), "INSERT INTO {ks}.user (username,created,nickname) VALUES (?,?,?)");
+    assert_eq!(User::statement_template(TableStatement::SelectAll), "SELECT {} FROM {ks}.user");
+    assert!(User::statement_template(TableStatement::CreateTable).ends_with("comment='Copy of test.user'"));
+
+    assert_eq!(User::create_table_cql_in("test").unwrap(), User::create_table_cql());
+    assert_eq!(user().update_query_in("test").unwrap().query(), user().update_query().unwrap().query());
+}
+
+#[test]
+fn test_invalid_keyspace() {
+    for ks in ["", "test; DROP TABLE x", "branch-42", "test.user", &"k".repeat(49)].iter() {
+        match User::create_table_cql_in(ks) {
+            Err(Error::InvalidIdentifier(identifier)) => assert_eq!(identifier, *ks),
+            other => panic!("Expected invalid identifier for `{}`, got {:?}", ks, other),
+        }
+    }
+
+    assert!(user().store_query_in("branch 42").is_err());
+}
@@ -0,0 +1,77 @@
+use cassandra_macro::LwtResult;
+use cassandra_macro_derive::CassandraTable;
+use cdrs::frame::frame_result::{BodyResResultRows, ColSpec, ColType, ColTypeOption, RowsMetadata};
+use cdrs::types::{ByName, CBytes, CString};
+use cdrs::types::rows::Row;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", derive_try_from_row)]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT")]
+    nickname: String,
+}
+
+fn col_spec(name: &str, id: ColType) -> ColSpec {
+    ColSpec {
+        ksname: None,
+        tablename: None,
+        name: CString::new(name.to_string()),
+        col_type: ColTypeOption { id, value: None },
+    }
+}
+
+/// Row of a conditional query response with the given columns
+fn lwt_row(columns: &[(&str, ColType, Vec<u8>)]) -> Row {
+    let metadata = RowsMetadata {
+        flags: 0,
+        columns_count: columns.len() as i32,
+        paging_state: None,
+        global_table_space: None,
+        col_specs: columns.iter().map(|(name, id, _)| col_spec(name, id.clone())).collect(),
+    };
+
+    let body = BodyResResultRows {
+        metadata,
+        rows_count: 1,
+        rows_content: vec![columns.iter().map(|(_, _, value)| CBytes::new(value.clone())).collect()],
+    };
+
+    Row::from_frame_body(body).remove(0)
+}
+
+#[test]
+fn test_lwt_applied() {
+    let result = LwtResult::from_row(lwt_row(&[("[applied]", ColType::Boolean, vec![1])])).unwrap();
+
+    assert!(result.applied());
+    assert!(result.current::<User>().unwrap().is_none());
+}
+
+#[test]
+fn test_lwt_not_applied() {
+    let row = lwt_row(&[
+        ("[applied]", ColType::Boolean, vec![0]),
+        ("username", ColType::Varchar, b"rust".to_vec()),
+        ("nickname", ColType::Varchar, b"ferris".to_vec()),
+    ]);
+
+    let result = LwtResult::from_row(row).unwrap();
+
+    assert!(!result.applied());
+    assert_eq!(result.row().r_by_name::<String>("nickname").unwrap(), "ferris");
+
+    let current: User = result.current().unwrap().unwrap();
+
+    assert_eq!(current.username, "rust");
+    assert_eq!(current.nickname, "ferris");
+}
+
+#[test]
+fn test_lwt_without_applied_column() {
+    let row = lwt_row(&[("username", ColType::Varchar, b"rust".to_vec())]);
+
+    assert!(LwtResult::from_row(row).is_err());
+}
@@ -0,0 +1,113 @@
+use cassandra_macro::{CassandraProjection, CassandraTable, Projection};
+use cassandra_macro_derive::{CassandraProjection, CassandraTable};
+use cdrs::frame::frame_result::{BodyResResultRows, ColSpec, ColType, ColTypeOption, RowsMetadata};
+use cdrs::frame::TryFromRow;
+use cdrs::types::{CBytes, CString};
+use cdrs::types::rows::Row;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Profile {
+    #[column(type = "TEXT", compound_key(position = 1))]
+    tenant: String,
+
+    #[column(type = "TEXT", compound_key(position = 2))]
+    username: String,
+
+    #[column(type = "TEXT")]
+    email: String,
+
+    #[column(type = "TEXT")]
+    biography: String,
+
+    #[column(type = "BIGINT")]
+    created: i64,
+}
+
+#[derive(Debug, PartialEq, CassandraProjection)]
+#[projection(of = "Profile", derive_try_from_row, infer_types)]
+pub struct ProfileContact {
+    #[column(type = "TEXT")]
+    email: String,
+
+    #[column()]
+    created: Option<i64>,
+
+    loaded: bool,
+}
+
+/// Stands for a type of the crate that `AccountColumn` would clash with
+#[allow(dead_code)]
+pub struct AccountColumn;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", column_enum = "AccountField")]
+pub struct Account {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT")]
+    email: String,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, CassandraProjection)]
+#[projection(of = "Account", column_enum = "AccountField")]
+pub struct AccountEmail {
+    #[column(type = "TEXT")]
+    email: String,
+}
+
+fn column(name: &str, id: ColType) -> ColSpec {
+    ColSpec {
+        ksname: None,
+        tablename: None,
+        name: CString::new(name.to_string()),
+        col_type: ColTypeOption { id, value: None },
+    }
+}
+
+fn contact_row() -> Row {
+    let col_specs = vec![column("email", ColType::Varchar), column("created", ColType::Bigint)];
+
+    let body = BodyResResultRows {
+        metadata: RowsMetadata {
+            flags: 0,
+            columns_count: col_specs.len() as i32,
+            paging_state: None,
+            global_table_space: None,
+            col_specs,
+        },
+        rows_count: 1,
+        rows_content: vec![vec![CBytes::new(b"rust@example.com".to_vec()), CBytes::new_empty()]],
+    };
+
+    Row::from_frame_body(body).remove(0)
+}
+
+#[test]
+fn test_projection_columns() {
+    assert_eq!(ProfileContact::columns(), &["email", "created"]);
+    assert_eq!(ProfileContact::projection().selection(), "email,created");
+}
+
+#[test]
+fn test_projection_selects() {
+    assert_eq!(ProfileContact::select_by_primary_keys(),
+               "SELECT email,created FROM test.profile WHERE  tenant=? AND username=? ");
+    assert_eq!(ProfileContact::select_by_primary_and_cluster_keys(),
+               Profile::select_by_primary_and_cluster_keys(Projection::Columns(vec![String::from("email"), String::from("created")])));
+}
+
+#[test]
+fn test_projection_from_row() {
+    let contact = ProfileContact::try_from_row(contact_row()).unwrap();
+
+    assert_eq!(contact, ProfileContact { email: String::from("rust@example.com"), created: None, loaded: false });
+}
+
+#[test]
+fn test_projection_of_renamed_column_enum() {
+    assert_eq!(AccountField::Email.as_str(), "email");
+    assert_eq!(AccountEmail::select_by_primary_keys(), "SELECT email FROM test.account WHERE  username=? ");
+}
@@ -0,0 +1,223 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use cassandra_macro::{BatchBuilder, CassandraTable, CqlQuery, Projection, QueryKind, SelectQuery};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::consistency::Consistency;
+use cdrs::query::QueryValues;
+use cdrs::types::value::Value;
+use uuid::Uuid;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT")]
+    first_name: String,
+}
+
+fn user() -> User {
+    User { username: String::from("rust"), first_name: String::from("ferris") }
+}
+
+fn describe<Q: CqlQuery>(query: &Q) -> (String, usize) {
+    (query.query().to_string(), query.values().len())
+}
+
+fn metadata<Q: CqlQuery>(query: &Q) -> (String, String, QueryKind) {
+    (query.keyspace().to_string(), query.table().to_string(), query.kind())
+}
+
+#[test]
+fn test_generic_cql_query() {
+    let user = user();
+
+    assert_eq!(describe(&user.store_query()).1, 2);
+    assert_eq!(describe(&user.update_query().unwrap()),
+               (String::from("UPDATE test.user SET first_name=? WHERE username=?"), 2));
+    assert_eq!(describe(&user.delete_query()), (String::from("DELETE FROM test.user WHERE username=?"), 1));
+    assert_eq!(describe(&user.select_query(Projection::All)).1, 1);
+
+    let batch = BatchBuilder::new().add_delete(user.delete_query()).build().unwrap();
+
+    assert_eq!(describe(&batch).0, "BEGIN BATCH DELETE FROM test.user WHERE username=?; APPLY BATCH");
+}
+
+#[test]
+fn test_into_parts() {
+    let user = user();
+
+    let store = user.store_query();
+    let (borrowed_query, borrowed_values) = (store.query().to_owned(), store.values().to_owned());
+
+    assert_eq!(store.into_parts(), (Cow::from(borrowed_query), borrowed_values));
+
+    let update = user.update_query().unwrap();
+    let borrowed_values = update.values().to_owned();
+
+    assert_eq!(update.into_values(), borrowed_values);
+
+    let delete = user.delete_query();
+    let borrowed_values = delete.values().to_owned();
+
+    let (query, values) = delete.into_parts();
+
+    assert_eq!(query, "DELETE FROM test.user WHERE username=?");
+    assert_eq!(values, borrowed_values);
+}
+
+#[test]
+fn test_clone_and_compare_queries() {
+    let user = user();
+
+    let store = user.store_query();
+    let retry = store.clone();
+
+    assert_eq!(store, retry);
+    assert_eq!(user.update_query().unwrap(), user.update_query().unwrap());
+    assert_eq!(user.delete_query().clone(), user.delete_query());
+
+    let other = User { username: String::from("rust"), first_name: String::from("crab") };
+
+    assert_ne!(user.update_query().unwrap(), other.update_query().unwrap());
+    assert_ne!(user.store_query(), other.store_query());
+    assert_ne!(user.update_query().unwrap(), user.update_query_if_exists().unwrap());
+}
+
+#[test]
+fn test_query_consistency() {
+    let user = user();
+
+    assert_eq!(user.store_query().consistency(), None);
+    assert_eq!(user.update_query().unwrap().consistency(), None);
+    assert_eq!(user.delete_query().consistency(), None);
+
+    let store = user.store_query().with_consistency(Consistency::Quorum);
+
+    assert_eq!(store.consistency(), Some(Consistency::Quorum));
+    assert_eq!(CqlQuery::consistency(&store), Some(Consistency::Quorum));
+    assert_eq!(store.query(), user.store_query().query());
+
+    assert_eq!(user.update_query().unwrap().with_consistency(Consistency::LocalQuorum).consistency(),
+               Some(Consistency::LocalQuorum));
+    assert_eq!(user.delete_query().with_consistency(Consistency::All).consistency(), Some(Consistency::All));
+    assert_eq!(CqlQuery::consistency(&user.select_query(Projection::All)), None);
+}
+
+#[test]
+fn test_generated_statements_are_borrowed() {
+    let user = user();
+
+    let (store, _) = user.store_query().into_parts();
+    let (update, _) = user.update_query().unwrap().into_parts();
+    let (delete, _) = user.delete_query().into_parts();
+
+    assert!(matches!(store, Cow::Borrowed(_)));
+    assert!(matches!(update, Cow::Borrowed(_)));
+    assert!(matches!(delete, Cow::Borrowed(_)));
+
+    let (if_exists, _) = user.delete_query_if_exists().unwrap().into_parts();
+
+    assert!(matches!(if_exists, Cow::Owned(_)));
+    assert_eq!(if_exists, "DELETE FROM test.user WHERE username=? IF EXISTS");
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Account {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "INT")]
+    age: i32,
+
+    #[column(type = "UUID")]
+    id: Uuid,
+}
+
+#[test]
+fn test_display_with_values() {
+    let account = Account {
+        username: String::from("rust"),
+        age: 7,
+        id: Uuid::parse_str("1b4e28ba-2fa1-11d2-883f-0016d3cca427").unwrap(),
+    };
+
+    let query = account.store_query();
+
+    assert_eq!(query.to_string(), "query:INSERT INTO test.account (username,age,id) VALUES (?,?,?)");
+    assert_eq!(query.display_with_values().to_string(),
+               "query:INSERT INTO test.account (username,age,id) VALUES (?,?,?) \
+                values:[0x72757374, 0x00000007, 0x1b4e28ba2fa111d2883f0016d3cca427]");
+
+    assert_eq!(query.display_with_values().with_columns::<Account>(Account::columns()).to_string(),
+               "query:INSERT INTO test.account (username,age,id) VALUES (?,?,?) \
+                values:['rust', 7, 1b4e28ba-2fa1-11d2-883f-0016d3cca427]");
+
+    assert_eq!(query.display_with_values().with_columns::<Account>(Account::columns()).redact(&["username"]).to_string(),
+               "query:INSERT INTO test.account (username,age,id) VALUES (?,?,?) \
+                values:[<redacted>, 7, 1b4e28ba-2fa1-11d2-883f-0016d3cca427]");
+
+    let mut named = HashMap::new();
+    named.insert(String::from("username"), Value::from("rust"));
+    named.insert(String::from("age"), Value::new_null());
+
+    let values = QueryValues::NamedValues(named);
+    let select = SelectQuery::new("SELECT * FROM test.account WHERE username=:username", values);
+
+    assert_eq!(select.display_with_values().to_string(),
+               "query:SELECT * FROM test.account WHERE username=:username values:[age=null, username=0x72757374]");
+
+    assert_eq!(select.display_with_values().with_columns::<Account>(&[]).redact(&["age"]).to_string(),
+               "query:SELECT * FROM test.account WHERE username=:username values:[age=<redacted>, username='rust']");
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Session {
+    #[column(type = "TEXT", primary_key)]
+    token: String,
+}
+
+#[test]
+fn test_query_metadata() {
+    let user = user();
+    let meta = |kind| (String::from("test"), String::from("user"), kind);
+
+    assert_eq!(metadata(&user.store_query()), meta(QueryKind::Insert));
+    assert_eq!(metadata(&user.store_query_if_not_exists().unwrap()), meta(QueryKind::Insert));
+    assert_eq!(metadata(&user.update_query().unwrap()), meta(QueryKind::Update));
+    assert_eq!(metadata(&user.update_columns_query(&["first_name"]).unwrap()), meta(QueryKind::Update));
+    assert_eq!(metadata(&user.delete_query()), meta(QueryKind::Delete));
+    assert_eq!(metadata(&user.delete_query_if_exists().unwrap()), meta(QueryKind::Delete));
+    assert_eq!(metadata(&user.select_query(Projection::All)), meta(QueryKind::Select));
+    assert_eq!(metadata(&user.exists_query()), meta(QueryKind::Select));
+
+    assert_eq!(user.store_query().table(), "user");
+    assert_eq!(user.store_query().kind().to_string(), "insert");
+    assert_eq!(metadata(&user.store_query_in("tenant_1").unwrap()), (String::from("tenant_1"), String::from("user"), QueryKind::Insert));
+
+    let unknown = SelectQuery::new("SELECT * FROM test.user", QueryValues::SimpleValues(vec![]));
+
+    assert_eq!(SelectQuery::for_table::<User>("SELECT * FROM test.user", QueryValues::SimpleValues(vec![])),
+               unknown.clone().with_table("test", "user"));
+
+    assert_eq!(metadata(&unknown), (String::new(), String::new(), QueryKind::Select));
+}
+
+#[test]
+fn test_batch_metadata() {
+    let user = user();
+
+    let same_table = BatchBuilder::new().add_store(user.store_query()).add_delete(user.delete_query()).build().unwrap();
+
+    assert_eq!(metadata(&same_table), (String::from("test"), String::from("user"), QueryKind::Batch));
+
+    let session = Session { token: String::from("secret") };
+
+    let tables = BatchBuilder::new().add_store(user.store_query()).add_store(session.store_query()).build().unwrap();
+
+    assert_eq!(metadata(&tables), (String::new(), String::new(), QueryKind::Batch));
+}
@@ -0,0 +1,310 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use cassandra_macro::{BatchBuilder, CassandraTable, clear_query_hook, CqlQuery, DeleteQuery, Error, PolicyError, QueryKind,
+                      set_query_hook, StoreQuery, UpdateQuery};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::query::QueryValues;
+use cdrs::types::value::Value;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT")]
+    first_name: String,
+}
+
+/// The hook is global, the tests installing it run one at a time
+static HOOK_LOCK: Mutex<()> = Mutex::new(());
+
+static CHECKED: AtomicUsize = AtomicUsize::new(0);
+
+fn counting_hook(query: &dyn CqlQuery) -> Result<(), PolicyError> {
+    assert_eq!(query.keyspace(), "test");
+    CHECKED.fetch_add(1, Ordering::SeqCst);
+
+    Ok(())
+}
+
+static STATEMENTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn recording_hook(query: &dyn CqlQuery) -> Result<(), PolicyError> {
+    STATEMENTS.lock().unwrap_or_else(|e| e.into_inner()).push(format!("{}.{} {}", query.keyspace(), query.table(), query.query()));
+
+    Ok(())
+}
+
+/// Queries checked by the recording hook while running `f`
+fn recorded<F: FnOnce()>(f: F) -> Vec<String> {
+    STATEMENTS.lock().unwrap_or_else(|e| e.into_inner()).clear();
+
+    set_query_hook(recording_hook);
+    f();
+    clear_query_hook();
+
+    std::mem::take(&mut *STATEMENTS.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Only inserts with a TTL are allowed
+fn ttl_hook(query: &dyn CqlQuery) -> Result<(), PolicyError> {
+    match query.kind() {
+        QueryKind::Insert if !query.query().contains("USING TTL") => Err(PolicyError::new("Insert without a TTL")),
+        _ => Ok(()),
+    }
+}
+
+/// Read only replica, only inserts of the `user` table are allowed
+fn read_only_hook(query: &dyn CqlQuery) -> Result<(), PolicyError> {
+    match query.kind() {
+        QueryKind::Delete => Err(PolicyError::new(format!("Delete from {} on a read only replica", query.table()))),
+        QueryKind::Batch if query.query().contains("DELETE") => Err(PolicyError::new("Batch with a delete on a read only replica")),
+        _ => Ok(()),
+    }
+}
+
+fn values() -> QueryValues {
+    QueryValues::SimpleValues(vec![Value::from("rust")])
+}
+
+fn store() -> Result<StoreQuery, Error> {
+    StoreQuery::try_new("INSERT INTO test.user (username) VALUES (?)", values(), "test", "user")
+}
+
+fn update() -> Result<UpdateQuery, Error> {
+    UpdateQuery::try_new("UPDATE test.user SET first_name=? WHERE username=?", values(), "test", "user")
+}
+
+fn delete() -> Result<DeleteQuery, Error> {
+    DeleteQuery::try_new("DELETE FROM test.user WHERE username=?", values(), "test", "user")
+}
+
+#[test]
+fn test_no_hook() {
+    let _lock = HOOK_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let query = delete().unwrap();
+
+    assert_eq!(query.query(), "DELETE FROM test.user WHERE username=?");
+    assert_eq!(query.keyspace(), "test");
+    assert_eq!(CqlQuery::table(&query), "user");
+    assert_eq!(query, DeleteQuery::new("DELETE FROM test.user WHERE username=?", values()).with_table("test", "user"));
+
+    assert!(BatchBuilder::new().add_delete(query).build().is_ok());
+}
+
+#[test]
+fn test_counting_hook() {
+    let _lock = HOOK_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    set_query_hook(counting_hook);
+    CHECKED.store(0, Ordering::SeqCst);
+
+    let batch = BatchBuilder::new()
+        .add_store(store().unwrap())
+        .add_update(update().unwrap())
+        .add_delete(delete().unwrap())
+        .build();
+
+    clear_query_hook();
+
+    assert!(batch.is_ok());
+    assert_eq!(CHECKED.load(Ordering::SeqCst), 4);
+
+    store().unwrap();
+    assert_eq!(CHECKED.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn test_rejecting_hook() {
+    let _lock = HOOK_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let user = User { username: String::from("rust"), first_name: String::from("ferris") };
+
+    set_query_hook(read_only_hook);
+
+    let stored = store();
+    let deleted = delete();
+    let batch = BatchBuilder::new().add_store(user.store_query()).add_delete(user.delete_query()).build();
+    let inserts = BatchBuilder::new().add_store(user.store_query()).build();
+
+    clear_query_hook();
+
+    assert!(stored.is_ok());
+    assert!(inserts.is_ok());
+
+    match deleted {
+        Err(Error::Policy(e)) => assert_eq!(e, PolicyError::new("Delete from user on a read only replica")),
+        other => panic!("Expected policy error, got {:?}", other),
+    }
+
+    match batch {
+        Err(e) => assert_eq!(e.to_string(), "Query rejected by the query hook: Batch with a delete on a read only replica"),
+        other => panic!("Expected policy error, got {:?}", other),
+    }
+
+    assert!(delete().is_ok());
+}
+
+#[test]
+fn test_rejecting_hook_of_derived_queries() {
+    let _lock = HOOK_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let user = User { username: String::from("rust"), first_name: String::from("ferris") };
+
+    set_query_hook(read_only_hook);
+
+    let stored = user.try_store_query();
+    let updated = user.try_update_query();
+    let deleted = user.try_delete_query();
+
+    clear_query_hook();
+
+    assert!(stored.is_ok());
+    assert!(updated.is_ok());
+
+    match deleted {
+        Err(Error::Policy(e)) => assert_eq!(e, PolicyError::new("Delete from user on a read only replica")),
+        other => panic!("Expected policy error, got {:?}", other),
+    }
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_rejecting_hook_of_repository() {
+    use cassandra_macro::CassandraRepository;
+    use cassandra_macro::testing::MockSession;
+
+    let _lock = HOOK_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let user = User { username: String::from("rust"), first_name: String::from("ferris") };
+    let repository = CassandraRepository::new(MockSession::new());
+
+    set_query_hook(read_only_hook);
+
+    let saved = repository.save(&user);
+    let deleted = repository.delete(&user);
+    let deleted_if_exists = repository.delete_if_exists(&user);
+
+    clear_query_hook();
+
+    assert!(saved.is_ok());
+    assert_eq!(deleted.unwrap_err().to_string(), "Query rejected by the query hook: Delete from user on a read only replica");
+    assert!(matches!(deleted_if_exists, Err(Error::Policy(_))));
+    assert_eq!(repository.session().executed_statements(), vec!["INSERT INTO test.user (username,first_name) VALUES (?,?)"]);
+}
+
+#[test]
+fn test_hook_checks_the_complete_queries_once() {
+    let _lock = HOOK_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let user = User { username: String::from("rust"), first_name: String::from("ferris") };
+
+    let checked = recorded(|| {
+        user.store_query_with_ttl(60).unwrap();
+        user.store_query_with_timestamp(1).unwrap();
+        user.store_query_if_not_exists().unwrap();
+        user.update_query_with_ttl(60).unwrap();
+        user.update_query_if_exists().unwrap();
+        user.update_query_if(&[("first_name", Value::from("ferris"))]).unwrap();
+        user.delete_query_if_exists().unwrap();
+        user.delete_query_if(&[("first_name", Value::from("ferris"))]).unwrap();
+        user.delete_query_with_timestamp(1).unwrap();
+        user.delete_columns_query(&["first_name"]).unwrap();
+    });
+
+    assert_eq!(checked, vec![
+        "test.user INSERT INTO test.user (username,first_name) VALUES (?,?) USING TTL ?",
+        "test.user INSERT INTO test.user (username,first_name) VALUES (?,?) USING TIMESTAMP ?",
+        "test.user INSERT INTO test.user (username,first_name) VALUES (?,?) IF NOT EXISTS",
+        "test.user UPDATE test.user USING TTL ? SET first_name=? WHERE username=?",
+        "test.user UPDATE test.user SET first_name=? WHERE username=? IF EXISTS",
+        "test.user UPDATE test.user SET first_name=? WHERE username=? IF first_name=?",
+        "test.user DELETE FROM test.user WHERE username=? IF EXISTS",
+        "test.user DELETE FROM test.user WHERE username=? IF first_name=?",
+        "test.user DELETE FROM test.user USING TIMESTAMP ? WHERE username=?",
+        "test.user DELETE first_name FROM test.user WHERE username=?",
+    ]);
+}
+
+#[test]
+fn test_rejecting_hook_of_lwt_ttl_and_timestamp_queries() {
+    let _lock = HOOK_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let user = User { username: String::from("rust"), first_name: String::from("ferris") };
+
+    set_query_hook(read_only_hook);
+
+    let deletes = vec![
+        user.delete_query_if_exists(),
+        user.delete_query_if(&[("first_name", Value::from("ferris"))]),
+        user.delete_query_with_timestamp(1),
+        user.delete_columns_query(&["first_name"]),
+    ];
+
+    set_query_hook(ttl_hook);
+
+    let with_ttl = user.store_query_with_ttl(60);
+    let if_not_exists = user.store_query_if_not_exists();
+    let with_timestamp = user.store_query_with_timestamp(1);
+
+    clear_query_hook();
+
+    for deleted in deletes {
+        assert!(matches!(deleted, Err(Error::Policy(_))), "{:?}", deleted);
+    }
+
+    assert!(with_ttl.is_ok());
+    assert!(matches!(if_not_exists, Err(Error::Policy(_))));
+    assert!(matches!(with_timestamp, Err(Error::Policy(_))));
+}
+
+#[test]
+fn test_hook_of_queries_in_keyspace() {
+    let _lock = HOOK_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let user = User { username: String::from("rust"), first_name: String::from("ferris") };
+
+    let checked = recorded(|| {
+        user.store_query_in("tenant_1").unwrap();
+        user.update_query_in("tenant_1").unwrap();
+        user.delete_query_in("tenant_1").unwrap();
+    });
+
+    assert_eq!(checked, vec![
+        "tenant_1.user INSERT INTO tenant_1.user (username,first_name) VALUES (?,?)",
+        "tenant_1.user UPDATE tenant_1.user SET first_name=? WHERE username=?",
+        "tenant_1.user DELETE FROM tenant_1.user WHERE username=?",
+    ]);
+
+    set_query_hook(read_only_hook);
+    let deleted = user.delete_query_in("tenant_1");
+    clear_query_hook();
+
+    assert!(matches!(deleted, Err(Error::Policy(_))));
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_hook_of_repository_checks_once() {
+    use cassandra_macro::CassandraRepository;
+    use cassandra_macro::testing::MockSession;
+
+    let _lock = HOOK_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let user = User { username: String::from("rust"), first_name: String::from("ferris") };
+    let repository = CassandraRepository::new(MockSession::new());
+    let tenant = |_: &str| String::from("tenant_1");
+
+    let checked = recorded(|| {
+        repository.save(&user).unwrap();
+        repository.update_in(&tenant, &user).unwrap();
+    });
+
+    assert_eq!(checked, vec![
+        "test.user INSERT INTO test.user (username,first_name) VALUES (?,?)",
+        "tenant_1.user UPDATE tenant_1.user SET first_name=? WHERE username=?",
+    ]);
+}
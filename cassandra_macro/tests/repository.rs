@@ -0,0 +1,373 @@
+#![cfg(feature = "repository")]
+#[macro_use]
+extern crate cdrs;
+
+use std::cell::RefCell;
+use std::io::{Read, Write};
+use std::net::Shutdown;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use cassandra_macro::{CassandraRepository, CassandraTable, Error, KeyspaceResolver, UnboundedPreparedCache};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::cluster::{GetCompressor, GetConnection};
+use cdrs::compression::Compression;
+use cdrs::query::{ExecExecutor, PrepareExecutor, QueryExecutor};
+use cdrs::transport::CDRSTransport;
+
+use common::{bytes, string, user_rows_body, void_body};
+
+mod common;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", derive_try_from_row)]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "BIGINT", cluster_key(order = "ASC", position = 1))]
+    created: i64,
+
+    #[column(type = "TEXT")]
+    nickname: String,
+}
+
+/// Bytes written by the driver and the responses it will read
+#[derive(Default)]
+struct Wire {
+    written: Vec<u8>,
+    responses: Vec<u8>,
+}
+
+#[derive(Clone)]
+struct MockTransport(Arc<Mutex<Wire>>);
+
+impl Read for MockTransport {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut wire = self.0.lock().unwrap();
+        let n = buf.len().min(wire.responses.len());
+
+        buf[..n].copy_from_slice(&wire.responses[..n]);
+        wire.responses.drain(..n);
+
+        Ok(n)
+    }
+}
+
+impl Write for MockTransport {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().written.extend_from_slice(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl CDRSTransport for MockTransport {
+    fn try_clone(&self) -> std::io::Result<Self> {
+        Ok(self.clone())
+    }
+
+    fn close(&mut self, _close: Shutdown) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn set_timeout(&mut self, _dur: Option<Duration>) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn is_alive(&self) -> bool {
+        true
+    }
+}
+
+struct MockManager(Arc<Mutex<Wire>>);
+
+impl r2d2::ManageConnection for MockManager {
+    type Connection = RefCell<MockTransport>;
+    type Error = cdrs::Error;
+
+    fn connect(&self) -> Result<Self::Connection, Self::Error> {
+        Ok(RefCell::new(MockTransport(self.0.clone())))
+    }
+
+    fn is_valid(&self, _conn: &mut Self::Connection) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn has_broken(&self, _conn: &mut Self::Connection) -> bool {
+        false
+    }
+}
+
+struct MockSession {
+    pool: r2d2::Pool<MockManager>,
+    wire: Arc<Mutex<Wire>>,
+}
+
+impl MockSession {
+    fn new(responses: Vec<Vec<u8>>) -> Self {
+        let wire = Arc::new(Mutex::new(Wire { written: Vec::new(), responses: responses.concat() }));
+
+        let pool = r2d2::Pool::builder().max_size(1).build(MockManager(wire.clone())).unwrap();
+
+        MockSession { pool, wire }
+    }
+}
+
+impl GetConnection<MockTransport, MockManager> for MockSession {
+    fn get_connection(&self) -> Option<r2d2::PooledConnection<MockManager>> {
+        self.pool.get().ok()
+    }
+}
+
+impl GetCompressor<'static> for MockSession {
+    fn get_compressor(&self) -> Compression {
+        Compression::None
+    }
+}
+
+impl QueryExecutor<MockTransport, MockManager> for MockSession {}
+
+impl PrepareExecutor<MockTransport, MockManager> for MockSession {}
+
+impl ExecExecutor<MockTransport, MockManager> for MockSession {}
+
+/// Response frame of protocol v4 with the given opcode
+fn frame(opcode: u8, body: Vec<u8>) -> Vec<u8> {
+    [&[0x84, 0x00, 0x00, 0x00, opcode][..], &(body.len() as i32).to_be_bytes(), &body].concat()
+}
+
+fn void() -> Vec<u8> {
+    frame(0x08, void_body())
+}
+
+fn server_error(message: &str) -> Vec<u8> {
+    frame(0x00, [&0x2200i32.to_be_bytes()[..], &string(message)].concat())
+}
+
+fn prepared(id: &[u8]) -> Vec<u8> {
+    let metadata = [0i32.to_be_bytes(); 5].concat();
+
+    frame(0x08, [&4i32.to_be_bytes()[..], &(id.len() as u16).to_be_bytes(), id, &metadata].concat())
+}
+
+fn unprepared(id: &[u8]) -> Vec<u8> {
+    let body = [&0x2500i32.to_be_bytes()[..], &string("Prepared query with ID not found"), &(id.len() as u16).to_be_bytes(), id].concat();
+
+    frame(0x00, body)
+}
+
+fn user_rows(users: &[(&str, i64, &str)]) -> Vec<u8> {
+    user_page(users, None)
+}
+
+/// Rows of the users, followed by more pages if there is a paging state
+fn user_page(users: &[(&str, i64, &str)], paging_state: Option<&[u8]>) -> Vec<u8> {
+    frame(0x08, user_rows_body(users, paging_state))
+}
+
+/// Tenants sharded by keyspace
+struct Tenant(u32);
+
+impl KeyspaceResolver for Tenant {
+    fn keyspace_for(&self, _table: &str) -> String {
+        format!("tenant_{}", self.0)
+    }
+}
+
+/// Row of a conditional query with only the `[applied]` column
+fn applied_rows(applied: bool) -> Vec<u8> {
+    let mut body = [2i32.to_be_bytes(), 1i32.to_be_bytes(), 1i32.to_be_bytes()].concat();
+
+    body.extend([string("test"), string("user"), string("[applied]")].concat());
+    body.extend(&0x0004u16.to_be_bytes());
+    body.extend(&1i32.to_be_bytes());
+    body.extend(bytes(&[applied as u8]));
+
+    frame(0x08, body)
+}
+
+fn written(session: &MockSession) -> String {
+    String::from_utf8_lossy(&session.wire.lock().unwrap().written).to_string()
+}
+
+fn user() -> User {
+    User { username: String::from("rust"), created: 7, nickname: String::from("ferris") }
+}
+
+#[test]
+fn test_repository_mutations() {
+    let repository = CassandraRepository::new(MockSession::new(vec![void(), void(), void(), void()]));
+
+    repository.create_table::<User>().unwrap();
+    repository.save(&user()).unwrap();
+    repository.update(&user()).unwrap();
+    repository.delete(&user()).unwrap();
+
+    let written = written(repository.session());
+
+    assert!(written.contains(User::create_table_cql()));
+    assert!(written.contains(user().store_query().query()));
+    assert!(written.contains(user().update_query().unwrap().query()));
+    assert!(written.contains(user().delete_query().query()));
+}
+
+#[test]
+fn test_repository_find_by_key() {
+    let repository = CassandraRepository::new(MockSession::new(vec![user_rows(&[("rust", 7, "ferris")]), user_rows(&[])]));
+
+    let found: Option<User> = repository.find_by_key(query_values!("rust", 7i64)).unwrap();
+
+    let found = found.unwrap();
+    assert_eq!(found.username, "rust");
+    assert_eq!(found.created, 7);
+    assert_eq!(found.nickname, "ferris");
+
+    let not_found: Option<User> = repository.find_by_key(query_values!("go", 7i64)).unwrap();
+    assert!(not_found.is_none());
+
+    assert!(written(repository.session()).contains("WHERE  username=?  AND  created=? "));
+}
+
+#[test]
+fn test_repository_find_all_in_partition() {
+    let rows = user_rows(&[("rust", 1, "ferris"), ("rust", 2, "crab")]);
+    let repository = CassandraRepository::new(MockSession::new(vec![rows]));
+
+    let users: Vec<User> = repository.find_all_in_partition(query_values!("rust")).unwrap();
+
+    assert_eq!(users.iter().map(|u| u.created).collect::<Vec<i64>>(), vec![1, 2]);
+    assert_eq!(users[1].nickname, "crab");
+}
+
+#[test]
+fn test_repository_driver_error() {
+    let repository = CassandraRepository::new(MockSession::new(vec![server_error("unconfigured table user")]));
+
+    match repository.save(&user()) {
+        Err(Error::Driver(e)) => assert!(e.to_string().contains("unconfigured table user")),
+        other => panic!("Expected driver error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_repository_keyspace_resolver() {
+    let responses = vec![void(), void(), void(), user_rows(&[("rust", 7, "ferris")]), void(), user_rows(&[])];
+    let repository = CassandraRepository::new(MockSession::new(responses));
+
+    repository.create_table_in::<User, _>(&Tenant(1)).unwrap();
+    repository.save_in(&Tenant(1), &user()).unwrap();
+    repository.update_in(&Tenant(2), &user()).unwrap();
+
+    let found: Option<User> = repository.find_by_key_in(&Tenant(1), query_values!("rust", 7i64)).unwrap();
+    assert_eq!(found.unwrap().nickname, "ferris");
+
+    repository.delete_in(&Tenant(2), &user()).unwrap();
+
+    let partition: Vec<User> = repository.find_all_in_partition_in(&|_: &str| String::from("tenant_2"), query_values!("rust")).unwrap();
+    assert!(partition.is_empty());
+
+    let written = written(repository.session());
+
+    assert!(written.contains(&User::create_table_cql_in("tenant_1").unwrap()));
+    assert!(written.contains(user().store_query_in("tenant_1").unwrap().query()));
+    assert!(written.contains(user().update_query_in("tenant_2").unwrap().query()));
+    assert!(written.contains(&User::select_by_primary_and_cluster_keys_in("tenant_1", cassandra_macro::Projection::All).unwrap()));
+    assert!(written.contains(user().delete_query_in("tenant_2").unwrap().query()));
+    assert!(written.contains(&User::select_by_primary_keys_in("tenant_2", cassandra_macro::Projection::All).unwrap()));
+    assert!(!written.contains("test.user"));
+}
+
+#[test]
+fn test_repository_invalid_resolved_keyspace() {
+    let repository = CassandraRepository::new(MockSession::new(vec![]));
+
+    match repository.save_in(&|table: &str| format!("tenant-{}", table), &user()) {
+        Err(Error::InvalidIdentifier(ks)) => assert_eq!(ks, "tenant-user"),
+        other => panic!("Expected invalid identifier, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_repository_find_all_paged() {
+    let pages = vec![
+        user_page(&[("rust", 1, "ferris"), ("rust", 2, "crab")], Some(b"next-page")),
+        user_page(&[("go", 3, "gopher")], None),
+    ];
+    let repository = CassandraRepository::new(MockSession::new(pages));
+
+    let users = repository.find_all_paged::<User>(2).collect::<Result<Vec<User>, Error>>().unwrap();
+
+    assert_eq!(users.iter().map(|u| u.nickname.as_str()).collect::<Vec<&str>>(), vec!["ferris", "crab", "gopher"]);
+
+    let written = written(repository.session());
+
+    assert_eq!(written.matches("SELECT * FROM test.user").count(), 2);
+    assert_eq!(written.matches("next-page").count(), 1);
+}
+
+#[test]
+fn test_repository_find_all_paged_error() {
+    let pages = vec![user_page(&[("rust", 1, "ferris")], Some(b"next-page")), server_error("read timeout")];
+    let repository = CassandraRepository::new(MockSession::new(pages));
+
+    let mut users = repository.find_all_paged::<User>(1);
+
+    assert_eq!(users.next().unwrap().unwrap().nickname, "ferris");
+    assert!(matches!(users.next(), Some(Err(Error::Driver(_)))));
+    assert!(users.next().is_none());
+}
+
+#[test]
+fn test_repository_prepared_cache() {
+    let responses = vec![prepared(b"store"), void(), void(), prepared(b"delete"), void(), void()];
+    let repository = CassandraRepository::new(MockSession::new(responses)).with_prepared_cache(UnboundedPreparedCache::new());
+
+    repository.save(&user()).unwrap();
+    repository.save(&user()).unwrap();
+    repository.delete(&user()).unwrap();
+    repository.delete(&user()).unwrap();
+
+    let written = written(repository.session());
+
+    assert_eq!(written.matches(user().store_query().query()).count(), 1);
+    assert_eq!(written.matches(user().delete_query().query()).count(), 1);
+    assert_eq!(written.matches("store").count(), 2);
+    assert_eq!(written.matches("delete").count(), 2);
+}
+
+#[test]
+fn test_repository_prepared_cache_unprepared() {
+    let responses = vec![prepared(b"first"), void(), unprepared(b"first"), prepared(b"second"), void(), void()];
+    let repository = CassandraRepository::new(MockSession::new(responses)).with_prepared_cache(UnboundedPreparedCache::new());
+
+    for _ in 0..3 {
+        repository.save(&user()).unwrap();
+    }
+
+    let written = written(repository.session());
+
+    assert_eq!(written.matches(user().store_query().query()).count(), 2);
+    assert_eq!(written.matches("first").count(), 2);
+    assert_eq!(written.matches("second").count(), 2);
+}
+
+#[test]
+fn test_repository_conditional_queries() {
+    let responses = vec![applied_rows(true), applied_rows(false), applied_rows(true)];
+    let repository = CassandraRepository::new(MockSession::new(responses));
+
+    assert!(repository.save_if_not_exists(&user()).unwrap().applied());
+    assert!(!repository.update_if_exists(&user()).unwrap().applied());
+    assert!(repository.delete_if_exists(&user()).unwrap().applied());
+
+    let written = written(repository.session());
+
+    assert!(written.contains(user().store_query_if_not_exists().unwrap().query()));
+    assert!(written.contains(user().update_query_if_exists().unwrap().query()));
+    assert!(written.contains(user().delete_query_if_exists().unwrap().query()));
+}
@@ -0,0 +1,96 @@
+use cassandra_macro::{CassandraTable, CountRow};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::frame::frame_result::{BodyResResultRows, ColSpec, ColType, ColTypeOption, RowsMetadata};
+use cdrs::frame::TryFromRow;
+use cdrs::types::{CBytes, CString};
+use cdrs::types::rows::Row;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", derive_try_from_row)]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "BIGINT")]
+    created: i64,
+
+    #[column(type = "TEXT")]
+    nickname: Option<String>,
+
+    #[column(type = "TEXT")]
+    email: Option<String>,
+
+    cached_display_name: String,
+}
+
+fn column(name: &str, id: ColType) -> ColSpec {
+    ColSpec {
+        ksname: None,
+        tablename: None,
+        name: CString::new(name.to_string()),
+        col_type: ColTypeOption { id, value: None },
+    }
+}
+
+fn synthetic_row() -> Row {
+    let col_specs = vec![
+        column("username", ColType::Varchar),
+        column("created", ColType::Bigint),
+        column("nickname", ColType::Varchar),
+        column("email", ColType::Varchar),
+    ];
+
+    let body = BodyResResultRows {
+        metadata: RowsMetadata {
+            flags: 0,
+            columns_count: col_specs.len() as i32,
+            paging_state: None,
+            global_table_space: None,
+            col_specs,
+        },
+        rows_count: 1,
+        rows_content: vec![vec![
+            CBytes::new(b"rust".to_vec()),
+            CBytes::new(7i64.to_be_bytes().to_vec()),
+            CBytes::new(b"ferris".to_vec()),
+            CBytes::new_empty(),
+        ]],
+    };
+
+    Row::from_frame_body(body).remove(0)
+}
+
+#[test]
+fn test_generated_try_from_row() {
+    let user = User::try_from_row(synthetic_row()).unwrap();
+
+    assert_eq!(user.username, "rust");
+    assert_eq!(user.created, 7);
+    assert_eq!(user.nickname, Some(String::from("ferris")));
+    assert_eq!(user.email, None);
+    assert_eq!(user.cached_display_name, "");
+
+    assert_eq!(User::table_name(), "user");
+}
+
+fn count_row(alias: &str, count: i64) -> Row {
+    let body = BodyResResultRows {
+        metadata: RowsMetadata {
+            flags: 0,
+            columns_count: 1,
+            paging_state: None,
+            global_table_space: None,
+            col_specs: vec![column(alias, ColType::Bigint)],
+        },
+        rows_count: 1,
+        rows_content: vec![vec![CBytes::new(count.to_be_bytes().to_vec())]],
+    };
+
+    Row::from_frame_body(body).remove(0)
+}
+
+#[test]
+fn test_count_row() {
+    assert_eq!(CountRow::try_from_row(count_row("count", 42)).unwrap(), CountRow { count: 42 });
+    assert!(CountRow::try_from_row(count_row("events", 42)).is_err());
+}
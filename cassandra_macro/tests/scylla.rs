@@ -0,0 +1,49 @@
+#![cfg(feature = "scylla")]
+#[macro_use]
+extern crate cdrs;
+
+use cassandra_macro::{CassandraTable, CqlQuery};
+use cassandra_macro_derive::CassandraTable;
+use scylla::frame::value::{SerializedValues, Unset};
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "BIGINT")]
+    created: i64,
+
+    #[column(type = "TEXT")]
+    nickname: Option<String>,
+}
+
+fn user() -> User {
+    User { username: String::from("rust"), created: 7, nickname: None }
+}
+
+#[test]
+fn test_scylla_serialized_values() {
+    let query = user().store_query();
+
+    let mut expected = SerializedValues::new();
+    expected.add_value(&"rust").unwrap();
+    expected.add_value(&7i64).unwrap();
+    expected.add_value(&None::<String>).unwrap();
+
+    assert_eq!(query.query(), "INSERT INTO test.user (username,created,nickname) VALUES (?,?,?)");
+    assert_eq!(query.serialized_values().unwrap(), expected);
+}
+
+#[test]
+fn test_scylla_serialized_unset_values() {
+    let query = cassandra_macro::UpdateQuery::new("UPDATE test.user SET nickname=? WHERE username=?",
+                                                  query_values!(cdrs::types::value::Value::new_not_set(), "rust"));
+
+    let mut expected = SerializedValues::new();
+    expected.add_value(&Unset).unwrap();
+    expected.add_value(&"rust").unwrap();
+
+    assert_eq!(query.serialized_values().unwrap(), expected);
+}
@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+
+use cassandra_macro::{AggregateFn, CassandraTable, CountAlias, Error, Order, Projection, SelectOptions, Selector};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::query::QueryValues;
+use cdrs::types::value::Value;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct UserEvent {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TIMESTAMP", cluster_key(order = "ASC", position = 1))]
+    created: i64,
+
+    #[column(type = "TEXT")]
+    payload: String,
+}
+
+#[test]
+fn test_select_order_by_cluster_key() {
+    let asc = UserEvent::select_by_primary_keys_with(Projection::All,
+                                                     &SelectOptions::new().order("created", Order::Asc));
+
+    assert_eq!(asc.unwrap(), "SELECT * FROM test.user_event WHERE  username=? ORDER BY created ASC");
+
+    let desc = UserEvent::select_by_primary_keys_with(Projection::All,
+                                                      &SelectOptions::new().order("created", Order::Desc).limit());
+
+    assert_eq!(desc.unwrap(), "SELECT * FROM test.user_event WHERE  username=? ORDER BY created DESC LIMIT ?");
+}
+
+#[test]
+fn test_select_order_by_non_cluster_key() {
+    let result = UserEvent::select_by_primary_and_cluster_keys_with(Projection::All,
+                                                                    &SelectOptions::new().order("payload", Order::Desc));
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_select_aggregate_with_plain_column() {
+    let projection = Projection::Selectors(vec![
+        Selector::Column(String::from("username")),
+        Selector::Aggregate(AggregateFn::Max, String::from("created"), None),
+    ]);
+
+    assert_eq!(UserEvent::select_by_primary_keys(projection),
+               "SELECT username,max(created) AS max_created FROM test.user_event WHERE  username=? ");
+
+    let unknown = Projection::Aggregate(AggregateFn::Min, String::from("removed"), None);
+
+    assert!(UserEvent::select_by_primary_keys_with(unknown, &SelectOptions::new()).is_err());
+}
+
+#[test]
+fn test_select_group_by() {
+    let projection = Projection::Selectors(vec![
+        Selector::Column(String::from("username")),
+        Selector::Aggregate(AggregateFn::Count, String::from("created"), None),
+    ]);
+
+    let by_partition = UserEvent::select_all_with(projection, &SelectOptions::new().group_by(&["username"]).limit());
+
+    assert_eq!(by_partition.unwrap(),
+               "SELECT username,count(created) AS count_created FROM test.user_event GROUP BY username LIMIT ?");
+
+    let by_partition_and_cluster = UserEvent::select_by_primary_keys_with(
+        Projection::Aggregate(AggregateFn::Max, String::from("payload"), None),
+        &SelectOptions::new().order("created", Order::Desc).group_by(&["username", "created"]));
+
+    assert_eq!(by_partition_and_cluster.unwrap(),
+               "SELECT max(payload) AS max_payload FROM test.user_event WHERE  username=? GROUP BY username,created ORDER BY created DESC");
+
+    assert!(UserEvent::select_all_with(Projection::All, &SelectOptions::new().group_by(&["created"])).is_err());
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct TenantUser {
+    #[column(type = "TEXT", compound_key(position = 1))]
+    tenant: String,
+
+    #[column(type = "TEXT", compound_key(position = 2))]
+    username: String,
+
+    #[column(type = "TEXT")]
+    first_name: String,
+}
+
+#[test]
+fn test_select_token_range() {
+    assert_eq!(UserEvent::select_token_range(Projection::All),
+               "SELECT * FROM test.user_event WHERE token(username) > ? AND token(username) <= ?");
+
+    assert_eq!(TenantUser::select_token_range(Projection::Columns(vec![String::from("first_name")])),
+               "SELECT first_name FROM test.tenant_user WHERE token(tenant, username) > ? AND token(tenant, username) <= ?");
+
+    assert_eq!(TenantUser::select_token_range_with_limit(Projection::All),
+               "SELECT * FROM test.tenant_user WHERE token(tenant, username) > ? AND token(tenant, username) <= ? LIMIT ?");
+}
+
+#[test]
+fn test_select_json() {
+    assert_eq!(UserEvent::select_by_primary_keys(Projection::Json),
+               "SELECT JSON * FROM test.user_event WHERE  username=? ");
+
+    assert_eq!(UserEvent::select_by_primary_keys(Projection::JsonColumns(vec![String::from("created"), String::from("payload")])),
+               "SELECT JSON created,payload FROM test.user_event WHERE  username=? ");
+
+    assert_eq!(UserEvent::select_by_primary_and_cluster_keys(Projection::Json),
+               "SELECT JSON * FROM test.user_event WHERE  username=?  AND  created=? ");
+
+    assert_eq!(UserEvent::select_by_primary_and_cluster_keys(Projection::JsonColumns(vec![String::from("payload")])),
+               "SELECT JSON payload FROM test.user_event WHERE  username=?  AND  created=? ");
+}
+
+#[test]
+fn test_select_write_time_and_ttl() {
+    assert_eq!(UserEvent::select_by_primary_and_cluster_keys(Projection::WriteTime(vec![String::from("payload")])),
+               "SELECT writetime(payload) AS writetime_payload FROM test.user_event WHERE  username=?  AND  created=? ");
+
+    assert!(UserEvent::select_by_primary_keys_with(Projection::Ttl(vec![String::from("created")]), &SelectOptions::new()).is_err());
+}
+
+#[test]
+fn test_exists_query() {
+    let event = UserEvent {
+        username: String::from("rust"),
+        created: 1,
+        payload: String::from("payload"),
+    };
+
+    let query = event.exists_query();
+
+    assert_eq!(query.query(), "SELECT count(*) AS count FROM test.user_event WHERE username=? AND created=? LIMIT 1");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![Value::from("rust"), Value::from(1i64)]));
+}
+
+#[test]
+fn test_count_partition_query() {
+    let event = UserEvent {
+        username: String::from("rust"),
+        created: 1,
+        payload: String::from("payload"),
+    };
+
+    let query = event.count_partition_query();
+
+    assert_eq!(query.query(), "SELECT count(*) AS count FROM test.user_event WHERE username=?");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![Value::from("rust")]));
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct TenantEvent {
+    #[column(type = "TEXT", compound_key(position = 2))]
+    username: String,
+
+    #[column(type = "TEXT", compound_key(position = 1))]
+    tenant: String,
+
+    #[column(type = "INT", cluster_key(order = "DESC", position = 2))]
+    sequence: i32,
+
+    #[column(type = "TIMESTAMP", cluster_key(order = "DESC", position = 1))]
+    created: i64,
+
+    #[column(type = "TEXT")]
+    payload: String,
+}
+
+#[test]
+fn test_select_query() {
+    let event = TenantEvent {
+        username: String::from("rust"),
+        tenant: String::from("mozilla"),
+        sequence: 7,
+        created: 1,
+        payload: String::from("payload"),
+    };
+
+    let query = event.select_query(Projection::Columns(vec![String::from("payload")]));
+
+    assert_eq!(query.query(),
+               "SELECT payload FROM test.tenant_event WHERE  tenant=? AND username=?  AND  created=? AND sequence=? ");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![
+        Value::from("mozilla"), Value::from("rust"), Value::from(1i64), Value::from(7i32)
+    ]));
+}
+
+#[test]
+fn test_primary_key_values_named() {
+    let mut keys = HashMap::new();
+    keys.insert("sequence", Value::from(7i32));
+    keys.insert("username", Value::from("rust"));
+    keys.insert("created", Value::from(1i64));
+    keys.insert("tenant", Value::from("mozilla"));
+
+    assert_eq!(TenantEvent::primary_key_values_named(keys.clone()).unwrap(), QueryValues::SimpleValues(vec![
+        Value::from("mozilla"), Value::from("rust"), Value::from(1i64), Value::from(7i32)
+    ]));
+
+    let mut extra = keys.clone();
+    extra.insert("payload", Value::from("payload"));
+
+    assert!(TenantEvent::primary_key_values_named(extra).is_err());
+
+    keys.remove("created");
+
+    assert!(TenantEvent::primary_key_values_named(keys).is_err());
+}
+
+#[test]
+fn test_select_query_paging() {
+    let event = UserEvent {
+        username: String::from("rust"),
+        created: 1,
+        payload: String::from("payload"),
+    };
+
+    let first = event.select_query(Projection::All);
+
+    assert_eq!(first.page_size(), None);
+    assert_eq!(first.paging_state(), None);
+
+    let page = event.select_query(Projection::All)
+        .with_page_size(100)
+        .with_paging_state(vec![1, 2, 3]);
+
+    assert_eq!(page.page_size(), Some(100));
+    assert_eq!(page.paging_state(), Some(&[1u8, 2, 3][..]));
+
+    assert_eq!(page.query(), first.query());
+    assert_eq!(page.values(), first.values());
+}
+
+#[test]
+fn test_select_by_keys_projections() {
+    assert_eq!(UserEvent::select_by_primary_keys(Projection::All),
+               "SELECT * FROM test.user_event WHERE  username=? ");
+    assert_eq!(UserEvent::select_by_primary_keys(Projection::Count),
+               "SELECT count(*) AS count FROM test.user_event WHERE  username=? ");
+    assert_eq!(UserEvent::select_by_primary_and_cluster_keys(Projection::Columns(vec![String::from("payload"), String::from("created")])),
+               "SELECT payload,created FROM test.user_event WHERE  username=?  AND  created=? ");
+
+    assert_eq!(TenantUser::select_by_primary_and_cluster_keys(Projection::Count),
+               "SELECT count(*) AS count FROM test.tenant_user WHERE  tenant=? AND username=? ");
+}
+
+#[test]
+fn test_select_count_alias() {
+    let alias = CountAlias::new("events").unwrap();
+
+    assert_eq!(alias.as_str(), "events");
+    assert_eq!(UserEvent::select_by_primary_keys(Projection::CountAs(alias)),
+               "SELECT count(*) AS events FROM test.user_event WHERE  username=? ");
+    assert!(Projection::CountAs(CountAlias::new("events").unwrap()).validated::<UserEvent>().is_ok());
+
+    match CountAlias::new("events FROM other.table --") {
+        Err(Error::InvalidIdentifier(alias)) => assert_eq!(alias, "events FROM other.table --"),
+        other => panic!("Expected invalid identifier, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_select_typed_projection() {
+    assert_eq!(UserEvent::select_by_primary_and_cluster_keys(Projection::typed(&[UserEventColumn::Payload, UserEventColumn::Created])),
+               UserEvent::select_by_primary_and_cluster_keys(Projection::Columns(vec![String::from("payload"), String::from("created")])));
+
+    assert_eq!(TenantUser::select_token_range(Projection::typed(&[TenantUserColumn::FirstName])),
+               "SELECT first_name FROM test.tenant_user WHERE token(tenant, username) > ? AND token(tenant, username) <= ?");
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Account {
+    #[column(type = "UUID", primary_key)]
+    id: String,
+
+    #[column(type = "TIMESTAMP", cluster_key(order = "DESC", position = 1))]
+    created: i64,
+
+    #[column(type = "TEXT", queryable)]
+    email: String,
+
+    #[column(type = "TEXT", queryable(allow_filtering))]
+    country: String,
+
+    #[column(type = "TEXT")]
+    name: String,
+}
+
+#[test]
+fn test_select_by_queryable_columns() {
+    assert_eq!(Account::select_by_email(Projection::All),
+               "SELECT * FROM test.account WHERE email=?");
+    assert_eq!(Account::select_by_country(Projection::Columns(vec![String::from("id"), String::from("name")])),
+               "SELECT id,name FROM test.account WHERE country=? ALLOW FILTERING");
+}
+
+#[test]
+fn test_select_keys_by_queryable_columns() {
+    assert_eq!(Account::select_keys_by_email(), "SELECT id,created FROM test.account WHERE email=?");
+    assert_eq!(Account::select_keys_by_country(), "SELECT id,created FROM test.account WHERE country=? ALLOW FILTERING");
+
+    let projected = Account::select_keys_by_email()
+        .trim_start_matches("SELECT ")
+        .split(' ')
+        .next()
+        .map(|columns| columns.split(',').map(String::from).collect::<Vec<String>>());
+
+    let keys = [Account::primary_key_columns(), Account::cluster_key_columns()].concat();
+
+    assert_eq!(projected.unwrap(), keys);
+}
+
+#[test]
+fn test_select_one() {
+    assert_eq!(UserEvent::select_one_by_primary_keys(Projection::All),
+               "SELECT * FROM test.user_event WHERE  username=? LIMIT 1");
+    assert_eq!(UserEvent::select_one_by_primary_and_cluster_keys(Projection::Columns(vec![String::from("payload")])),
+               "SELECT payload FROM test.user_event WHERE  username=?  AND  created=? LIMIT 1");
+    assert_eq!(UserEvent::select_one_by_primary_keys(Projection::Count),
+               "SELECT count(*) AS count FROM test.user_event WHERE  username=? LIMIT 1");
+
+    let options = SelectOptions::new().order("created", Order::Desc).limit_to(10);
+
+    assert_eq!(UserEvent::select_by_primary_keys_with(Projection::All, &options).unwrap(),
+               "SELECT * FROM test.user_event WHERE  username=? ORDER BY created DESC LIMIT 10");
+}
+
+fn columns(columns: &[&str]) -> Projection {
+    Projection::Columns(columns.iter().map(|c| c.to_string()).collect())
+}
+
+#[test]
+fn test_validated_projection() {
+    let projection = columns(&["payload", "created"]).validated::<UserEvent>().unwrap();
+
+    assert_eq!(UserEvent::select_by_primary_keys(projection), "SELECT payload,created FROM test.user_event WHERE  username=? ");
+
+    assert!(Projection::All.validated::<UserEvent>().is_ok());
+    assert!(Projection::JsonColumns(vec![String::from("payload")]).validated::<UserEvent>().is_ok());
+}
+
+#[test]
+fn test_validated_projection_misspelled() {
+    match columns(&["payload", "craeted", "colour", "craeted"]).validated::<UserEvent>() {
+        Err(Error::UnknownColumn(e)) => {
+            assert_eq!(e.columns(), vec!["craeted", "colour"]);
+            assert_eq!(e.suggestion("craeted"), Some("created"));
+            assert_eq!(e.suggestion("colour"), None);
+        }
+        other => panic!("Expected unknown columns, got {:?}", other.err()),
+    }
+
+    let with_options = UserEvent::select_by_primary_keys_with(columns(&["usernme"]), &SelectOptions::new());
+
+    assert_eq!(with_options.unwrap_err().to_string(), "Unknown column `usernme` (did you mean `username`?) of table test.user_event");
+}
+
+#[test]
+fn test_projection_duplicated_columns() {
+    let projection = columns(&["payload", "created", "payload"]);
+
+    assert_eq!(projection.selection(), "payload,created");
+    assert_eq!(UserEvent::select_all(projection.validated::<UserEvent>().unwrap()), "SELECT payload,created FROM test.user_event");
+
+    assert_eq!(Projection::JsonColumns(vec![String::from("payload"), String::from("payload")]).selection(), "JSON payload");
+}
@@ -0,0 +1,105 @@
+use cassandra_macro::{CassandraTable, ClusterKeyFilter, Order, Projection};
+use cassandra_macro_derive::CassandraTable;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "chat")]
+pub struct Message {
+    #[column(type = "TEXT", compound_key(position = 1))]
+    tenant: String,
+
+    #[column(type = "TEXT", compound_key(position = 2))]
+    channel: String,
+
+    #[column(type = "TIMESTAMP", cluster_key(order = "DESC", position = 1))]
+    created: i64,
+
+    #[column(type = "TIMEUUID", cluster_key(order = "ASC", position = 2))]
+    id: uuid::Uuid,
+
+    #[column(type = "TEXT")]
+    body: String,
+
+    #[column(type = "BOOLEAN")]
+    pinned: bool,
+}
+
+#[test]
+fn test_select_builder_whole_table() {
+    assert_eq!(Message::select().build().unwrap(), "SELECT * FROM chat.message");
+    assert_eq!(Message::select().build().unwrap(), Message::select_all(Projection::All));
+}
+
+#[test]
+fn test_select_builder_latest_messages() {
+    let stmt = Message::select()
+        .columns(&["created", "body"])
+        .where_key_eq()
+        .cluster_range("created", ClusterKeyFilter::Gte)
+        .order_by("created", Order::Desc)
+        .limit()
+        .build();
+
+    assert_eq!(stmt.unwrap(),
+               "SELECT created,body FROM chat.message WHERE tenant=? AND channel=? AND created>=? ORDER BY created DESC LIMIT ?");
+}
+
+#[test]
+fn test_select_builder_range_on_last_restricted_cluster_key() {
+    let stmt = Message::select()
+        .where_key_eq()
+        .cluster_range("created", ClusterKeyFilter::Eq)
+        .cluster_range("id", ClusterKeyFilter::Gt)
+        .cluster_range("id", ClusterKeyFilter::Lte)
+        .limit_to(100)
+        .build();
+
+    assert_eq!(stmt.unwrap(), "SELECT * FROM chat.message WHERE tenant=? AND channel=? AND created=? AND id>? AND id<=? LIMIT 100");
+}
+
+#[test]
+fn test_select_builder_filtering_and_partition_limit() {
+    let stmt = Message::select()
+        .projection(Projection::Count)
+        .cluster_range("created", ClusterKeyFilter::Lt)
+        .allow_filtering()
+        .build();
+
+    assert_eq!(stmt.unwrap(), "SELECT count(*) AS count FROM chat.message WHERE created<? ALLOW FILTERING");
+
+    let stmt = Message::select()
+        .columns(&["tenant", "channel", "body"])
+        .per_partition_limit_to(1)
+        .limit()
+        .build();
+
+    assert_eq!(stmt.unwrap(), "SELECT tenant,channel,body FROM chat.message PER PARTITION LIMIT 1 LIMIT ?");
+}
+
+#[test]
+fn test_select_builder_invalid() {
+    let range_before_last = Message::select()
+        .where_key_eq()
+        .cluster_range("created", ClusterKeyFilter::Gt)
+        .cluster_range("id", ClusterKeyFilter::Eq)
+        .build();
+
+    assert!(range_before_last.is_err());
+
+    let order_by_regular_column = Message::select().where_key_eq().order_by("body", Order::Asc).build();
+
+    assert!(order_by_regular_column.is_err());
+
+    let order_without_keys = Message::select().order_by("created", Order::Asc).build();
+
+    assert!(order_without_keys.is_err());
+
+    let cluster_keys_without_filtering = Message::select().cluster_range("created", ClusterKeyFilter::Gte).build();
+
+    assert!(cluster_keys_without_filtering.is_err());
+
+    let skipped_cluster_key = Message::select().where_key_eq().cluster_range("id", ClusterKeyFilter::Eq).build();
+
+    assert!(skipped_cluster_key.is_err());
+
+    assert!(Message::select().columns(&["bdy"]).build().is_err());
+}
@@ -0,0 +1,61 @@
+use cassandra_macro::CassandraTable;
+use cassandra_macro_derive::CassandraTable;
+use cdrs::query::QueryValues;
+use cdrs::types::value::Value;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct ChannelMessage {
+    #[column(type = "TEXT", primary_key)]
+    channel: String,
+
+    #[column(type = "TIMESTAMP", cluster_key(order = "DESC", position = 1))]
+    created: i64,
+
+    #[column(type = "TEXT", static)]
+    topic: String,
+
+    #[column(type = "TEXT")]
+    body: String,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Channel {
+    #[column(type = "TEXT", primary_key)]
+    channel: String,
+
+    #[column(type = "TEXT")]
+    topic: String,
+}
+
+fn message() -> ChannelMessage {
+    ChannelMessage {
+        channel: String::from("rust"),
+        created: 7,
+        topic: String::from("Ownership"),
+        body: String::from("Hello"),
+    }
+}
+
+#[test]
+fn test_select_static_columns() {
+    assert_eq!(ChannelMessage::static_columns(), &["topic"]);
+    assert_eq!(ChannelMessage::select_static_columns().unwrap(), "SELECT DISTINCT channel,topic FROM test.channel_message");
+
+    assert!(Channel::static_columns().is_empty());
+    assert!(Channel::select_static_columns().is_err());
+}
+
+#[test]
+fn test_update_static_query() {
+    let query = message().update_static_query().unwrap();
+
+    assert_eq!(query.query(), "UPDATE test.channel_message SET topic=? WHERE channel=?");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![Value::from("Ownership"), Value::from("rust")]));
+    assert_eq!(query.table(), "channel_message");
+
+    let channel = Channel { channel: String::from("rust"), topic: String::from("Ownership") };
+
+    assert!(channel.update_static_query().is_err());
+}
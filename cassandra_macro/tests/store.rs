@@ -0,0 +1,206 @@
+use cassandra_macro::{CassandraTable, Error, MAX_TTL, StoreQuery};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::query::QueryValues;
+use cdrs::types::value::Value;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct UserEvent {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TIMESTAMP", cluster_key(order = "ASC", position = 1))]
+    created: i64,
+
+    #[column(type = "TEXT")]
+    payload: String,
+}
+
+#[test]
+fn test_store_json() {
+    assert_eq!(UserEvent::store_json_cql(), "INSERT INTO test.user_event JSON ?");
+    assert_eq!(UserEvent::store_json_default_unset_cql(), "INSERT INTO test.user_event JSON ? DEFAULT UNSET");
+
+    let query = StoreQuery::from_json::<UserEvent>(String::from(r#"{"username": "rust", "created": 0}"#));
+
+    assert_eq!(query.query(), UserEvent::store_json_cql());
+    assert_eq!(query.values().len(), 1);
+
+    assert_eq!(UserEvent::store_json_query(String::from("{}")).query(), UserEvent::store_json_cql());
+}
+
+#[test]
+fn test_store_query_with_ttl() {
+    let event = UserEvent {
+        username: String::from("rust"),
+        created: 1,
+        payload: String::from("payload"),
+    };
+
+    let query = event.store_query_with_ttl(3600).unwrap();
+
+    assert_eq!(query.query(), &format!("{} USING TTL ?", event.store_query().query()));
+    assert_eq!(query.values().len(), event.store_query().values().len() + 1);
+
+    match query.values() {
+        QueryValues::SimpleValues(values) => assert_eq!(values.last(), Some(&Value::from(3600i32))),
+        _ => panic!("Expected values without names"),
+    }
+
+    assert_eq!(event.store_query_with_ttl(0).unwrap().values().len(), 4);
+    assert_eq!(event.store_query_with_ttl(MAX_TTL).unwrap().values().len(), 4);
+
+    match event.store_query_with_ttl(MAX_TTL + 1) {
+        Err(Error::InvalidTtl(ttl)) => assert_eq!(ttl, MAX_TTL + 1),
+        other => panic!("Expected invalid TTL, got {:?}", other),
+    }
+
+    assert!(event.store_query_with_ttl(u32::MAX).is_err());
+    assert!(event.store_query_using(Some(u32::MAX), Some(1)).is_err());
+}
+
+#[test]
+fn test_store_query_using() {
+    let event = UserEvent {
+        username: String::from("rust"),
+        created: 1,
+        payload: String::from("payload"),
+    };
+
+    let insert = event.store_query().query().to_owned();
+
+    let with_timestamp = event.store_query_with_timestamp(1_000_000).unwrap();
+
+    assert_eq!(with_timestamp.query(), &format!("{} USING TIMESTAMP ?", insert));
+
+    let with_both = event.store_query_using(Some(60), Some(1_000_000)).unwrap();
+
+    assert_eq!(with_both.query(), &format!("{} USING TTL ? AND TIMESTAMP ?", insert));
+
+    match with_both.values() {
+        QueryValues::SimpleValues(values) => {
+            assert_eq!(values.len(), 5);
+            assert_eq!(values[3], Value::from(60i32));
+            assert_eq!(values[4], Value::from(1_000_000i64));
+        }
+        _ => panic!("Expected values without names"),
+    }
+
+    assert_eq!(event.store_query_using(Some(60), None).unwrap().query(), &format!("{} USING TTL ?", insert));
+
+    let without_both = event.store_query_using(None, None).unwrap();
+
+    assert_eq!(without_both.query(), &insert);
+    assert_eq!(without_both.values().len(), 3);
+}
+
+#[test]
+fn test_store_query_if_not_exists() {
+    let event = UserEvent {
+        username: String::from("rust"),
+        created: 1,
+        payload: String::from("payload"),
+    };
+
+    let query = event.store_query_if_not_exists().unwrap();
+
+    assert!(query.query().ends_with(") IF NOT EXISTS"));
+    assert_eq!(query.values(), event.store_query().values());
+    assert!(query.is_lwt());
+    assert!(!event.store_query().is_lwt());
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct WideRow {
+    #[column(type = "INT", primary_key)]
+    id: i32,
+
+    #[column(type = "INT")]
+    c01: i32,
+
+    #[column(type = "INT")]
+    c02: i32,
+
+    #[column(type = "INT")]
+    c03: i32,
+
+    #[column(type = "INT")]
+    c04: i32,
+
+    #[column(type = "INT")]
+    c05: i32,
+
+    #[column(type = "INT")]
+    c06: i32,
+
+    #[column(type = "INT")]
+    c07: i32,
+
+    #[column(type = "INT")]
+    c08: i32,
+
+    #[column(type = "INT")]
+    c09: i32,
+
+    #[column(type = "INT")]
+    c10: i32,
+
+    #[column(type = "INT")]
+    c11: i32,
+}
+
+#[test]
+fn test_store_query_column_order() {
+    let row = WideRow { id: 0, c01: 1, c02: 2, c03: 3, c04: 4, c05: 5, c06: 6, c07: 7, c08: 8, c09: 9, c10: 10, c11: 11 };
+
+    let query = row.store_query();
+
+    assert_eq!(query.query(),
+               "INSERT INTO test.wide_row (id,c01,c02,c03,c04,c05,c06,c07,c08,c09,c10,c11) VALUES (?,?,?,?,?,?,?,?,?,?,?,?)");
+    assert_eq!(query.values(), &QueryValues::SimpleValues((0..12).map(Value::from).collect()));
+
+    assert_eq!(WideRow::columns(), &["id", "c01", "c02", "c03", "c04", "c05", "c06", "c07", "c08", "c09", "c10", "c11"]);
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Attachment {
+    #[column(type = "TEXT", primary_key)]
+    name: String,
+
+    #[column(type = "TEXT", moved)]
+    content: String,
+}
+
+#[test]
+fn test_into_store_query_moves_marked_columns() {
+    let attachment = Attachment { name: String::from("notes.txt"), content: "x".repeat(1024 * 1024) };
+
+    let content = attachment.content.as_ptr();
+
+    let borrowed = attachment.store_query();
+    let moved = attachment.into_store_query();
+
+    assert_eq!(moved.query(), borrowed.query());
+    assert_eq!(moved.values(), borrowed.values());
+
+    match (moved.values(), borrowed.values()) {
+        (QueryValues::SimpleValues(moved), QueryValues::SimpleValues(borrowed)) => {
+            assert_eq!(moved[1].body.as_ptr(), content);
+            assert_ne!(borrowed[1].body.as_ptr(), content);
+        }
+        _ => panic!("Expected values without names"),
+    }
+}
+
+#[test]
+fn test_into_update_query() {
+    let attachment = Attachment { name: String::from("notes.txt"), content: String::from("hello") };
+
+    let borrowed = attachment.update_query().unwrap();
+    let moved = attachment.into_update_query().unwrap();
+
+    assert_eq!(moved.query(), borrowed.query());
+    assert_eq!(moved.values(), &QueryValues::SimpleValues(vec![Value::from("hello"), Value::from("notes.txt")]));
+}
@@ -0,0 +1,168 @@
+use cassandra_macro::{CassandraTable, CqlTable, CqlTableBuilder, Error, Order, Projection};
+use cassandra_macro_derive::CassandraTable;
+use uuid::Uuid;
+
+/// Events of the devices
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "telemetry", options = "default_time_to_live = 86400", doc_comment)]
+pub struct DeviceEvent {
+    #[column(type = "TEXT", compound_key(position = 1))]
+    tenant: String,
+
+    #[column(type = "TEXT", compound_key(position = 2))]
+    device: String,
+
+    #[column(type = "TIMEUUID", cluster_key(order = "DESC", position = 1), generated = "now()")]
+    id: Uuid,
+
+    #[column(type = "INT", cluster_key(order = "ASC", position = 2))]
+    sequence: i32,
+
+    #[column(type = "TEXT", static)]
+    firmware: String,
+
+    #[column(type = "TEXT", queryable(allow_filtering))]
+    kind: String,
+
+    #[column(type = "frozen<list<text>>")]
+    payload: Vec<String>,
+}
+
+fn device_event_table() -> CqlTable {
+    CqlTableBuilder::new("device_event")
+        .keyspace("telemetry")
+        .column("tenant", "TEXT")
+        .column("device", "TEXT")
+        .column("id", "TIMEUUID")
+        .column("sequence", "INT")
+        .static_column("firmware", "TEXT")
+        .column("kind", "TEXT")
+        .column("payload", "frozen<list<text>>")
+        .partition_key("tenant")
+        .partition_key("device")
+        .cluster_key("sequence", Order::Asc, 2)
+        .cluster_key("id", Order::Desc, 1)
+        .generated("id", "now()")
+        .options("default_time_to_live = 86400")
+        .comment("Events of the devices")
+        .build()
+        .unwrap()
+}
+
+fn event() -> DeviceEvent {
+    DeviceEvent {
+        tenant: String::from("acme"),
+        device: String::from("sensor-1"),
+        id: Uuid::nil(),
+        sequence: 1,
+        firmware: String::from("1.0.2"),
+        kind: String::from("boot"),
+        payload: vec![],
+    }
+}
+
+#[test]
+fn test_builder_metadata_as_derive() {
+    let table = device_event_table();
+
+    assert_eq!(table.key_space(), DeviceEvent::key_space());
+    assert_eq!(table.table_name(), DeviceEvent::table_name());
+    assert_eq!(table.columns(), DeviceEvent::columns());
+    assert_eq!(table.primary_key_columns(), DeviceEvent::primary_key_columns());
+    assert_eq!(table.cluster_key_columns(), DeviceEvent::cluster_key_columns());
+    assert_eq!(table.static_columns(), DeviceEvent::static_columns());
+    assert_eq!(table.column_type("payload"), DeviceEvent::column_type("payload"));
+}
+
+#[test]
+fn test_builder_statements_as_derive() {
+    let table = device_event_table();
+    let event = event();
+
+    assert_eq!(table.create_table_cql(), DeviceEvent::create_table_cql());
+    assert_eq!(table.drop_table_cql(), DeviceEvent::drop_table_cql());
+    assert_eq!(table.insert_cql(), event.store_query().query());
+    assert_eq!(table.insert_json_cql(), DeviceEvent::store_json_cql());
+    assert_eq!(table.insert_json_default_unset_cql(), DeviceEvent::store_json_default_unset_cql());
+    assert_eq!(table.update_cql().unwrap(), event.update_query().unwrap().query());
+    assert_eq!(table.delete_cql(), event.delete_query().query());
+    assert_eq!(table.delete_partition_cql(), event.delete_partition_query().query());
+    assert_eq!(table.exists_cql(), event.exists_query().query());
+    assert_eq!(table.count_partition_cql(), event.count_partition_query().query());
+    assert_eq!(table.delete_by_primary_keys_cql(), DeviceEvent::delete_by_primary_keys());
+    assert_eq!(table.delete_by_primary_and_cluster_keys_cql(), DeviceEvent::delete_by_primary_and_cluster_keys());
+}
+
+#[test]
+fn test_builder_selects_as_derive() {
+    let table = device_event_table();
+
+    assert_eq!(table.select_cql("*"), DeviceEvent::select_all(Projection::All));
+    assert_eq!(table.select_by_primary_keys_cql("*"), DeviceEvent::select_by_primary_keys(Projection::All));
+    assert_eq!(table.select_by_primary_and_cluster_keys_cql(&Projection::Count.selection()),
+               DeviceEvent::select_by_primary_and_cluster_keys(Projection::Count));
+    assert_eq!(table.select_by_column_cql("kind", "*", true).unwrap(), DeviceEvent::select_by_kind(Projection::All));
+    assert_eq!(table.select_by_column_cql("kind", "tenant,device,id,sequence", true).unwrap(), DeviceEvent::select_keys_by_kind());
+    assert_eq!(table.time_range_cql("id", "*").unwrap(), DeviceEvent::select_by_id_time_range(Projection::All));
+}
+
+#[test]
+fn test_builder_updates_as_derive() {
+    let table = device_event_table();
+
+    assert_eq!(table.update_by_primary_keys_cql("firmware=?"),
+               DeviceEvent::update_by_primary_keys(vec![String::from("firmware")]).unwrap());
+    assert_eq!(table.update_by_primary_and_cluster_keys_cql("kind=?,payload=?"),
+               DeviceEvent::update_by_primary_and_cluster_keys(vec![String::from("kind"), String::from("payload")]).unwrap());
+}
+
+#[test]
+fn test_builder_invalid_table() {
+    let result: Result<CqlTable, Error> = CqlTableBuilder::new("events")
+        .keyspace("ks")
+        .column("id", "uuid")
+        .column("duration", "duration")
+        .partition_key("id")
+        .cluster_key("duration", Order::Asc, 1)
+        .build()
+        .map_err(Error::from);
+
+    assert_eq!(result.unwrap_err().to_string(), "column `duration` is a key, a `DURATION` can not be a key");
+
+    assert!(device_event_table().select_by_column_cql("device", "*", false).is_err());
+    assert!(device_event_table().time_range_cql("sequence", "*").is_err());
+}
+
+/// Columns of `INSERT INTO t (a,b) VALUES (?,now())` with a bound value
+fn bound_insert_columns(statement: &str) -> Vec<String> {
+    let (columns, marks) = statement.split_once(" VALUES ").unwrap();
+
+    let columns = columns.split_once('(').unwrap().1.trim_end_matches(')');
+    let marks = marks.strip_prefix('(').unwrap().strip_suffix(')').unwrap();
+
+    columns.split(',').zip(marks.split(','))
+        .filter(|(_, mark)| *mark == "?")
+        .map(|(column, _)| column.to_string())
+        .collect()
+}
+
+/// Columns of `UPDATE t SET a=?,b=? WHERE ...`
+fn set_columns(statement: &str) -> Vec<String> {
+    let set = statement.split(" SET ").nth(1).unwrap().split(" WHERE ").next().unwrap();
+
+    set.split(',').map(|c| c.trim().trim_end_matches("=?").to_string()).collect()
+}
+
+#[test]
+fn test_statement_columns() {
+    let event = event();
+
+    assert_eq!(bound_insert_columns(event.store_query().query()), DeviceEvent::insert_columns());
+    assert_eq!(DeviceEvent::insert_columns(), ["tenant", "device", "sequence", "firmware", "kind", "payload"]);
+    assert_eq!(event.store_query().values().len(), DeviceEvent::insert_columns().len());
+    assert_eq!(device_event_table().stored_columns(), DeviceEvent::insert_columns());
+
+    assert_eq!(set_columns(event.update_query().unwrap().query()), DeviceEvent::updatable_columns());
+    assert_eq!(DeviceEvent::updatable_columns(), ["firmware", "kind", "payload"]);
+    assert_eq!(device_event_table().updatable_columns(), DeviceEvent::updatable_columns());
+}
@@ -0,0 +1,47 @@
+use cassandra_macro::CassandraTable;
+use cassandra_macro_derive::CassandraTable;
+
+/// Orders of the customer's cart,
+/// one row per item
+///
+/// Only the first paragraph is the comment.
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "shop", doc_comment)]
+pub struct CartItem {
+    #[column(type = "TEXT", primary_key)]
+    cart: String,
+
+    #[column(type = "TEXT", cluster_key(order = "ASC", position = 1))]
+    sku: String,
+}
+
+/// Not used, the comment is explicit
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "shop", doc_comment, comment = "Customer's wishes", options = "gc_grace_seconds = 3600")]
+pub struct Wish {
+    #[column(type = "TEXT", primary_key)]
+    customer: String,
+}
+
+/// Not used without `doc_comment`
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "shop")]
+pub struct Coupon {
+    #[column(type = "TEXT", primary_key)]
+    code: String,
+}
+
+#[test]
+fn test_doc_comment_as_table_comment() {
+    assert_eq!(CartItem::create_table_cql(),
+               "CREATE TABLE IF NOT EXISTS shop.cart_item  (cart TEXT,sku TEXT, PRIMARY KEY ((cart), sku) ) \
+                WITH CLUSTERING ORDER BY (sku ASC) AND comment='Orders of the customer''s cart, one row per item'");
+}
+
+#[test]
+fn test_explicit_table_comment() {
+    assert_eq!(Wish::create_table_cql(),
+               "CREATE TABLE IF NOT EXISTS shop.wish  (customer TEXT, PRIMARY KEY (customer) ) \
+                WITH gc_grace_seconds = 3600 AND comment='Customer''s wishes'");
+    assert_eq!(Coupon::create_table_cql(), "CREATE TABLE IF NOT EXISTS shop.coupon  (code TEXT, PRIMARY KEY (code) ) ");
+}
@@ -0,0 +1,104 @@
+use cassandra_macro::{CassandraTable, Projection};
+use cassandra_macro_derive::CassandraTable;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct UserLoginEvent {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", case = "snake_case")]
+pub struct DeviceLoginEvent {
+    #[column(type = "TEXT", primary_key)]
+    device: String,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", case = "lowercase")]
+pub struct AccountLoginEvent {
+    #[column(type = "TEXT", primary_key)]
+    account: String,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", case = "preserve")]
+pub struct TenantLoginEvent {
+    #[column(type = "TEXT", primary_key)]
+    tenant: String,
+}
+
+/// Prefix and suffix of the environment of the build, of the tables
+/// that do not give theirs
+const PREFIX: Option<&str> = option_env!("CASSANDRA_TABLE_PREFIX");
+const SUFFIX: Option<&str> = option_env!("CASSANDRA_TABLE_SUFFIX");
+
+fn affixed(name: &str) -> String {
+    format!("{}{}{}", PREFIX.unwrap_or_default(), name, SUFFIX.unwrap_or_default())
+}
+
+#[test]
+fn test_default_snake_case() {
+    assert_eq!(UserLoginEvent::table_name(), affixed("user_login_event"));
+    assert_eq!(DeviceLoginEvent::table_name(), affixed("device_login_event"));
+    assert_eq!(DeviceLoginEvent::drop_table_cql(), format!("DROP TABLE IF EXISTS test.{}", affixed("device_login_event")));
+}
+
+#[test]
+fn test_lowercase() {
+    assert_eq!(AccountLoginEvent::table_name(), affixed("accountloginevent"));
+    assert_eq!(AccountLoginEvent::select_all(Projection::All), format!("SELECT * FROM test.{}", affixed("accountloginevent")));
+}
+
+#[test]
+fn test_preserve() {
+    let table = format!("\"{}\"", affixed("TenantLoginEvent"));
+
+    assert_eq!(TenantLoginEvent::table_name(), table);
+    assert_eq!(TenantLoginEvent::create_table_cql(),
+               format!("CREATE TABLE IF NOT EXISTS test.{}  (tenant TEXT, PRIMARY KEY (tenant) ) ", table));
+
+    let event = TenantLoginEvent { tenant: String::from("acme") };
+
+    assert_eq!(event.delete_query().query(), format!("DELETE FROM test.{} WHERE tenant=?", table));
+    assert_eq!(event.delete_query_in("branch").unwrap().query(), format!("DELETE FROM branch.{} WHERE tenant=?", table));
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", prefix = "billing_")]
+pub struct Invoice {
+    #[column(type = "TEXT", primary_key)]
+    number: String,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", suffix = "_v2")]
+pub struct Receipt {
+    #[column(type = "TEXT", primary_key)]
+    number: String,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", prefix = "billing_", suffix = "_v2", case = "lowercase")]
+pub struct CreditNote {
+    #[column(type = "TEXT", primary_key)]
+    number: String,
+}
+
+#[test]
+fn test_prefix_and_suffix() {
+    let (prefix, suffix) = (PREFIX.unwrap_or_default(), SUFFIX.unwrap_or_default());
+
+    assert_eq!(Invoice::table_name(), format!("billing_invoice{}", suffix));
+    assert_eq!(Invoice::drop_table_cql(), format!("DROP TABLE IF EXISTS test.billing_invoice{}", suffix));
+
+    assert_eq!(Receipt::table_name(), format!("{}receipt_v2", prefix));
+    assert_eq!(Receipt::select_all(Projection::All), format!("SELECT * FROM test.{}receipt_v2", prefix));
+
+    assert_eq!(CreditNote::table_name(), "billing_creditnote_v2");
+
+    let note = CreditNote { number: String::from("1") };
+
+    assert_eq!(note.store_query().query(), "INSERT INTO test.billing_creditnote_v2 (number) VALUES (?)");
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "testing")]
+#[macro_use]
+extern crate cdrs;
+
+use cassandra_macro::{CassandraRepository, CassandraTable, Error, Value};
+use cassandra_macro::testing::MockSession;
+use cassandra_macro_derive::CassandraTable;
+use cdrs::frame::frame_result::ColType;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", derive_try_from_row)]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "BIGINT", cluster_key(order = "ASC", position = 1))]
+    created: i64,
+
+    #[column(type = "TEXT")]
+    nickname: String,
+}
+
+fn user() -> User {
+    User { username: String::from("rust"), created: 7, nickname: String::from("ferris") }
+}
+
+/// Code under test, renaming the user unless it was already renamed
+fn rename(repository: &CassandraRepository<MockSession>, nickname: &str) -> Result<bool, Error> {
+    let found: Option<User> = repository.find_by_key(query_values!("rust", 7i64))?;
+
+    match found {
+        Some(ref user) if user.nickname == nickname => Ok(false),
+        Some(user) => {
+            repository.update(&User { nickname: nickname.to_string(), ..user })?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+fn columns() -> Vec<(&'static str, ColType)> {
+    vec![("username", ColType::Varchar), ("created", ColType::Bigint), ("nickname", ColType::Varchar)]
+}
+
+#[test]
+fn test_mock_session_repository() {
+    let row = vec![Value::from("rust"), Value::from(7i64), Value::from("ferris")];
+    let repository = CassandraRepository::new(MockSession::new().with_rows("SELECT", &columns(), vec![row]));
+
+    assert!(rename(&repository, "crab").unwrap());
+
+    let renamed = User { nickname: String::from("crab"), ..user() };
+    let executed = repository.session().executed();
+
+    assert_eq!(executed.len(), 2);
+    assert_eq!(executed[0].1, query_values!("rust", 7i64));
+    assert_eq!(executed[1].0, renamed.update_query().unwrap().query());
+    assert_eq!(&executed[1].1, renamed.update_query().unwrap().values());
+}
+
+#[test]
+fn test_mock_session_no_rows() {
+    let repository = CassandraRepository::new(MockSession::new().with_rows("SELECT", &columns(), vec![]));
+
+    assert!(!rename(&repository, "crab").unwrap());
+    assert_eq!(repository.session().executed_statements().len(), 1);
+}
+
+#[test]
+fn test_mock_session_failure() {
+    let row = vec![Value::from("rust"), Value::from(7i64), Value::from("ferris")];
+    let session = MockSession::new()
+        .with_rows("SELECT", &columns(), vec![row])
+        .with_error("UPDATE", "Operation timed out");
+
+    let repository = CassandraRepository::new(session);
+
+    match rename(&repository, "crab") {
+        Err(Error::Driver(e)) => assert!(e.to_string().contains("Operation timed out")),
+        other => panic!("Expected driver error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_mock_session_conditional() {
+    let repository = CassandraRepository::new(MockSession::new().with_applied("IF NOT EXISTS", false));
+
+    assert!(!repository.save_if_not_exists(&user()).unwrap().applied());
+    assert_eq!(repository.session().executed_statements(), vec![user().store_query_if_not_exists().unwrap().query()]);
+}
@@ -0,0 +1,73 @@
+use cassandra_macro::{CassandraTable, Projection};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::query::QueryValues;
+use cdrs::types::value::Value;
+use uuid::Uuid;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "metrics")]
+pub struct Reading {
+    #[column(type = "TEXT", primary_key)]
+    sensor: String,
+
+    #[column(type = "TIMEUUID", cluster_key(order = "DESC", position = 1), generated = "now()")]
+    id: Uuid,
+
+    #[column(type = "DOUBLE")]
+    value: f64,
+
+    #[column(type = "TIMEUUID", generated = "now()")]
+    received: Uuid,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "metrics")]
+pub struct Sample {
+    #[column(type = "TEXT", primary_key)]
+    sensor: String,
+
+    #[column(type = "TIMESTAMP", cluster_key(order = "DESC", position = 1))]
+    created: i64,
+
+    #[column(type = "TIMEUUID")]
+    id: Uuid,
+}
+
+fn reading() -> Reading {
+    Reading { sensor: String::from("north"), id: Uuid::nil(), value: 21.5, received: Uuid::nil() }
+}
+
+#[test]
+fn test_time_range_select() {
+    assert_eq!(Reading::select_by_id_time_range(Projection::All),
+               "SELECT * FROM metrics.reading WHERE sensor=? AND id > maxTimeuuid(?) AND id < minTimeuuid(?)");
+    assert_eq!(Reading::select_by_id_time_range(Projection::Columns(vec![String::from("value")])),
+               "SELECT value FROM metrics.reading WHERE sensor=? AND id > maxTimeuuid(?) AND id < minTimeuuid(?)");
+}
+
+#[test]
+fn test_generated_columns_on_insert() {
+    let query = reading().store_query();
+
+    assert_eq!(query.query(), "INSERT INTO metrics.reading (sensor,id,value,received) VALUES (?,now(),?,now())");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![Value::from("north"), Value::from(21.5f64)]));
+
+    assert_eq!(reading().into_store_query().values(), query.values());
+    assert_eq!(reading().try_store_query().unwrap().values(), query.values());
+}
+
+#[test]
+fn test_generated_columns_on_update() {
+    let query = reading().update_query().unwrap();
+
+    assert_eq!(query.query(), "UPDATE metrics.reading SET value=? WHERE sensor=? AND id=?");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![Value::from(21.5f64), Value::from("north"), Value::from(Uuid::nil())]));
+}
+
+#[test]
+fn test_regular_time_uuid_column() {
+    let sample = Sample { sensor: String::from("north"), created: 7, id: Uuid::nil() };
+
+    assert_eq!(sample.store_query().query(), "INSERT INTO metrics.sample (sensor,created,id) VALUES (?,?,?)");
+    assert_eq!(Sample::column_type("id"), Some("TIMEUUID"));
+}
@@ -0,0 +1,74 @@
+use cassandra_macro::Tracked;
+use cassandra_macro_derive::CassandraTable;
+use cdrs::query::QueryValues;
+use cdrs::types::value::Value;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT")]
+    first_name: String,
+
+    #[column(type = "TEXT")]
+    last_name: String,
+
+    #[column(type = "TIMESTAMP")]
+    updated: i64,
+}
+
+fn tracked_user() -> Tracked<User> {
+    Tracked::new(User {
+        username: String::from("rust"),
+        first_name: String::from("ferris"),
+        last_name: String::from("crab"),
+        updated: 1,
+    })
+}
+
+#[test]
+fn test_tracked_without_changes() {
+    let user = tracked_user();
+
+    assert!(!user.is_dirty());
+    assert!(user.dirty_update_query().is_err());
+}
+
+#[test]
+fn test_tracked_single_change() {
+    let mut user = tracked_user();
+
+    user.update("first_name", |u| u.first_name = String::from("Ferris")).unwrap();
+
+    let query = user.take_dirty_update_query().unwrap();
+
+    assert_eq!(query.query(), "UPDATE test.user SET first_name=? WHERE username=?");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![Value::from("Ferris"), Value::from("rust")]));
+
+    assert!(!user.is_dirty());
+}
+
+#[test]
+fn test_tracked_multiple_changes() {
+    let mut user = tracked_user();
+
+    user.update("updated", |u| u.updated = 2).unwrap();
+    user.update("first_name", |u| u.first_name = String::from("Ferris")).unwrap();
+    user.update("updated", |u| u.updated = 3).unwrap();
+
+    assert_eq!(user.dirty_columns(), &["updated", "first_name"]);
+
+    let query = user.dirty_update_query().unwrap();
+
+    assert_eq!(query.query(), "UPDATE test.user SET updated=?,first_name=? WHERE username=?");
+    assert_eq!(query.values().len(), 3);
+
+    assert!(user.update("username", |u| u.username = String::from("other")).is_err());
+    assert_eq!(user.username, "rust");
+
+    user.reset();
+
+    assert!(user.dirty_update_query().is_err());
+}
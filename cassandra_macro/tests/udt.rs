@@ -0,0 +1,130 @@
+use cassandra_macro::CassandraUdt;
+use cassandra_macro_derive::CassandraUdt;
+use cdrs::frame::frame_result::{ColType, ColTypeOption, ColTypeOptionValue, CUdt};
+use cdrs::frame::TryFromUDT;
+use cdrs::types::{CBytes, CString};
+use cdrs::types::udt::UDT;
+
+#[derive(Debug, PartialEq, CassandraUdt)]
+#[udt(keyspace = "test", derive_try_from_udt)]
+pub struct Address {
+    #[field(type = "text")]
+    street: String,
+
+    #[field(type = "int")]
+    number: i32,
+}
+
+#[derive(Debug, CassandraUdt)]
+#[udt(keyspace = "test", derive_try_from_udt)]
+pub struct ContactInfo {
+    #[field(type = "text")]
+    email: String,
+
+    #[field(type = "text")]
+    phone: Option<String>,
+
+    #[field(type = "frozen<address>", udt)]
+    home: Address,
+
+    #[field(type = "frozen<address>", udt)]
+    work: Option<Address>,
+
+    verified: bool,
+}
+
+fn description(name: &str, id: ColType, value: Option<ColTypeOptionValue>) -> (CString, ColTypeOption) {
+    (CString::new(name.to_string()), ColTypeOption { id, value })
+}
+
+fn address_metadata() -> CUdt {
+    CUdt {
+        ks: CString::new(String::from("test")),
+        udt_name: CString::new(String::from("address")),
+        descriptions: vec![
+            description("street", ColType::Varchar, None),
+            description("number", ColType::Int, None),
+        ],
+    }
+}
+
+/// Serialized user defined type, each field is prefixed by its length
+fn udt_bytes(fields: &[&[u8]]) -> Vec<u8> {
+    fields.iter().flat_map(|f| [&(f.len() as i32).to_be_bytes()[..], f].concat()).collect()
+}
+
+fn synthetic_contact_info() -> UDT {
+    let metadata = CUdt {
+        ks: CString::new(String::from("test")),
+        udt_name: CString::new(String::from("contact_info")),
+        descriptions: vec![
+            description("email", ColType::Varchar, None),
+            description("phone", ColType::Varchar, None),
+            description("home", ColType::Udt, Some(ColTypeOptionValue::UdtType(address_metadata()))),
+            description("work", ColType::Udt, Some(ColTypeOptionValue::UdtType(address_metadata()))),
+        ],
+    };
+
+    let data = vec![
+        CBytes::new(b"rust@example.com".to_vec()),
+        CBytes::new_empty(),
+        CBytes::new(udt_bytes(&[b"Main Street", &7i32.to_be_bytes()])),
+        CBytes::new_empty(),
+    ];
+
+    UDT::new(data, &metadata)
+}
+
+#[test]
+fn test_create_type_cql() {
+    assert_eq!(Address::create_type_cql(), "CREATE TYPE IF NOT EXISTS test.address (street TEXT,number INT)");
+    assert_eq!(ContactInfo::create_type_cql(),
+               "CREATE TYPE IF NOT EXISTS test.contact_info (email TEXT,phone TEXT,home FROZEN<ADDRESS>,work FROZEN<ADDRESS>)");
+
+    assert_eq!(ContactInfo::key_space(), "test");
+    assert_eq!(ContactInfo::type_name(), "contact_info");
+    assert_eq!(ContactInfo::fields(), &["email", "phone", "home", "work"]);
+}
+
+#[test]
+fn test_drop_type_cql() {
+    assert_eq!(Address::drop_type_cql(), "DROP TYPE IF EXISTS test.address");
+    assert_eq!(ContactInfo::drop_type_cql(), "DROP TYPE IF EXISTS test.contact_info");
+}
+
+#[test]
+fn test_alter_add_field_cql() {
+    assert_eq!(Address::alter_add_field_cql("country", "text").unwrap(), "ALTER TYPE test.address ADD country text");
+    assert_eq!(ContactInfo::alter_add_field_cql("others", "frozen<list<frozen<address>>>").unwrap(),
+               "ALTER TYPE test.contact_info ADD others frozen<list<frozen<address>>>");
+    assert_eq!(ContactInfo::alter_add_field_cql("tags", "map<text, test.address>").unwrap(),
+               "ALTER TYPE test.contact_info ADD tags map<text, test.address>");
+
+    assert_eq!(Address::alter_add_field_cql("Street", "text").unwrap_err().to_string(), "type address already has the field `street`");
+    assert_eq!(Address::alter_add_field_cql("zip code", "text").unwrap_err().to_string(), "Invalid identifier `zip code`");
+    assert_eq!(Address::alter_add_field_cql("zip", "list<text").unwrap_err().to_string(), "Invalid CQL type `list<text`");
+    assert_eq!(Address::alter_add_field_cql("zip", "set<big int>").unwrap_err().to_string(), "Invalid CQL type `set<big int>`");
+    assert_eq!(Address::alter_add_field_cql("visits", "counter").unwrap_err().to_string(),
+               "a user defined type can not have a `COUNTER` field, declared as `counter`");
+}
+
+#[test]
+fn test_alter_rename_field_cql() {
+    assert_eq!(Address::alter_rename_field_cql("number", "house_number").unwrap(),
+               "ALTER TYPE test.address RENAME number TO house_number");
+
+    assert_eq!(Address::alter_rename_field_cql("zip", "zip_code").unwrap_err().to_string(), "Unknown column `zip`");
+    assert_eq!(Address::alter_rename_field_cql("number", "street").unwrap_err().to_string(), "type address already has the field `street`");
+    assert_eq!(Address::alter_rename_field_cql("number", "house-number").unwrap_err().to_string(), "Invalid identifier `house-number`");
+}
+
+#[test]
+fn test_generated_try_from_udt() {
+    let contact_info = ContactInfo::try_from_udt(synthetic_contact_info()).unwrap();
+
+    assert_eq!(contact_info.email, "rust@example.com");
+    assert_eq!(contact_info.phone, None);
+    assert_eq!(contact_info.home, Address { street: String::from("Main Street"), number: 7 });
+    assert_eq!(contact_info.work, None);
+    assert!(!contact_info.verified);
+}
@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+
+use cassandra_macro::{CassandraTable, Error, MAX_TTL, UpdateQuery};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::query::QueryValues;
+use cdrs::types::value::Value;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct UserEvent {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TIMESTAMP", cluster_key(order = "ASC", position = 1))]
+    created: i64,
+
+    #[column(type = "TEXT")]
+    payload: String,
+
+    #[column(type = "TIMESTAMP")]
+    updated: i64,
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct UserFollower {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT", cluster_key(order = "ASC", position = 1))]
+    follower: String,
+}
+
+fn user_event() -> UserEvent {
+    UserEvent {
+        username: String::from("rust"),
+        created: 1,
+        payload: String::from("payload"),
+        updated: 2,
+    }
+}
+
+#[test]
+fn test_update_query_if_exists() {
+    let event = user_event();
+
+    let update = event.update_query().unwrap();
+    let conditional = event.update_query_if_exists().unwrap();
+
+    assert_eq!(conditional.query(), &format!("{} IF EXISTS", update.query()));
+    assert_eq!(conditional.values(), update.values());
+    assert!(conditional.is_lwt());
+
+    let follower = UserFollower {
+        username: String::from("rust"),
+        follower: String::from("ferris"),
+    };
+
+    assert!(follower.update_query().is_err());
+    assert!(follower.update_query_if_exists().is_err());
+}
+
+#[test]
+fn test_update_query_if() {
+    let event = user_event();
+
+    let update = event.update_query().unwrap();
+
+    let single = event.update_query_if(&[("updated", Value::from(2i64))]).unwrap();
+
+    assert_eq!(single.query(), &format!("{} IF updated=?", update.query()));
+    assert!(single.is_lwt());
+
+    let multiple = event.update_query_if(&[("updated", Value::from(2i64)), ("payload", Value::from("old"))]).unwrap();
+
+    assert_eq!(multiple.query(), &format!("{} IF updated=? AND payload=?", update.query()));
+
+    match (multiple.values(), update.values()) {
+        (QueryValues::SimpleValues(values), QueryValues::SimpleValues(update_values)) => {
+            assert_eq!(&values[..update_values.len()], &update_values[..]);
+            assert_eq!(&values[update_values.len()..], &[Value::from(2i64), Value::from("old")]);
+        }
+        _ => panic!("Expected values without names"),
+    }
+
+    assert!(event.update_query_if(&[("created", Value::from(1i64))]).is_err());
+    assert!(event.update_query_if(&[("unknown", Value::from(1i64))]).is_err());
+    assert!(event.update_query_if(&[]).is_err());
+}
+
+#[test]
+fn test_update_columns_query() {
+    let event = user_event();
+
+    let query = event.update_columns_query(&["updated", "payload"]).unwrap();
+
+    assert_eq!(query.query(), "UPDATE test.user_event SET updated=?,payload=? WHERE username=? AND created=?");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![
+        Value::from(2i64), Value::from("payload"), Value::from("rust"), Value::from(1i64)
+    ]));
+
+    assert!(event.update_columns_query(&["username"]).is_err());
+    assert!(event.update_columns_query(&["unknown"]).is_err());
+}
+
+#[test]
+fn test_update_query_with_ttl() {
+    let event = user_event();
+
+    let query = event.update_query_with_ttl(3600).unwrap();
+
+    assert_eq!(query.query(), "UPDATE test.user_event USING TTL ? SET payload=?,updated=? WHERE username=? AND created=?");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![
+        Value::from(3600i32), Value::from("payload"), Value::from(2i64), Value::from("rust"), Value::from(1i64)
+    ]));
+
+    let columns = event.update_columns_query_with_ttl(&["updated"], 60).unwrap();
+
+    assert_eq!(columns.query(), "UPDATE test.user_event USING TTL ? SET updated=? WHERE username=? AND created=?");
+    assert_eq!(columns.values(), &QueryValues::SimpleValues(vec![
+        Value::from(60i32), Value::from(2i64), Value::from("rust"), Value::from(1i64)
+    ]));
+
+    let follower = UserFollower {
+        username: String::from("rust"),
+        follower: String::from("ferris"),
+    };
+
+    assert!(follower.update_query_with_ttl(60).is_err());
+    assert!(event.update_query_with_ttl(MAX_TTL + 1).is_err());
+    assert!(event.update_columns_query_with_ttl(&["updated"], u32::MAX).is_err());
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct PageViews {
+    #[column(type = "TEXT", primary_key)]
+    page: String,
+
+    #[column(type = "DATE", cluster_key(order = "DESC", position = 1))]
+    day: i32,
+
+    #[column(type = "COUNTER")]
+    hits: i64,
+}
+
+#[test]
+fn test_counter_delta_query() {
+    assert_eq!(PageViews::counter_delta_cql("hits").unwrap(),
+               "UPDATE test.page_views SET hits=hits+? WHERE page=? AND day=?");
+
+    let views = PageViews { page: String::from("home"), day: 18000, hits: 0 };
+
+    let query = views.counter_delta_query("hits").unwrap().prepend_values(vec![Value::from(-1i64)]).unwrap();
+
+    assert_eq!(query.query(), "UPDATE test.page_views SET hits=hits+? WHERE page=? AND day=?");
+    assert_eq!(query.values(), &QueryValues::SimpleValues(vec![
+        Value::from(-1i64), Value::from("home"), Value::from(18000i32)
+    ]));
+}
+
+#[test]
+fn test_prepend_values_with_names() {
+    let mut names = HashMap::new();
+    names.insert(String::from("page"), Value::from("home"));
+
+    let query = UpdateQuery::new("UPDATE test.page_views SET hits=hits+? WHERE page=:page", QueryValues::NamedValues(names));
+
+    match query.prepend_values(vec![Value::from(1i64)]) {
+        Err(Error::NamedValues) => {}
+        other => panic!("Expected named values error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_counter_table_columns() {
+    assert!(PageViews::insert_columns().is_empty());
+    assert!(PageViews::updatable_columns().is_empty());
+}
+
+#[test]
+fn test_counter_delta_query_non_counter_column() {
+    assert!(UserEvent::counter_delta_cql("payload").is_err());
+    assert!(user_event().counter_delta_query("updated").is_err());
+    assert!(PageViews::counter_delta_cql("day").is_err());
+}
+
+#[test]
+fn test_counter_table_only_updated_by_delta() {
+    let views = PageViews { page: String::from("home"), day: 18000, hits: 0 };
+
+    assert_eq!(views.try_update_query().unwrap_err().to_string(),
+               "Table page_views only has counter columns, use counter_delta_query");
+    assert_eq!(views.try_store_query().unwrap_err().to_string(),
+               "Table page_views has counter columns, it can not be inserted, use counter_delta_query");
+    assert!(PageViews::create_table_cql().contains("hits COUNTER"));
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Report {
+    #[column(type = "TEXT", primary_key)]
+    name: String,
+
+    #[column(type = "list<text>")]
+    columns: Vec<String>,
+}
+
+#[test]
+fn test_update_by_keys_statements() {
+    assert_eq!(UserEvent::update_by_primary_keys(vec![String::from("payload")]).unwrap(),
+               "UPDATE test.user_event SET payload=? WHERE username=?");
+
+    assert_eq!(UserEvent::update_by_primary_and_cluster_keys(vec![String::from("payload"), String::from("updated")]).unwrap(),
+               "UPDATE test.user_event SET payload=?,updated=? WHERE username=? AND created=?");
+
+    let unknown = UserEvent::update_by_primary_keys(vec![String::from("removed"), String::from("paylod")]).unwrap_err();
+
+    assert_eq!(unknown.columns(), vec!["removed", "paylod"]);
+    assert_eq!(unknown.suggestion("paylod"), Some("payload"));
+    assert_eq!(unknown.table(), Some("test.user_event"));
+
+    let key = UserEvent::update_by_primary_and_cluster_keys(vec![String::from("created")]).unwrap_err();
+
+    assert_eq!(key.columns(), vec!["created"]);
+    assert_eq!(key.to_string(), "Unknown non key column `created` of table test.user_event");
+
+    assert!(UserEvent::update_by_primary_keys(vec![]).unwrap_err().columns().is_empty());
+
+    assert_eq!(Report::update_by_primary_keys(vec![String::from("columns")]).unwrap(),
+               "UPDATE test.report SET columns=? WHERE name=?");
+}
+
+#[test]
+fn test_update_by_keys_typed_statements() {
+    assert_eq!(UserEvent::update_by_primary_keys_typed(&[UserEventColumn::Payload]).unwrap(),
+               UserEvent::update_by_primary_keys(vec![String::from("payload")]).unwrap());
+
+    assert_eq!(UserEvent::update_by_primary_and_cluster_keys_typed(&[UserEventColumn::Payload, UserEventColumn::Updated]).unwrap(),
+               "UPDATE test.user_event SET payload=?,updated=? WHERE username=? AND created=?");
+
+    assert!(UserEvent::update_by_primary_and_cluster_keys_typed(&[UserEventColumn::Created]).is_err());
+}
+
+#[test]
+fn test_column_enum_matches_columns() {
+    let columns = [UserEventColumn::Username, UserEventColumn::Created, UserEventColumn::Payload, UserEventColumn::Updated];
+
+    assert_eq!(columns.iter().map(|c| c.as_str()).collect::<Vec<&str>>(), UserEvent::columns());
+    assert_eq!(ReportColumn::Columns.as_str(), Report::columns()[1]);
+}
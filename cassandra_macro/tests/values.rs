@@ -0,0 +1,112 @@
+use std::collections::{BTreeSet, HashMap};
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use cassandra_macro::{CassandraTable, CqlDuration, Error};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::query::QueryValues;
+use cdrs::types::value::Value;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Job {
+    #[column(type = "TEXT")]
+    owner: String,
+
+    #[column(type = "TEXT", primary_key)]
+    name: String,
+
+    #[column(type = "set<int>")]
+    retries: BTreeSet<i32>,
+
+    #[column(type = "DURATION")]
+    timeout: Option<CqlDuration>,
+}
+
+fn job() -> Job {
+    Job {
+        owner: String::from("ops"),
+        name: String::from("backup"),
+        retries: vec![3, 1].into_iter().collect(),
+        timeout: Some(CqlDuration::new(0, 0, 90_000_000_000)),
+    }
+}
+
+fn column_values() -> Vec<Value> {
+    vec![
+        Value::from("ops"),
+        Value::from("backup"),
+        Value::from(vec![1, 3]),
+        Value::from(Some(CqlDuration::new(0, 0, 90_000_000_000))),
+    ]
+}
+
+#[test]
+fn test_values_in_column_order() {
+    let values = QueryValues::from(&job());
+
+    assert_eq!(values.len(), Job::columns().len());
+    assert_eq!(values, QueryValues::SimpleValues(column_values()));
+
+    for (column, value) in Job::columns().iter().zip(column_values()) {
+        assert_eq!(job().column_value(column), Some(value));
+    }
+}
+
+#[test]
+fn test_named_values() {
+    let values: HashMap<String, Value> = Job::columns().iter()
+        .map(|c| c.to_string())
+        .zip(column_values())
+        .collect();
+
+    assert_eq!(job().to_named_values(), QueryValues::NamedValues(values));
+    assert_eq!(job().to_named_values().len(), Job::columns().len());
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Lease {
+    #[column(type = "TEXT", primary_key)]
+    name: String,
+
+    #[column(type = "DURATION")]
+    timeout: Duration,
+}
+
+#[test]
+fn test_values_of_fallible_columns() {
+    let lease = Lease { name: String::from("backup"), timeout: Duration::from_secs(90) };
+    let values = vec![Value::from("backup"), Value::from(CqlDuration::new(0, 0, 90_000_000_000))];
+
+    assert_eq!(QueryValues::try_from(&lease).unwrap(), QueryValues::SimpleValues(values.clone()));
+    assert_eq!(lease.try_to_named_values().unwrap(),
+               QueryValues::NamedValues(Lease::columns().iter().map(|c| c.to_string()).zip(values).collect()));
+
+    let lease = Lease { timeout: Duration::MAX, ..lease };
+
+    assert!(matches!(QueryValues::try_from(&lease), Err(Error::InvalidValue(_))));
+    assert!(matches!(lease.try_to_named_values(), Err(Error::InvalidValue(_))));
+}
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+pub struct Report {
+    #[column(type = "TEXT", primary_key)]
+    name: String,
+}
+
+impl Report {
+    /// Named like the values of the columns of the generated code
+    fn column_values(&self) -> Vec<String> {
+        vec![self.name.clone()]
+    }
+}
+
+#[test]
+fn test_user_method_named_like_the_values() {
+    let report = Report { name: String::from("weekly") };
+
+    assert_eq!(QueryValues::from(&report), QueryValues::SimpleValues(vec![Value::from("weekly")]));
+    assert_eq!(report.column_values(), vec![String::from("weekly")]);
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "bigint")]
+
+use std::str::FromStr;
+
+use cassandra_macro::num_bigint::BigInt;
+use cassandra_macro::{CassandraTable, rows_with_raw_columns, Varint};
+use cassandra_macro_derive::CassandraTable;
+use cdrs::frame::frame_response::ResponseBody;
+use cdrs::frame::frame_result::{BodyResResultRows, ColSpec, ColType, ColTypeOption, ResResultBody, RowsMetadata};
+use cdrs::frame::TryFromRow;
+use cdrs::query::QueryValues;
+use cdrs::types::{CBytes, CString};
+use cdrs::types::rows::Row;
+use cdrs::types::value::{Value, ValueType};
+
+#[derive(Debug, PartialEq, CassandraTable)]
+#[table(keyspace = "ledger", derive_try_from_row, infer_types)]
+pub struct Account {
+    #[column(type = "TEXT", primary_key)]
+    iban: String,
+
+    #[column(type = "VARINT")]
+    balance: BigInt,
+
+    #[column()]
+    overdraft: Option<BigInt>,
+
+    #[column(type = "VARINT")]
+    transactions: i64,
+}
+
+#[test]
+fn test_varint_ddl() {
+    assert_eq!(Account::create_table_cql(),
+               "CREATE TABLE IF NOT EXISTS ledger.account  (iban TEXT,balance VARINT,overdraft VARINT,transactions VARINT, \
+                PRIMARY KEY (iban) ) ");
+}
+
+#[test]
+fn test_varint_values() {
+    let balance = BigInt::from_str("123456789012345678901234567890").unwrap();
+    let overdraft = BigInt::from_str("-987654321098765432109876543210").unwrap();
+
+    let account = Account {
+        iban: String::from("PT50"),
+        balance: balance.clone(),
+        overdraft: Some(overdraft.clone()),
+        transactions: 3,
+    };
+
+    assert_eq!(account.update_query().unwrap().values(), &QueryValues::SimpleValues(vec![
+        Value::from(Varint(balance.clone())),
+        Value::from(Some(Varint(overdraft.clone()))),
+        Value::from(3i64),
+        Value::from("PT50"),
+    ]));
+
+    let body = account.column_value("balance").unwrap().body;
+
+    assert_eq!(body.len(), 13);
+    assert_eq!(Varint::from_cql_bytes(&body), Some(Varint(balance)));
+
+    let body = account.column_value("overdraft").unwrap().body;
+
+    assert_eq!(Varint::from_cql_bytes(&body).map(Varint::into_inner), Some(overdraft));
+}
+
+#[test]
+fn test_varint_serialization() {
+    assert_eq!(Varint(BigInt::from(0)).to_cql_bytes(), vec![0x00]);
+    assert_eq!(Varint(BigInt::from(128)).to_cql_bytes(), vec![0x00, 0x80]);
+    assert_eq!(Varint(BigInt::from(-1)).to_cql_bytes(), vec![0xFF]);
+    assert_eq!(Varint(BigInt::from(-129)).to_cql_bytes(), vec![0xFF, 0x7F]);
+    assert_eq!(Varint::from_cql_bytes(&[]), None);
+}
+
+fn account() -> Account {
+    Account {
+        iban: String::from("PT50"),
+        balance: BigInt::from_str("123456789012345678901234567890").unwrap(),
+        overdraft: None,
+        transactions: 3,
+    }
+}
+
+fn body(account: &Account) -> BodyResResultRows {
+    let col_spec = |name: &str| ColSpec {
+        ksname: None,
+        tablename: None,
+        name: CString::new(name.to_string()),
+        col_type: ColTypeOption { id: if name == "iban" { ColType::Varchar } else { ColType::Varint }, value: None },
+    };
+
+    let values = Account::columns().iter()
+        .map(|c| match account.column_value(c).unwrap() {
+            Value { value_type: ValueType::Null, .. } => CBytes::new_empty(),
+            value => CBytes::new(value.body),
+        })
+        .collect();
+
+    BodyResResultRows {
+        metadata: RowsMetadata {
+            flags: 0,
+            columns_count: 4,
+            paging_state: None,
+            global_table_space: None,
+            col_specs: Account::columns().iter().map(|c| col_spec(c)).collect(),
+        },
+        rows_count: 1,
+        rows_content: vec![values],
+    }
+}
+
+#[test]
+fn test_varint_from_row() {
+    assert_eq!(Account::raw_columns(), &["balance", "overdraft"]);
+
+    let rows = rows_with_raw_columns(ResponseBody::Result(ResResultBody::Rows(body(&account()))), Account::raw_columns()).unwrap();
+
+    assert_eq!(Account::try_from_row(rows.into_iter().next().unwrap()).unwrap(), account());
+}
+
+#[test]
+fn test_varint_from_driver_row() {
+    let account = Account { balance: BigInt::from(-42), overdraft: Some(BigInt::from(7)), ..account() };
+
+    let row = Row::from_frame_body(body(&account)).remove(0);
+
+    assert_eq!(Account::try_from_row(row).unwrap(), account);
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_find_varint_by_key() {
+    use cassandra_macro::CassandraRepository;
+    use cassandra_macro::testing::MockSession;
+
+    let columns = [("iban", ColType::Varchar), ("balance", ColType::Varint), ("overdraft", ColType::Varint), ("transactions", ColType::Varint)];
+    let row = Account::columns().iter().map(|c| account().column_value(c).unwrap()).collect();
+
+    let repository = CassandraRepository::new(MockSession::new().with_rows("FROM ledger.account", &columns, vec![row]));
+
+    let found: Option<Account> = repository.find_by_key(QueryValues::SimpleValues(vec![Value::from("PT50")])).unwrap();
+
+    assert_eq!(found, Some(account()));
+}
@@ -0,0 +1,35 @@
+use cassandra_macro::{CassandraTable, Projection};
+use cassandra_macro_derive::CassandraTable;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test")]
+#[view(name = "user_by_email", partition_keys = "email", cluster_keys = "username")]
+#[view(name = "user_by_country", partition_keys = "country, username")]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT")]
+    email: String,
+
+    #[column(type = "TEXT")]
+    country: String,
+}
+
+#[test]
+fn test_select_from_view() {
+    assert_eq!(User::select_from_user_by_email(Projection::All),
+               "SELECT * FROM test.user_by_email WHERE email=? AND username=?");
+    assert_eq!(User::select_from_user_by_email(Projection::Columns(vec![String::from("country")])),
+               "SELECT country FROM test.user_by_email WHERE email=? AND username=?");
+    assert_eq!(User::select_from_user_by_country(Projection::All),
+               "SELECT * FROM test.user_by_country WHERE country=? AND username=?");
+
+    assert_eq!(User::select_by_primary_keys(Projection::All), "SELECT * FROM test.user WHERE  username=? ");
+}
+
+#[test]
+fn test_count_from_view() {
+    assert_eq!(User::count_from_user_by_email(), "SELECT count(*) AS count FROM test.user_by_email WHERE email=?");
+    assert_eq!(User::count_from_user_by_country(), "SELECT count(*) AS count FROM test.user_by_country WHERE country=? AND username=?");
+}
@@ -30,18 +30,12 @@
 //! cassandra_macro_derive = "0.1.3"
 //! ```
 //!
-//! In your `main.rs`
-//!
-//! ```
-//! #[macro_use]
-//! extern crate cdrs;
-//! ```
+//! The generated code only refers to `cassandra_macro`, which
+//! re-exports the `cdrs` types it needs, so `#[macro_use] extern
+//! crate cdrs` is not required.
 //!
 //! # Example
-//! ```
-//! #[macro_use]
-//!extern crate cdrs;
-//!
+//! ```no_run
 //!use std::sync::Arc;
 //!
 //!use cassandra_macro::{CassandraTable, DeleteQuery, Projection, UpdateQuery};
@@ -60,8 +54,8 @@
 //!use chrono::Utc;
 //!use uuid::Uuid;
 //!
-//!#[table(keyspace = "test", options = "comment='Only for RUST users' | COMPACTION = {'class':'SizeTieredCompactionStrategy'}")]
 //!#[derive(Debug, CassandraTable)]
+//!#[table(keyspace = "test", options = "comment='Only for RUST users' | COMPACTION = {'class':'SizeTieredCompactionStrategy'}")]
 //!pub struct User {
 //!    #[column(type = "TEXT", primary_key)]
 //!    username: String,
@@ -136,21 +130,27 @@
 //!        }
 //!    }
 //!
-//!    pub fn execute_store_query(&self, query: &StoreQuery) -> Result<bool, CassandraDriverError> {
-//!        self.execute_query(query.query(), query.values())
+//!    pub fn execute_store_query(&self, query: StoreQuery) -> Result<bool, CassandraDriverError> {
+//!        let (query, values) = query.into_parts();
+//!
+//!        self.execute_query(&query, values)
 //!    }
 //!
-//!    pub fn execute_update_query(&self, query: &UpdateQuery) -> Result<bool, CassandraDriverError> {
-//!        self.execute_query(query.query(), query.values())
+//!    pub fn execute_update_query(&self, query: UpdateQuery) -> Result<bool, CassandraDriverError> {
+//!        let (query, values) = query.into_parts();
+//!
+//!        self.execute_query(&query, values)
 //!    }
 //!
-//!    pub fn execute_delete_query(&self, query: &DeleteQuery) -> Result<bool, CassandraDriverError> {
-//!        self.execute_query(query.query(), query.values())
+//!    pub fn execute_delete_query(&self, query: DeleteQuery) -> Result<bool, CassandraDriverError> {
+//!        let (query, values) = query.into_parts();
+//!
+//!        self.execute_query(&query, values)
 //!    }
 //!
-//!    pub fn execute_query(&self, query: &String, values: &QueryValues) -> Result<bool, CassandraDriverError> {
+//!    pub fn execute_query(&self, query: &str, values: QueryValues) -> Result<bool, CassandraDriverError> {
 //!        let result = self.connection
-//!            .query_with_values(query, values.to_owned());
+//!            .query_with_values(query, values);
 //!
 //!        result.map(|_| true)
 //!    }
@@ -163,7 +163,7 @@
 //!        let result_frame = self.connection.query_with_values(stmt, QueryValues::SimpleValues(values))?;
 //!
 //!        Ok(result_frame.get_body()?.into_rows()
-//!            .map(|r| { r.first().map(|r| T::try_from_row(r.to_owned()).unwrap()) }).flatten())
+//!            .and_then(|r| { r.first().map(|r| T::try_from_row(r.to_owned()).unwrap()) }))
 //!    }
 //!
 //!    pub fn new_from_config(cassandra_configs: &CassandraConfig) -> Self {
@@ -206,15 +206,15 @@
 //!    println!("You can test those by yourself");
 //!    println!("{}", User::select_by_primary_keys(Projection::Columns(vec!["created".to_string()])));
 //!    println!("{}", User::select_by_primary_and_cluster_keys(Projection::All));
-//!    println!("{}", User::update_by_primary_keys(vec!["updated".to_string()]));
-//!    println!("{}", User::update_by_primary_and_cluster_keys(vec!["updated".to_string()]));
+//!    println!("{}", User::update_by_primary_keys(vec!["updated".to_string()]).unwrap());
+//!    println!("{}", User::update_by_primary_and_cluster_keys(vec!["updated".to_string()]).unwrap());
 //!    println!("{}", User::delete_by_primary_keys());
 //!    println!("{}", User::delete_by_primary_and_cluster_keys());
 //!
 //!    let mut rust_user = User::default();
 //!
 //!    println!("Storing rust: {}", rust_user.store_query().query());
-//!    connection.execute_store_query(&rust_user.store_query()).expect("User must be stored");
+//!    connection.execute_store_query(rust_user.store_query()).expect("User must be stored");
 //!
 //!    let rust_user_from_db: Option<User> = connection.find::<User>(vec!["Rust".to_string()]).unwrap();
 //!    assert!(rust_user_from_db.unwrap().username.eq(&rust_user.username), "Must be the same");
@@ -222,14 +222,14 @@
 //!    println!("Update rust:{}", rust_user.update_query().unwrap().query());
 //!    rust_user.set_first_name(String::from("IamRoot"));
 //!
-//!    connection.execute_update_query(&rust_user.update_query().unwrap()).unwrap();
+//!    connection.execute_update_query(rust_user.update_query().unwrap()).unwrap();
 //!
 //!    let rust_user_from_db_1 = connection.find::<User>(vec!["Rust".to_string()]).unwrap();
 //!
 //!    assert!(rust_user_from_db_1.unwrap().username.eq(&rust_user.username), "Must be the same");
 //!
 //!    println!("Delete:{}", rust_user.delete_query().query());
-//!    connection.execute_delete_query(&rust_user.delete_query()).expect("Must be deleted");
+//!    connection.execute_delete_query(rust_user.delete_query()).expect("Must be deleted");
 //!
 //!    println!("Dropping table: {}", User::drop_table_cql());
 //!    connection.execute_simple_statement(User::drop_table_cql()).expect("Table must be removed");
@@ -239,15 +239,15 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::BTreeMap;
 
-use syn;
 use syn::NestedMeta;
+use cassandra_macro_table::{CqlTable, CqlTableBuilder, Order};
 use std::str::FromStr;
 
 use quote::{quote, ToTokens};
 
-#[proc_macro_derive(CassandraTable, attributes(column, table))]
+#[proc_macro_derive(CassandraTable, attributes(column, table, view))]
 pub fn cassandra_macro_derive(input: TokenStream) -> TokenStream {
     // Construct a representation of Rust code as a syntax tree
     // that we can manipulate
@@ -257,8 +257,42 @@ pub fn cassandra_macro_derive(input: TokenStream) -> TokenStream {
     impl_cassandra_macro(&ast)
 }
 
+/// Projection of some columns of a table, a field with `column` that
+/// is not a column of the table does not compile:
+///
+/// ```compile_fail
+/// use cassandra_macro_derive::{CassandraProjection, CassandraTable};
+///
+/// #[derive(CassandraTable)]
+/// #[table(keyspace = "test")]
+/// pub struct User {
+///     #[column(type = "TEXT", primary_key)]
+///     username: String,
+/// }
+///
+/// #[derive(CassandraProjection)]
+/// #[projection(of = "User")]
+/// pub struct UserEmail {
+///     #[column(type = "TEXT")]
+///     email: String,
+/// }
+/// ```
+#[proc_macro_derive(CassandraProjection, attributes(column, projection))]
+pub fn cassandra_projection_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse(input).unwrap();
+
+    impl_cassandra_projection(&ast)
+}
+
+#[proc_macro_derive(CassandraUdt, attributes(field, udt))]
+pub fn cassandra_udt_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse(input).unwrap();
+
+    impl_cassandra_udt(&ast)
+}
+
 fn impl_cassandra_macro(ast: &syn::DeriveInput) -> TokenStream {
-    let table_name = pascal_case_to_snake_case(&ast.ident.to_string());
+    let table_name = TableNaming::default().table_name(&ast.ident.to_string());
 
     let mut table_meta = TableMeta::with_name(&table_name);
 
@@ -273,28 +307,38 @@ fn impl_cassandra_macro(ast: &syn::DeriveInput) -> TokenStream {
         _ => panic!("#[derive(CassandraConfig)] can only be used with structs"),
     };
 
-    extract_struct_attributes(&mut table_meta, &fields);
+    let mut naming = TableNaming::default();
 
     for attr in ast.attrs.iter() {
         match attr.parse_meta() {
             Ok(syn::Meta::List(syn::MetaList { ref path, ref nested, .. })) => {
                 let ident = path.get_ident().unwrap();
-                match ident.to_string().as_ref() {
-                    "table" => {
-                        let mut meta_items_iter = nested.iter();
+                if ident == "table" {
+                    let meta_items: Vec<&NestedMeta> = nested.iter().collect();
 
-                        let mut meta_items = Vec::new();
+                    let (key_space, options, try_from_row, doc_comment, comment, table_naming, idempotence, infer_types) = extract_table_properties(&meta_items);
 
-                        while let Some(n) = meta_items_iter.next() {
-                            meta_items.push(n);
-                        }
+                    naming = table_naming;
+
+                    table_meta.name = naming.table_name(&ast.ident.to_string());
 
-                        let (key_space, options) = extract_table_properties(&meta_items);
+                    table_meta.set_key_space(&key_space);
+                    table_meta.set_table_options(&options);
+                    table_meta.try_from_row = try_from_row;
+                    table_meta.infer_types = infer_types;
+                    table_meta.idempotence = idempotence;
 
-                        table_meta.set_key_space(&key_space);
-                        table_meta.set_table_options(&options);
+                    let comment = comment.or_else(|| if doc_comment { first_doc_paragraph(&ast.attrs) } else { None });
+
+                    if let Some(comment) = comment {
+                        table_meta.set_comment(&comment);
                     }
-                    _ => {}
+                } else if ident == "view" {
+                    let meta_items: Vec<&NestedMeta> = nested.iter().collect();
+
+                    let view = extract_view_properties(&meta_items);
+
+                    table_meta.views.push(view);
                 }
             }
             Err(_) => unreachable!(
@@ -303,43 +347,274 @@ fn impl_cassandra_macro(ast: &syn::DeriveInput) -> TokenStream {
         }
     }
 
-    let create_table_sql = table_meta.create_table_cql();
-    let drop_table_sql = table_meta.drop_table_cql();
+    extract_struct_attributes(&mut table_meta, &fields);
+
+    let table = table_meta.cql_table();
+
+    let create_table_sql = table.create_table_cql();
+    let drop_table_sql = table.drop_table_cql();
+    let create_indexes_sql = table.create_indexes_cql();
+    let drop_indexes_sql = table.drop_indexes_cql();
     let key_space = table_meta.key_space();
     let table_name = table_meta.table_name();
-    let select_by_key = table_meta.select_by_key();
-    let select_by_keys = table_meta.select_by_keys();
+    let columns = table_meta.column_names();
+    let primary_key_columns = table_meta.primary_key_columns();
+    let cluster_key_columns = table_meta.cluster_key_columns();
+    let static_columns = &table_meta.static_columns;
+    let insert_columns = if table.is_counter_table() { vec![] } else { table_meta.stored_columns() };
+    let updatable_columns = table_meta.updatable_columns();
+    let select_all = table.select_cql("{}");
+    let select_by_key = table.select_by_primary_keys_cql("{}");
+    let select_by_keys = table.select_by_primary_and_cluster_keys_cql("{}");
+
+    let update_by_key = table.update_by_primary_keys_cql("{}");
+    let update_by_keys = table.update_by_primary_and_cluster_keys_cql("{}");
+
+    let delete_by_key = table.delete_by_primary_keys_cql();
+    let delete_by_keys = table.delete_by_primary_and_cluster_keys_cql();
+
+    let (store_stmt, store_values) = table_meta.store_stmt();
+    let column_value_arms = table_meta.column_value_arms();
+    let column_type_arms = table_meta.column_type_arms();
+    let raw_columns = table_meta.raw_columns();
+    let store_json_stmt = table.insert_json_cql();
+    let store_json_default_unset_stmt = table.insert_json_default_unset_cql();
 
-    let update_by_key = table_meta.update_by_key();
-    let update_by_keys = table_meta.update_by_keys();
+    let (update_stmt, update_values) = table_meta.update_stmt()
+        .unwrap_or((String::new(), proc_macro2::TokenStream::new()));
 
-    let delete_by_key = table_meta.delete_by_key();
-    let delete_by_keys = table_meta.delete_by_keys();
+    let template_table = table.with_key_space_placeholder();
+    let create_table_template = template_table.create_table_cql();
+    let drop_table_template = template_table.drop_table_cql();
+    let select_all_template = template_table.select_cql("{}");
+    let select_by_key_template = template_table.select_by_primary_keys_cql("{}");
+    let select_by_keys_template = template_table.select_by_primary_and_cluster_keys_cql("{}");
+    let store_template = template_table.insert_cql();
+    let update_template = template_table.update_cql().unwrap_or_default();
+    let delete_template = template_table.delete_cql();
 
-    let store_stmt = table_meta.store_stmt();
-    let store_values = table_meta.store_values();
+    let try_column_value = table_meta.try_column_value();
 
-    let (update_stmt, update_values) = table_meta.update_stmt()
-        .unwrap_or((String::new(), proc_macro2::TokenStream::new()));
+    let try_queries = table_meta.try_queries(&table);
+
+    let store_moved_values = table_meta.store_moved_values();
+    let update_moved_values = table_meta.update_moved_values();
+
+    let store_idempotent = table_meta.is_store_idempotent();
+    let update_idempotent = table_meta.idempotence.update;
+    let delete_idempotent = table_meta.idempotence.delete;
+
+    let no_updatable_columns_error = table_meta.no_updatable_columns_error(&table);
 
     let (delete_stmt, delete_values) = table_meta.delete_stmt();
 
+    let (delete_partition_stmt, delete_partition_values) = table_meta.delete_partition_stmt();
+
+    let (exists_stmt, exists_values) = table_meta.exists_stmt();
+
+    let (count_partition_stmt, count_partition_values) = table_meta.count_partition_stmt();
+
+    let full_key_values = table_meta.values_of(&table_meta.full_key_columns());
+
+    let (queryable_columns, finder_stmts): (Vec<String>, Vec<String>) = table_meta.queryable_stmts().into_iter().unzip();
+
+    let finders = queryable_columns.iter()
+        .map(|c| syn::Ident::new(&format!("select_by_{}", c), proc_macro2::Span::call_site()));
+
+    let key_finders = queryable_columns.iter()
+        .map(|c| syn::Ident::new(&format!("select_keys_by_{}", c), proc_macro2::Span::call_site()));
+
+    let key_finder_stmts = table_meta.queryable_columns.iter()
+        .map(|(c, allow_filtering)| table.select_by_column_cql(c, &table_meta.full_key_columns().join(","), *allow_filtering).unwrap());
+
+    let (time_range_columns, time_range_stmts): (Vec<String>, Vec<String>) = table_meta.time_range_stmts().into_iter().unzip();
+
+    let time_range_finders = time_range_columns.iter()
+        .map(|c| syn::Ident::new(&format!("select_by_{}_time_range", c), proc_macro2::Span::call_site()));
+
+    let time_range_docs = time_range_columns.iter()
+        .map(|c| format!("Rows of the partition with `{}` after `maxTimeuuid` of the first timestamp \
+                          and before `minTimeuuid` of the second", c));
+
+    let views = table.views();
+
+    let view_finders = views.iter()
+        .map(|v| syn::Ident::new(&format!("select_from_{}", v), proc_macro2::Span::call_site()));
+
+    let view_finder_stmts = views.iter().map(|v| table.view_select_cql(v, "{}").unwrap());
+
+    let view_finder_docs = views.iter()
+        .map(|v| format!("Rows of the materialized view `{}.{}` by its partition and cluster keys", key_space, v));
+
+    let view_counters = views.iter()
+        .map(|v| syn::Ident::new(&format!("count_from_{}", v), proc_macro2::Span::call_site()));
+
+    let view_counter_stmts = views.iter().map(|v| table.view_count_partition_cql(v).unwrap());
+
+    let view_counter_docs = views.iter()
+        .map(|v| format!("Count of the rows of a partition of the materialized view `{}.{}`", key_space, v));
+
     let ident = &ast.ident;
 
+    let vis = &ast.vis;
+
+    let column_enum = naming.column_enum(&ident.to_string());
+
+    let column_variants = column_variants(&columns, &column_enum);
+
+    let column_enum_doc = format!("Columns of the table `{}.{}`", key_space, table_name);
+
+    let key_struct = naming.key_struct(&ident.to_string());
+
+    let key_struct_doc = format!("Primary and cluster keys of a row of the table `{}.{}`, \
+                                  in the order of the where clauses", key_space, table_name);
+
+    let key_fields: Vec<syn::Ident> = table_meta.full_key_columns().iter()
+        .map(|c| syn::Ident::new(c, proc_macro2::Span::call_site()))
+        .collect();
+
+    // Borrowed keys are owned by the key, which does not have the lifetime of the entity
+    let key_types = table_meta.full_key_columns().into_iter().map(|c| {
+        let field = fields.iter().find(|f| f.ident.as_ref().is_some_and(|i| *i == c)).unwrap();
+
+        owned_key_type(&field.ty).unwrap_or_else(|| field.ty.clone())
+    });
+
+    let key_values = table_meta.full_key_columns().into_iter().map(|c| {
+        let field = syn::Ident::new(&c, proc_macro2::Span::call_site());
+
+        if table_meta.is_borrowed(&c) {
+            quote!(self.#field.to_owned())
+        } else {
+            quote!(self.#field.clone())
+        }
+    });
+
+    let key_values_of_key = table_meta.key_values_of_key();
+    let partition_values_of_key = table_meta.partition_values_of_key();
+
+    let column_values = table_meta.values_of(&columns);
+
     // Helper is provided for handling complex generic types correctly and effortlessly
     let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    // the queries that cannot fail, counter tables are only changed
+    // by `counter_delta_query`, Cassandra rejects their inserts
+    let infallible_queries = if table_meta.has_infallible_queries(&table) {
+        quote!(
+            impl #impl_generics cassandra_macro::InfallibleQueries for #ident #ty_generics #where_clause {
+                fn infallible_store_query(&self) -> cassandra_macro::StoreQuery {
+                    cassandra_macro::StoreQuery::for_table::<Self>(std::borrow::Cow::Borrowed(#store_stmt), cassandra_macro::QueryValues::SimpleValues(vec![#store_values])).with_idempotent(#store_idempotent)
+                }
+
+                fn infallible_into_store_query(self) -> cassandra_macro::StoreQuery {
+                    cassandra_macro::StoreQuery::for_table::<Self>(std::borrow::Cow::Borrowed(#store_stmt), cassandra_macro::QueryValues::SimpleValues(vec![#store_moved_values])).with_idempotent(#store_idempotent)
+                }
+
+                fn infallible_update_query(&self) -> Result<cassandra_macro::UpdateQuery, cassandra_macro::TableWithNoUpdatableColumnsError> {
+                    if #update_stmt.is_empty() {
+                        return Err(cassandra_macro::TableWithNoUpdatableColumnsError::new(String::from(#no_updatable_columns_error)));
+                    }
+
+                    Ok(cassandra_macro::UpdateQuery::for_table::<Self>(std::borrow::Cow::Borrowed(#update_stmt), cassandra_macro::QueryValues::SimpleValues(vec![#update_values])).with_idempotent(#update_idempotent))
+                }
+
+                fn infallible_into_update_query(self) -> Result<cassandra_macro::UpdateQuery, cassandra_macro::TableWithNoUpdatableColumnsError> {
+                    if #update_stmt.is_empty() {
+                        return Err(cassandra_macro::TableWithNoUpdatableColumnsError::new(String::from(#no_updatable_columns_error)));
+                    }
+
+                    Ok(cassandra_macro::UpdateQuery::for_table::<Self>(std::borrow::Cow::Borrowed(#update_stmt), cassandra_macro::QueryValues::SimpleValues(vec![#update_moved_values])).with_idempotent(#update_idempotent))
+                }
+            }
+        )
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+
+    // the conversions of all the values, fallible when a column
+    // can fail to convert, see `fallible_value_of`
+    let column_values_impl = if !table_meta.has_fallible_columns() {
+        quote!(
+            /// Values of all the columns, in the order of `columns()`
+            impl #impl_generics From<&#ident #ty_generics> for cassandra_macro::QueryValues #where_clause {
+                fn from(entity: &#ident #ty_generics) -> Self {
+                    cassandra_macro::QueryValues::SimpleValues(entity.__cassandra_column_values())
+                }
+            }
+
+            impl #impl_generics #ident #ty_generics #where_clause {
+                fn __cassandra_column_values(&self) -> Vec<cassandra_macro::Value> {
+                    vec![#column_values]
+                }
+
+                /// Values of all the columns by their names
+                pub fn to_named_values(&self) -> cassandra_macro::QueryValues {
+                    let columns = <Self as cassandra_macro::CassandraTable>::columns();
+
+                    cassandra_macro::QueryValues::NamedValues(
+                        columns.iter().map(|c| c.to_string()).zip(self.__cassandra_column_values()).collect())
+                }
+            }
+        )
+    } else {
+        quote!(
+            /// Values of all the columns, in the order of `columns()`,
+            /// failing if a column can not be converted
+            impl #impl_generics std::convert::TryFrom<&#ident #ty_generics> for cassandra_macro::QueryValues #where_clause {
+                type Error = cassandra_macro::Error;
+
+                fn try_from(entity: &#ident #ty_generics) -> Result<Self, Self::Error> {
+                    Ok(cassandra_macro::QueryValues::SimpleValues(entity.__cassandra_column_values()?))
+                }
+            }
+
+            impl #impl_generics #ident #ty_generics #where_clause {
+                fn __cassandra_column_values(&self) -> Result<Vec<cassandra_macro::Value>, cassandra_macro::Error> {
+                    Ok(vec![#column_values])
+                }
+
+                /// Values of all the columns by their names,
+                /// failing if a column can not be converted
+                pub fn try_to_named_values(&self) -> Result<cassandra_macro::QueryValues, cassandra_macro::Error> {
+                    let columns = <Self as cassandra_macro::CassandraTable>::columns();
+
+                    Ok(cassandra_macro::QueryValues::NamedValues(
+                        columns.iter().map(|c| c.to_string()).zip(self.__cassandra_column_values()?).collect()))
+                }
+            }
+        )
+    };
+
+    let env_vars = naming.env_vars();
+
     let impl_ast = quote!(
+        // the table name depends on these variables, `option_env!` rebuilds the crate when they change
+        #(const _: Option<&str> = option_env!(#env_vars);)*
 
-        impl #impl_generics CassandraTable for #ident #ty_generics #where_clause {
+        impl #impl_generics cassandra_macro::CassandraTable for #ident #ty_generics #where_clause {
+            type Key = #key_struct;
 
             fn create_table_cql() -> &'static str {
                 &#create_table_sql
             }
 
+            fn idempotence() -> cassandra_macro::Idempotence {
+                cassandra_macro::Idempotence { store: #store_idempotent, update: #update_idempotent, delete: #delete_idempotent }
+            }
+
             fn drop_table_cql() -> &'static str {
                 &#drop_table_sql
             }
 
+            fn create_indexes_cql() -> Vec<&'static str> {
+                vec![#(#create_indexes_sql),*]
+            }
+
+            fn drop_indexes_cql() -> Vec<&'static str> {
+                vec![#(#drop_indexes_sql),*]
+            }
+
             fn key_space() -> &'static str {
                 &#key_space
             }
@@ -348,50 +623,82 @@ fn impl_cassandra_macro(ast: &syn::DeriveInput) -> TokenStream {
                 &#table_name
             }
 
-            fn select_by_primary_keys(projection: cassandra_macro::Projection) -> String {
-                match projection {
-                    cassandra_macro::Projection::Count => {
-                         #select_by_key.to_string().replace("*", "count(*) as count")
-                    },
-                    cassandra_macro::Projection::All => {
-                        #select_by_key.to_string()
-                    },
-                    cassandra_macro::Projection::Columns(c) => {
-                         let column_selection : String = c.join(",");
-
-                         #select_by_key.to_string().replace("*", column_selection.as_str())
-                    }
+            fn columns() -> &'static [&'static str] {
+                &[#(#columns),*]
+            }
+
+            fn primary_key_columns() -> &'static [&'static str] {
+                &[#(#primary_key_columns),*]
+            }
+
+            fn cluster_key_columns() -> &'static [&'static str] {
+                &[#(#cluster_key_columns),*]
+            }
+
+            fn static_columns() -> &'static [&'static str] {
+                &[#(#static_columns),*]
+            }
+
+            fn insert_columns() -> &'static [&'static str] {
+                &[#(#insert_columns),*]
+            }
+
+            fn updatable_columns() -> &'static [&'static str] {
+                &[#(#updatable_columns),*]
+            }
+
+            fn column_type(column: &str) -> Option<&'static str> {
+                match column {
+                    #column_type_arms
+                    _ => None,
                 }
             }
 
-            fn select_by_primary_and_cluster_keys(projection: cassandra_macro::Projection) -> String {
-                match projection {
-                    cassandra_macro::Projection::Count => {
-                         #select_by_keys.to_string().replace("*", "count(*) as count")
-                    },
-                    cassandra_macro::Projection::All => {
-                        #select_by_keys.to_string()
-                    },
-                    cassandra_macro::Projection::Columns(c) => {
-                         let column_selection : String = c.join(",");
-
-                         #select_by_keys.to_string().replace("*", column_selection.as_str())
-                    }
+            #raw_columns
+
+            fn statement_template(statement: cassandra_macro::TableStatement) -> &'static str {
+                match statement {
+                    cassandra_macro::TableStatement::CreateTable => #create_table_template,
+                    cassandra_macro::TableStatement::DropTable => #drop_table_template,
+                    cassandra_macro::TableStatement::SelectAll => #select_all_template,
+                    cassandra_macro::TableStatement::SelectByPrimaryKeys => #select_by_key_template,
+                    cassandra_macro::TableStatement::SelectByPrimaryAndClusterKeys => #select_by_keys_template,
+                    cassandra_macro::TableStatement::Store => #store_template,
+                    cassandra_macro::TableStatement::Update => #update_template,
+                    cassandra_macro::TableStatement::Delete => #delete_template,
                 }
             }
 
-            fn update_by_primary_keys(columns: Vec<String>) -> String {
+            fn select_all(projection: cassandra_macro::Projection) -> String {
+                format!(#select_all, projection.selection())
+            }
+
+            fn select_by_primary_keys(projection: cassandra_macro::Projection) -> String {
+                format!(#select_by_key, projection.selection())
+            }
+
+            fn select_by_primary_and_cluster_keys(projection: cassandra_macro::Projection) -> String {
+                format!(#select_by_keys, projection.selection())
+            }
+
+            fn update_by_primary_keys(columns: Vec<String>) -> Result<String, cassandra_macro::UnknownColumnError> {
+                let columns = columns.iter().map(|c| c.as_str()).collect::<Vec<&str>>();
 
-                let update_columns = columns.iter().map(|c| format!(" {}=?", c)).collect::<Vec<String>>().join(",");
+                cassandra_macro::validate_non_key_columns::<Self>(&columns)?;
 
-                #update_by_key.to_string().replace(":columns", update_columns.as_str())
+                let update_columns = columns.iter().map(|c| format!("{}=?", c)).collect::<Vec<String>>().join(",");
+
+                Ok(format!(#update_by_key, update_columns))
             }
 
-            fn update_by_primary_and_cluster_keys(columns: Vec<String>) -> String {
+            fn update_by_primary_and_cluster_keys(columns: Vec<String>) -> Result<String, cassandra_macro::UnknownColumnError> {
+                let columns = columns.iter().map(|c| c.as_str()).collect::<Vec<&str>>();
+
+                cassandra_macro::validate_non_key_columns::<Self>(&columns)?;
 
-                let update_columns = columns.iter().map(|c| format!(" {}=?", c)).collect::<Vec<String>>().join(",");
+                let update_columns = columns.iter().map(|c| format!("{}=?", c)).collect::<Vec<String>>().join(",");
 
-                #update_by_keys.to_string().replace(":columns", update_columns.as_str())
+                Ok(format!(#update_by_keys, update_columns))
             }
 
             fn delete_by_primary_keys() -> String {
@@ -402,510 +709,2808 @@ fn impl_cassandra_macro(ast: &syn::DeriveInput) -> TokenStream {
                 #delete_by_keys.to_string()
             }
 
-            fn store_query(&self) -> cassandra_macro::StoreQuery {
-                cassandra_macro::StoreQuery::new(#store_stmt.to_string(), query_values!(#store_values))
+            fn store_json_cql() -> &'static str {
+                &#store_json_stmt
             }
 
-            fn update_query(&self) -> Result<cassandra_macro::UpdateQuery, cassandra_macro::TableWithNoUpdatableColumnsError>
-            {
-               if #update_stmt.to_string().is_empty() {
-                    return Err(cassandra_macro::TableWithNoUpdatableColumnsError::new(format!("Table {} does not have any updatable column", #table_name)) );
-               }
-
-               Ok(cassandra_macro::UpdateQuery::new(#update_stmt.to_string(), query_values!(#update_values)))
+            fn store_json_default_unset_cql() -> &'static str {
+                &#store_json_default_unset_stmt
             }
 
-            fn delete_query(&self) -> cassandra_macro::DeleteQuery {
-                cassandra_macro::DeleteQuery::new(#delete_stmt.to_string(), query_values!(#delete_values))
+            fn column_value(&self, column: &str) -> Option<cassandra_macro::Value> {
+                match column {
+                    #column_value_arms
+                    _ => None,
+                }
             }
 
-        }
-    );
+            #try_column_value
 
-    impl_ast.into()
-}
+            #try_queries
 
-struct TableMeta {
-    name: String,
-    key_space: String,
-    table_options: String,
-    columns: HashMap<String, String>,
-    static_columns: Vec<String>,
-    primary_keys: BTreeMap<u8, String>,
-    cluster_keys: BTreeMap<u8, (String, String)>,
-}
+            fn delete_query(&self) -> cassandra_macro::DeleteQuery {
+                cassandra_macro::DeleteQuery::for_table::<Self>(std::borrow::Cow::Borrowed(#delete_stmt), cassandra_macro::QueryValues::SimpleValues(vec![#delete_values])).with_idempotent(#delete_idempotent)
+            }
 
-/// @TODO Refactor duplicated code
-impl TableMeta {
-    fn with_name(name: &String) -> Self {
-        TableMeta {
-            name: name.to_owned(),
-            key_space: String::new(),
-            table_options: String::new(),
-            columns: HashMap::new(),
-            static_columns: Vec::new(),
-            primary_keys: BTreeMap::new(),
-            cluster_keys: BTreeMap::new(),
-        }
-    }
+            fn delete_partition_query(&self) -> cassandra_macro::DeleteQuery {
+                cassandra_macro::DeleteQuery::for_table::<Self>(std::borrow::Cow::Borrowed(#delete_partition_stmt), cassandra_macro::QueryValues::SimpleValues(vec![#delete_partition_values])).with_idempotent(#delete_idempotent)
+            }
 
-    fn delete_stmt(&self) -> (String, proc_macro2::TokenStream) {
-        let pk_values: Vec<String> = self.primary_keys.values().map(|p| p.to_owned()).collect();
+            fn exists_query(&self) -> cassandra_macro::SelectQuery {
+                cassandra_macro::SelectQuery::for_table::<Self>(std::borrow::Cow::Borrowed(#exists_stmt), cassandra_macro::QueryValues::SimpleValues(vec![#exists_values]))
+            }
 
-        let ck_values: Vec<String> = self.cluster_keys.values().map(|(c, _)| c.to_owned()).collect();
+            fn select_query(&self, projection: cassandra_macro::Projection) -> cassandra_macro::SelectQuery {
+                cassandra_macro::SelectQuery::for_table::<Self>(Self::select_by_primary_and_cluster_keys(projection), cassandra_macro::QueryValues::SimpleValues(vec![#full_key_values]))
+            }
 
-        let keys: Vec<(String, String)> = [&pk_values[..], &ck_values[..]]
-            .concat()
-            .iter()
-            .map(|c| {
-                (format!("{}=?", c), format!("self.{}.clone()", c))
-            })
-            .collect::<Vec<(String, String)>>();
-
-        (format!("DELETE FROM {}.{} WHERE {}",
-                 self.key_space,
-                 self.name,
-                 keys.iter().map(|(v, _)| v.to_owned())
-                     .collect::<Vec<String>>()
-                     .join(" AND ")),
-         proc_macro2::TokenStream::from_str(keys.iter().map(|(_, v)| v.to_owned())
-             .collect::<Vec<String>>()
-             .join(",").as_str()).unwrap()
-        )
-    }
+            fn count_partition_query(&self) -> cassandra_macro::SelectQuery {
+                cassandra_macro::SelectQuery::for_table::<Self>(std::borrow::Cow::Borrowed(#count_partition_stmt), cassandra_macro::QueryValues::SimpleValues(vec![#count_partition_values]))
+            }
 
-    fn update_stmt(&self) -> Option<(String, proc_macro2::TokenStream)> {
-        let mut updatable_columns = Vec::new();
+            fn key(&self) -> #key_struct {
+                #key_struct {
+                    #(#key_fields: #key_values),*
+                }
+            }
 
-        for (column_name, _) in self.columns.iter() {
-            if self.primary_keys.values().any(|p| p.eq(column_name)) {
-                continue;
+            fn select_by_key(key: &#key_struct, projection: cassandra_macro::Projection) -> cassandra_macro::SelectQuery {
+                cassandra_macro::SelectQuery::for_table::<Self>(Self::select_by_primary_and_cluster_keys(projection), cassandra_macro::QueryValues::SimpleValues(vec![#key_values_of_key]))
             }
 
-            if self.cluster_keys.values().any(|ck| ck.0.eq(column_name)) {
-                continue;
+            fn delete_by_key(key: &#key_struct) -> cassandra_macro::DeleteQuery {
+                cassandra_macro::DeleteQuery::for_table::<Self>(std::borrow::Cow::Borrowed(#delete_stmt), cassandra_macro::QueryValues::SimpleValues(vec![#key_values_of_key])).with_idempotent(#delete_idempotent)
             }
 
-            updatable_columns.push(column_name);
         }
 
-        if updatable_columns.is_empty() {
-            return None;
+        #[doc = #key_struct_doc]
+        #[derive(Debug, Clone, PartialEq)]
+        #vis struct #key_struct {
+            #(#vis #key_fields: #key_types),*
         }
 
-        let update_values = updatable_columns.iter().map(|c| {
-            (format!("{}=?", c), format!("self.{}.clone()", c))
-        }).collect::<Vec<(String, String)>>();
+        impl cassandra_macro::TableKey for #key_struct {
+            fn partition_values(&self) -> Vec<cassandra_macro::Value> {
+                let key = self;
 
-        let p_keys = self.primary_keys.iter().map(|(_, pk)| {
-            (format!("{}=?", pk), format!("self.{}.clone()", pk))
-        }).collect::<Vec<(String, String)>>();
+                vec![#partition_values_of_key]
+            }
+        }
 
-        let ck_keys = self.cluster_keys.iter().map(|(_, (ck, _))| {
-            (format!("{}=?", ck), format!("self.{}.clone()", ck))
-        }).collect::<Vec<(String, String)>>();
+        #[doc = #column_enum_doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #vis enum #column_enum {
+            #(#column_variants),*
+        }
 
-        let values: String = [&update_values[..], &p_keys[..], &ck_keys[..]]
-            .concat()
-            .iter()
-            .map(|(_, c)| c.to_owned())
-            .collect::<Vec<String>>()
-            .join(",");
+        impl #column_enum {
+            /// Name of the column in the statements
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    #(#column_enum::#column_variants => #columns),*
+                }
+            }
+        }
+
+        impl cassandra_macro::TableColumn for #column_enum {
+            fn as_str(&self) -> &'static str {
+                #column_enum::as_str(self)
+            }
+        }
 
-        let pk_values: Vec<String> = self.primary_keys.values().map(|p| p.to_owned()).collect();
+        #infallible_queries
 
-        let ck_values: Vec<String> = self.cluster_keys.values().map(|(c, _)| c.to_owned()).collect();
+        #column_values_impl
 
-        let keys: Vec<(String, String)> = [&pk_values[..], &ck_values[..]]
-            .concat()
-            .iter()
-            .map(|c| {
-                (format!("{}=?", c), format!("self.{}.clone()", c))
-            })
-            .collect::<Vec<(String, String)>>();
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Same as `update_by_primary_keys`, with typed columns
+            pub fn update_by_primary_keys_typed(columns: &[#column_enum]) -> Result<String, cassandra_macro::UnknownColumnError> {
+                <Self as cassandra_macro::CassandraTable>::update_by_primary_keys(
+                    columns.iter().map(|c| c.as_str().to_string()).collect())
+            }
 
-        Some((format!("UPDATE {}.{} SET {} WHERE {}",
-                      self.key_space,
-                      self.name,
-                      update_values.iter().map(|(v, _)| v.to_owned()).collect::<Vec<String>>().join(","),
-                      keys.iter().map(|(v, _)| v.to_owned()).collect::<Vec<String>>().join(" AND ")),
-              proc_macro2::TokenStream::from_str(values.as_str()).unwrap()
-        ))
-    }
+            /// Same as `update_by_primary_and_cluster_keys`, with typed columns
+            pub fn update_by_primary_and_cluster_keys_typed(columns: &[#column_enum]) -> Result<String, cassandra_macro::UnknownColumnError> {
+                <Self as cassandra_macro::CassandraTable>::update_by_primary_and_cluster_keys(
+                    columns.iter().map(|c| c.as_str().to_string()).collect())
+            }
 
-    fn store_stmt(&self) -> String {
-        let fields = self.columns.iter().map(|(n, _)| n.to_owned()).collect::<Vec<String>>().join(",");
+            #(
+                pub fn #finders(projection: cassandra_macro::Projection) -> String {
+                    format!(#finder_stmts, projection.selection())
+                }
+            )*
 
-        let mut bind_marks = "?,".repeat(self.columns.len());
-        bind_marks.pop();
+            #(
+                pub fn #key_finders() -> String {
+                    #key_finder_stmts.to_string()
+                }
+            )*
 
-        format!("INSERT INTO {}.{} ({}) VALUES ({})", self.key_space, self.name, fields, bind_marks)
-    }
+            #(
+                #[doc = #time_range_docs]
+                pub fn #time_range_finders(projection: cassandra_macro::Projection) -> String {
+                    format!(#time_range_stmts, projection.selection())
+                }
+            )*
 
-    fn store_values(&self) -> proc_macro2::TokenStream {
-        let fields_tokens = self.columns.iter().map(|(v, _)| {
-            format!("self.{}.clone()", v.to_owned())
-        }).collect::<Vec<String>>().join(",");
+            #(
+                #[doc = #view_finder_docs]
+                pub fn #view_finders(projection: cassandra_macro::Projection) -> String {
+                    format!(#view_finder_stmts, projection.selection())
+                }
+            )*
 
-        proc_macro2::TokenStream::from_str(fields_tokens.as_str()).unwrap()
-    }
+            #(
+                #[doc = #view_counter_docs]
+                pub fn #view_counters() -> String {
+                    #view_counter_stmts.to_string()
+                }
+            )*
+        }
+    );
 
-    fn set_key_space(&mut self, key_space: &String) {
-        self.key_space = key_space.to_owned();
+    if !table_meta.try_from_row {
+        return impl_ast.into();
     }
 
-    fn select_by_key(&self) -> String {
-        let where_part = self.primary_keys
-            .iter()
-            .map(|(_, v)| format!(" {}=? ", v))
-            .collect::<Vec<String>>()
-            .join("AND");
+    let try_from_row_ast = impl_try_from_row(ast, &fields, &table_meta, quote!(cassandra_macro::cdrs));
 
-        format!("SELECT * FROM {}.{} WHERE {}", self.key_space, self.name, where_part)
+    if cfg!(feature = "cdrs-tokio") {
+        let async_try_from_row_ast = impl_try_from_row(ast, &fields, &table_meta, quote!(cassandra_macro::cdrs_tokio));
+
+        return quote!(#impl_ast #try_from_row_ast #async_try_from_row_ast).into();
     }
 
-    fn select_by_keys(&self) -> String {
-        let pk_select = self.select_by_key();
+    quote!(#impl_ast #try_from_row_ast).into()
+}
 
-        if self.cluster_keys.is_empty() {
-            pk_select
-        } else {
-            let where_part = self.cluster_keys
-                .iter()
-                .map(|(_, (c, _))| format!(" {}=? ", c))
-                .collect::<Vec<String>>()
-                .join("AND");
+fn impl_cassandra_projection(ast: &syn::DeriveInput) -> TokenStream {
+    let mut table_meta = TableMeta::with_name(&pascal_case_to_snake_case(&ast.ident.to_string()));
 
-            format!("{} AND {}", pk_select, where_part)
+    let fields: Vec<syn::Field> = match ast.data {
+        syn::Data::Struct(syn::DataStruct { ref fields, .. }) => {
+            if fields.iter().any(|field| field.ident.is_none()) {
+                panic!("struct has unnamed fields");
+            }
+            fields.iter().cloned().collect()
         }
-    }
+        _ => panic!("#[derive(CassandraProjection)] can only be used with structs"),
+    };
 
-    fn update_by_key(&self) -> String {
-        let where_part = self.primary_keys
-            .iter()
-            .map(|(_, v)| format!(" {}=? ", v))
-            .collect::<Vec<String>>()
-            .join("AND");
+    let mut table: Option<syn::Path> = None;
+    let mut table_column_enum: Option<String> = None;
 
-        format!("UPDATE {}.{} SET :columns WHERE {}", self.key_space, self.name, where_part)
+    for attr in ast.attrs.iter().filter(|attr| attr.path.is_ident("projection")) {
+        match attr.parse_meta() {
+            Ok(syn::Meta::List(syn::MetaList { ref nested, .. })) => {
+                for meta_item in nested.iter() {
+                    match *meta_item {
+                        syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("derive_try_from_row") => {
+                            table_meta.try_from_row = true;
+                        }
+                        syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("infer_types") => {
+                            table_meta.infer_types = true;
+                        }
+                        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue { ref path, ref lit, .. }))
+                        if path.is_ident("of") => {
+                            let of = lit_to_string(lit).unwrap_or_default();
+
+                            table = Some(syn::parse_str(&of).unwrap_or_else(|_| panic!("`of` must be the path of a table, got `{}`", of)));
+                        }
+                        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue { ref path, ref lit, .. }))
+                        if path.is_ident("column_enum") => {
+                            table_column_enum = lit_to_string(lit);
+                        }
+                        _ => panic!("unknown argument for attribute `projection`, expected `of`, `column_enum`, `infer_types` or `derive_try_from_row`"),
+                    }
+                }
+            }
+            _ => panic!("Got something other than a list of attributes while checking projection attribute"),
+        }
     }
 
-    fn update_by_keys(&self) -> String {
-        let pk_select = self.update_by_key();
+    let table = table.unwrap_or_else(|| {
+        panic!("#[derive(CassandraProjection)] needs the table of its columns, e.g. `#[projection(of = \"User\")]`")
+    });
 
-        if self.cluster_keys.is_empty() {
-            pk_select
-        } else {
-            let where_part = self.cluster_keys
-                .iter()
-                .map(|(_, (c, _))| format!(" {}=? ", c))
-                .collect::<Vec<String>>()
-                .join("AND");
+    extract_struct_attributes(&mut table_meta, &fields);
 
-            format!("{} AND {}", pk_select, where_part)
-        }
+    let columns = table_meta.column_names();
+
+    if columns.is_empty() {
+        panic!("projection {} does not have columns, mark its fields with `column`", ast.ident);
     }
 
-    fn delete_by_key(&self) -> String {
-        let where_part = self.primary_keys
-            .iter()
-            .map(|(_, v)| format!(" {}=? ", v))
-            .collect::<Vec<String>>()
-            .join("AND");
+    // `<Table>Column` of the derive of the table, or its `column_enum`,
+    // it does not have a variant for a field that is not a column
+    let mut column_enum = table.clone();
 
-        format!("DELETE FROM {}.{} WHERE {}", self.key_space, self.name, where_part)
+    if let Some(last) = column_enum.segments.last_mut() {
+        last.ident = TableNaming { column_enum: table_column_enum, ..TableNaming::default() }.column_enum(&last.ident.to_string());
+        last.arguments = syn::PathArguments::None;
     }
 
-    fn delete_by_keys(&self) -> String {
-        let pk_select = self.delete_by_key();
+    let column_variants = fields.iter()
+        .filter_map(|f| f.ident.as_ref())
+        .filter(|i| columns.contains(&i.to_string()))
+        .map(|i| syn::Ident::new(&snake_case_to_pascal_case(&i.to_string()), i.span()));
 
-        if self.cluster_keys.is_empty() {
-            pk_select
-        } else {
-            let where_part = self.cluster_keys
-                .iter()
-                .map(|(_, (c, _))| format!(" {}=? ", c))
-                .collect::<Vec<String>>()
-                .join("AND");
+    let ident = &ast.ident;
+
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
-            format!("{} AND {}", pk_select, where_part)
+    let impl_ast = quote!(
+        impl #impl_generics cassandra_macro::CassandraProjection for #ident #ty_generics #where_clause {
+            type Table = #table;
+
+            fn columns() -> &'static [&'static str] {
+                &[#(#columns),*]
+            }
         }
-    }
 
-    fn set_table_options(&mut self, table_options: &String) {
-        self.table_options = table_options.to_owned();
-    }
+        const _: () = {
+            let _ = [#(#column_enum::#column_variants),*];
+        };
+    );
 
-    fn new_column(&mut self, name: &String, data_type: &String) {
-        self.columns.insert(name.to_owned(), data_type.to_owned());
+    if !table_meta.try_from_row {
+        return impl_ast.into();
     }
 
-    fn set_column_as_static(&mut self, name: &String) {
-        self.static_columns.push(name.to_owned());
-    }
+    let try_from_row_ast = impl_try_from_row(ast, &fields, &table_meta, quote!(cassandra_macro::cdrs));
 
-    fn new_primary_key(&mut self, key: &String, position: Option<u8>) {
-        self.primary_keys.insert(position.unwrap_or(1), key.to_owned());
-    }
+    if cfg!(feature = "cdrs-tokio") {
+        let async_try_from_row_ast = impl_try_from_row(ast, &fields, &table_meta, quote!(cassandra_macro::cdrs_tokio));
 
-    fn new_cluster_key(&mut self, name: &String, order: &String, position: Option<u8>) {
-        self.cluster_keys.insert(position.unwrap_or(1), (name.to_owned(), order.to_owned()));
+        return quote!(#impl_ast #try_from_row_ast #async_try_from_row_ast).into();
     }
 
-    fn key_space(&self) -> &String {
-        &self.key_space
-    }
+    quote!(#impl_ast #try_from_row_ast).into()
+}
 
-    fn table_name(&self) -> &String {
-        &self.name
-    }
+/// Field of a column that is read from its bytes, see `raw_columns`,
+/// decoded by the `from_cql_bytes` of `raw` and converted into the field,
+/// the bytes are an error when they are not a `cql_type`
+///
+/// A row that was not read with `rows_with_raw_columns` is read
+/// as the `fallback` type of the driver converted into `raw`,
+/// without a fallback it is an error.
+fn raw_field_value(field: &syn::Field,
+                   column: &str,
+                   cql_type: &str,
+                   raw: proc_macro2::TokenStream,
+                   fallback: Option<proc_macro2::TokenStream>,
+                   driver: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let field_ident = field.ident.as_ref().unwrap();
+
+    let (ty, optional) = match option_inner_type(&field.ty) {
+        Some(inner) => (inner, true),
+        None => (&field.ty, false),
+    };
+
+    let read = if optional { quote!(by_name) } else { quote!(r_by_name) };
+
+    let not_blob = match fallback {
+        Some(fallback) if optional => quote!(Err(_) => row.by_name::<#fallback>(#column)?.map(#raw::from)),
+        Some(fallback) => quote!(Err(_) => #raw::from(row.r_by_name::<#fallback>(#column)?)),
+        None => quote!(Err(e) => return Err(e)),
+    };
+
+    let decode = quote!(
+        |raw: #driver::types::blob::Blob| #raw::from_cql_bytes(&raw.into_vec())
+            .ok_or_else(|| #driver::Error::General(format!("column `{}` is not a valid `{}`", #column, #cql_type)))
+    );
 
-    fn drop_table_cql(&self) -> String {
-        format!("DROP TABLE IF EXISTS {}.{}", self.key_space, self.name)
+    let convert = quote!(
+        |raw: #raw| <#ty as std::convert::TryFrom<#raw>>::try_from(raw)
+            .map_err(|e| #driver::Error::General(format!("column `{}`: {}", #column, e)))
+    );
+
+    if optional {
+        quote!(#field_ident: match row.#read::<#driver::types::blob::Blob>(#column) {
+            Ok(raw) => raw.map(#decode).transpose()?,
+            #not_blob,
+        }.map(#convert).transpose()?)
+    } else {
+        quote!(#field_ident: {
+            let raw = match row.#read::<#driver::types::blob::Blob>(#column) {
+                Ok(raw) => (#decode)(raw)?,
+                #not_blob,
+            };
+
+            (#convert)(raw)?
+        })
     }
+}
 
-    fn create_table_cql(&self) -> String {
-        let mut table_options = String::new();
-        let mut c_order = Vec::new();
-        let mut c_keys = Vec::new();
+/// `TryFromRow` of the `driver` crate reading each column by name,
+/// the fields that are not columns are set to their default value
+fn impl_try_from_row(ast: &syn::DeriveInput,
+                     fields: &[syn::Field],
+                     table_meta: &TableMeta,
+                     driver: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let ident = &ast.ident;
 
-        let columns: String = self.columns
-            .iter()
-            .map(|(k, t)| {
-                if self.static_columns.contains(k) {
-                    format!("{} {} STATIC", k, t)
-                } else {
-                    format!("{} {}", k, t.to_uppercase())
-                }
-            })
-            .collect::<Vec<String>>()
-            .join(",");
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
-        let opt_parts: Vec<&str> = self.table_options.split("|").filter(|opt| !opt.is_empty()).collect();
+    let columns = table_meta.column_names();
 
-        if self.cluster_keys.len() > 0 {
-            for (_, (column, order)) in self.cluster_keys.iter() {
-                c_order.push(format!("{} {}", column, order));
-                c_keys.push(format!("{}", column))
-            }
-            table_options = format!("WITH CLUSTERING ORDER BY ({})", c_order.join(","));
+    let field_values = fields.iter().map(|field| {
+        let field_ident = field.ident.as_ref().unwrap();
+        let column = field_ident.to_string();
 
-            if opt_parts.len() > 0 {
-                table_options = format!("{} AND {}", table_options, opt_parts.join(" AND "))
-            }
-        } else {
-            if opt_parts.len() > 0 {
-                table_options = format!("WITH {}", opt_parts.join(" AND "))
-            }
+        if !columns.contains(&column) {
+            return quote!(#field_ident: Default::default());
         }
 
-        let primary_keys: String = self.primary_keys
-            .iter()
-            .map(|(_, k)| format!("{}", k))
-            .collect::<Vec<String>>()
-            .join(",");
+        if table_meta.is_json(&column) {
+            return quote!(
+                #field_ident: cassandra_macro::serde_json::from_str(&row.r_by_name::<String>(#column)?)
+                    .map_err(|e| #driver::Error::General(format!("column `{}` is not valid JSON: {}", #column, e)))?
+            );
+        }
 
-        let create_stmt = format!("CREATE TABLE IF NOT EXISTS {}.{} ", self.key_space, self.name);
+        if table_meta.is_borrowed(&column) {
+            panic!("column `{}` borrows its value, a row can not be read into it, remove `derive_try_from_row`", column);
+        }
 
-        if c_keys.len() > 0 {
-            format!("{} ({}, PRIMARY KEY (({}), {}) ) {}", create_stmt, columns, primary_keys, c_keys.join(","), table_options)
-        } else {
-            format!("{} ({}, PRIMARY KEY ({}) ) {}", create_stmt, columns, primary_keys, table_options)
+        if table_meta.duration_columns.iter().any(|(c, _, _)| *c == column) {
+            return raw_field_value(field, &column, "DURATION", quote!(cassandra_macro::CqlDuration), None, &driver);
         }
-    }
-}
 
-/// Parse struct attributes
-fn extract_struct_attributes(table_meta: &mut TableMeta, fields: &Vec<syn::Field>) {
-    for field in fields {
-        let field_ident = field.ident.clone().unwrap().to_string();
+        if table_meta.varint_columns.iter().any(|(c, _)| *c == column) {
+            return raw_field_value(field, &column, "VARINT", quote!(cassandra_macro::Varint), Some(quote!(i64)), &driver);
+        }
 
-        if field.attrs.len() > 0 {
-            for attr in &field.attrs {
-                if !attr.path.to_token_stream().to_string().contains("column") {
-                    continue;
-                }
+        if table_meta.decimal_columns.iter().any(|(c, _)| *c == column) {
+            return raw_field_value(field, &column, "DECIMAL", quote!(cassandra_macro::CqlDecimal),
+                                   Some(quote!(#driver::types::decimal::Decimal)), &driver);
+        }
 
-                match attr.parse_meta() {
-                    Ok(syn::Meta::List(syn::MetaList { ref nested, .. })) => {
-                        let mut meta_items_iter = nested.iter();
+        let date_time = table_meta.date_columns.iter().map(|c| (c, quote!(cassandra_macro::CqlDate), quote!(i32), quote!(as u32), "date"))
+            .chain(table_meta.time_columns.iter().map(|c| (c, quote!(cassandra_macro::CqlTime), quote!(i64), quote!(), "time")))
+            .find(|((c, _), _, _, _, _)| *c == column);
 
-                        let mut meta_items = Vec::new();
+        if let Some(((_, optional), value, raw, cast, kind)) = date_time {
+            let invalid = quote!(#driver::Error::General(format!("column `{}` is not a valid {}", #column, #kind)));
 
-                        while let Some(n) = meta_items_iter.next() {
-                            meta_items.push(n);
-                        }
+            return if *optional {
+                quote!(#field_ident: row.by_name::<#raw>(#column)?
+                    .map(|raw| #value::from_cql_value(raw #cast).map(#value::into_inner).ok_or_else(|| #invalid))
+                    .transpose()?)
+            } else {
+                quote!(#field_ident: #value::from_cql_value(row.r_by_name::<#raw>(#column)? #cast).ok_or_else(|| #invalid)?.into_inner())
+            };
+        }
 
-                        // only validation from there on
-                        for meta_item in meta_items {
-                            match *meta_item {
-                                syn::NestedMeta::Meta(ref item) => match *item {
-                                    syn::Meta::Path(ref name) => {
-                                        match name.get_ident().unwrap().to_string().as_ref() {
-                                            "primary_key" => {
-                                                table_meta.new_primary_key(&field_ident, None);
-                                            }
-                                            "static" => {
-                                                table_meta.set_column_as_static(&field_ident);
-                                            }
-                                            _ => panic!("Unexpected validator: {:?}", name.get_ident()),
-                                        }
-                                    }
-                                    syn::Meta::NameValue(syn::MetaNameValue { ref path, ref lit, .. }) => {
-                                        let ident = path.get_ident().unwrap();
-                                        match ident.to_string().as_ref() {
-                                            "type" => {
-                                                table_meta.new_column(&field_ident.clone(), &lit_to_string(lit).unwrap_or(String::new()));
-                                            }
-                                            v => panic!("unexpected name value validator: {:?}", v),
-                                        };
-                                    }
-                                    syn::Meta::List(syn::MetaList { ref path, ref nested, .. }) => {
-                                        let mut meta_items_iter = nested.iter();
+        if let Some((_, ip, optional)) = table_meta.inet_columns.iter().find(|(c, _, _)| *c == column) {
+            let (ip_type, variant) = match ip.as_str() {
+                "Ipv4Addr" => (quote!(std::net::Ipv4Addr), quote!(std::net::IpAddr::V4)),
+                _ => (quote!(std::net::Ipv6Addr), quote!(std::net::IpAddr::V6)),
+            };
+
+            let mismatch = quote!(
+                return Err(#driver::Error::General(format!("column `{}` is not an `{}`: {}", #column, stringify!(#ip_type), ip)))
+            );
+
+            return if *optional {
+                quote!(#field_ident: match row.by_name::<std::net::IpAddr>(#column)? {
+                    Some(#variant(ip)) => Some(ip),
+                    Some(ip) => #mismatch,
+                    None => None,
+                })
+            } else {
+                quote!(#field_ident: match row.r_by_name::<std::net::IpAddr>(#column)? {
+                    #variant(ip) => ip,
+                    ip => #mismatch,
+                })
+            };
+        }
 
-                                        let mut meta_items: Vec<&NestedMeta> = Vec::new();
+        match table_meta.blob_columns.iter().find(|(c, _)| *c == column) {
+            Some((_, false)) => return quote!(#field_ident: row.r_by_name::<#driver::types::blob::Blob>(#column)?.into_vec()),
+            Some((_, true)) => {
+                return quote!(#field_ident: row.by_name::<#driver::types::blob::Blob>(#column)?.map(#driver::types::blob::Blob::into_vec))
+            }
+            None => {}
+        }
 
-                                        while let Some(n) = meta_items_iter.next() {
-                                            meta_items.push(n);
-                                        }
+        match option_inner_type(&field.ty) {
+            Some(inner) => quote!(#field_ident: row.by_name::<#inner>(#column)?),
+            None => {
+                let ty = &field.ty;
+                quote!(#field_ident: row.r_by_name::<#ty>(#column)?)
+            }
+        }
+    });
 
-                                        let ident = path.get_ident().unwrap();
-                                        match ident.to_string().as_ref() {
-                                            "cluster_key" => {
-                                                let (order, position) = extract_cluster_properties(&meta_items);
+    quote!(
+        impl #impl_generics #driver::frame::TryFromRow for #ident #ty_generics #where_clause {
+            fn try_from_row(row: #driver::types::rows::Row) -> Result<Self, #driver::Error> {
+                use #driver::types::ByName;
 
-                                                table_meta.new_cluster_key(&field_ident, &order, Some(position));
-                                            }
-                                            "compound_key" => {
-                                                let (_, position) = extract_cluster_properties(&meta_items);
+                Ok(#ident {
+                    #(#field_values),*
+                })
+            }
+        }
+    )
+}
 
-                                                table_meta.new_primary_key(&field_ident, Some(position))
-                                            }
-                                            v => panic!("unexpected list validator: {:?}", v),
-                                        }
-                                    }
-                                },
-                                _ => unreachable!("Found a non Meta while looking for validators"),
-                            };
+fn impl_cassandra_udt(ast: &syn::DeriveInput) -> TokenStream {
+    let mut udt_meta = UdtMeta::with_name(&pascal_case_to_snake_case(&ast.ident.to_string()));
+
+    let fields: Vec<syn::Field> = match ast.data {
+        syn::Data::Struct(syn::DataStruct { ref fields, .. }) => {
+            if fields.iter().any(|field| field.ident.is_none()) {
+                panic!("struct has unnamed fields");
+            }
+            fields.iter().cloned().collect()
+        }
+        _ => panic!("#[derive(CassandraUdt)] can only be used with structs"),
+    };
+
+    extract_udt_fields(&mut udt_meta, &fields);
+
+    for attr in ast.attrs.iter().filter(|attr| attr.path.is_ident("udt")) {
+        match attr.parse_meta() {
+            Ok(syn::Meta::List(syn::MetaList { ref nested, .. })) => {
+                for meta_item in nested.iter() {
+                    match *meta_item {
+                        syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("derive_try_from_udt") => {
+                            udt_meta.try_from_udt = true;
                         }
+                        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue { ref path, ref lit, .. }))
+                        if path.is_ident("keyspace") => {
+                            udt_meta.key_space = lit_to_string(lit).unwrap_or_default();
+                        }
+                        _ => panic!("unknown argument for attribute `udt`, expected `keyspace` or `derive_try_from_udt`"),
                     }
-                    Ok(syn::Meta::NameValue(_)) => panic!("Unexpected name=value argument"),
-                    Err(e) => unreachable!(
-                        "Got something other than a list of attributes while checking field `{}`: {:?}",
-                        field_ident, e
-                    ),
-                    _ => {}
                 }
             }
+            _ => panic!("Got something other than a list of attributes while checking udt attribute"),
         }
     }
-}
 
-fn lit_to_string(lit: &syn::Lit) -> Option<String> {
-    match *lit {
-        syn::Lit::Str(ref s) => Some(s.value()),
-        _ => None,
-    }
-}
+    let create_type_cql = udt_meta.create_type_cql();
+    let drop_type_cql = udt_meta.drop_type_cql();
+    let key_space = &udt_meta.key_space;
+    let type_name = &udt_meta.name;
+    let field_names = udt_meta.fields.iter().map(|(name, _, _)| name);
 
-fn lit_to_int(lit: &syn::Lit) -> Option<i64> {
-    match *lit {
-        syn::Lit::Int(ref s) => Some(s.base10_parse().unwrap()),
-        _ => None,
+    let ident = &ast.ident;
+
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let impl_ast = quote!(
+        impl #impl_generics cassandra_macro::CassandraUdt for #ident #ty_generics #where_clause {
+            fn create_type_cql() -> &'static str {
+                #create_type_cql
+            }
+
+            fn drop_type_cql() -> &'static str {
+                #drop_type_cql
+            }
+
+            fn key_space() -> &'static str {
+                #key_space
+            }
+
+            fn type_name() -> &'static str {
+                #type_name
+            }
+
+            fn fields() -> &'static [&'static str] {
+                &[#(#field_names),*]
+            }
+        }
+    );
+
+    if !udt_meta.try_from_udt {
+        return impl_ast.into();
     }
+
+    let try_from_udt_ast = impl_try_from_udt(ast, &fields, &udt_meta);
+
+    quote!(#impl_ast #try_from_udt_ast).into()
 }
 
-fn extract_cluster_properties(meta_items: &Vec<&syn::NestedMeta>) -> (String, u8) {
-    let mut order = String::from("DESC");
-    let mut position = 1;
+/// `TryFromUDT` reading each field by name, the nested user
+/// defined types are read with their own `TryFromUDT`
+fn impl_try_from_udt(ast: &syn::DeriveInput, fields: &[syn::Field], udt_meta: &UdtMeta) -> proc_macro2::TokenStream {
+    let ident = &ast.ident;
 
-    for meta_item in meta_items {
-        if let syn::NestedMeta::Meta(ref item) = **meta_item {
-            if let syn::Meta::NameValue(syn::MetaNameValue { ref path, ref lit, .. }) = *item {
-                let ident = path.get_ident().unwrap();
-                match ident.to_string().as_ref() {
-                    "order" => {
-                        order = lit_to_string(lit).unwrap_or(String::from("DESC"))
-                    }
-                    "position" => {
-                        position = lit_to_int(lit).unwrap_or(1) as u8;
-                    }
-                    v => panic!("unknown argument `{}` for column `cluster_key`", v)
-                }
-            } else {
-                panic!("unexpected item while parsing `cluster_key` column of field")
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let field_values = fields.iter().map(|field| {
+        let field_ident = field.ident.as_ref().unwrap();
+        let name = field_ident.to_string();
+
+        let nested = match udt_meta.fields.iter().find(|(n, _, _)| n.eq(&name)) {
+            Some((_, _, nested)) => *nested,
+            None => return quote!(#field_ident: Default::default()),
+        };
+
+        match (option_inner_type(&field.ty), nested) {
+            (Some(inner), true) => quote!(
+                #field_ident: udt.by_name::<cassandra_macro::cdrs::types::udt::UDT>(#name)?
+                    .map(<#inner as cassandra_macro::cdrs::frame::TryFromUDT>::try_from_udt)
+                    .transpose()?
+            ),
+            (Some(inner), false) => quote!(#field_ident: udt.by_name::<#inner>(#name)?),
+            (None, true) => {
+                let ty = &field.ty;
+                quote!(#field_ident: <#ty as cassandra_macro::cdrs::frame::TryFromUDT>::try_from_udt(udt.r_by_name::<cassandra_macro::cdrs::types::udt::UDT>(#name)?)?)
+            }
+            (None, false) => {
+                let ty = &field.ty;
+                quote!(#field_ident: udt.r_by_name::<#ty>(#name)?)
+            }
+        }
+    });
+
+    quote!(
+        impl #impl_generics cassandra_macro::cdrs::frame::TryFromUDT for #ident #ty_generics #where_clause {
+            fn try_from_udt(udt: cassandra_macro::cdrs::types::udt::UDT) -> Result<Self, cassandra_macro::cdrs::Error> {
+                use cassandra_macro::cdrs::types::ByName;
+
+                Ok(#ident {
+                    #(#field_values),*
+                })
             }
         }
+    )
+}
+
+/// CQL type of a column without a `type`, from the type of its field
+///
+/// `Option<T>` is the type of `T`, `Vec<T>` is a list, `HashSet<T>`
+/// and `BTreeSet<T>` a set and `HashMap<K, V>` a map, the items of
+/// sets and the keys of maps must be native types. Collections nested
+/// in lists and maps are frozen, as Cassandra requires.
+fn infer_cql_type(ty: &syn::Type) -> Option<String> {
+    let segment = match *ty {
+        syn::Type::Path(syn::TypePath { ref path, .. }) => path.segments.last()?,
+        syn::Type::Reference(syn::TypeReference { ref elem, .. }) => return match **elem {
+            syn::Type::Slice(syn::TypeSlice { ref elem, .. }) if elem.to_token_stream().to_string() == "u8" => Some(String::from("BLOB")),
+            _ => infer_native_type(elem).map(String::from),
+        },
+        _ => return None,
+    };
+
+    let arguments: Vec<&syn::Type> = match segment.arguments {
+        syn::PathArguments::AngleBracketed(ref arguments) => arguments.args
+            .iter()
+            .filter_map(|argument| match argument {
+                syn::GenericArgument::Type(ref ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    match (segment.ident.to_string().as_str(), arguments.as_slice()) {
+        ("Option", [inner]) => infer_cql_type(inner),
+        ("Vec", [item]) if item.to_token_stream().to_string() == "u8" => Some(String::from("BLOB")),
+        ("Vec", [item]) => Some(format!("LIST<{}>", infer_nested_cql_type(item)?)),
+        ("HashSet", [item]) | ("BTreeSet", [item]) => Some(format!("SET<{}>", infer_native_type(item)?)),
+        ("HashMap", [key, value]) => {
+            let key_type = infer_native_type(key)
+                .unwrap_or_else(|| panic!("unsupported map key type `{}`, keys must be native types", key.to_token_stream()));
+
+            Some(format!("MAP<{}, {}>", key_type, infer_nested_cql_type(value)?))
+        }
+        _ => infer_native_type(ty).map(String::from),
     }
+}
 
-    (order, position)
+/// Same as `infer_cql_type`, for the items of a collection,
+/// a nested collection is `FROZEN<...>`
+fn infer_nested_cql_type(ty: &syn::Type) -> Option<String> {
+    let cql_type = infer_cql_type(ty)?;
+
+    if ["LIST<", "SET<", "MAP<"].iter().any(|collection| cql_type.starts_with(collection)) {
+        Some(format!("FROZEN<{}>", cql_type))
+    } else {
+        Some(cql_type)
+    }
 }
 
-fn extract_table_properties(meta_items: &Vec<&syn::NestedMeta>) -> (String, String) {
-    let mut keyspace = String::new();
-    let mut options = String::new();
+/// Container and type `T` of a field declared as
+/// `Vec<T>`, `HashSet<T>` or `BTreeSet<T>`
+fn collection_item_type(ty: &syn::Type) -> Option<(&'static str, &syn::Type)> {
+    if let syn::Type::Path(syn::TypePath { ref path, .. }) = *ty {
+        let segment = path.segments.last()?;
 
-    for meta_item in meta_items {
-        if let syn::NestedMeta::Meta(ref item) = **meta_item {
-            if let syn::Meta::NameValue(syn::MetaNameValue { ref path, ref lit, .. }) = *item {
-                let ident = path.get_ident().unwrap();
-                match ident.to_string().as_ref() {
-                    "keyspace" => {
-                        keyspace = lit_to_string(lit).unwrap_or(String::new())
-                    }
-                    "options" => {
-                        options = lit_to_string(lit).unwrap_or(String::new());
-                    }
-                    v => panic!("unknown argument `{}` for column `table`", v)
-                }
-            } else {
-                panic!("unexpected item while parsing `table` column of field")
+        let container = ["Vec", "HashSet", "BTreeSet"].iter().find(|c| segment.ident == c)?;
+
+        if let syn::PathArguments::AngleBracketed(ref arguments) = segment.arguments {
+            if let Some(syn::GenericArgument::Type(ref item)) = arguments.args.first() {
+                return Some((container, item));
             }
         }
     }
 
-    (keyspace, options)
+    None
 }
 
-const OFFSET: u8 = 32;
-const UNDERSCORE: u8 = 95;
+/// Type `T` of a field declared as `&'a T`
+fn borrowed_type(ty: &syn::Type) -> Option<&syn::Type> {
+    match *ty {
+        syn::Type::Reference(syn::TypeReference { ref elem, .. }) => Some(elem),
+        _ => None,
+    }
+}
 
-fn pascal_case_to_snake_case(table_name: &String) -> String {
-    let word_size = table_name.len();
+/// Owned type of the `<Struct>Key` field of a borrowed key,
+/// a `String` of a `&'a str` and a `Vec<u8>` of a `&'a [u8]`
+fn owned_key_type(ty: &syn::Type) -> Option<syn::Type> {
+    let owned = match borrowed_type(ty)?.to_token_stream().to_string().replace(' ', "").as_str() {
+        "str" => "String",
+        "[u8]" => "Vec<u8>",
+        _ => return None,
+    };
 
-    if word_size < 2 {
-        return String::from(table_name);
-    }
+    Some(syn::parse_str(owned).unwrap())
+}
 
-    let mut counter = 1;
-    let chars = table_name.as_bytes();
-    let mut sk_table_name: Vec<u8> = Vec::new();
+/// Native CQL type of a scalar field
+fn infer_native_type(ty: &syn::Type) -> Option<&'static str> {
+    let (path, segment) = match *ty {
+        syn::Type::Path(syn::TypePath { ref path, .. }) => (path, path.segments.last()?),
+        syn::Type::Reference(syn::TypeReference { ref elem, .. }) => return infer_native_type(elem),
+        _ => return None,
+    };
 
-    if chars[0] < 90 {
-        sk_table_name.push(chars[0] + OFFSET);
-    } else {
-        sk_table_name.push(chars[0]);
+    if !segment.arguments.is_empty() {
+        return None;
     }
 
-    while counter < word_size {
-        let current = chars[counter];
+    match segment.ident.to_string().as_str() {
+        "String" | "str" => Some("TEXT"),
+        "i8" => Some("TINYINT"),
+        "i16" => Some("SMALLINT"),
+        "i32" => Some("INT"),
+        "i64" => Some("BIGINT"),
+        "f32" => Some("FLOAT"),
+        "f64" => Some("DOUBLE"),
+        "bool" => Some("BOOLEAN"),
+        "Uuid" => Some("UUID"),
+        "IpAddr" | "Ipv4Addr" | "Ipv6Addr" => Some("INET"),
+        "NaiveDate" => Some("DATE"),
+        "NaiveTime" => Some("TIME"),
+        "Blob" => Some("BLOB"),
+        "Decimal" | "BigDecimal" | "CqlDecimal" => Some("DECIMAL"),
+        "CqlDuration" => Some("DURATION"),
+        "Duration" if is_std_duration(path) => Some("DURATION"),
+        "BigInt" => Some("VARINT"),
+        _ => None,
+    }
+}
 
-        if current < 90 {
-            sk_table_name.push(UNDERSCORE);
-            sk_table_name.push(current + OFFSET)
-        } else {
-            sk_table_name.push(current);
+/// If the path is a `std::time::Duration`, a `Duration` without
+/// a path is taken to be one, e.g. not a `chrono::Duration`
+fn is_std_duration(path: &syn::Path) -> bool {
+    let segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+
+    matches!(segments.iter().map(String::as_str).collect::<Vec<&str>>().as_slice(),
+             ["Duration"] | ["std", "time", "Duration"] | ["core", "time", "Duration"])
+}
+
+/// If the field is declared as `serde_json::Value`
+fn is_json_value(ty: &syn::Type) -> bool {
+    match *ty {
+        syn::Type::Path(syn::TypePath { ref path, .. }) => {
+            let segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+
+            segments.ends_with(&[String::from("serde_json"), String::from("Value")])
         }
+        _ => false,
+    }
+}
 
-        counter += 1;
+/// Type `T` of a field declared as `Option<T>`
+fn option_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
+    if let syn::Type::Path(syn::TypePath { ref path, .. }) = *ty {
+        let segment = path.segments.last()?;
+
+        if segment.ident == "Option" {
+            if let syn::PathArguments::AngleBracketed(ref arguments) = segment.arguments {
+                if let Some(syn::GenericArgument::Type(ref inner)) = arguments.args.first() {
+                    return Some(inner);
+                }
+            }
+        }
     }
 
-    String::from_utf8(sk_table_name).unwrap()
+    None
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::pascal_case_to_snake_case;
+struct TableMeta {
+    name: String,
+    key_space: String,
+    table_options: String,
+    /// `comment` option, appended to the options unless they already have one
+    comment: Option<String>,
+    /// Columns and their types, in the order the fields were declared
+    columns: Vec<(String, String)>,
+    static_columns: Vec<String>,
+    /// Columns with a finder statement, and if it allows filtering
+    queryable_columns: Vec<(String, bool)>,
+    /// Columns moved out of the entity by the `into_` queries
+    moved_columns: Vec<String>,
+    /// `serde_json::Value` columns, bound as their `TEXT` serialization
+    json_columns: Vec<String>,
+    /// Columns marked `json`, bound as their `TEXT` serialization with serde
+    serialized_columns: Vec<String>,
+    /// `Vec<u8>` columns, bound as a `BLOB` instead of a list, and if they are optional
+    blob_columns: Vec<(String, bool)>,
+    /// `&'a str` and `&'a [u8]` columns, bound without cloning the entity, if they borrow bytes and if they are optional
+    borrowed_columns: Vec<(String, bool, bool)>,
+    /// `HashSet` and `BTreeSet` columns, bound as a list of their items, and if they are optional
+    set_columns: Vec<(String, bool)>,
+    /// `DURATION` columns, bound as a `CqlDuration`, if their field is a `std::time::Duration` and if they are optional
+    duration_columns: Vec<(String, bool, bool)>,
+    /// `BigInt` and `Varint` columns, bound as a `Varint`, and if they are optional
+    varint_columns: Vec<(String, bool)>,
+    /// `BigDecimal` and `CqlDecimal` columns, bound as a `CqlDecimal`, and if they are optional
+    decimal_columns: Vec<(String, bool)>,
+    /// `Ipv4Addr` and `Ipv6Addr` columns, bound as an `IpAddr`, their field type and if they are optional
+    inet_columns: Vec<(String, String, bool)>,
+    /// `NaiveDate` columns, bound as a `CqlDate`, and if they are optional
+    date_columns: Vec<(String, bool)>,
+    /// `NaiveTime` columns, bound as a `CqlTime`, and if they are optional
+    time_columns: Vec<(String, bool)>,
+    /// Columns generated by Cassandra on insert, and their CQL expression, e.g. `now()`
+    generated_columns: Vec<(String, String)>,
+    /// Columns with a secondary index, and its name unless it is the default one
+    indexes: Vec<(String, Option<String>)>,
+    /// Materialized views, their partition keys and cluster keys
+    views: Vec<(String, Vec<String>, Vec<String>)>,
+    primary_keys: BTreeMap<u8, String>,
+    cluster_keys: BTreeMap<u8, (String, Order)>,
+    /// If `TryFromRow` must also be implemented
+    try_from_row: bool,
+    /// If the columns without a `type` get the CQL type of their field, `infer_types`
+    infer_types: bool,
+    /// Operations marked `idempotent` in the `table` attribute
+    idempotence: Idempotence,
+}
+
+/// @TODO Refactor duplicated code
+impl TableMeta {
+    fn with_name(name: &String) -> Self {
+        TableMeta {
+            name: name.to_owned(),
+            key_space: String::new(),
+            table_options: String::new(),
+            comment: None,
+            columns: Vec::new(),
+            static_columns: Vec::new(),
+            queryable_columns: Vec::new(),
+            moved_columns: Vec::new(),
+            json_columns: Vec::new(),
+            serialized_columns: Vec::new(),
+            blob_columns: Vec::new(),
+            borrowed_columns: Vec::new(),
+            set_columns: Vec::new(),
+            duration_columns: Vec::new(),
+            varint_columns: Vec::new(),
+            decimal_columns: Vec::new(),
+            inet_columns: Vec::new(),
+            date_columns: Vec::new(),
+            time_columns: Vec::new(),
+            generated_columns: Vec::new(),
+            indexes: Vec::new(),
+            views: Vec::new(),
+            primary_keys: BTreeMap::new(),
+            cluster_keys: BTreeMap::new(),
+            try_from_row: false,
+            infer_types: false,
+            idempotence: Idempotence::default(),
+        }
+    }
+
+    fn delete_stmt(&self) -> (String, proc_macro2::TokenStream) {
+        (self.cql_table().delete_cql(), self.values_of(&self.full_key_columns()))
+    }
+
+    fn delete_partition_stmt(&self) -> (String, proc_macro2::TokenStream) {
+        (self.cql_table().delete_partition_cql(), self.values_of(&self.primary_key_columns()))
+    }
+
+    fn exists_stmt(&self) -> (String, proc_macro2::TokenStream) {
+        (self.cql_table().exists_cql(), self.values_of(&self.full_key_columns()))
+    }
+
+    fn count_partition_stmt(&self) -> (String, proc_macro2::TokenStream) {
+        (self.cql_table().count_partition_cql(), self.values_of(&self.primary_key_columns()))
+    }
+
+    /// Non key columns, but the `generated` columns, which are only set on insert
+    fn updatable_columns(&self) -> Vec<String> {
+        self.cql_table().updatable_columns().into_iter().map(String::from).collect()
+    }
+
+    fn update_stmt(&self) -> Option<(String, proc_macro2::TokenStream)> {
+        let values = [self.updatable_columns(), self.full_key_columns()]
+            .concat()
+            .iter()
+            .map(|c| self.value_of(c))
+            .collect::<Vec<String>>()
+            .join(",");
+
+        self.cql_table().update_cql().map(|stmt| (stmt, proc_macro2::TokenStream::from_str(values.as_str()).unwrap()))
+    }
+
+    /// Insert of all columns, the `generated` columns are
+    /// set to their expression instead of being bound
+    fn store_stmt(&self) -> (String, proc_macro2::TokenStream) {
+        let values = self.stored_columns().iter().map(|c| self.value_of(c)).collect::<Vec<String>>();
+
+        (self.cql_table().insert_cql(), proc_macro2::TokenStream::from_str(values.join(",").as_str()).unwrap())
+    }
+
+    /// Columns bound by the insert, all but the `generated` columns
+    fn stored_columns(&self) -> Vec<String> {
+        self.cql_table().stored_columns().into_iter().map(String::from).collect()
+    }
+
+    /// `store_query_without_hook` and `update_query_without_hook`
+    /// returning the errors of the columns that can fail to convert
+    ///
+    /// The insert of a counter table is an error.
+    fn try_queries(&self, table: &CqlTable) -> proc_macro2::TokenStream {
+        let try_store_query = if table.is_counter_table() {
+            let counter_error = self.counter_table_error();
+
+            quote!(Err(cassandra_macro::Error::InvalidColumn(String::from(#counter_error))))
+        } else {
+            let (store_stmt, store_values) = self.store_stmt();
+            let store_idempotent = self.is_store_idempotent();
+
+            quote!(Ok(cassandra_macro::StoreQuery::for_table::<Self>(std::borrow::Cow::Borrowed(#store_stmt), cassandra_macro::QueryValues::SimpleValues(vec![#store_values])).with_idempotent(#store_idempotent)))
+        };
+
+        let update_stmt = self.update_stmt().map(|(stmt, _)| stmt).unwrap_or_default();
+        let no_updatable_columns_error = self.no_updatable_columns_error(table);
+        let update_values = self.values_of(&[self.updatable_columns(), self.full_key_columns()].concat());
+        let update_idempotent = self.idempotence.update;
+
+        quote!(
+            fn store_query_without_hook(&self) -> Result<cassandra_macro::StoreQuery, cassandra_macro::Error> {
+                #try_store_query
+            }
+
+            fn update_query_without_hook(&self) -> Result<cassandra_macro::UpdateQuery, cassandra_macro::Error> {
+                if #update_stmt.is_empty() {
+                    return Err(cassandra_macro::TableWithNoUpdatableColumnsError::new(String::from(#no_updatable_columns_error)).into());
+                }
+
+                Ok(cassandra_macro::UpdateQuery::for_table::<Self>(std::borrow::Cow::Borrowed(#update_stmt), cassandra_macro::QueryValues::SimpleValues(vec![#update_values])).with_idempotent(#update_idempotent))
+            }
+        )
+    }
+
+    /// If the inserts are idempotent, an insert with `generated`
+    /// columns, such as `now()`, writes a new value on each retry
+    fn is_store_idempotent(&self) -> bool {
+        self.idempotence.store && self.generated_columns.is_empty()
+    }
+
+    fn counter_table_error(&self) -> String {
+        format!("Table {} has counter columns, it can not be inserted, use counter_delta_query", self.name)
+    }
+
+    /// Error of `update_query` when the table only has keys, or counters that only are updated by a delta
+    fn no_updatable_columns_error(&self, table: &CqlTable) -> String {
+        if table.is_counter_table() {
+            format!("Table {} only has counter columns, use counter_delta_query", self.name)
+        } else {
+            format!("Table {} does not have any updatable column", self.name)
+        }
+    }
+
+    /// Values of the insert, moving the `moved` fields out of the entity
+    fn store_moved_values(&self) -> proc_macro2::TokenStream {
+        self.moved_values(&self.stored_columns())
+    }
+
+    /// Values of the update, moving the `moved` fields out of the entity
+    fn update_moved_values(&self) -> proc_macro2::TokenStream {
+        self.moved_values(&[self.updatable_columns(), self.full_key_columns()].concat())
+    }
+
+    /// Values of the given columns, the `moved` fields are
+    /// moved instead of cloned
+    fn moved_values(&self, columns: &[String]) -> proc_macro2::TokenStream {
+        let values = columns.iter()
+            .map(|c| if self.moved_columns.contains(c) && !self.is_json(c) && !self.is_borrowed(c) {
+                self.bind(c, format!("self.{}", c))
+            } else {
+                self.value_of(c)
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+
+        proc_macro2::TokenStream::from_str(values.as_str()).unwrap()
+    }
+
+    /// Values of the given columns, cloned from the entity
+    fn values_of(&self, columns: &[String]) -> proc_macro2::TokenStream {
+        let values = columns.iter()
+            .map(|c| self.value_of(c))
+            .collect::<Vec<String>>()
+            .join(",");
+
+        proc_macro2::TokenStream::from_str(values.as_str()).unwrap()
+    }
+
+    /// Values of the keys, cloned from the fields of the `<Struct>Key`
+    fn key_values_of_key(&self) -> proc_macro2::TokenStream {
+        self.values_of_key(&self.full_key_columns())
+    }
+
+    /// Values of the partition keys of `key`, see `key_values_of_key`
+    fn partition_values_of_key(&self) -> proc_macro2::TokenStream {
+        self.values_of_key(&self.primary_key_columns())
+    }
+
+    fn values_of_key(&self, keys: &[String]) -> proc_macro2::TokenStream {
+        let values = keys.iter()
+            .map(|k| self.bind(k, format!("key.{}.clone()", k)))
+            .collect::<Vec<String>>()
+            .join(",");
+
+        proc_macro2::TokenStream::from_str(values.as_str()).unwrap()
+    }
+
+    /// Value of the column, cloned from the entity
+    ///
+    /// The error of a column that can fail to convert, see
+    /// `fallible_value_of`, is returned with `?`, the value of such
+    /// a column only is in functions returning a
+    /// `Result<_, cassandra_macro::Error>`. A borrowed `&str` is bound
+    /// as it is, a borrowed `&[u8]` is copied once into the `Blob` of
+    /// the driver.
+    fn value_of(&self, column: &str) -> String {
+        if let Some(value) = self.fallible_value_of(column) {
+            return format!("{}?", value);
+        }
+
+        if let Some((_, bytes, optional)) = self.borrowed_columns.iter().find(|(c, _, _)| c == column) {
+            return match (bytes, optional) {
+                (false, _) => self.bind(column, format!("self.{}", column)),
+                (true, false) => self.bind(column, format!("self.{}.to_vec()", column)),
+                (true, true) => self.bind(column, format!("self.{}.map(<[u8]>::to_vec)", column)),
+            };
+        }
+
+        if self.json_columns.contains(&column.to_string()) {
+            format!("cassandra_macro::Value::from(self.{}.to_string())", column)
+        } else {
+            self.bind(column, format!("self.{}.clone()", column))
+        }
+    }
+
+    /// Value of a column that can fail to convert, as a
+    /// `Result<cassandra_macro::Value, cassandra_macro::Error>`
+    ///
+    /// A `json` column serialized by serde fails when its field can
+    /// not be serialized, a `std::time::Duration` when its days do not
+    /// fit in a `CqlDuration` and a decimal when its scale does not fit
+    /// in an `i32`.
+    fn fallible_value_of(&self, column: &str) -> Option<String> {
+        if self.serialized_columns.contains(&column.to_string()) {
+            return Some(format!(
+                "cassandra_macro::serde_json::to_string(&self.{}).map(cassandra_macro::Value::from).map_err(cassandra_macro::Error::from)",
+                column));
+        }
+
+        let try_from = "<cassandra_macro::cdrs::types::value::Bytes as std::convert::TryFrom<cassandra_macro::CqlDecimal>>::try_from";
+
+        match self.decimal_columns.iter().find(|(c, _)| c == column) {
+            Some((_, false)) => {
+                return Some(format!("{}(cassandra_macro::CqlDecimal::from(self.{}.clone())).map(cassandra_macro::Value::from)",
+                                    try_from, column));
+            }
+            Some((_, true)) => {
+                return Some(format!("self.{}.clone().map(|d| {}(cassandra_macro::CqlDecimal::from(d))).transpose().map(cassandra_macro::Value::from)",
+                                    column, try_from));
+            }
+            None => {}
+        }
+
+        let try_from = "<cassandra_macro::CqlDuration as std::convert::TryFrom<std::time::Duration>>::try_from";
+
+        match self.duration_columns.iter().find(|(c, std, _)| c == column && *std) {
+            Some((_, _, false)) => Some(format!("{}(self.{}).map(cassandra_macro::Value::from)", try_from, column)),
+            Some((_, _, true)) => Some(format!("self.{}.map({}).transpose().map(cassandra_macro::Value::from)", column, try_from)),
+            None => None,
+        }
+    }
+
+    /// If a column can fail to convert, see `fallible_value_of`
+    fn has_fallible_columns(&self) -> bool {
+        self.columns.iter().any(|(c, _)| self.fallible_value_of(c).is_some())
+    }
+
+    /// Value of the column from the owned field
+    ///
+    /// Sets are serialized as lists, which is the same
+    /// serialization in the CQL binary protocol.
+    fn bind(&self, column: &str, field: String) -> String {
+        match self.set_columns.iter().find(|(c, _)| c == column) {
+            Some((_, false)) => {
+                return format!("cassandra_macro::Value::from({}.into_iter().collect::<Vec<_>>())", field);
+            }
+            Some((_, true)) => {
+                return format!("cassandra_macro::Value::from({}.map(|set| set.into_iter().collect::<Vec<_>>()))", field);
+            }
+            None => {}
+        }
+
+        match self.varint_columns.iter().find(|(c, _)| c == column) {
+            Some((_, false)) => return format!("cassandra_macro::Value::from(cassandra_macro::Varint::from({}))", field),
+            Some((_, true)) => return format!("cassandra_macro::Value::from({}.map(cassandra_macro::Varint::from))", field),
+            None => {}
+        }
+
+        match self.inet_columns.iter().find(|(c, _, _)| c == column) {
+            Some((_, _, false)) => return format!("cassandra_macro::Value::from(std::net::IpAddr::from({}))", field),
+            Some((_, _, true)) => return format!("cassandra_macro::Value::from({}.map(std::net::IpAddr::from))", field),
+            None => {}
+        }
+
+        for (columns, value) in [(&self.date_columns, "CqlDate"), (&self.time_columns, "CqlTime")].iter() {
+            match columns.iter().find(|(c, _)| c == column) {
+                Some((_, false)) => return format!("cassandra_macro::Value::from(cassandra_macro::{}::from({}))", value, field),
+                Some((_, true)) => return format!("cassandra_macro::Value::from({}.map(cassandra_macro::{}::from))", field, value),
+                None => {}
+            }
+        }
+
+        match self.blob_columns.iter().find(|(c, _)| c == column) {
+            Some((_, false)) => format!("cassandra_macro::Value::from(cassandra_macro::cdrs::types::blob::Blob::from({}))", field),
+            Some((_, true)) => format!("cassandra_macro::Value::from({}.map(cassandra_macro::cdrs::types::blob::Blob::from))", field),
+            None => format!("cassandra_macro::Value::from({})", field),
+        }
+    }
+
+    /// If the field of the column is a `&'a str` or a `&'a [u8]`
+    fn is_borrowed(&self, column: &str) -> bool {
+        self.borrowed_columns.iter().any(|(c, _, _)| c == column)
+    }
+
+    /// If the column is stored as the JSON serialization of the field
+    fn is_json(&self, column: &str) -> bool {
+        let column = column.to_string();
+
+        self.json_columns.contains(&column) || self.serialized_columns.contains(&column)
+    }
+
+    /// Arms of `column_value`, a column that can fail to convert
+    /// does not have a value when it fails
+    fn column_value_arms(&self) -> proc_macro2::TokenStream {
+        let arms = self.columns.iter().map(|(c, _)| match self.fallible_value_of(c) {
+            Some(value) => format!("\"{}\" => {}.ok(),", c, value),
+            None => format!("\"{}\" => Some({}),", c, self.value_of(c)),
+        }).collect::<Vec<String>>().join("\n");
+
+        proc_macro2::TokenStream::from_str(arms.as_str()).unwrap()
+    }
+
+    /// `try_column_value` returning the errors of the columns that
+    /// can fail to convert, the default of the trait is kept when
+    /// there are no such columns
+    fn try_column_value(&self) -> proc_macro2::TokenStream {
+        if !self.has_fallible_columns() {
+            return proc_macro2::TokenStream::new();
+        }
+
+        let arms = self.columns.iter()
+            .map(|(c, _)| format!("\"{}\" => Ok(Some({})),", c, self.value_of(c)))
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let arms = proc_macro2::TokenStream::from_str(arms.as_str()).unwrap();
+
+        quote!(
+            fn try_column_value(&self, column: &str) -> Result<Option<cassandra_macro::Value>, cassandra_macro::Error> {
+                match column {
+                    #arms
+                    _ => Ok(None),
+                }
+            }
+        )
+    }
+
+    /// If `store_query` and `update_query` always have their values,
+    /// and the table is not a counter table, see `InfallibleQueries`
+    fn has_infallible_queries(&self, table: &CqlTable) -> bool {
+        !self.has_fallible_columns() && !table.is_counter_table()
+    }
+
+    /// `raw_columns` of the `DURATION`, `VARINT` and `DECIMAL` columns, which `TryFromRow`
+    /// reads from their bytes, the default of the trait is kept
+    /// without `derive_try_from_row` or without such columns
+    fn raw_columns(&self) -> proc_macro2::TokenStream {
+        let columns: Vec<&String> = self.columns.iter()
+            .map(|(c, _)| c)
+            .filter(|c| self.duration_columns.iter().any(|(d, _, _)| d == *c)
+                || self.varint_columns.iter().any(|(v, _)| v == *c)
+                || self.decimal_columns.iter().any(|(d, _)| d == *c))
+            .collect();
+
+        if !self.try_from_row || columns.is_empty() {
+            return proc_macro2::TokenStream::new();
+        }
+
+        quote!(
+            fn raw_columns() -> &'static [&'static str] {
+                &[#(#columns),*]
+            }
+        )
+    }
+
+    fn column_type_arms(&self) -> proc_macro2::TokenStream {
+        let arms = self.columns.iter().map(|(c, t)| {
+            format!("\"{}\" => Some({:?}),", c, t)
+        }).collect::<Vec<String>>().join("\n");
+
+        proc_macro2::TokenStream::from_str(arms.as_str()).unwrap()
+    }
+
+    fn set_key_space(&mut self, key_space: &String) {
+        self.key_space = key_space.to_owned();
+    }
+
+    fn set_table_options(&mut self, table_options: &String) {
+        self.table_options = table_options.to_owned();
+    }
+
+    /// The `comment` option, unless the options already have one
+    fn set_comment(&mut self, comment: &str) {
+        self.comment = Some(comment.to_owned());
+    }
+
+    fn new_column(&mut self, name: &String, data_type: &String) {
+        self.columns.push((name.to_owned(), data_type.to_owned()));
+    }
+
+    fn set_column_as_static(&mut self, name: &String) {
+        self.static_columns.push(name.to_owned());
+    }
+
+    fn set_column_as_moved(&mut self, name: &String) {
+        self.moved_columns.push(name.to_owned());
+    }
+
+    /// The column holds a `serde_json::Value`, it must be a
+    /// `TEXT` column that is not a key
+    fn set_column_as_json(&mut self, name: &String) {
+        if !self.columns.iter().any(|(c, _)| c == name) {
+            return;
+        }
+
+        self.validate_json_column(name);
+
+        self.json_columns.push(name.to_owned());
+    }
+
+    /// The column is marked `json`, it is declared as `TEXT`
+    /// when the `column` attribute has no `type`
+    fn set_column_as_serialized(&mut self, name: &String) {
+        if !self.columns.iter().any(|(c, _)| c == name) {
+            self.new_column(name, &String::from("TEXT"));
+        }
+
+        self.validate_json_column(name);
+
+        self.serialized_columns.push(name.to_owned());
+    }
+
+    /// A `&'a str` field must be a `TEXT`, `VARCHAR` or `ASCII` and
+    /// a `&'a [u8]` field a `BLOB`, no other type can be borrowed
+    fn validate_borrowed_column(&mut self, name: &String, ty: &syn::Type) {
+        let (elem, optional) = match option_inner_type(ty) {
+            Some(inner) => (borrowed_type(inner), true),
+            None => (borrowed_type(ty), false),
+        };
+
+        let elem = match elem {
+            Some(elem) => elem,
+            None => return,
+        };
+
+        let declared = match self.columns.iter().find(|(c, _)| c == name) {
+            Some((_, t)) => t.to_uppercase(),
+            None => return,
+        };
+
+        let bytes = match elem.to_token_stream().to_string().replace(' ', "").as_str() {
+            "str" if ["TEXT", "VARCHAR", "ASCII"].contains(&declared.as_str()) => false,
+            "str" => panic!("column `{}` is a `&str`, its type must be `TEXT`, `VARCHAR` or `ASCII`, not `{}`", name, declared),
+            "[u8]" if declared == "BLOB" => true,
+            "[u8]" => panic!("column `{}` is a `&[u8]`, its type must be `BLOB`, not `{}`", name, declared),
+            other => panic!("column `{}` borrows a `{}`, only a `&str` or a `&[u8]` can be borrowed", name, other),
+        };
+
+        if bytes {
+            self.blob_columns.push((name.to_owned(), optional));
+        }
+
+        self.borrowed_columns.push((name.to_owned(), bytes, optional));
+    }
+
+    /// A `Vec<u8>` field is a `BLOB`, any other `Vec<T>` field must
+    /// be a list or a set of the CQL type of `T`, and a `HashSet<T>`
+    /// or `BTreeSet<T>` field must be a set of the CQL type of `T`
+    ///
+    /// A `Vec` declared as a set is bound as a list, its duplicates
+    /// included, Cassandra stores each distinct item once, in the order
+    /// of the set, so the `Vec` read back has no duplicates and is
+    /// sorted. Use a `HashSet` or a `BTreeSet` to keep the field equal
+    /// to what is stored.
+    fn validate_collection_column(&mut self, name: &String, ty: &syn::Type) {
+        let (collection, optional) = match option_inner_type(ty) {
+            Some(inner) => (collection_item_type(inner), true),
+            None => (collection_item_type(ty), false),
+        };
+
+        let (container, item) = match collection {
+            Some(collection) => collection,
+            None => return,
+        };
+
+        let declared = match self.columns.iter().find(|(c, _)| c == name) {
+            Some((_, t)) => t.to_uppercase().replace(' ', ""),
+            None => return,
+        };
+
+        if container == "Vec" && item.to_token_stream().to_string() == "u8" {
+            if declared != "BLOB" {
+                panic!("column `{}` is a `Vec<u8>`, its type must be `BLOB`, not `{}`", name, declared);
+            }
+
+            self.blob_columns.push((name.to_owned(), optional));
+
+            return;
+        }
+
+        let unfrozen = declared.strip_prefix("FROZEN<").and_then(|t| t.strip_suffix('>')).unwrap_or(&declared);
+
+        let (kinds, declared_item) = if container == "Vec" {
+            ("a list or a set", unfrozen.strip_prefix("LIST<").or_else(|| unfrozen.strip_prefix("SET<")))
+        } else {
+            ("a set", unfrozen.strip_prefix("SET<"))
+        };
+
+        let declared_item = declared_item
+            .and_then(|t| t.strip_suffix('>'))
+            .unwrap_or_else(|| panic!("column `{}` is a `{}`, its type must be {}, not `{}`", name, container, kinds, declared));
+
+        if let Some(inferred) = infer_nested_cql_type(item) {
+            if inferred.replace(' ', "") != declared_item {
+                panic!("column `{}` is a `{}` of `{}`, its type must be {} of `{}`, not `{}`",
+                       name, container, item.to_token_stream(), kinds, inferred, declared);
+            }
+        }
+
+        if container != "Vec" {
+            self.set_columns.push((name.to_owned(), optional));
+        }
+    }
+
+    /// A `DURATION` column is bound from a `CqlDuration` or a
+    /// `std::time::Duration`, a `VARINT` column from a `BigInt`, a
+    /// `Varint` or an integer, a `DECIMAL` column from a `BigDecimal`
+    /// or a `Decimal`, an `INET` column from an `IpAddr`, an `Ipv4Addr`
+    /// or an `Ipv6Addr`, and `DATE` and `TIME` columns from a chrono
+    /// `NaiveDate` and `NaiveTime`, converted to the types of `cassandra_macro`
+    ///
+    /// Any other field of an `INET` column but a `String` is bound
+    /// with its own conversion to the bytes of the driver.
+    fn validate_converted_column(&mut self, name: &String, ty: &syn::Type) {
+        let declared = match self.columns.iter().find(|(c, _)| c == name) {
+            Some((_, t)) => t.trim().to_uppercase(),
+            None => return,
+        };
+
+        let (inner, optional) = match option_inner_type(ty) {
+            Some(inner) => (inner, true),
+            None => (ty, false),
+        };
+
+        let field = match *inner {
+            syn::Type::Path(syn::TypePath { ref path, .. }) => path.segments.last().map(|s| s.ident.to_string()),
+            _ => None,
+        };
+
+        let is_std_duration = match *inner {
+            syn::Type::Path(syn::TypePath { ref path, .. }) => is_std_duration(path),
+            _ => false,
+        };
+
+        let is_big_int = matches!(field.as_deref(), Some("BigInt") | Some("Varint"));
+        let is_big_decimal = matches!(field.as_deref(), Some("BigDecimal") | Some("CqlDecimal"));
+        let is_ip = matches!(field.as_deref(), Some("IpAddr") | Some("Ipv4Addr") | Some("Ipv6Addr"));
+
+        match declared.as_str() {
+            "DURATION" => {
+                if field.as_deref() != Some("CqlDuration") && !is_std_duration {
+                    panic!("column `{}` is a `DURATION`, its field must be a `CqlDuration` or a `std::time::Duration`, not `{}`",
+                           name, ty.to_token_stream());
+                }
+
+                self.duration_columns.push((name.to_owned(), is_std_duration, optional));
+            }
+            "VARINT" if is_big_int => self.varint_columns.push((name.to_owned(), optional)),
+            "VARINT" if matches!(field.as_deref(), Some("i8") | Some("i16") | Some("i32") | Some("i64")) => {}
+            "VARINT" => {
+                panic!("column `{}` is a `VARINT`, its field must be a `num_bigint::BigInt`, a `Varint` or an integer, not `{}`",
+                       name, ty.to_token_stream())
+            }
+            _ if is_big_int => panic!("column `{}` is a `{}`, its type must be `VARINT`, not `{}`", name, field.unwrap_or_default(), declared),
+            "DECIMAL" if is_big_decimal => self.decimal_columns.push((name.to_owned(), optional)),
+            "DECIMAL" if field.as_deref() == Some("Decimal") => {}
+            "DECIMAL" if matches!(field.as_deref(), Some("f32") | Some("f64")) => {
+                panic!("column `{}` is a `DECIMAL`, a `{}` would lose its precision, use a `bigdecimal::BigDecimal`",
+                       name, ty.to_token_stream())
+            }
+            "DECIMAL" => {
+                panic!("column `{}` is a `DECIMAL`, its field must be a `bigdecimal::BigDecimal`, not `{}`", name, ty.to_token_stream())
+            }
+            _ if is_big_decimal => panic!("column `{}` is a `{}`, its type must be `DECIMAL`, not `{}`", name, field.unwrap_or_default(), declared),
+            "INET" if field.as_deref() == Some("IpAddr") => {}
+            "INET" if is_ip => self.inet_columns.push((name.to_owned(), field.unwrap_or_default(), optional)),
+            "INET" if field.as_deref() == Some("String") => {
+                panic!("column `{}` is an `INET`, its field must be an `IpAddr`, an `Ipv4Addr` or an `Ipv6Addr`, not `{}`",
+                       name, ty.to_token_stream())
+            }
+            "TIMEUUID" if field.as_deref() != Some("Uuid") => {
+                panic!("column `{}` is a `TIMEUUID`, its field must be a `Uuid`, not `{}`", name, ty.to_token_stream())
+            }
+            "DATE" if field.as_deref() == Some("NaiveDate") => self.date_columns.push((name.to_owned(), optional)),
+            "TIME" if field.as_deref() == Some("NaiveTime") => self.time_columns.push((name.to_owned(), optional)),
+            _ if field.as_deref() == Some("NaiveDate") => panic!("column `{}` is a `NaiveDate`, its type must be `DATE`, not `{}`", name, declared),
+            _ if field.as_deref() == Some("NaiveTime") => panic!("column `{}` is a `NaiveTime`, its type must be `TIME`, not `{}`", name, declared),
+            _ if is_ip => panic!("column `{}` is an `{}`, its type must be `INET`, not `{}`", name, field.unwrap_or_default(), declared),
+            _ => {}
+        }
+    }
+
+    fn validate_json_column(&self, name: &String) {
+        match self.columns.iter().find(|(c, _)| c == name) {
+            Some((_, t)) if t.eq_ignore_ascii_case("text") || t.eq_ignore_ascii_case("varchar") => {}
+            Some((_, t)) => panic!("column `{}` is stored as JSON, its type must be `TEXT`, not `{}`", name, t),
+            None => {}
+        }
+
+        if self.full_key_columns().contains(name) {
+            panic!("column `{}` is a key, a column stored as JSON can not be a key", name);
+        }
+    }
+
+    /// Definition of the table for the statements, shared with the
+    /// `CqlTableBuilder` of `cassandra_macro` so the two never differ
+    ///
+    /// A definition that Cassandra would not accept panics, e.g.
+    /// a key that is not frozen.
+    fn cql_table(&self) -> CqlTable {
+        let mut builder = CqlTableBuilder::new(&self.name)
+            .keyspace(&self.key_space)
+            .options(&self.table_options);
+
+        for (column, data_type) in self.columns.iter() {
+            builder = if self.static_columns.contains(column) {
+                builder.static_column(column, data_type)
+            } else {
+                builder.column(column, data_type)
+            };
+        }
+
+        for (position, key) in self.primary_keys.iter() {
+            builder = builder.partition_key_at(key, *position);
+        }
+
+        for (position, (key, order)) in self.cluster_keys.iter() {
+            builder = builder.cluster_key(key, *order, *position);
+        }
+
+        for (column, expression) in self.generated_columns.iter() {
+            builder = builder.generated(column, expression);
+        }
+
+        for (view, partition_keys, cluster_keys) in self.views.iter() {
+            let partition_keys: Vec<&str> = partition_keys.iter().map(|k| k.as_str()).collect();
+            let cluster_keys: Vec<&str> = cluster_keys.iter().map(|k| k.as_str()).collect();
+
+            builder = builder.view(view, &partition_keys, &cluster_keys);
+        }
+
+        for (column, index_name) in self.indexes.iter() {
+            builder = match index_name {
+                Some(index_name) => builder.named_index(column, index_name),
+                None => builder.index(column),
+            };
+        }
+
+        if let Some(comment) = &self.comment {
+            builder = builder.comment(comment);
+        }
+
+        builder.build().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn set_column_as_queryable(&mut self, name: &String, allow_filtering: bool) {
+        self.queryable_columns.push((name.to_owned(), allow_filtering));
+    }
+
+    fn set_column_as_indexed(&mut self, name: &String, index_name: Option<String>) {
+        self.indexes.push((name.to_owned(), index_name));
+    }
+
+    fn set_column_as_generated(&mut self, name: &String, expression: &str) {
+        if expression.trim().is_empty() {
+            panic!("column `{}` must have a CQL expression in `generated`, e.g. `now()`", name);
+        }
+
+        self.generated_columns.push((name.to_owned(), expression.trim().to_owned()));
+    }
+
+    /// Each `TIMEUUID` cluster key and the format string of its statement,
+    /// for the rows in a time window, restricting the keys before it
+    fn time_range_stmts(&self) -> Vec<(String, String)> {
+        let table = self.cql_table();
+
+        table.time_range_columns()
+            .into_iter()
+            .map(|column| (column.to_owned(), table.time_range_cql(column, "{}").unwrap()))
+            .collect()
+    }
+
+    /// Each queryable column and the format string of its statement
+    fn queryable_stmts(&self) -> Vec<(String, String)> {
+        let table = self.cql_table();
+
+        self.queryable_columns.iter().map(|(column, allow_filtering)| {
+            (column.to_owned(), table.select_by_column_cql(column, "{}", *allow_filtering).unwrap_or_else(|e| panic!("{}", e)))
+        }).collect()
+    }
+
+    fn new_primary_key(&mut self, key: &String, position: Option<u8>) {
+        self.primary_keys.insert(position.unwrap_or(1), key.to_owned());
+    }
+
+    fn new_cluster_key(&mut self, name: &String, order: Order, position: Option<u8>) {
+        self.cluster_keys.insert(position.unwrap_or(1), (name.to_owned(), order));
+    }
+
+    fn column_names(&self) -> Vec<String> {
+        self.columns.iter().map(|(c, _)| c.to_owned()).collect()
+    }
+
+    fn primary_key_columns(&self) -> Vec<String> {
+        self.primary_keys.values().map(|p| p.to_owned()).collect()
+    }
+
+    fn cluster_key_columns(&self) -> Vec<String> {
+        self.cluster_keys.values().map(|(c, _)| c.to_owned()).collect()
+    }
+
+    fn full_key_columns(&self) -> Vec<String> {
+        [self.primary_key_columns(), self.cluster_key_columns()].concat()
+    }
+
+    fn key_space(&self) -> &String {
+        &self.key_space
+    }
+
+    fn table_name(&self) -> &String {
+        &self.name
+    }
+}
+
+struct UdtMeta {
+    name: String,
+    key_space: String,
+    /// Fields, their types and if they are a nested user defined type
+    fields: Vec<(String, String, bool)>,
+    /// If `TryFromUDT` must also be implemented
+    try_from_udt: bool,
+}
+
+impl UdtMeta {
+    fn with_name(name: &String) -> Self {
+        UdtMeta {
+            name: name.to_owned(),
+            key_space: String::new(),
+            fields: Vec::new(),
+            try_from_udt: false,
+        }
+    }
+
+    fn create_type_cql(&self) -> String {
+        let fields = self.fields
+            .iter()
+            .map(|(name, data_type, _)| format!("{} {}", name, data_type.to_uppercase()))
+            .collect::<Vec<String>>()
+            .join(",");
+
+        format!("CREATE TYPE IF NOT EXISTS {}.{} ({})", self.key_space, self.name, fields)
+    }
+
+    fn drop_type_cql(&self) -> String {
+        format!("DROP TYPE IF EXISTS {}.{}", self.key_space, self.name)
+    }
+}
+
+/// Parse struct attributes
+fn extract_struct_attributes(table_meta: &mut TableMeta, fields: &Vec<syn::Field>) {
+    for field in fields {
+        let field_ident = field.ident.clone().unwrap().to_string();
+
+        let mut serialized = false;
+        let mut is_column = false;
+
+        if !field.attrs.is_empty() {
+            for attr in &field.attrs {
+                if !attr.path.to_token_stream().to_string().contains("column") {
+                    continue;
+                }
+
+                is_column = true;
+
+                match attr.parse_meta() {
+                    Ok(syn::Meta::List(syn::MetaList { ref nested, .. })) => {
+                        let meta_items: Vec<&NestedMeta> = nested.iter().collect();
+
+                        // only validation from there on
+                        for meta_item in meta_items {
+                            match *meta_item {
+                                syn::NestedMeta::Meta(ref item) => match *item {
+                                    syn::Meta::Path(ref name) => {
+                                        match name.get_ident().unwrap().to_string().as_ref() {
+                                            "primary_key" => {
+                                                table_meta.new_primary_key(&field_ident, None);
+                                            }
+                                            "static" => {
+                                                table_meta.set_column_as_static(&field_ident);
+                                            }
+                                            "queryable" => {
+                                                table_meta.set_column_as_queryable(&field_ident, false);
+                                            }
+                                            "moved" => {
+                                                table_meta.set_column_as_moved(&field_ident);
+                                            }
+                                            "index" => {
+                                                table_meta.set_column_as_indexed(&field_ident, None);
+                                            }
+                                            "json" => {
+                                                serialized = true;
+                                            }
+                                            _ => panic!("Unexpected validator: {:?}", name.get_ident()),
+                                        }
+                                    }
+                                    syn::Meta::NameValue(syn::MetaNameValue { ref path, ref lit, .. }) => {
+                                        let ident = path.get_ident().unwrap();
+                                        match ident.to_string().as_ref() {
+                                            "type" => {
+                                                table_meta.new_column(&field_ident.clone(), &lit_to_string(lit).unwrap_or_default());
+                                            }
+                                            "generated" => {
+                                                table_meta.set_column_as_generated(&field_ident, &lit_to_string(lit).unwrap_or_default());
+                                            }
+                                            "index" => {
+                                                table_meta.set_column_as_indexed(&field_ident, Some(lit_to_string(lit).unwrap_or_default()));
+                                            }
+                                            v => panic!("unexpected name value validator: {:?}", v),
+                                        };
+                                    }
+                                    syn::Meta::List(syn::MetaList { ref path, ref nested, .. }) => {
+                                        let meta_items: Vec<&NestedMeta> = nested.iter().collect();
+
+                                        let ident = path.get_ident().unwrap();
+                                        match ident.to_string().as_ref() {
+                                            "cluster_key" => {
+                                                let (order, position) = extract_cluster_properties(&meta_items);
+
+                                                table_meta.new_cluster_key(&field_ident, order, Some(position));
+                                            }
+                                            "compound_key" => {
+                                                let (_, position) = extract_cluster_properties(&meta_items);
+
+                                                table_meta.new_primary_key(&field_ident, Some(position))
+                                            }
+                                            "queryable" => {
+                                                let allow_filtering = meta_items.iter().any(|item| match item {
+                                                    syn::NestedMeta::Meta(syn::Meta::Path(ref name)) if name.is_ident("allow_filtering") => true,
+                                                    _ => panic!("unknown argument for column `queryable`, expected `allow_filtering`"),
+                                                });
+
+                                                table_meta.set_column_as_queryable(&field_ident, allow_filtering);
+                                            }
+                                            v => panic!("unexpected list validator: {:?}", v),
+                                        }
+                                    }
+                                },
+                                _ => unreachable!("Found a non Meta while looking for validators"),
+                            };
+                        }
+                    }
+                    Ok(syn::Meta::NameValue(_)) => panic!("Unexpected name=value argument"),
+                    Err(e) => unreachable!(
+                        "Got something other than a list of attributes while checking field `{}`: {:?}",
+                        field_ident, e
+                    ),
+                    _ => {}
+                }
+            }
+        }
+
+        let has_type = table_meta.columns.iter().any(|(c, _)| *c == field_ident);
+
+        if is_column && !has_type && !serialized {
+            if !table_meta.infer_types {
+                panic!("column `{}` does not have a `type`, add one to its `column` attribute, \
+                        or infer the types of the columns with `infer_types`", field_ident);
+            }
+
+            let data_type = infer_cql_type(&field.ty).unwrap_or_else(|| {
+                panic!("cannot infer the CQL type of field `{}`, add a `type` to its `column` attribute", field_ident)
+            });
+
+            table_meta.new_column(&field_ident, &data_type);
+        }
+
+        if is_column && !serialized {
+            table_meta.validate_collection_column(&field_ident, &field.ty);
+            table_meta.validate_converted_column(&field_ident, &field.ty);
+            table_meta.validate_borrowed_column(&field_ident, &field.ty);
+        }
+
+        if serialized {
+            table_meta.set_column_as_serialized(&field_ident);
+        } else if is_json_value(&field.ty) {
+            table_meta.set_column_as_json(&field_ident);
+        }
+    }
+}
+
+/// Parse the `field` attributes of an user defined type
+fn extract_udt_fields(udt_meta: &mut UdtMeta, fields: &[syn::Field]) {
+    for field in fields {
+        let field_ident = field.ident.clone().unwrap().to_string();
+
+        for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("field")) {
+            let mut data_type = None;
+            let mut nested = false;
+
+            match attr.parse_meta() {
+                Ok(syn::Meta::List(syn::MetaList { nested: ref items, .. })) => {
+                    for meta_item in items.iter() {
+                        match *meta_item {
+                            syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("udt") => nested = true,
+                            syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue { ref path, ref lit, .. }))
+                            if path.is_ident("type") => {
+                                data_type = lit_to_string(lit);
+                            }
+                            _ => panic!("unknown argument for field `{}`, expected `type` or `udt`", field_ident),
+                        }
+                    }
+                }
+                _ => panic!("Got something other than a list of attributes while checking field `{}`", field_ident),
+            }
+
+            let data_type = data_type.unwrap_or_else(|| panic!("field `{}` must have a `type`", field_ident));
+
+            udt_meta.fields.push((field_ident.clone(), data_type, nested));
+        }
+    }
+}
+
+fn lit_to_string(lit: &syn::Lit) -> Option<String> {
+    match *lit {
+        syn::Lit::Str(ref s) => Some(s.value()),
+        _ => None,
+    }
+}
+
+fn lit_to_int(lit: &syn::Lit) -> Option<i64> {
+    match *lit {
+        syn::Lit::Int(ref s) => Some(s.base10_parse().unwrap()),
+        _ => None,
+    }
+}
+
+fn extract_cluster_properties(meta_items: &Vec<&syn::NestedMeta>) -> (Order, u8) {
+    let mut order = Order::Desc;
+    let mut position = 1;
+
+    for meta_item in meta_items {
+        if let syn::NestedMeta::Meta(ref item) = **meta_item {
+            if let syn::Meta::NameValue(syn::MetaNameValue { ref path, ref lit, .. }) = *item {
+                let ident = path.get_ident().unwrap();
+                match ident.to_string().as_ref() {
+                    "order" => {
+                        if let Some(o) = lit_to_string(lit) {
+                            order = o.parse().unwrap_or_else(|e| panic!("{} for column `cluster_key`", e));
+                        }
+                    }
+                    "position" => {
+                        position = lit_to_int(lit).unwrap_or(1) as u8;
+                    }
+                    v => panic!("unknown argument `{}` for column `cluster_key`", v)
+                }
+            } else {
+                panic!("unexpected item while parsing `cluster_key` column of field")
+            }
+        }
+    }
+
+    (order, position)
+}
+
+fn extract_table_properties(meta_items: &Vec<&syn::NestedMeta>) -> (String, String, bool, bool, Option<String>, TableNaming, Idempotence, bool) {
+    let mut keyspace = String::new();
+    let mut options = String::new();
+    let mut try_from_row = false;
+    let mut doc_comment = false;
+    let mut comment = None;
+    let mut naming = TableNaming::default();
+    let mut idempotence = Idempotence::default();
+    let mut infer_types = false;
+
+    for meta_item in meta_items {
+        if let syn::NestedMeta::Meta(ref item) = **meta_item {
+            if let syn::Meta::Path(ref path) = *item {
+                match path.get_ident().unwrap().to_string().as_ref() {
+                    "derive_try_from_row" => try_from_row = true,
+                    "doc_comment" => doc_comment = true,
+                    "infer_types" => infer_types = true,
+                    "idempotent" => idempotence = Idempotence { store: true, update: true, delete: true },
+                    v => panic!("unknown argument `{}` for column `table`", v)
+                }
+            } else if let syn::Meta::List(syn::MetaList { ref path, ref nested, .. }) = *item {
+                if !path.is_ident("idempotent") {
+                    panic!("unknown argument `{}` for column `table`", path.to_token_stream());
+                }
+
+                for operation in nested {
+                    match operation {
+                        syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("store") => idempotence.store = true,
+                        syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("update") => idempotence.update = true,
+                        syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("delete") => idempotence.delete = true,
+                        _ => panic!("unknown operation `{}` of `idempotent`, expected `store`, `update` or `delete`", operation.to_token_stream()),
+                    }
+                }
+            } else if let syn::Meta::NameValue(syn::MetaNameValue { ref path, ref lit, .. }) = *item {
+                let ident = path.get_ident().unwrap();
+                match ident.to_string().as_ref() {
+                    "keyspace" => {
+                        keyspace = lit_to_string(lit).unwrap_or_default()
+                    }
+                    "options" => {
+                        options = lit_to_string(lit).unwrap_or_default();
+                    }
+                    "comment" => {
+                        comment = lit_to_string(lit);
+                    }
+                    "column_enum" => {
+                        naming.column_enum = lit_to_string(lit);
+                    }
+                    "key" => {
+                        naming.key = lit_to_string(lit);
+                    }
+                    "case" => {
+                        naming.case = lit_to_string(lit);
+                    }
+                    "prefix" => {
+                        naming.prefix = lit_to_string(lit);
+                    }
+                    "suffix" => {
+                        naming.suffix = lit_to_string(lit);
+                    }
+                    v => panic!("unknown argument `{}` for column `table`", v)
+                }
+            } else {
+                panic!("unexpected item while parsing `table` column of field")
+            }
+        }
+    }
+
+    (keyspace, options, try_from_row, doc_comment, comment, naming, idempotence, infer_types)
+}
+
+/// Operations whose generated queries are safe to retry, all of
+/// them with `idempotent` or the ones of `idempotent(store, delete)`
+#[derive(Default)]
+struct Idempotence {
+    store: bool,
+    update: bool,
+    delete: bool,
+}
+
+/// How the struct name becomes the table name, the `case`,
+/// `prefix` and `suffix` of the `table` attribute, and the
+/// names of the enum of its columns, its `column_enum`, and
+/// of the struct of its keys, its `key`
+#[derive(Default)]
+struct TableNaming {
+    case: Option<String>,
+    prefix: Option<String>,
+    suffix: Option<String>,
+    column_enum: Option<String>,
+    key: Option<String>,
+}
+
+impl TableNaming {
+    /// Enum of the columns, `<Struct>Column` unless it is renamed,
+    /// e.g. when the struct already has a type of that name
+    fn column_enum(&self, ident: &str) -> syn::Ident {
+        rust_ident(self.column_enum.clone().unwrap_or_else(|| format!("{}Column", ident)), "column_enum")
+    }
+
+    /// Struct of the keys, `<Struct>Key` unless it is renamed
+    fn key_struct(&self, ident: &str) -> syn::Ident {
+        rust_ident(self.key.clone().unwrap_or_else(|| format!("{}Key", ident)), "key")
+    }
+
+    /// Table name of the struct, see `table_name_with`
+    ///
+    /// The prefix and suffix default to the `CASSANDRA_TABLE_PREFIX` and
+    /// `CASSANDRA_TABLE_SUFFIX` environment variables of the build.
+    fn table_name(&self, ident: &str) -> String {
+        self.table_name_with(ident, |var| std::env::var(var).ok())
+    }
+
+    /// Variables of the environment of the build read by `table_name`,
+    /// the ones of the prefix and suffix that are not given
+    fn env_vars(&self) -> Vec<&'static str> {
+        let mut vars = vec![];
+
+        if self.prefix.is_none() {
+            vars.push("CASSANDRA_TABLE_PREFIX");
+        }
+
+        if self.suffix.is_none() {
+            vars.push("CASSANDRA_TABLE_SUFFIX");
+        }
+
+        vars
+    }
+
+    /// Table name of the struct in the `case`, snake case by default,
+    /// between the prefix and the suffix, as given or as the variable
+    /// of the environment `env`
+    ///
+    /// Unquoted CQL identifiers are case insensitive, so a preserved
+    /// name with upper case letters is quoted.
+    fn table_name_with(&self, ident: &str, env: impl Fn(&str) -> Option<String>) -> String {
+        let case = self.case.as_deref().unwrap_or("snake_case");
+
+        let name = match case {
+            "snake_case" => pascal_case_to_snake_case(&ident.to_string()),
+            "lowercase" => ident.to_lowercase(),
+            "preserve" => ident.to_string(),
+            v => panic!("unknown case `{}` for attribute `table`, expected `snake_case`, `lowercase` or `preserve`", v),
+        };
+
+        let affix = |value: &Option<String>, var: &str| {
+            let affix = value.clone().or_else(|| env(var)).unwrap_or_default();
+
+            if !affix.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                panic!("table name `{}` of `{}` can only have letters, digits and `_`", affix, var.trim_start_matches("CASSANDRA_TABLE_").to_lowercase());
+            }
+
+            affix
+        };
+
+        let name = format!("{}{}{}", affix(&self.prefix, "CASSANDRA_TABLE_PREFIX"), name, affix(&self.suffix, "CASSANDRA_TABLE_SUFFIX"));
+
+        if case == "preserve" && name.chars().any(|c| c.is_ascii_uppercase()) {
+            format!("\"{}\"", name)
+        } else {
+            name
+        }
+    }
+}
+
+/// Name, partition keys and cluster keys of a `view` attribute,
+/// the keys are separated with `,`
+fn extract_view_properties(meta_items: &Vec<&syn::NestedMeta>) -> (String, Vec<String>, Vec<String>) {
+    let mut name = String::new();
+    let mut partition_keys = Vec::new();
+    let mut cluster_keys = Vec::new();
+
+    let keys = |lit: &syn::Lit| -> Vec<String> {
+        lit_to_string(lit).unwrap_or_default().split(',').map(|k| k.trim().to_string()).filter(|k| !k.is_empty()).collect()
+    };
+
+    for meta_item in meta_items {
+        match **meta_item {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue { ref path, ref lit, .. })) => {
+                match path.get_ident().unwrap().to_string().as_ref() {
+                    "name" => name = lit_to_string(lit).unwrap_or_default(),
+                    "partition_keys" => partition_keys = keys(lit),
+                    "cluster_keys" => cluster_keys = keys(lit),
+                    v => panic!("unknown argument `{}` for attribute `view`, expected `name`, `partition_keys` or `cluster_keys`", v),
+                }
+            }
+            _ => panic!("unexpected item while parsing `view` attribute, expected `name = \"...\"`"),
+        }
+    }
+
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        panic!("view must have a `name` that is a CQL identifier, got `{}`", name);
+    }
+
+    (name, partition_keys, cluster_keys)
+}
+
+/// First paragraph of the `///` comment of the struct, its lines
+/// joined by a space
+fn first_doc_paragraph(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines = attrs.iter()
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(syn::MetaNameValue { ref path, ref lit, .. })) if path.is_ident("doc") => lit_to_string(lit),
+            _ => None,
+        })
+        .map(|line| line.trim().to_string())
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| !line.is_empty())
+        .collect::<Vec<String>>();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join(" "))
+    }
+}
+
+const OFFSET: u8 = 32;
+const UNDERSCORE: u8 = 95;
+
+fn pascal_case_to_snake_case(table_name: &String) -> String {
+    let word_size = table_name.len();
+
+    if word_size < 2 {
+        return String::from(table_name);
+    }
+
+    let mut counter = 1;
+    let chars = table_name.as_bytes();
+    let mut sk_table_name: Vec<u8> = Vec::new();
+
+    if chars[0] < 90 {
+        sk_table_name.push(chars[0] + OFFSET);
+    } else {
+        sk_table_name.push(chars[0]);
+    }
+
+    while counter < word_size {
+        let current = chars[counter];
+
+        if current < 90 {
+            sk_table_name.push(UNDERSCORE);
+            sk_table_name.push(current + OFFSET)
+        } else {
+            sk_table_name.push(current);
+        }
+
+        counter += 1;
+    }
+
+    String::from_utf8(sk_table_name).unwrap()
+}
+
+/// Identifier of a generated type named by the argument `argument`
+fn rust_ident(name: String, argument: &str) -> syn::Ident {
+    syn::parse_str::<syn::Ident>(&name)
+        .unwrap_or_else(|_| panic!("`{}` of `{}` must be a Rust identifier", name, argument))
+}
+
+/// Variants of the columns in the enum of the columns, two columns
+/// with the same variant, e.g. `foo_1` and `foo1`, panic
+fn column_variants(columns: &[String], column_enum: &syn::Ident) -> Vec<syn::Ident> {
+    let variants: Vec<String> = columns.iter().map(|c| snake_case_to_pascal_case(c)).collect();
+
+    for (i, variant) in variants.iter().enumerate() {
+        if let Some(j) = variants[..i].iter().position(|v| v == variant) {
+            panic!("columns `{}` and `{}` are both the variant `{}` of `{}`, rename one of them",
+                   columns[j], columns[i], variant, column_enum);
+        }
+    }
+
+    variants.iter().map(|v| syn::Ident::new(v, proc_macro2::Span::call_site())).collect()
+}
+
+/// Variant of the column enum, `first_name` is `FirstName`
+fn snake_case_to_pascal_case(column: &str) -> String {
+    column.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use cassandra_macro_table::Order;
+
+    use crate::{column_variants, extract_table_properties, first_doc_paragraph, impl_cassandra_macro, impl_cassandra_projection, infer_cql_type, pascal_case_to_snake_case,
+                snake_case_to_pascal_case, Idempotence, TableMeta, TableNaming};
+
+    fn user_event_meta() -> TableMeta {
+        let mut table_meta = TableMeta::with_name(&String::from("user_event"));
+
+        table_meta.set_key_space(&String::from("test"));
+
+        for (column, data_type) in [("username", "text"), ("created", "timestamp"), ("payload", "text"), ("tags", "set<text>")].iter() {
+            table_meta.new_column(&column.to_string(), &data_type.to_string());
+        }
+
+        table_meta.new_primary_key(&String::from("username"), None);
+        table_meta.new_cluster_key(&String::from("created"), Order::Desc, Some(1));
+
+        table_meta
+    }
+
+    #[test]
+    fn test_create_table_cql_column_order() {
+        let ddl = user_event_meta().cql_table().create_table_cql();
+
+        assert_eq!(ddl, user_event_meta().cql_table().create_table_cql());
+        assert_eq!(ddl, "CREATE TABLE IF NOT EXISTS test.user_event  (username TEXT,created TIMESTAMP,payload TEXT,tags SET<TEXT>, \
+                         PRIMARY KEY ((username), created) ) WITH CLUSTERING ORDER BY (created DESC)");
+    }
+
+    #[test]
+    fn test_statements_of_table_meta() {
+        let table_meta = user_event_meta();
+
+        assert_eq!(table_meta.store_stmt().0, "INSERT INTO test.user_event (username,created,payload,tags) VALUES (?,?,?,?)");
+        assert_eq!(table_meta.update_stmt().unwrap().0, "UPDATE test.user_event SET payload=?,tags=? WHERE username=? AND created=?");
+        assert_eq!(table_meta.delete_stmt().0, "DELETE FROM test.user_event WHERE username=? AND created=?");
+        assert_eq!(table_meta.delete_partition_stmt().0, "DELETE FROM test.user_event WHERE username=?");
+        assert_eq!(table_meta.exists_stmt().0, "SELECT count(*) AS count FROM test.user_event WHERE username=? AND created=? LIMIT 1");
+        assert_eq!(table_meta.count_partition_stmt().0, "SELECT count(*) AS count FROM test.user_event WHERE username=?");
+
+        let mut sensor_meta = TableMeta::with_name(&String::from("sensor"));
+
+        sensor_meta.set_key_space(&String::from("iot"));
+        sensor_meta.new_column(&String::from("site"), &String::from("text"));
+        sensor_meta.new_column(&String::from("id"), &String::from("int"));
+        sensor_meta.new_primary_key(&String::from("id"), Some(2));
+        sensor_meta.new_primary_key(&String::from("site"), Some(1));
+
+        assert_eq!(sensor_meta.cql_table().create_table_cql(),
+                   "CREATE TABLE IF NOT EXISTS iot.sensor  (site TEXT,id INT, PRIMARY KEY ((site,id)) ) ");
+        assert!(sensor_meta.update_stmt().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "columns `username` and `UserName` of table user_event are the same CQL column")]
+    fn test_columns_differing_in_case() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("UserName"), &String::from("text"));
+
+        table_meta.cql_table();
+    }
+
+    #[test]
+    #[should_panic(expected = "column `username` is a partition key and a cluster key")]
+    fn test_partition_and_cluster_key() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_cluster_key(&String::from("username"), Order::Asc, Some(2));
+
+        table_meta.cql_table();
+    }
+
+    #[test]
+    #[should_panic(expected = "column `username` is a partition key, a key can not be static")]
+    fn test_static_partition_key() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.set_column_as_static(&String::from("username"));
+
+        table_meta.cql_table();
+    }
+
+    #[test]
+    #[should_panic(expected = "column `created` is a cluster key, a key can not be static")]
+    fn test_static_cluster_key() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.set_column_as_static(&String::from("created"));
+
+        table_meta.cql_table();
+    }
+
+    #[test]
+    #[should_panic(expected = "column `payload` is not a counter, table user_event has the counter column `views`")]
+    fn test_counter_column_with_regular_columns() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("views"), &String::from("counter"));
+
+        table_meta.cql_table();
+    }
+
+    #[test]
+    fn test_queryable_stmts() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.set_column_as_queryable(&String::from("payload"), false);
+        table_meta.set_column_as_queryable(&String::from("tags"), true);
+
+        assert_eq!(table_meta.queryable_stmts(), vec![
+            (String::from("payload"), String::from("SELECT {} FROM test.user_event WHERE payload=?")),
+            (String::from("tags"), String::from("SELECT {} FROM test.user_event WHERE tags=? ALLOW FILTERING")),
+        ]);
+    }
+
+    #[test]
+    fn test_frozen_key_types() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("location"), &String::from("frozen<map<text, int>>"));
+        table_meta.new_column(&String::from("home"), &String::from("FROZEN<address>"));
+        table_meta.new_primary_key(&String::from("location"), Some(2));
+        table_meta.new_cluster_key(&String::from("home"), Order::Asc, Some(2));
+
+        table_meta.cql_table();
+    }
+
+    #[test]
+    #[should_panic(expected = "column `labels` is a key, its type must be frozen, use `frozen<list<text>>` instead of `list<text>`")]
+    fn test_unfrozen_list_cluster_key() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("labels"), &String::from("list<text>"));
+        table_meta.new_cluster_key(&String::from("labels"), Order::Asc, Some(2));
+
+        table_meta.cql_table();
+    }
+
+    #[test]
+    #[should_panic(expected = "use `frozen<address>` instead of `address`")]
+    fn test_unfrozen_udt_primary_key() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("home"), &String::from("address"));
+        table_meta.new_primary_key(&String::from("home"), Some(2));
+
+        table_meta.cql_table();
+    }
+
+    #[test]
+    #[should_panic(expected = "column `created` is a key")]
+    fn test_queryable_key_column() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.set_column_as_queryable(&String::from("created"), false);
+
+        table_meta.queryable_stmts();
+    }
+
+    #[test]
+    fn test_moved_values() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.set_column_as_moved(&String::from("payload"));
+
+        let store = table_meta.store_moved_values().to_string().replace(' ', "");
+        let update = table_meta.update_moved_values().to_string().replace(' ', "");
+
+        assert!(store.contains("cassandra_macro::Value::from(self.payload)"));
+        assert!(!store.contains("self.payload.clone()"));
+        assert!(store.contains("self.tags.clone()"));
+
+        assert_eq!(update, "cassandra_macro::Value::from(self.payload),cassandra_macro::Value::from(self.tags.clone()),\
+                            cassandra_macro::Value::from(self.username.clone()),cassandra_macro::Value::from(self.created.clone())");
+    }
+
+    #[test]
+    fn test_json_values() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.set_column_as_json(&String::from("payload"));
+
+        let (_, store) = table_meta.store_stmt();
+
+        assert!(store.to_string().replace(' ', "").contains("cassandra_macro::Value::from(self.payload.to_string())"));
+    }
+
+    #[test]
+    #[should_panic(expected = "its type must be `TEXT`")]
+    fn test_json_column_type() {
+        user_event_meta().set_column_as_json(&String::from("tags"));
+    }
+
+    fn inferred(ty: &str) -> Option<String> {
+        infer_cql_type(&syn::parse_str::<syn::Type>(ty).unwrap())
+    }
+
+    #[test]
+    fn test_infer_cql_type() {
+        assert_eq!(inferred("String"), Some(String::from("TEXT")));
+        assert_eq!(inferred("Option<i64>"), Some(String::from("BIGINT")));
+        assert_eq!(inferred("uuid::Uuid"), Some(String::from("UUID")));
+        assert_eq!(inferred("Vec<f64>"), Some(String::from("LIST<DOUBLE>")));
+        assert_eq!(inferred("HashMap<String, i64>"), Some(String::from("MAP<TEXT, BIGINT>")));
+        assert_eq!(inferred("std::collections::HashMap<String, HashMap<String, bool>>"), Some(String::from("MAP<TEXT, FROZEN<MAP<TEXT, BOOLEAN>>>")));
+        assert_eq!(inferred("std::collections::HashMap<String, Vec<String>>"), Some(String::from("MAP<TEXT, FROZEN<LIST<TEXT>>>")));
+        assert_eq!(inferred("HashMap<String, HashSet<i32>>"), Some(String::from("MAP<TEXT, FROZEN<SET<INT>>>")));
+        assert_eq!(inferred("Vec<Vec<String>>"), Some(String::from("LIST<FROZEN<LIST<TEXT>>>")));
+        assert_eq!(inferred("Vec<Option<HashMap<String, i32>>>"), Some(String::from("LIST<FROZEN<MAP<TEXT, INT>>>")));
+        assert_eq!(inferred("Vec<Vec<u8>>"), Some(String::from("LIST<BLOB>")));
+        assert_eq!(inferred("Vec<u8>"), Some(String::from("BLOB")));
+        assert_eq!(inferred("Option<Vec<u8>>"), Some(String::from("BLOB")));
+        assert_eq!(inferred("&'a str"), Some(String::from("TEXT")));
+        assert_eq!(inferred("Option<&'a [u8]>"), Some(String::from("BLOB")));
+        assert_eq!(inferred("Vec<i8>"), Some(String::from("LIST<TINYINT>")));
+        assert_eq!(inferred("HashSet<String>"), Some(String::from("SET<TEXT>")));
+        assert_eq!(inferred("std::collections::BTreeSet<i32>"), Some(String::from("SET<INT>")));
+        assert_eq!(inferred("HashMap<String, u64>"), None);
+        assert_eq!(inferred("Config"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "unsupported map key type")]
+    fn test_infer_map_key_type() {
+        inferred("HashMap<Vec<String>, i64>");
+    }
+
+    #[test]
+    #[should_panic(expected = "column `email` does not have a `type`, add one to its `column` attribute, \
+                               or infer the types of the columns with `infer_types`")]
+    fn test_column_without_type_nor_inference() {
+        let ast: syn::DeriveInput = syn::parse_str("#[table(keyspace = \"test\")] pub struct User { #[column()] email: String }").unwrap();
+
+        impl_cassandra_macro(&ast);
+    }
+
+    #[test]
+    fn test_column_enum_name() {
+        assert_eq!(naming(None, None, None).column_enum("User").to_string(), "UserColumn");
+
+        let renamed = TableNaming { column_enum: Some(String::from("UserField")), ..naming(None, None, None) };
+
+        assert_eq!(renamed.column_enum("User").to_string(), "UserField");
+    }
+
+    #[test]
+    #[should_panic(expected = "`User-Column` of `column_enum` must be a Rust identifier")]
+    fn test_invalid_column_enum_name() {
+        TableNaming { column_enum: Some(String::from("User-Column")), ..naming(None, None, None) }.column_enum("User");
+    }
+
+    #[test]
+    fn test_key_struct_name() {
+        assert_eq!(naming(None, None, None).key_struct("User").to_string(), "UserKey");
+
+        let renamed = TableNaming { key: Some(String::from("UserId")), ..naming(None, None, None) };
+
+        assert_eq!(renamed.key_struct("User").to_string(), "UserId");
+    }
+
+    #[test]
+    #[should_panic(expected = "columns `foo_1` and `foo1` are both the variant `Foo1` of `UserColumn`, rename one of them")]
+    fn test_column_variants_collision() {
+        let columns = vec![String::from("foo_1"), String::from("bar"), String::from("foo1")];
+
+        column_variants(&columns, &syn::Ident::new("UserColumn", proc_macro2::Span::call_site()));
+    }
+
+    #[test]
+    fn test_list_column_values() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("avatar"), &String::from("blob"));
+        table_meta.validate_collection_column(&String::from("tags"), &syn::parse_str("Vec<String>").unwrap());
+        table_meta.validate_collection_column(&String::from("avatar"), &syn::parse_str("Vec<u8>").unwrap());
+
+        let (_, store) = table_meta.store_stmt();
+        let store = store.to_string().replace(' ', "");
+
+        assert!(store.contains("cassandra_macro::Value::from(self.tags.clone())"));
+        assert!(store.contains("cassandra_macro::Value::from(cassandra_macro::cdrs::types::blob::Blob::from(self.avatar.clone()))"));
+    }
+
+    #[test]
+    fn test_counter_table_has_no_infallible_queries() {
+        let mut table_meta = TableMeta::with_name(&String::from("page_views"));
+
+        table_meta.set_key_space(&String::from("test"));
+        table_meta.new_column(&String::from("page"), &String::from("text"));
+        table_meta.new_column(&String::from("hits"), &String::from("counter"));
+        table_meta.new_primary_key(&String::from("page"), None);
+
+        assert!(!table_meta.has_infallible_queries(&table_meta.cql_table()));
+        assert!(user_event_meta().has_infallible_queries(&user_event_meta().cql_table()));
+    }
+
+    #[test]
+    fn test_duration_column_values() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("every"), &String::from("duration"));
+        table_meta.new_column(&String::from("timeout"), &String::from("DURATION"));
+        table_meta.validate_converted_column(&String::from("every"), &syn::parse_str("CqlDuration").unwrap());
+        table_meta.validate_converted_column(&String::from("timeout"), &syn::parse_str("Option<std::time::Duration>").unwrap());
+
+        let (_, store) = table_meta.store_stmt();
+        let store = store.to_string().replace(' ', "");
+
+        assert!(store.contains("cassandra_macro::Value::from(self.every.clone())"));
+        assert!(store.contains("self.timeout.map(<cassandra_macro::CqlDurationasstd::convert::TryFrom<std::time::Duration>>::try_from)\
+                                .transpose().map(cassandra_macro::Value::from)?"));
+        assert!(!table_meta.has_infallible_queries(&table_meta.cql_table()));
+        assert_eq!(inferred("std::time::Duration"), Some(String::from("DURATION")));
+        assert_eq!(inferred("Duration"), Some(String::from("DURATION")));
+        assert_eq!(inferred("chrono::Duration"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "column `every` is a `DURATION`, its field must be a `CqlDuration` or a `std::time::Duration`, not `chrono :: Duration`")]
+    fn test_chrono_duration_column() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("every"), &String::from("duration"));
+        table_meta.validate_converted_column(&String::from("every"), &syn::parse_str("chrono::Duration").unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "column `every` is a `DURATION`, its field must be a `CqlDuration` or a `std::time::Duration`, not `i64`")]
+    fn test_duration_column_field_type() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("every"), &String::from("duration"));
+        table_meta.validate_converted_column(&String::from("every"), &syn::parse_str("i64").unwrap());
+    }
+
+    #[test]
+    fn test_varint_column_values() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("balance"), &String::from("varint"));
+        table_meta.validate_converted_column(&String::from("balance"), &syn::parse_str("num_bigint::BigInt").unwrap());
+
+        let (_, store) = table_meta.store_stmt();
+        let store = store.to_string().replace(' ', "");
+
+        assert!(store.contains("cassandra_macro::Value::from(cassandra_macro::Varint::from(self.balance.clone()))"));
+        assert_eq!(inferred("Option<BigInt>"), Some(String::from("VARINT")));
+
+        table_meta.new_column(&String::from("visits"), &String::from("VARINT"));
+        table_meta.new_column(&String::from("logins"), &String::from("VARINT"));
+        table_meta.validate_converted_column(&String::from("visits"), &syn::parse_str("Option<Varint>").unwrap());
+        table_meta.validate_converted_column(&String::from("logins"), &syn::parse_str("i64").unwrap());
+
+        assert!(table_meta.raw_columns().is_empty());
+
+        table_meta.try_from_row = true;
+
+        assert!(table_meta.raw_columns().to_string().replace(' ', "").contains("&[\"balance\",\"visits\"]"));
+    }
+
+    #[test]
+    #[should_panic(expected = "column `balance` is a `BigInt`, its type must be `VARINT`, not `BIGINT`")]
+    fn test_varint_column_type() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("balance"), &String::from("bigint"));
+        table_meta.validate_converted_column(&String::from("balance"), &syn::parse_str("BigInt").unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "column `balance` is a `VARINT`, its field must be a `num_bigint::BigInt`, a `Varint` or an integer, not `String`")]
+    fn test_varint_column_field_type() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("balance"), &String::from("VARINT"));
+        table_meta.validate_converted_column(&String::from("balance"), &syn::parse_str("i64").unwrap());
+        table_meta.validate_converted_column(&String::from("balance"), &syn::parse_str("String").unwrap());
+    }
+
+    #[test]
+    fn test_decimal_column_values() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("price"), &String::from("decimal"));
+        table_meta.new_column(&String::from("discount"), &String::from("decimal"));
+        table_meta.validate_converted_column(&String::from("price"), &syn::parse_str("bigdecimal::BigDecimal").unwrap());
+        table_meta.validate_converted_column(&String::from("discount"), &syn::parse_str("Option<cdrs::types::decimal::Decimal>").unwrap());
+
+        let (_, store) = table_meta.store_stmt();
+        let store = store.to_string().replace(' ', "");
+
+        assert!(store.contains("<cassandra_macro::cdrs::types::value::Bytesasstd::convert::TryFrom<cassandra_macro::CqlDecimal>>::try_from(\
+                                cassandra_macro::CqlDecimal::from(self.price.clone())).map(cassandra_macro::Value::from)?"));
+        assert!(store.contains("cassandra_macro::Value::from(self.discount.clone())"));
+        assert!(!table_meta.has_infallible_queries(&table_meta.cql_table()));
+        assert_eq!(inferred("BigDecimal"), Some(String::from("DECIMAL")));
+    }
+
+    #[test]
+    #[should_panic(expected = "column `price` is a `DECIMAL`, a `f64` would lose its precision, use a `bigdecimal::BigDecimal`")]
+    fn test_decimal_column_from_float() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("price"), &String::from("DECIMAL"));
+        table_meta.validate_converted_column(&String::from("price"), &syn::parse_str("f64").unwrap());
+    }
+
+    #[test]
+    fn test_inet_column_values() {
+        let mut table_meta = user_event_meta();
+
+        for (column, ty) in [("client", "std::net::Ipv4Addr"), ("proxy", "Option<Ipv6Addr>"), ("gateway", "IpAddr"), ("peer", "Peer")].iter() {
+            table_meta.new_column(&column.to_string(), &String::from("inet"));
+            table_meta.validate_converted_column(&column.to_string(), &syn::parse_str(ty).unwrap());
+        }
+
+        let (_, store) = table_meta.store_stmt();
+        let store = store.to_string().replace(' ', "");
+
+        assert!(store.contains("cassandra_macro::Value::from(std::net::IpAddr::from(self.client.clone()))"));
+        assert!(store.contains("cassandra_macro::Value::from(self.proxy.clone().map(std::net::IpAddr::from))"));
+        assert!(store.contains("cassandra_macro::Value::from(self.gateway.clone())"));
+        assert!(store.contains("cassandra_macro::Value::from(self.peer.clone())"));
+        assert_eq!(inferred("Ipv6Addr"), Some(String::from("INET")));
+    }
+
+    #[test]
+    #[should_panic(expected = "column `client` is an `INET`, its field must be an `IpAddr`, an `Ipv4Addr` or an `Ipv6Addr`, not `String`")]
+    fn test_inet_column_from_string() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("client"), &String::from("INET"));
+        table_meta.validate_converted_column(&String::from("client"), &syn::parse_str("String").unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "column `client` is an `Ipv4Addr`, its type must be `INET`, not `TEXT`")]
+    fn test_ip_column_type() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("client"), &String::from("text"));
+        table_meta.validate_converted_column(&String::from("client"), &syn::parse_str("Ipv4Addr").unwrap());
+    }
+
+    #[test]
+    fn test_date_time_column_values() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("day"), &String::from("date"));
+        table_meta.new_column(&String::from("at"), &String::from("time"));
+        table_meta.validate_converted_column(&String::from("day"), &syn::parse_str("chrono::NaiveDate").unwrap());
+        table_meta.validate_converted_column(&String::from("at"), &syn::parse_str("Option<NaiveTime>").unwrap());
+
+        let (_, store) = table_meta.store_stmt();
+        let store = store.to_string().replace(' ', "");
+
+        assert!(store.contains("cassandra_macro::Value::from(cassandra_macro::CqlDate::from(self.day.clone()))"));
+        assert!(store.contains("cassandra_macro::Value::from(self.at.clone().map(cassandra_macro::CqlTime::from))"));
+        assert_eq!(inferred("NaiveDate"), Some(String::from("DATE")));
+        assert_eq!(inferred("NaiveTime"), Some(String::from("TIME")));
+    }
+
+    #[test]
+    #[should_panic(expected = "column `at` is a `NaiveTime`, its type must be `TIME`, not `TIMESTAMP`")]
+    fn test_time_column_type() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("at"), &String::from("timestamp"));
+        table_meta.validate_converted_column(&String::from("at"), &syn::parse_str("NaiveTime").unwrap());
+    }
+
+    #[test]
+    fn test_time_range_stmts() {
+        let mut table_meta = user_event_meta();
+
+        assert!(table_meta.time_range_stmts().is_empty());
+
+        table_meta.new_column(&String::from("event_id"), &String::from("TIMEUUID"));
+        table_meta.new_column(&String::from("request_id"), &String::from("timeuuid"));
+        table_meta.new_cluster_key(&String::from("event_id"), Order::Desc, Some(2));
+
+        assert_eq!(table_meta.time_range_stmts(), vec![
+            (String::from("event_id"),
+             String::from("SELECT {} FROM test.user_event WHERE username=? AND created=? AND event_id > maxTimeuuid(?) AND event_id < minTimeuuid(?)")),
+        ]);
+    }
+
+    #[test]
+    fn test_generated_column_store_stmt() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.set_column_as_generated(&String::from("payload"), &String::from(" now() "));
+
+        let (stmt, values) = table_meta.store_stmt();
+
+        assert_eq!(stmt, "INSERT INTO test.user_event (username,created,payload,tags) VALUES (?,?,now(),?)");
+        assert!(!values.to_string().contains("payload"));
+        assert_eq!(table_meta.updatable_columns(), vec![String::from("tags")]);
+    }
+
+    #[test]
+    fn test_table_comment() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.set_comment("Events of the user's\n  account");
+
+        assert!(table_meta.cql_table().create_table_cql().ends_with("WITH CLUSTERING ORDER BY (created DESC) AND comment='Events of the user''s account'"));
+
+        let mut table_meta = user_event_meta();
+
+        table_meta.set_table_options(&String::from("COMMENT = 'Explicit'|gc_grace_seconds = 10"));
+        table_meta.set_comment("From the doc");
+
+        assert!(table_meta.cql_table().create_table_cql().ends_with("AND COMMENT = 'Explicit' AND gc_grace_seconds = 10"));
+    }
+
+    #[test]
+    fn test_first_doc_paragraph() {
+        let ast: syn::DeriveInput = syn::parse_str("
+            ///
+            /// Events of the
+            /// user's account
+            ///
+            /// Not in the comment
+            struct UserEvent;
+        ").unwrap();
+
+        assert_eq!(first_doc_paragraph(&ast.attrs), Some(String::from("Events of the user's account")));
+
+        let ast: syn::DeriveInput = syn::parse_str("struct UserEvent;").unwrap();
+
+        assert_eq!(first_doc_paragraph(&ast.attrs), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "a `DURATION` can not be a key")]
+    fn test_duration_key() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("every"), &String::from("duration"));
+        table_meta.new_cluster_key(&String::from("every"), Order::Asc, Some(2));
+
+        table_meta.cql_table();
+    }
+
+    #[test]
+    #[should_panic(expected = "its type must be a list or a set of `BIGINT`")]
+    fn test_list_column_item_type() {
+        user_event_meta().validate_collection_column(&String::from("tags"), &syn::parse_str("Vec<i64>").unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "column `tags` is a `HashSet` of `String`, its type must be a set of `TEXT`, not `SET<INT>`")]
+    fn test_set_column_item_type() {
+        let mut table_meta = TableMeta::with_name(&String::from("user"));
+
+        table_meta.new_column(&String::from("tags"), &String::from("set<int>"));
+
+        table_meta.validate_collection_column(&String::from("tags"), &syn::parse_str("HashSet<String>").unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "its type must be a set, not `LIST<TEXT>`")]
+    fn test_set_column_declared_as_list() {
+        let mut table_meta = TableMeta::with_name(&String::from("user"));
+
+        table_meta.new_column(&String::from("tags"), &String::from("list<text>"));
+
+        table_meta.validate_collection_column(&String::from("tags"), &syn::parse_str("BTreeSet<String>").unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "its type must be `BLOB`")]
+    fn test_bytes_column_type() {
+        user_event_meta().validate_collection_column(&String::from("tags"), &syn::parse_str("Vec<u8>").unwrap());
+    }
+
+    #[test]
+    fn test_borrowed_column_values() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.new_column(&String::from("avatar"), &String::from("blob"));
+        table_meta.validate_borrowed_column(&String::from("payload"), &syn::parse_str("&'a str").unwrap());
+        table_meta.validate_borrowed_column(&String::from("avatar"), &syn::parse_str("Option<&'a [u8]>").unwrap());
+
+        let (_, store) = table_meta.store_stmt();
+        let store = store.to_string().replace(' ', "");
+
+        assert!(store.contains("cassandra_macro::Value::from(self.payload)"));
+        assert!(store.contains("cassandra_macro::Value::from(self.avatar.map(<[u8]>::to_vec).map(cassandra_macro::cdrs::types::blob::Blob::from))"));
+    }
+
+    #[test]
+    #[should_panic(expected = "column `tags` is a `&[u8]`, its type must be `BLOB`")]
+    fn test_borrowed_bytes_column_type() {
+        user_event_meta().validate_borrowed_column(&String::from("tags"), &syn::parse_str("&'a [u8]").unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "only a `&str` or a `&[u8]` can be borrowed")]
+    fn test_borrowed_column_of_other_type() {
+        user_event_meta().validate_borrowed_column(&String::from("payload"), &syn::parse_str("&'a String").unwrap());
+    }
+
+    #[test]
+    fn test_indexes_cql() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.set_column_as_indexed(&String::from("payload"), None);
+        table_meta.set_column_as_indexed(&String::from("tags"), Some(String::from("by_tag")));
+
+        assert_eq!(table_meta.cql_table().create_indexes_cql(), vec![
+            "CREATE INDEX IF NOT EXISTS user_event_payload_idx ON test.user_event (payload)",
+            "CREATE INDEX IF NOT EXISTS by_tag ON test.user_event (tags)",
+        ]);
+        assert_eq!(table_meta.cql_table().drop_indexes_cql(), vec![
+            "DROP INDEX IF EXISTS test.user_event_payload_idx",
+            "DROP INDEX IF EXISTS test.by_tag",
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "column `username` is the only partition key of table user_event, it can not have an index")]
+    fn test_index_of_only_partition_key() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.set_column_as_indexed(&String::from("username"), None);
+
+        table_meta.cql_table();
+    }
+
+    #[test]
+    #[should_panic(expected = "index `by-tag` of table user_event is not a CQL identifier")]
+    fn test_index_name_not_identifier() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.set_column_as_indexed(&String::from("tags"), Some(String::from("by-tag")));
+
+        table_meta.cql_table();
+    }
+
+    #[test]
+    #[should_panic(expected = "key `created` of table user_event is not a key of view user_event_by_payload")]
+    fn test_view_without_table_keys() {
+        let mut table_meta = user_event_meta();
+
+        table_meta.views.push((String::from("user_event_by_payload"), vec![String::from("payload")], vec![String::from("username")]));
+
+        table_meta.cql_table();
+    }
+
+    fn naming(case: Option<&str>, prefix: Option<&str>, suffix: Option<&str>) -> TableNaming {
+        TableNaming {
+            case: case.map(String::from),
+            prefix: prefix.map(String::from),
+            suffix: suffix.map(String::from),
+            column_enum: None,
+            key: None,
+        }
+    }
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_table_name_in_case() {
+        assert_eq!(naming(None, None, None).table_name_with("UserLoginEvent", no_env), "user_login_event");
+        assert_eq!(naming(Some("snake_case"), None, None).table_name_with("UserLoginEvent", no_env), "user_login_event");
+        assert_eq!(naming(Some("lowercase"), None, None).table_name_with("UserLoginEvent", no_env), "userloginevent");
+        assert_eq!(naming(Some("preserve"), None, None).table_name_with("UserLoginEvent", no_env), "\"UserLoginEvent\"");
+        assert_eq!(naming(Some("preserve"), None, None).table_name_with("events", no_env), "events");
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown case `camelCase` for attribute `table`")]
+    fn test_unknown_table_name_case() {
+        naming(Some("camelCase"), None, None).table_name_with("UserLoginEvent", no_env);
+    }
+
+    #[test]
+    fn test_table_name_prefix_and_suffix() {
+        assert_eq!(naming(None, Some("billing_"), None).table_name_with("Invoice", no_env), "billing_invoice");
+        assert_eq!(naming(None, None, Some("_v2")).table_name_with("Invoice", no_env), "invoice_v2");
+        assert_eq!(naming(None, Some("billing_"), Some("_v2")).table_name_with("Invoice", no_env), "billing_invoice_v2");
+        assert_eq!(naming(Some("preserve"), Some("billing_"), None).table_name_with("Invoice", no_env), "\"billing_Invoice\"");
+    }
+
+    #[test]
+    fn test_table_name_prefix_and_suffix_of_env() {
+        let env = |var: &str| match var {
+            "CASSANDRA_TABLE_PREFIX" => Some(String::from("billing_")),
+            "CASSANDRA_TABLE_SUFFIX" => Some(String::from("_v2")),
+            _ => None,
+        };
+
+        assert_eq!(naming(None, None, None).table_name_with("Invoice", env), "billing_invoice_v2");
+        assert_eq!(naming(None, Some("shop_"), Some("")).table_name_with("Invoice", env), "shop_invoice");
+
+        assert_eq!(naming(None, None, None).table_name("Invoice"),
+                   format!("{}invoice{}", std::env::var("CASSANDRA_TABLE_PREFIX").unwrap_or_default(),
+                           std::env::var("CASSANDRA_TABLE_SUFFIX").unwrap_or_default()));
+    }
+
+    #[test]
+    fn test_table_name_env_vars() {
+        assert_eq!(naming(None, None, None).env_vars(), vec!["CASSANDRA_TABLE_PREFIX", "CASSANDRA_TABLE_SUFFIX"]);
+        assert_eq!(naming(None, Some("billing_"), None).env_vars(), vec!["CASSANDRA_TABLE_SUFFIX"]);
+        assert!(naming(None, Some(""), Some("_v2")).env_vars().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "table name `billing-` of `prefix` can only have letters, digits and `_`")]
+    fn test_invalid_table_name_prefix() {
+        naming(None, Some("billing-"), None).table_name_with("Invoice", no_env);
+    }
+
+    fn idempotence_of(attribute: &str) -> Idempotence {
+        let ast: syn::DeriveInput = syn::parse_str(&format!("#[table({})] pub struct User {{ }}", attribute)).unwrap();
+
+        match ast.attrs[0].parse_meta().unwrap() {
+            syn::Meta::List(list) => extract_table_properties(&list.nested.iter().collect()).6,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_idempotent_operations() {
+        let all = idempotence_of("keyspace = \"ks\", idempotent");
+        let deletes = idempotence_of("idempotent(delete)");
+        let none = idempotence_of("keyspace = \"ks\"");
+
+        assert!(all.store && all.update && all.delete);
+        assert!(!deletes.store && !deletes.update && deletes.delete);
+        assert!(!none.store && !none.update && !none.delete);
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown operation `select` of `idempotent`, expected `store`, `update` or `delete`")]
+    fn test_idempotent_unknown_operation() {
+        idempotence_of("idempotent(store, select)");
+    }
+
+    #[test]
+    #[should_panic(expected = "#[derive(CassandraProjection)] needs the table of its columns")]
+    fn test_projection_without_table() {
+        let ast: syn::DeriveInput = syn::parse_str("pub struct UserEmail { #[column(type = \"TEXT\")] email: String }").unwrap();
+
+        impl_cassandra_projection(&ast);
+    }
+
+    #[test]
+    #[should_panic(expected = "projection UserEmail does not have columns, mark its fields with `column`")]
+    fn test_projection_without_columns() {
+        let ast: syn::DeriveInput = syn::parse_str("#[projection(of = \"User\")] pub struct UserEmail { email: String }").unwrap();
+
+        impl_cassandra_projection(&ast);
+    }
+
+    #[test]
+    fn test_snake_case_to_pascal_case() {
+        assert_eq!(snake_case_to_pascal_case("username"), "Username");
+        assert_eq!(snake_case_to_pascal_case("first_name"), "FirstName");
+        assert_eq!(snake_case_to_pascal_case("key_1"), "Key1");
+    }
 
     #[test]
     fn test_pascal_case_to_snake_case() {
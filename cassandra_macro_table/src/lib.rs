@@ -0,0 +1,1028 @@
+//! Definition of a Cassandra table and the CQL of its statements
+//!
+//! Shared by `cassandra_macro`, which re-exports it, and by the derive of
+//! `cassandra_macro_derive`, so a table declared at runtime with a
+//! `CqlTableBuilder` has the same statements as the table derived
+//! from a struct with the same columns and keys.
+//!
+//! ```rust
+//! use cassandra_macro_table::{CqlTableBuilder, Order};
+//!
+//! let table = CqlTableBuilder::new("events")
+//!     .keyspace("ks")
+//!     .column("id", "uuid")
+//!     .column("ts", "timestamp")
+//!     .column("payload", "text")
+//!     .partition_key("id")
+//!     .cluster_key("ts", Order::Desc, 1)
+//!     .build()
+//!     .unwrap();
+//!
+//! assert_eq!(table.insert_cql(), "INSERT INTO ks.events (id,ts,payload) VALUES (?,?,?)");
+//! assert_eq!(table.delete_cql(), "DELETE FROM ks.events WHERE id=? AND ts=?");
+//! ```
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+const NATIVE_CQL_TYPES: &[&str] = &[
+    "ASCII", "BIGINT", "BLOB", "BOOLEAN", "COUNTER", "DATE", "DECIMAL", "DOUBLE", "DURATION", "FLOAT", "INET", "INT",
+    "SMALLINT", "TEXT", "TIME", "TIMESTAMP", "TIMEUUID", "TINYINT", "UUID", "VARCHAR", "VARINT",
+];
+
+/// Keyspace of the statements of `CqlTable::with_key_space_placeholder`,
+/// which is replaced by the keyspace the statements are run in
+pub const KEY_SPACE_PLACEHOLDER: &str = "{ks}";
+
+/// Keyspaces, tables and views have names of at most 48 characters
+const MAX_NAME_LENGTH: usize = 48;
+
+/// Whether `name` is a valid unquoted CQL name of a keyspace, a table
+/// or a view, alphanumeric characters and underscores, at most 48 of them
+pub fn is_valid_name(name: &str) -> bool {
+    is_identifier(name) && name.len() <= MAX_NAME_LENGTH
+}
+
+fn is_identifier(identifier: &str) -> bool {
+    !identifier.is_empty() && identifier.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Same as `is_identifier`, also between double quotes, which keep its case
+fn is_quotable_identifier(identifier: &str, max_length: usize) -> bool {
+    let unquoted = identifier.strip_prefix('"').and_then(|i| i.strip_suffix('"')).unwrap_or(identifier);
+
+    is_identifier(unquoted) && unquoted.len() <= max_length
+}
+
+/// Sort direction of a cluster key column
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+impl Display for Order {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Order::Asc => write!(f, "ASC"),
+            Order::Desc => write!(f, "DESC"),
+        }
+    }
+}
+
+/// `ASC` or `DESC`, in any case
+impl FromStr for Order {
+    type Err = TableDefinitionError;
+
+    fn from_str(order: &str) -> Result<Self, Self::Err> {
+        match order.trim().to_uppercase().as_str() {
+            "ASC" => Ok(Order::Asc),
+            "DESC" => Ok(Order::Desc),
+            _ => Err(TableDefinitionError::new(format!("unknown order `{}`, expected `ASC` or `DESC`", order))),
+        }
+    }
+}
+
+/// Definition of a table that Cassandra would not accept, with the reason
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDefinitionError {
+    message: String,
+}
+
+impl TableDefinitionError {
+    pub fn new(message: String) -> Self {
+        TableDefinitionError { message }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl std::error::Error for TableDefinitionError {}
+
+impl Display for TableDefinitionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Builder of a `CqlTable`, for the tables that are only known at
+/// runtime, e.g. their columns come from a configuration file
+///
+/// Columns are declared in the order of the statements, and the
+/// definition is only validated by `build`.
+#[derive(Debug, Clone, Default)]
+pub struct CqlTableBuilder {
+    name: String,
+    key_space: String,
+    columns: Vec<(String, String)>,
+    static_columns: Vec<String>,
+    partition_keys: Vec<(u8, String)>,
+    cluster_keys: Vec<(u8, String, Order)>,
+    options: Vec<String>,
+    comment: Option<String>,
+    generated_columns: Vec<(String, String)>,
+    indexes: Vec<(String, String)>,
+    views: Vec<(String, Vec<String>, Vec<String>)>,
+}
+
+impl CqlTableBuilder {
+    pub fn new(name: &str) -> Self {
+        CqlTableBuilder { name: name.to_string(), ..Default::default() }
+    }
+
+    pub fn keyspace(mut self, key_space: &str) -> Self {
+        self.key_space = key_space.to_string();
+        self
+    }
+
+    /// Column of the given CQL type, e.g. `frozen<list<text>>`
+    pub fn column(mut self, name: &str, cql_type: &str) -> Self {
+        self.columns.push((name.to_string(), cql_type.to_string()));
+        self
+    }
+
+    /// Column shared by all the rows of a partition
+    pub fn static_column(mut self, name: &str, cql_type: &str) -> Self {
+        self.static_columns.push(name.to_string());
+        self.column(name, cql_type)
+    }
+
+    /// Column set by Cassandra on insert to the CQL expression,
+    /// e.g. `now()`, it is only bound by the updates
+    pub fn generated(mut self, column: &str, expression: &str) -> Self {
+        self.generated_columns.push((column.to_string(), expression.trim().to_string()));
+        self
+    }
+
+    /// Secondary index of the column, named `<table>_<column>_idx`,
+    /// without the quotes of a quoted table name
+    pub fn index(self, column: &str) -> Self {
+        let name = format!("{}_{}_idx", self.name.trim_matches('"'), column);
+
+        self.named_index(column, &name)
+    }
+
+    /// Secondary index of the column with the given name, which
+    /// `build` rejects unless it is a CQL identifier
+    pub fn named_index(mut self, column: &str, name: &str) -> Self {
+        self.indexes.push((column.to_string(), name.trim().to_string()));
+        self
+    }
+
+    /// Materialized view of the table, with its own partition and cluster keys
+    ///
+    /// Only its selects are generated, the view must already exist.
+    pub fn view(mut self, name: &str, partition_keys: &[&str], cluster_keys: &[&str]) -> Self {
+        let columns = |keys: &[&str]| keys.iter().map(|k| k.trim().to_string()).collect();
+
+        self.views.push((name.trim().to_string(), columns(partition_keys), columns(cluster_keys)));
+        self
+    }
+
+    /// Partition key after the ones already added
+    pub fn partition_key(self, column: &str) -> Self {
+        let position = self.partition_keys.iter().map(|(p, _)| *p).max().map_or(1, |p| p.saturating_add(1));
+
+        self.partition_key_at(column, position)
+    }
+
+    /// Partition key at the position of a compound partition key
+    pub fn partition_key_at(mut self, column: &str, position: u8) -> Self {
+        self.partition_keys.push((position, column.to_string()));
+        self
+    }
+
+    pub fn cluster_key(mut self, column: &str, order: Order, position: u8) -> Self {
+        self.cluster_keys.push((position, column.to_string(), order));
+        self
+    }
+
+    /// Table option of the create statement, e.g. `default_time_to_live = 3600`
+    pub fn option(mut self, option: &str) -> Self {
+        self.options.push(option.to_string());
+        self
+    }
+
+    /// Table options separated by `|`, as in the `options` of the `table` attribute
+    pub fn options(self, options: &str) -> Self {
+        options.split('|').filter(|opt| !opt.is_empty()).fold(self, |builder, opt| builder.option(opt))
+    }
+
+    /// `comment` option of the table, unless the options already have one
+    pub fn comment(mut self, comment: &str) -> Self {
+        self.comment = Some(comment.to_string());
+        self
+    }
+
+    /// The table, if Cassandra would accept its definition
+    ///
+    /// It must have a partition key, the keys and the static, generated
+    /// and indexed columns must be declared columns, and the keys
+    /// can not be a `DURATION` or a collection that is not frozen.
+    /// The names of the keyspace, the table, its columns, indexes and
+    /// views must be CQL identifiers, quoted or not.
+    pub fn build(self) -> Result<CqlTable, TableDefinitionError> {
+        let CqlTableBuilder {
+            name, key_space, columns, static_columns, mut partition_keys,
+            mut cluster_keys, mut options, comment, generated_columns, indexes, views
+        } = self;
+
+        if !is_quotable_identifier(&name, MAX_NAME_LENGTH) {
+            return Err(TableDefinitionError::new(format!("table name `{}` is not a CQL identifier", name)));
+        }
+
+        if !is_quotable_identifier(&key_space, MAX_NAME_LENGTH) {
+            return Err(TableDefinitionError::new(format!("keyspace `{}` of table {} is not a CQL identifier", key_space, name)));
+        }
+
+        if let Some((column, _)) = columns.iter().find(|(c, _)| !is_quotable_identifier(c, usize::MAX)) {
+            return Err(TableDefinitionError::new(format!("column `{}` of table {} is not a CQL identifier", column, name)));
+        }
+
+        if let Some((_, index)) = indexes.iter().find(|(_, i)| !i.is_empty() && !is_quotable_identifier(i, usize::MAX)) {
+            return Err(TableDefinitionError::new(format!("index `{}` of table {} is not a CQL identifier", index, name)));
+        }
+
+        let declared = |column: &str| columns.iter().find(|(c, _)| c == column).map(|(_, t)| t.as_str());
+
+        // unquoted CQL identifiers are case insensitive, `userId` and `userid` are the same column
+        for (i, (column, _)) in columns.iter().enumerate() {
+            if let Some((other, _)) = columns[..i].iter().find(|(c, _)| c.eq_ignore_ascii_case(column)) {
+                return Err(if other == column {
+                    TableDefinitionError::new(format!("column `{}` of table {} is declared twice", column, name))
+                } else {
+                    TableDefinitionError::new(format!("columns `{}` and `{}` of table {} are the same CQL column, \
+                                                       identifiers are case insensitive", other, column, name))
+                });
+            }
+        }
+
+        if partition_keys.is_empty() {
+            return Err(TableDefinitionError::new(format!("Table {} does not have a partition key", name)));
+        }
+
+        partition_keys.sort_by_key(|(p, _)| *p);
+        cluster_keys.sort_by_key(|(p, _, _)| *p);
+
+        for keys in [
+            partition_keys.iter().map(|(p, c)| (*p, c.as_str())).collect::<Vec<(u8, &str)>>(),
+            cluster_keys.iter().map(|(p, c, _)| (*p, c.as_str())).collect(),
+        ].iter() {
+            for pair in keys.windows(2) {
+                if pair[0].0 == pair[1].0 {
+                    return Err(TableDefinitionError::new(format!("keys `{}` and `{}` of table {} have the same position {}",
+                                                                 pair[0].1, pair[1].1, name, pair[0].0)));
+                }
+            }
+        }
+
+        for (_, key) in partition_keys.iter() {
+            if cluster_keys.iter().any(|(_, c, _)| c == key) {
+                return Err(TableDefinitionError::new(format!("column `{}` is a partition key and a cluster key, \
+                                                              a column can only be one of them", key)));
+            }
+        }
+
+        let keys: Vec<&str> = partition_keys.iter().map(|(_, c)| c.as_str())
+            .chain(cluster_keys.iter().map(|(_, c, _)| c.as_str()))
+            .collect();
+
+        for (i, key) in keys.iter().enumerate() {
+            if keys[..i].contains(key) {
+                return Err(TableDefinitionError::new(format!("column `{}` of table {} is a key twice", key, name)));
+            }
+
+            let cql_type = declared(key).ok_or_else(|| {
+                TableDefinitionError::new(format!("key `{}` is not a column of table {}", key, name))
+            })?;
+
+            validate_key_type(key, cql_type)?;
+        }
+
+        // static columns are shared by the rows of a partition, e.g. `username TEXT STATIC`
+        // in the primary key is rejected by Cassandra when the table is created
+        for column in static_columns.iter() {
+            if partition_keys.iter().any(|(_, k)| k == column) {
+                return Err(TableDefinitionError::new(format!("column `{}` is a partition key, a key can not be static", column)));
+            }
+
+            if cluster_keys.iter().any(|(_, k, _)| k == column) {
+                return Err(TableDefinitionError::new(format!("column `{}` is a cluster key, a key can not be static", column)));
+            }
+        }
+
+        // Cassandra only accepts counter tables with counter columns besides the keys
+        let is_key = |column: &str| keys.contains(&column);
+
+        if let Some((counter, _)) = columns.iter().find(|(c, t)| !is_key(c) && is_counter(t)) {
+            if let Some((column, _)) = columns.iter().find(|(c, t)| !is_key(c) && !is_counter(t)) {
+                return Err(TableDefinitionError::new(format!("column `{}` is not a counter, table {} has the counter column `{}`, \
+                                                              a table with counters only can have counter columns besides its keys",
+                                                             column, name, counter)));
+            }
+        }
+
+        for (column, expression) in generated_columns.iter() {
+            if declared(column).is_none() {
+                return Err(TableDefinitionError::new(format!("generated column `{}` is not a column of table {}", column, name)));
+            }
+
+            if expression.is_empty() {
+                return Err(TableDefinitionError::new(format!("column `{}` must have a CQL expression in `generated`, e.g. `now()`", column)));
+            }
+        }
+
+        for (i, (column, index)) in indexes.iter().enumerate() {
+            if declared(column).is_none() {
+                return Err(TableDefinitionError::new(format!("indexed column `{}` is not a column of table {}", column, name)));
+            }
+
+            if index.is_empty() {
+                return Err(TableDefinitionError::new(format!("index of column `{}` of table {} must have a name", column, name)));
+            }
+
+            if partition_keys.len() == 1 && partition_keys[0].1 == *column {
+                return Err(TableDefinitionError::new(format!("column `{}` is the only partition key of table {}, \
+                                                              it can not have an index", column, name)));
+            }
+
+            if let Some((other, _)) = indexes[..i].iter().find(|(c, _)| c == column) {
+                return Err(TableDefinitionError::new(format!("column `{}` of table {} has two indexes", other, name)));
+            }
+
+            // index names are unique in the keyspace, and case insensitive as the columns
+            if let Some((other, _)) = indexes[..i].iter().find(|(_, n)| n.eq_ignore_ascii_case(index)) {
+                return Err(TableDefinitionError::new(format!("columns `{}` and `{}` of table {} have the same index name `{}`",
+                                                             other, column, name, index)));
+            }
+        }
+
+        // the key of a view is the key of the table and at most one other column
+        for (i, (view, view_partition_keys, view_cluster_keys)) in views.iter().enumerate() {
+            if view.is_empty() || views[..i].iter().any(|(v, _, _)| v.eq_ignore_ascii_case(view)) || view.eq_ignore_ascii_case(&name) {
+                return Err(TableDefinitionError::new(format!("view `{}` of table {} must have a name of its own", view, name)));
+            }
+
+            if !is_quotable_identifier(view, MAX_NAME_LENGTH) {
+                return Err(TableDefinitionError::new(format!("view `{}` of table {} is not a CQL identifier", view, name)));
+            }
+
+            if view_partition_keys.is_empty() {
+                return Err(TableDefinitionError::new(format!("view {} of table {} does not have a partition key", view, name)));
+            }
+
+            let view_keys: Vec<&str> = view_partition_keys.iter().chain(view_cluster_keys.iter()).map(|k| k.as_str()).collect();
+
+            for (j, key) in view_keys.iter().enumerate() {
+                if view_keys[..j].contains(key) {
+                    return Err(TableDefinitionError::new(format!("column `{}` is a key of view {} twice", key, view)));
+                }
+
+                if declared(key).is_none() {
+                    return Err(TableDefinitionError::new(format!("key `{}` of view {} is not a column of table {}", key, view, name)));
+                }
+
+                if static_columns.iter().any(|c| c == key) {
+                    return Err(TableDefinitionError::new(format!("column `{}` is static, it can not be a key of view {}", key, view)));
+                }
+            }
+
+            if let Some(key) = keys.iter().find(|k| !view_keys.contains(k)) {
+                return Err(TableDefinitionError::new(format!("key `{}` of table {} is not a key of view {}, \
+                                                              a view must have all the keys of its table", key, name, view)));
+            }
+
+            let others: Vec<&str> = view_keys.iter().filter(|k| !keys.contains(k)).copied().collect();
+
+            if others.len() > 1 {
+                return Err(TableDefinitionError::new(format!("view {} has the keys `{}` that are not keys of table {}, \
+                                                              a view only can have one of them", view, others.join("`, `"), name)));
+            }
+        }
+
+        if let Some(comment) = comment {
+            let has_comment = options.iter().any(|opt| opt.trim().to_lowercase().starts_with("comment"));
+
+            if !has_comment {
+                options.push(format!("comment='{}'", comment.split_whitespace().collect::<Vec<&str>>().join(" ").replace('\'', "''")));
+            }
+        }
+
+        Ok(CqlTable {
+            name,
+            key_space,
+            columns,
+            static_columns,
+            partition_keys: partition_keys.into_iter().map(|(_, c)| c).collect(),
+            cluster_keys: cluster_keys.into_iter().map(|(_, c, o)| (c, o)).collect(),
+            options,
+            generated_columns,
+            indexes,
+            views,
+        })
+    }
+}
+
+fn is_counter(cql_type: &str) -> bool {
+    cql_type.trim().eq_ignore_ascii_case("counter")
+}
+
+/// Cassandra only accepts frozen collections, tuples and user
+/// defined types in the primary and cluster keys
+fn validate_key_type(key: &str, cql_type: &str) -> Result<(), TableDefinitionError> {
+    let declared = cql_type.trim().to_uppercase();
+
+    let base = declared.split('<').next().unwrap_or_default().trim();
+
+    if base == "DURATION" || base == "COUNTER" {
+        return Err(TableDefinitionError::new(format!("column `{}` is a key, a `{}` can not be a key", key, base)));
+    }
+
+    let unfrozen = match base {
+        "FROZEN" => false,
+        "LIST" | "SET" | "MAP" | "TUPLE" => true,
+        _ => !NATIVE_CQL_TYPES.contains(&base),
+    };
+
+    if unfrozen {
+        return Err(TableDefinitionError::new(format!("column `{}` is a key, its type must be frozen, use `frozen<{}>` instead of `{}`",
+                                                     key, declared.to_lowercase(), declared.to_lowercase())));
+    }
+
+    Ok(())
+}
+
+/// Validated definition of a table, and the CQL of its statements
+///
+/// The statements taking a selection or a set clause return it in
+/// place, so passing `{}` gives a format string of the statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CqlTable {
+    name: String,
+    key_space: String,
+    columns: Vec<(String, String)>,
+    static_columns: Vec<String>,
+    partition_keys: Vec<String>,
+    cluster_keys: Vec<(String, Order)>,
+    options: Vec<String>,
+    generated_columns: Vec<(String, String)>,
+    indexes: Vec<(String, String)>,
+    views: Vec<(String, Vec<String>, Vec<String>)>,
+}
+
+impl CqlTable {
+    pub fn key_space(&self) -> &str {
+        &self.key_space
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Same table with `KEY_SPACE_PLACEHOLDER` as its keyspace, whose
+    /// statements are templates for running them in any keyspace
+    ///
+    /// The placeholder is the first one of each statement, the table
+    /// options come after the qualified name of the table.
+    pub fn with_key_space_placeholder(&self) -> CqlTable {
+        CqlTable { key_space: KEY_SPACE_PLACEHOLDER.to_string(), ..self.clone() }
+    }
+
+    /// Columns of the table, in the order they were declared
+    pub fn columns(&self) -> Vec<&str> {
+        self.columns.iter().map(|(c, _)| c.as_str()).collect()
+    }
+
+    /// Declared CQL type of the column
+    pub fn column_type(&self, column: &str) -> Option<&str> {
+        self.columns.iter().find(|(c, _)| c == column).map(|(_, t)| t.as_str())
+    }
+
+    /// Partition key columns, sorted by their position
+    pub fn primary_key_columns(&self) -> Vec<&str> {
+        self.partition_keys.iter().map(|c| c.as_str()).collect()
+    }
+
+    /// Cluster key columns, sorted by their position
+    pub fn cluster_key_columns(&self) -> Vec<&str> {
+        self.cluster_keys.iter().map(|(c, _)| c.as_str()).collect()
+    }
+
+    /// Partition key columns followed by the cluster key columns
+    pub fn key_columns(&self) -> Vec<&str> {
+        [self.primary_key_columns(), self.cluster_key_columns()].concat()
+    }
+
+    pub fn static_columns(&self) -> Vec<&str> {
+        self.static_columns.iter().map(|c| c.as_str()).collect()
+    }
+
+    /// CQL expression of a `generated` column
+    pub fn generated_expression(&self, column: &str) -> Option<&str> {
+        self.generated_columns.iter().find(|(c, _)| c == column).map(|(_, e)| e.as_str())
+    }
+
+    /// Name of the secondary index of the column
+    pub fn index_name(&self, column: &str) -> Option<&str> {
+        self.indexes.iter().find(|(c, _)| c == column).map(|(_, n)| n.as_str())
+    }
+
+    /// Materialized views of the table, in the order they were declared
+    pub fn views(&self) -> Vec<&str> {
+        self.views.iter().map(|(v, _, _)| v.as_str()).collect()
+    }
+
+    /// Partition and cluster keys of the view
+    fn view_keys(&self, view: &str) -> Option<(Vec<&str>, Vec<&str>)> {
+        let (_, partition_keys, cluster_keys) = self.views.iter().find(|(v, _, _)| v == view)?;
+
+        Some((partition_keys.iter().map(|k| k.as_str()).collect(), cluster_keys.iter().map(|k| k.as_str()).collect()))
+    }
+
+    /// `COUNTER` columns, which are only changed by a delta
+    pub fn counter_columns(&self) -> Vec<&str> {
+        self.columns.iter().filter(|(_, t)| is_counter(t)).map(|(c, _)| c.as_str()).collect()
+    }
+
+    /// If the table has counter columns, its rows can not be
+    /// inserted nor updated by value, only by a delta
+    pub fn is_counter_table(&self) -> bool {
+        !self.counter_columns().is_empty()
+    }
+
+    /// Non key columns, but the `generated` columns, which are only
+    /// set on insert, and the `COUNTER` columns
+    pub fn updatable_columns(&self) -> Vec<&str> {
+        let keys = self.key_columns();
+        let counters = self.counter_columns();
+
+        self.columns()
+            .into_iter()
+            .filter(|c| !keys.contains(c) && !counters.contains(c) && self.generated_expression(c).is_none())
+            .collect()
+    }
+
+    /// Columns bound by the insert, all but the `generated` columns
+    pub fn stored_columns(&self) -> Vec<&str> {
+        self.columns().into_iter().filter(|c| self.generated_expression(c).is_none()).collect()
+    }
+
+    /// `TIMEUUID` cluster keys, which have a `time_range_cql`
+    pub fn time_range_columns(&self) -> Vec<&str> {
+        self.cluster_key_columns()
+            .into_iter()
+            .filter(|c| self.column_type(c).is_some_and(|t| t.trim().eq_ignore_ascii_case("timeuuid")))
+            .collect()
+    }
+
+    pub fn create_table_cql(&self) -> String {
+        let mut table_options = String::new();
+
+        let columns: String = self.columns
+            .iter()
+            .map(|(k, t)| {
+                if self.static_columns.contains(k) {
+                    format!("{} {} STATIC", k, t)
+                } else {
+                    format!("{} {}", k, t.to_uppercase())
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+
+        if !self.cluster_keys.is_empty() {
+            let c_order = self.cluster_keys.iter().map(|(c, o)| format!("{} {}", c, o)).collect::<Vec<String>>();
+
+            table_options = format!("WITH CLUSTERING ORDER BY ({})", c_order.join(","));
+
+            if !self.options.is_empty() {
+                table_options = format!("{} AND {}", table_options, self.options.join(" AND "))
+            }
+        } else if !self.options.is_empty() {
+            table_options = format!("WITH {}", self.options.join(" AND "))
+        }
+
+        let create_stmt = format!("CREATE TABLE IF NOT EXISTS {}.{} ", self.key_space, self.name);
+
+        if !self.cluster_keys.is_empty() {
+            format!("{} ({}, PRIMARY KEY (({}), {}) ) {}", create_stmt, columns, self.partition_keys.join(","),
+                    self.cluster_key_columns().join(","), table_options)
+        } else if self.partition_keys.len() > 1 {
+            // without the inner parentheses the keys after the first would be cluster keys
+            format!("{} ({}, PRIMARY KEY (({})) ) {}", create_stmt, columns, self.partition_keys.join(","), table_options)
+        } else {
+            format!("{} ({}, PRIMARY KEY ({}) ) {}", create_stmt, columns, self.partition_keys.join(","), table_options)
+        }
+    }
+
+    pub fn drop_table_cql(&self) -> String {
+        format!("DROP TABLE IF EXISTS {}.{}", self.key_space, self.name)
+    }
+
+    /// Creation of the secondary indexes, in the order they were declared
+    ///
+    /// Always with `IF NOT EXISTS`, as the creation of the table, so
+    /// that a migration can run them again.
+    pub fn create_indexes_cql(&self) -> Vec<String> {
+        self.indexes.iter()
+            .map(|(c, n)| format!("CREATE INDEX IF NOT EXISTS {} ON {}.{} ({})", n, self.key_space, self.name, c))
+            .collect()
+    }
+
+    /// Drop of the secondary indexes, in the order they were declared
+    pub fn drop_indexes_cql(&self) -> Vec<String> {
+        self.indexes.iter().map(|(_, n)| format!("DROP INDEX IF EXISTS {}.{}", self.key_space, n)).collect()
+    }
+
+    /// Insert of all columns, the `generated` columns are
+    /// set to their expression instead of being bound
+    pub fn insert_cql(&self) -> String {
+        let bind_marks = self.columns()
+            .into_iter()
+            .map(|c| self.generated_expression(c).unwrap_or("?"))
+            .collect::<Vec<&str>>();
+
+        format!("INSERT INTO {}.{} ({}) VALUES ({})", self.key_space, self.name, self.columns().join(","), bind_marks.join(","))
+    }
+
+    pub fn insert_json_cql(&self) -> String {
+        format!("INSERT INTO {}.{} JSON ?", self.key_space, self.name)
+    }
+
+    /// Same as `insert_json_cql`, the columns missing in the JSON are left unset
+    pub fn insert_json_default_unset_cql(&self) -> String {
+        format!("{} DEFAULT UNSET", self.insert_json_cql())
+    }
+
+    /// Update of all the updatable columns of a row, `None` when
+    /// the table only has key and `generated` columns
+    pub fn update_cql(&self) -> Option<String> {
+        let updatable_columns = self.updatable_columns();
+
+        if updatable_columns.is_empty() {
+            return None;
+        }
+
+        let set_part = updatable_columns.iter().map(|c| format!("{}=?", c)).collect::<Vec<String>>().join(",");
+
+        Some(self.update_by_primary_and_cluster_keys_cql(&set_part))
+    }
+
+    pub fn update_by_primary_keys_cql(&self, set_clause: &str) -> String {
+        format!("UPDATE {}.{} SET {} WHERE {}", self.key_space, self.name, set_clause, key_where(&self.primary_key_columns()))
+    }
+
+    pub fn update_by_primary_and_cluster_keys_cql(&self, set_clause: &str) -> String {
+        format!("UPDATE {}.{} SET {} WHERE {}", self.key_space, self.name, set_clause, key_where(&self.key_columns()))
+    }
+
+    pub fn select_cql(&self, selection: &str) -> String {
+        format!("SELECT {} FROM {}.{}", selection, self.key_space, self.name)
+    }
+
+    pub fn select_by_primary_keys_cql(&self, selection: &str) -> String {
+        format!("{} WHERE {}", self.select_cql(selection), spaced_key_where(&self.primary_key_columns()))
+    }
+
+    pub fn select_by_primary_and_cluster_keys_cql(&self, selection: &str) -> String {
+        format!("{} WHERE {}", self.select_cql(selection), self.spaced_full_key_where())
+    }
+
+    /// Select by a non key column, e.g. one with a secondary index
+    pub fn select_by_column_cql(&self, column: &str, selection: &str, allow_filtering: bool) -> Result<String, TableDefinitionError> {
+        if self.key_columns().contains(&column) {
+            return Err(TableDefinitionError::new(format!(
+                "column `{}` is a key, it is already selected by `select_by_primary_keys`, remove `queryable`", column)));
+        }
+
+        if self.column_type(column).is_none() {
+            return Err(TableDefinitionError::new(format!("`{}` is not a column of table {}", column, self.name)));
+        }
+
+        let filtering = if allow_filtering { " ALLOW FILTERING" } else { "" };
+
+        Ok(format!("{} WHERE {}=?{}", self.select_cql(selection), column, filtering))
+    }
+
+    /// Rows of a partition in a time window of a `TIMEUUID` cluster key,
+    /// bound by the keys before it and the two timestamps of the window
+    pub fn time_range_cql(&self, column: &str, selection: &str) -> Result<String, TableDefinitionError> {
+        if !self.time_range_columns().contains(&column) {
+            return Err(TableDefinitionError::new(format!("column `{}` is not a `TIMEUUID` cluster key of table {}", column, self.name)));
+        }
+
+        let keys = self.key_columns();
+        let restricted = keys.split(|k| *k == column).next().unwrap_or_default();
+
+        Ok(format!("{} WHERE {} AND {} > maxTimeuuid(?) AND {} < minTimeuuid(?)",
+                   self.select_cql(selection), key_where(restricted), column, column))
+    }
+
+    /// Delete of a row
+    pub fn delete_cql(&self) -> String {
+        format!("DELETE FROM {}.{} WHERE {}", self.key_space, self.name, key_where(&self.key_columns()))
+    }
+
+    /// Delete of all the rows of a partition
+    pub fn delete_partition_cql(&self) -> String {
+        format!("DELETE FROM {}.{} WHERE {}", self.key_space, self.name, key_where(&self.primary_key_columns()))
+    }
+
+    pub fn delete_by_primary_keys_cql(&self) -> String {
+        format!("DELETE FROM {}.{} WHERE {}", self.key_space, self.name, spaced_key_where(&self.primary_key_columns()))
+    }
+
+    pub fn delete_by_primary_and_cluster_keys_cql(&self) -> String {
+        format!("DELETE FROM {}.{} WHERE {}", self.key_space, self.name, self.spaced_full_key_where())
+    }
+
+    /// Count of a row, to know if it exists
+    pub fn exists_cql(&self) -> String {
+        format!("SELECT count(*) AS count FROM {}.{} WHERE {} LIMIT 1", self.key_space, self.name, key_where(&self.key_columns()))
+    }
+
+    /// Count of the rows of a partition
+    pub fn count_partition_cql(&self) -> String {
+        format!("SELECT count(*) AS count FROM {}.{} WHERE {}", self.key_space, self.name, key_where(&self.primary_key_columns()))
+    }
+
+    /// Select of a row of the view by its partition and cluster keys,
+    /// `None` if the table does not have the view
+    pub fn view_select_cql(&self, view: &str, selection: &str) -> Option<String> {
+        let (partition_keys, cluster_keys) = self.view_keys(view)?;
+
+        Some(format!("SELECT {} FROM {}.{} WHERE {}", selection, self.key_space, view,
+                     key_where(&[partition_keys, cluster_keys].concat())))
+    }
+
+    /// Count of the rows of a partition of the view
+    pub fn view_count_partition_cql(&self, view: &str) -> Option<String> {
+        let (partition_keys, _) = self.view_keys(view)?;
+
+        Some(format!("SELECT count(*) AS count FROM {}.{} WHERE {}", self.key_space, view, key_where(&partition_keys)))
+    }
+
+    fn spaced_full_key_where(&self) -> String {
+        let pk_where = spaced_key_where(&self.primary_key_columns());
+
+        if self.cluster_keys.is_empty() {
+            pk_where
+        } else {
+            format!("{} AND {}", pk_where, spaced_key_where(&self.cluster_key_columns()))
+        }
+    }
+}
+
+/// `pk=? AND ck=?`
+fn key_where(keys: &[&str]) -> String {
+    keys.iter().map(|k| format!("{}=?", k)).collect::<Vec<String>>().join(" AND ")
+}
+
+/// ` pk=? AND ck=? `, as the statements by keys of the `CassandraTable` trait
+fn spaced_key_where(keys: &[&str]) -> String {
+    keys.iter().map(|k| format!(" {}=? ", k)).collect::<Vec<String>>().join("AND")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CqlTableBuilder, Order};
+
+    fn user_event() -> CqlTableBuilder {
+        CqlTableBuilder::new("user_event")
+            .keyspace("test")
+            .column("username", "text")
+            .column("created", "timestamp")
+            .column("payload", "text")
+            .partition_key("username")
+            .cluster_key("created", Order::Desc, 1)
+    }
+
+    #[test]
+    fn test_create_table_cql() {
+        let table = user_event().options("default_time_to_live = 3600|").comment("User's events").build().unwrap();
+
+        assert_eq!(table.create_table_cql(),
+                   "CREATE TABLE IF NOT EXISTS test.user_event  (username TEXT,created TIMESTAMP,payload TEXT, \
+                    PRIMARY KEY ((username), created) ) WITH CLUSTERING ORDER BY (created DESC) \
+                    AND default_time_to_live = 3600 AND comment='User''s events'");
+
+        let table = CqlTableBuilder::new("user").keyspace("test").column("username", "text").partition_key("username").build().unwrap();
+
+        assert_eq!(table.create_table_cql(), "CREATE TABLE IF NOT EXISTS test.user  (username TEXT, PRIMARY KEY (username) ) ");
+    }
+
+    #[test]
+    fn test_statements_by_keys() {
+        let table = user_event().build().unwrap();
+
+        assert_eq!(table.select_by_primary_keys_cql("{}"), "SELECT {} FROM test.user_event WHERE  username=? ");
+        assert_eq!(table.delete_by_primary_and_cluster_keys_cql(), "DELETE FROM test.user_event WHERE  username=?  AND  created=? ");
+        assert_eq!(table.update_cql().unwrap(), "UPDATE test.user_event SET payload=? WHERE username=? AND created=?");
+        assert_eq!(table.exists_cql(), "SELECT count(*) AS count FROM test.user_event WHERE username=? AND created=? LIMIT 1");
+    }
+
+    #[test]
+    fn test_key_space_placeholder() {
+        let table = user_event().options("comment='Copy of test.user_event'").build().unwrap().with_key_space_placeholder();
+
+        assert!(table.create_table_cql().starts_with("CREATE TABLE IF NOT EXISTS {ks}.user_event "));
+        assert!(table.create_table_cql().ends_with("comment='Copy of test.user_event'"));
+        assert_eq!(table.insert_cql(), "INSERT INTO {ks}.user_event (username,created,payload) VALUES (?,?,?)");
+        assert_eq!(table.drop_table_cql(), "DROP TABLE IF EXISTS {ks}.user_event");
+    }
+
+    #[test]
+    fn test_compound_partition_key_positions() {
+        let table = CqlTableBuilder::new("t")
+            .keyspace("ks")
+            .column("a", "int")
+            .column("b", "int")
+            .partition_key_at("b", 2)
+            .partition_key_at("a", 1)
+            .build()
+            .unwrap();
+
+        assert_eq!(table.primary_key_columns(), vec!["a", "b"]);
+
+        let result = CqlTableBuilder::new("t")
+            .keyspace("test")
+            .column("a", "int")
+            .column("b", "int")
+            .partition_key_at("a", 1)
+            .partition_key_at("b", 1)
+            .build();
+
+        assert_eq!(result.unwrap_err().message(), "keys `a` and `b` of table t have the same position 1");
+    }
+
+    #[test]
+    fn test_invalid_definitions() {
+        let no_key = CqlTableBuilder::new("t").keyspace("test").column("a", "int").build();
+
+        assert_eq!(no_key.unwrap_err().message(), "Table t does not have a partition key");
+
+        let undeclared = CqlTableBuilder::new("t").keyspace("test").column("a", "int").partition_key("id").build();
+
+        assert_eq!(undeclared.unwrap_err().message(), "key `id` is not a column of table t");
+
+        let unfrozen = user_event().column("tags", "set<text>").cluster_key("tags", Order::Asc, 2).build();
+
+        assert_eq!(unfrozen.unwrap_err().message(), "column `tags` is a key, its type must be frozen, use `frozen<set<text>>` instead of `set<text>`");
+
+        let both_keys = user_event().cluster_key("username", Order::Asc, 2).build();
+
+        assert_eq!(both_keys.unwrap_err().message(),
+                   "column `username` is a partition key and a cluster key, a column can only be one of them");
+
+        let twice = CqlTableBuilder::new("t").keyspace("test").column("a", "int").column("a", "text").partition_key("a").build();
+
+        assert_eq!(twice.unwrap_err().message(), "column `a` of table t is declared twice");
+
+        let same_column = CqlTableBuilder::new("t").keyspace("test").column("user_id", "int").column("User_Id", "text").partition_key("user_id").build();
+
+        assert_eq!(same_column.unwrap_err().message(),
+                   "columns `user_id` and `User_Id` of table t are the same CQL column, identifiers are case insensitive");
+
+        let static_key = CqlTableBuilder::new("t").keyspace("test").static_column("a", "int").partition_key("a").build();
+
+        assert_eq!(static_key.unwrap_err().message(), "column `a` is a partition key, a key can not be static");
+
+        let static_cluster_key = user_event().static_column("tag", "text").cluster_key("tag", Order::Asc, 2).build();
+
+        assert_eq!(static_cluster_key.unwrap_err().message(), "column `tag` is a cluster key, a key can not be static");
+    }
+
+    #[test]
+    fn test_invalid_identifiers() {
+        let table = CqlTableBuilder::new("user event").keyspace("test").column("id", "int").partition_key("id").build();
+
+        assert_eq!(table.unwrap_err().message(), "table name `user event` is not a CQL identifier");
+
+        let long_name = CqlTableBuilder::new(&"t".repeat(49)).keyspace("test").column("id", "int").partition_key("id").build();
+
+        assert!(long_name.is_err());
+
+        let no_key_space = CqlTableBuilder::new("t").column("id", "int").partition_key("id").build();
+
+        assert_eq!(no_key_space.unwrap_err().message(), "keyspace `` of table t is not a CQL identifier");
+
+        let column = user_event().column("payload; DROP", "text").build();
+
+        assert_eq!(column.unwrap_err().message(), "column `payload; DROP` of table user_event is not a CQL identifier");
+
+        let index = user_event().named_index("payload", "by-payload").build();
+
+        assert_eq!(index.unwrap_err().message(), "index `by-payload` of table user_event is not a CQL identifier");
+
+        let view = user_event().view("user event by payload", &["payload"], &["username", "created"]).build();
+
+        assert_eq!(view.unwrap_err().message(), "view `user event by payload` of table user_event is not a CQL identifier");
+
+        let quoted = CqlTableBuilder::new("\"billing_Invoice\"").keyspace("test").column("\"Id\"", "int").partition_key("\"Id\"").build();
+
+        assert_eq!(quoted.unwrap().create_table_cql(),
+                   "CREATE TABLE IF NOT EXISTS test.\"billing_Invoice\"  (\"Id\" INT, PRIMARY KEY (\"Id\") ) ");
+    }
+
+    #[test]
+    fn test_counter_table() {
+        let table = CqlTableBuilder::new("page_views")
+            .keyspace("test")
+            .column("page", "text")
+            .column("hits", "counter")
+            .column("shares", "COUNTER")
+            .partition_key("page")
+            .build()
+            .unwrap();
+
+        assert!(table.is_counter_table());
+        assert_eq!(table.counter_columns(), vec!["hits", "shares"]);
+        assert!(table.update_cql().is_none());
+
+        let mixed = user_event().column("hits", "counter").build();
+
+        assert_eq!(mixed.unwrap_err().message(),
+                   "column `payload` is not a counter, table user_event has the counter column `hits`, \
+                    a table with counters only can have counter columns besides its keys");
+
+        let counter_key = CqlTableBuilder::new("t").keyspace("test").column("hits", "counter").partition_key("hits").build();
+
+        assert_eq!(counter_key.unwrap_err().message(), "column `hits` is a key, a `COUNTER` can not be a key");
+    }
+
+    #[test]
+    fn test_indexes() {
+        let table = user_event().index("payload").named_index("created", "by_created").build().unwrap();
+
+        assert_eq!(table.create_indexes_cql(), vec![
+            "CREATE INDEX IF NOT EXISTS user_event_payload_idx ON test.user_event (payload)",
+            "CREATE INDEX IF NOT EXISTS by_created ON test.user_event (created)",
+        ]);
+        assert_eq!(table.drop_indexes_cql(), vec![
+            "DROP INDEX IF EXISTS test.user_event_payload_idx",
+            "DROP INDEX IF EXISTS test.by_created",
+        ]);
+        assert_eq!(table.index_name("payload"), Some("user_event_payload_idx"));
+        assert!(user_event().build().unwrap().create_indexes_cql().is_empty());
+
+        let quoted = CqlTableBuilder::new("\"UserEvent\"").keyspace("test").column("id", "int").column("kind", "text")
+            .partition_key("id").index("kind").build().unwrap();
+
+        assert_eq!(quoted.create_indexes_cql(), vec!["CREATE INDEX IF NOT EXISTS UserEvent_kind_idx ON test.\"UserEvent\" (kind)"]);
+
+        let only_partition_key = user_event().index("username").build();
+
+        assert_eq!(only_partition_key.unwrap_err().message(),
+                   "column `username` is the only partition key of table user_event, it can not have an index");
+
+        let undeclared = user_event().index("tags").build();
+
+        assert_eq!(undeclared.unwrap_err().message(), "indexed column `tags` is not a column of table user_event");
+
+        let same_name = user_event().named_index("payload", "idx").named_index("created", "IDX").build();
+
+        assert_eq!(same_name.unwrap_err().message(), "columns `payload` and `created` of table user_event have the same index name `IDX`");
+    }
+
+    #[test]
+    fn test_views() {
+        let table = user_event()
+            .view("user_event_by_payload", &["payload"], &["username", "created"])
+            .build()
+            .unwrap();
+
+        assert_eq!(table.views(), vec!["user_event_by_payload"]);
+        assert_eq!(table.view_select_cql("user_event_by_payload", "*").unwrap(),
+                   "SELECT * FROM test.user_event_by_payload WHERE payload=? AND username=? AND created=?");
+        assert_eq!(table.view_count_partition_cql("user_event_by_payload").unwrap(),
+                   "SELECT count(*) AS count FROM test.user_event_by_payload WHERE payload=?");
+        assert!(table.view_select_cql("user_event", "*").is_none());
+
+        let missing_key = user_event().view("by_payload", &["payload"], &["username"]).build();
+
+        assert_eq!(missing_key.unwrap_err().message(),
+                   "key `created` of table user_event is not a key of view by_payload, a view must have all the keys of its table");
+
+        let two_others = user_event().column("kind", "text").view("by_kind", &["kind", "payload"], &["username", "created"]).build();
+
+        assert_eq!(two_others.unwrap_err().message(),
+                   "view by_kind has the keys `kind`, `payload` that are not keys of table user_event, a view only can have one of them");
+
+        let undeclared = user_event().view("by_tag", &["tag"], &["username", "created"]).build();
+
+        assert_eq!(undeclared.unwrap_err().message(), "key `tag` of view by_tag is not a column of table user_event");
+
+        let no_partition_key = user_event().view("by_nothing", &[], &["username", "created"]).build();
+
+        assert_eq!(no_partition_key.unwrap_err().message(), "view by_nothing of table user_event does not have a partition key");
+    }
+
+    #[test]
+    fn test_order_from_str() {
+        assert_eq!("desc".parse::<Order>().unwrap(), Order::Desc);
+        assert_eq!("ASC".parse::<Order>().unwrap(), Order::Asc);
+        assert!("up".parse::<Order>().is_err());
+    }
+}
@@ -0,0 +1,196 @@
+use cassandra_macro_table::{CqlTable, CqlTableBuilder, Order};
+
+/// Every statement of the table, one per line
+fn snapshot(table: &CqlTable) -> Vec<String> {
+    let mut lines = vec![
+        format!("create: {}", table.create_table_cql()),
+        format!("drop: {}", table.drop_table_cql()),
+        format!("insert: {}", table.insert_cql()),
+        format!("insert json: {}", table.insert_json_cql()),
+        format!("insert json unset: {}", table.insert_json_default_unset_cql()),
+        format!("update: {}", table.update_cql().unwrap_or_default()),
+        format!("select: {}", table.select_cql("*")),
+        format!("select by primary keys: {}", table.select_by_primary_keys_cql("*")),
+        format!("select by keys: {}", table.select_by_primary_and_cluster_keys_cql("*")),
+        format!("update by primary keys: {}", table.update_by_primary_keys_cql("{}")),
+        format!("update by keys: {}", table.update_by_primary_and_cluster_keys_cql("{}")),
+        format!("delete: {}", table.delete_cql()),
+        format!("delete partition: {}", table.delete_partition_cql()),
+        format!("delete by primary keys: {}", table.delete_by_primary_keys_cql()),
+        format!("delete by keys: {}", table.delete_by_primary_and_cluster_keys_cql()),
+        format!("exists: {}", table.exists_cql()),
+        format!("count partition: {}", table.count_partition_cql()),
+    ];
+
+    for column in table.time_range_columns() {
+        lines.push(format!("time range {}: {}", column, table.time_range_cql(column, "*").unwrap()));
+    }
+
+    lines.extend(table.create_indexes_cql().into_iter().map(|stmt| format!("create index: {}", stmt)));
+    lines.extend(table.drop_indexes_cql().into_iter().map(|stmt| format!("drop index: {}", stmt)));
+
+    lines
+}
+
+#[test]
+fn test_single_key_statements() {
+    let table = CqlTableBuilder::new("user")
+        .keyspace("fog")
+        .column("username", "text")
+        .column("email", "text")
+        .column("age", "int")
+        .partition_key("username")
+        .build()
+        .unwrap();
+
+    assert_eq!(snapshot(&table), vec![
+        "create: CREATE TABLE IF NOT EXISTS fog.user  (username TEXT,email TEXT,age INT, PRIMARY KEY (username) ) ",
+        "drop: DROP TABLE IF EXISTS fog.user",
+        "insert: INSERT INTO fog.user (username,email,age) VALUES (?,?,?)",
+        "insert json: INSERT INTO fog.user JSON ?",
+        "insert json unset: INSERT INTO fog.user JSON ? DEFAULT UNSET",
+        "update: UPDATE fog.user SET email=?,age=? WHERE username=?",
+        "select: SELECT * FROM fog.user",
+        "select by primary keys: SELECT * FROM fog.user WHERE  username=? ",
+        "select by keys: SELECT * FROM fog.user WHERE  username=? ",
+        "update by primary keys: UPDATE fog.user SET {} WHERE username=?",
+        "update by keys: UPDATE fog.user SET {} WHERE username=?",
+        "delete: DELETE FROM fog.user WHERE username=?",
+        "delete partition: DELETE FROM fog.user WHERE username=?",
+        "delete by primary keys: DELETE FROM fog.user WHERE  username=? ",
+        "delete by keys: DELETE FROM fog.user WHERE  username=? ",
+        "exists: SELECT count(*) AS count FROM fog.user WHERE username=? LIMIT 1",
+        "count partition: SELECT count(*) AS count FROM fog.user WHERE username=?",
+    ]);
+}
+
+#[test]
+fn test_composite_partition_key_statements() {
+    let table = CqlTableBuilder::new("sensor_reading")
+        .keyspace("iot")
+        .column("site", "text")
+        .column("sensor", "int")
+        .column("value", "double")
+        .partition_key_at("sensor", 2)
+        .partition_key_at("site", 1)
+        .build()
+        .unwrap();
+
+    assert_eq!(snapshot(&table), vec![
+        "create: CREATE TABLE IF NOT EXISTS iot.sensor_reading  (site TEXT,sensor INT,value DOUBLE, PRIMARY KEY ((site,sensor)) ) ",
+        "drop: DROP TABLE IF EXISTS iot.sensor_reading",
+        "insert: INSERT INTO iot.sensor_reading (site,sensor,value) VALUES (?,?,?)",
+        "insert json: INSERT INTO iot.sensor_reading JSON ?",
+        "insert json unset: INSERT INTO iot.sensor_reading JSON ? DEFAULT UNSET",
+        "update: UPDATE iot.sensor_reading SET value=? WHERE site=? AND sensor=?",
+        "select: SELECT * FROM iot.sensor_reading",
+        "select by primary keys: SELECT * FROM iot.sensor_reading WHERE  site=? AND sensor=? ",
+        "select by keys: SELECT * FROM iot.sensor_reading WHERE  site=? AND sensor=? ",
+        "update by primary keys: UPDATE iot.sensor_reading SET {} WHERE site=? AND sensor=?",
+        "update by keys: UPDATE iot.sensor_reading SET {} WHERE site=? AND sensor=?",
+        "delete: DELETE FROM iot.sensor_reading WHERE site=? AND sensor=?",
+        "delete partition: DELETE FROM iot.sensor_reading WHERE site=? AND sensor=?",
+        "delete by primary keys: DELETE FROM iot.sensor_reading WHERE  site=? AND sensor=? ",
+        "delete by keys: DELETE FROM iot.sensor_reading WHERE  site=? AND sensor=? ",
+        "exists: SELECT count(*) AS count FROM iot.sensor_reading WHERE site=? AND sensor=? LIMIT 1",
+        "count partition: SELECT count(*) AS count FROM iot.sensor_reading WHERE site=? AND sensor=?",
+    ]);
+}
+
+#[test]
+fn test_cluster_keys_and_options_statements() {
+    let table = CqlTableBuilder::new("message")
+        .keyspace("chat")
+        .column("channel", "text")
+        .column("created", "timeuuid")
+        .column("author", "text")
+        .column("body", "text")
+        .partition_key("channel")
+        .cluster_key("author", Order::Asc, 2)
+        .cluster_key("created", Order::Desc, 1)
+        .generated("created", "now()")
+        .index("author")
+        .option("default_time_to_live = 3600")
+        .option("compaction = {'class': 'TimeWindowCompactionStrategy'}")
+        .comment("Messages of a channel")
+        .build()
+        .unwrap();
+
+    assert_eq!(snapshot(&table), vec![
+        "create: CREATE TABLE IF NOT EXISTS chat.message  (channel TEXT,created TIMEUUID,author TEXT,body TEXT, \
+         PRIMARY KEY ((channel), created,author) ) WITH CLUSTERING ORDER BY (created DESC,author ASC) \
+         AND default_time_to_live = 3600 AND compaction = {'class': 'TimeWindowCompactionStrategy'} \
+         AND comment='Messages of a channel'",
+        "drop: DROP TABLE IF EXISTS chat.message",
+        "insert: INSERT INTO chat.message (channel,created,author,body) VALUES (?,now(),?,?)",
+        "insert json: INSERT INTO chat.message JSON ?",
+        "insert json unset: INSERT INTO chat.message JSON ? DEFAULT UNSET",
+        "update: UPDATE chat.message SET body=? WHERE channel=? AND created=? AND author=?",
+        "select: SELECT * FROM chat.message",
+        "select by primary keys: SELECT * FROM chat.message WHERE  channel=? ",
+        "select by keys: SELECT * FROM chat.message WHERE  channel=?  AND  created=? AND author=? ",
+        "update by primary keys: UPDATE chat.message SET {} WHERE channel=?",
+        "update by keys: UPDATE chat.message SET {} WHERE channel=? AND created=? AND author=?",
+        "delete: DELETE FROM chat.message WHERE channel=? AND created=? AND author=?",
+        "delete partition: DELETE FROM chat.message WHERE channel=?",
+        "delete by primary keys: DELETE FROM chat.message WHERE  channel=? ",
+        "delete by keys: DELETE FROM chat.message WHERE  channel=?  AND  created=? AND author=? ",
+        "exists: SELECT count(*) AS count FROM chat.message WHERE channel=? AND created=? AND author=? LIMIT 1",
+        "count partition: SELECT count(*) AS count FROM chat.message WHERE channel=?",
+        "time range created: SELECT * FROM chat.message WHERE channel=? AND created > maxTimeuuid(?) AND created < minTimeuuid(?)",
+        "create index: CREATE INDEX IF NOT EXISTS message_author_idx ON chat.message (author)",
+        "drop index: DROP INDEX IF EXISTS chat.message_author_idx",
+    ]);
+}
+
+#[test]
+fn test_static_columns_statements() {
+    let table = CqlTableBuilder::new("channel_message")
+        .keyspace("chat")
+        .column("channel", "text")
+        .column("created", "timestamp")
+        .static_column("topic", "text")
+        .column("body", "text")
+        .partition_key("channel")
+        .cluster_key("created", Order::Desc, 1)
+        .build()
+        .unwrap();
+
+    assert_eq!(table.static_columns(), vec!["topic"]);
+
+    assert_eq!(snapshot(&table), vec![
+        "create: CREATE TABLE IF NOT EXISTS chat.channel_message  (channel TEXT,created TIMESTAMP,topic text STATIC,body TEXT, \
+         PRIMARY KEY ((channel), created) ) WITH CLUSTERING ORDER BY (created DESC)",
+        "drop: DROP TABLE IF EXISTS chat.channel_message",
+        "insert: INSERT INTO chat.channel_message (channel,created,topic,body) VALUES (?,?,?,?)",
+        "insert json: INSERT INTO chat.channel_message JSON ?",
+        "insert json unset: INSERT INTO chat.channel_message JSON ? DEFAULT UNSET",
+        "update: UPDATE chat.channel_message SET topic=?,body=? WHERE channel=? AND created=?",
+        "select: SELECT * FROM chat.channel_message",
+        "select by primary keys: SELECT * FROM chat.channel_message WHERE  channel=? ",
+        "select by keys: SELECT * FROM chat.channel_message WHERE  channel=?  AND  created=? ",
+        "update by primary keys: UPDATE chat.channel_message SET {} WHERE channel=?",
+        "update by keys: UPDATE chat.channel_message SET {} WHERE channel=? AND created=?",
+        "delete: DELETE FROM chat.channel_message WHERE channel=? AND created=?",
+        "delete partition: DELETE FROM chat.channel_message WHERE channel=?",
+        "delete by primary keys: DELETE FROM chat.channel_message WHERE  channel=? ",
+        "delete by keys: DELETE FROM chat.channel_message WHERE  channel=?  AND  created=? ",
+        "exists: SELECT count(*) AS count FROM chat.channel_message WHERE channel=? AND created=? LIMIT 1",
+        "count partition: SELECT count(*) AS count FROM chat.channel_message WHERE channel=?",
+    ]);
+}
+
+#[test]
+fn test_only_key_columns_statements() {
+    let table = CqlTableBuilder::new("follower")
+        .keyspace("fog")
+        .column("username", "text")
+        .column("follower", "text")
+        .partition_key("username")
+        .cluster_key("follower", Order::Asc, 1)
+        .build()
+        .unwrap();
+
+    assert!(table.update_cql().is_none());
+    assert!(table.updatable_columns().is_empty());
+}
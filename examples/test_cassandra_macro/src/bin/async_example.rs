@@ -0,0 +1,56 @@
+use cassandra_macro::{AsyncCassandraRepository, CassandraTable, QueryValues, Value};
+use cassandra_macro_derive::CassandraTable;
+use cdrs_tokio::authenticators::StaticPasswordAuthenticator;
+use cdrs_tokio::cluster::{ClusterTcpConfig, NodeTcpConfigBuilder};
+use cdrs_tokio::cluster::session::new_lz4;
+use cdrs_tokio::load_balancing::RoundRobin;
+use chrono::Utc;
+
+#[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", derive_try_from_row)]
+pub struct AsyncUserExample {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "TEXT")]
+    first_name: String,
+
+    #[column(type = "TIMESTAMP", cluster_key(order = "ASC", position = 1))]
+    created: i64,
+}
+
+#[tokio::main]
+async fn main() {
+    let authenticator = StaticPasswordAuthenticator::new("test", "test");
+
+    let node_tcp = NodeTcpConfigBuilder::new("192.168.1.41:9042", authenticator).build();
+
+    let session = new_lz4(&ClusterTcpConfig(vec![node_tcp]), RoundRobin::new())
+        .await
+        .expect("Cassandra session must be created");
+
+    let repository = AsyncCassandraRepository::new(session);
+
+    println!("Creating table:{}", AsyncUserExample::create_table_cql());
+    repository.create_table::<AsyncUserExample>().await.expect("Must create table");
+
+    let mut rust_user = AsyncUserExample {
+        username: String::from("Rust"),
+        first_name: String::from("rust"),
+        created: Utc::now().timestamp_millis(),
+    };
+
+    repository.save(&rust_user).await.expect("User must be stored");
+
+    rust_user.first_name = String::from("IamRoot");
+    repository.update(&rust_user).await.expect("User must be updated");
+
+    let rust_user_from_db: Option<AsyncUserExample> = repository
+        .find_by_key(QueryValues::SimpleValues(vec![Value::from(rust_user.username.clone()), Value::from(rust_user.created)]))
+        .await
+        .unwrap();
+
+    assert_eq!(rust_user_from_db.unwrap().first_name, "IamRoot", "Must be updated");
+
+    repository.delete(&rust_user).await.expect("Must be deleted");
+}
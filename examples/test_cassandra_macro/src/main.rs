@@ -1,6 +1,3 @@
-#[macro_use]
-extern crate cdrs;
-
 use std::sync::Arc;
 
 use cassandra_macro::{CassandraTable, DeleteQuery, Projection, UpdateQuery};
@@ -19,8 +16,8 @@ use cdrs::types::value::Value;
 use chrono::Utc;
 use uuid::Uuid;
 
-#[table(keyspace = "test", options = "comment='Only for RUST users' | COMPACTION = {'class':'SizeTieredCompactionStrategy'}")]
 #[derive(Debug, CassandraTable)]
+#[table(keyspace = "test", options = "comment='Only for RUST users' | COMPACTION = {'class':'SizeTieredCompactionStrategy'}")]
 pub struct UserTestExample {
     #[column(type = "TEXT", primary_key)]
     username: String,
@@ -95,21 +92,27 @@ impl CassandraDriver {
         }
     }
 
-    pub fn execute_store_query(&self, query: &StoreQuery) -> Result<bool, CassandraDriverError> {
-        self.execute_query(query.query(), query.values())
+    pub fn execute_store_query(&self, query: StoreQuery) -> Result<bool, CassandraDriverError> {
+        let (query, values) = query.into_parts();
+
+        self.execute_query(&query, values)
     }
 
-    pub fn execute_update_query(&self, query: &UpdateQuery) -> Result<bool, CassandraDriverError> {
-        self.execute_query(query.query(), query.values())
+    pub fn execute_update_query(&self, query: UpdateQuery) -> Result<bool, CassandraDriverError> {
+        let (query, values) = query.into_parts();
+
+        self.execute_query(&query, values)
     }
 
-    pub fn execute_delete_query(&self, query: &DeleteQuery) -> Result<bool, CassandraDriverError> {
-        self.execute_query(query.query(), query.values())
+    pub fn execute_delete_query(&self, query: DeleteQuery) -> Result<bool, CassandraDriverError> {
+        let (query, values) = query.into_parts();
+
+        self.execute_query(&query, values)
     }
 
-    pub fn execute_query(&self, query: &String, values: &QueryValues) -> Result<bool, CassandraDriverError> {
+    pub fn execute_query(&self, query: &str, values: QueryValues) -> Result<bool, CassandraDriverError> {
         let result = self.connection
-            .query_with_values(query, values.to_owned());
+            .query_with_values(query, values);
 
         result.map(|_| true)
     }
@@ -122,7 +125,7 @@ impl CassandraDriver {
         let result_frame = self.connection.query_with_values(stmt, QueryValues::SimpleValues(values))?;
 
         Ok(result_frame.get_body()?.into_rows()
-            .map(|r| { r.first().map(|r| T::try_from_row(r.to_owned()).unwrap()) }).flatten())
+            .and_then(|r| { r.first().map(|r| T::try_from_row(r.to_owned()).unwrap()) }))
     }
 
     pub fn new_from_config(cassandra_configs: &CassandraConfig) -> Self {
@@ -165,15 +168,15 @@ fn main() {
     println!("You can test those by yourself");
     println!("{}", UserTestExample::select_by_primary_keys(Projection::Columns(vec!["created".to_string()])));
     println!("{}", UserTestExample::select_by_primary_and_cluster_keys(Projection::All));
-    println!("{}", UserTestExample::update_by_primary_keys(vec!["updated".to_string()]));
-    println!("{}", UserTestExample::update_by_primary_and_cluster_keys(vec!["updated".to_string()]));
+    println!("{}", UserTestExample::update_by_primary_keys(vec!["updated".to_string()]).unwrap());
+    println!("{}", UserTestExample::update_by_primary_and_cluster_keys(vec!["updated".to_string()]).unwrap());
     println!("{}", UserTestExample::delete_by_primary_keys());
     println!("{}", UserTestExample::delete_by_primary_and_cluster_keys());
 
     let mut rust_user = UserTestExample::default();
 
     println!("Storing rust: {}", rust_user.store_query().query());
-    connection.execute_store_query(&rust_user.store_query()).expect("User must be stored");
+    connection.execute_store_query(rust_user.store_query()).expect("User must be stored");
 
     let rust_user_from_db: Option<UserTestExample> = connection.find::<UserTestExample>(vec!["Rust".to_string()]).unwrap();
     assert!(rust_user_from_db.unwrap().username.eq(&rust_user.username), "Must be the same");
@@ -181,14 +184,14 @@ fn main() {
     println!("Update rust:{}", rust_user.update_query().unwrap().query());
     rust_user.set_first_name(String::from("IamRoot"));
 
-    connection.execute_update_query(&rust_user.update_query().unwrap()).unwrap();
+    connection.execute_update_query(rust_user.update_query().unwrap()).unwrap();
 
     let rust_user_from_db_1 = connection.find::<UserTestExample>(vec!["Rust".to_string()]).unwrap();
 
     assert!(rust_user_from_db_1.unwrap().username.eq(&rust_user.username), "Must be the same");
 
     println!("Delete:{}", rust_user.delete_query().query());
-    connection.execute_delete_query(&rust_user.delete_query()).expect("Must be deleted");
+    connection.execute_delete_query(rust_user.delete_query()).expect("Must be deleted");
 
     println!("Dropping table: {}", UserTestExample::drop_table_cql());
     connection.execute_simple_statement(UserTestExample::drop_table_cql()).expect("Table must be removed");
@@ -0,0 +1 @@
+//! Tables derived by a crate without `cdrs` in its dependencies, see the tests
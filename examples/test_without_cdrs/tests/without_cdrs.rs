@@ -0,0 +1,48 @@
+//! Derived code only depends on `cassandra_macro`, no
+//! `#[macro_use] extern crate cdrs` and no `cdrs` imports
+
+use cassandra_macro::{CassandraTable, CassandraUdt, Projection, QueryValues, Value};
+
+#[derive(Debug, cassandra_macro_derive::CassandraTable)]
+#[table(keyspace = "test", derive_try_from_row)]
+pub struct User {
+    #[column(type = "TEXT", primary_key)]
+    username: String,
+
+    #[column(type = "BIGINT", cluster_key(order = "ASC", position = 1))]
+    created: i64,
+
+    #[column(type = "TEXT")]
+    nickname: Option<String>,
+}
+
+#[derive(Debug, cassandra_macro_derive::CassandraUdt)]
+#[udt(keyspace = "test", derive_try_from_udt)]
+pub struct Address {
+    #[field(type = "text")]
+    street: String,
+}
+
+fn mapped_from_row<T: cassandra_macro::cdrs::frame::TryFromRow>() {}
+
+fn mapped_from_udt<T: cassandra_macro::cdrs::frame::TryFromUDT>() {}
+
+fn user() -> User {
+    User { username: String::from("rust"), created: 7, nickname: None }
+}
+
+#[test]
+fn test_generated_values_without_cdrs() {
+    let store_values = QueryValues::SimpleValues(vec![Value::from("rust"), Value::from(7i64), Value::from(None::<String>)]);
+
+    assert_eq!(user().store_query().values(), &store_values);
+    assert_eq!(user().delete_query().values(), &QueryValues::SimpleValues(vec![Value::from("rust"), Value::from(7i64)]));
+    assert_eq!(user().select_query(Projection::All).values(), user().delete_query().values());
+    assert_eq!(user().column_value("created"), Some(Value::from(7i64)));
+
+    assert_eq!(Address::create_type_cql(), "CREATE TYPE IF NOT EXISTS test.address (street TEXT)");
+    assert_eq!(Address { street: String::from("Main Street") }.street, "Main Street");
+
+    mapped_from_row::<User>();
+    mapped_from_udt::<Address>();
+}